@@ -145,6 +145,18 @@ impl Deployment for CloudDeployment {
     fn github_user_cache(&self) -> &services::services::github_user_cache::GitHubUserCache {
         self.inner.github_user_cache()
     }
+
+    fn executor_health(&self) -> &services::services::executor_health::ExecutorHealthService {
+        self.inner.executor_health()
+    }
+
+    fn identity_providers(&self) -> &services::services::identity::IdentityProviders {
+        self.inner.identity_providers()
+    }
+
+    fn webhooks(&self) -> &services::services::webhooks::WebhookService {
+        self.inner.webhooks()
+    }
 }
 
 impl CloudDeployment {
@@ -0,0 +1,38 @@
+PRAGMA foreign_keys = ON;
+
+-- Additional repositories owned by a project. The project's own git_repo_path
+-- remains the primary repository; rows here are checked out next to it.
+CREATE TABLE project_repositories (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    name            TEXT NOT NULL,
+    git_repo_path   TEXT NOT NULL,
+    default_target_branch TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name),
+    UNIQUE (project_id, git_repo_path)
+);
+
+CREATE INDEX idx_project_repositories_project_id ON project_repositories(project_id);
+
+-- Per-attempt worktree and merge state for each additional repository
+CREATE TABLE task_attempt_repositories (
+    id                    BLOB PRIMARY KEY,
+    task_attempt_id       BLOB NOT NULL,
+    project_repository_id BLOB NOT NULL,
+    worktree_path         TEXT NOT NULL,
+    target_branch         TEXT NOT NULL,
+    merge_commit          TEXT,
+    pr_number             INTEGER,
+    pr_url                TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_repository_id) REFERENCES project_repositories(id) ON DELETE CASCADE,
+    UNIQUE (task_attempt_id, project_repository_id)
+);
+
+CREATE INDEX idx_task_attempt_repositories_task_attempt_id
+    ON task_attempt_repositories(task_attempt_id);
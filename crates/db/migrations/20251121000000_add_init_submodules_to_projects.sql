@@ -0,0 +1,3 @@
+-- Add init_submodules column to projects table
+-- When set, attempt worktrees check out submodules (shallow, from a shared cache)
+ALTER TABLE projects ADD COLUMN init_submodules BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+-- Add sparse checkout settings to projects table
+-- sparse_checkout_patterns stores comma-separated cone-mode directories; NULL checks out everything
+ALTER TABLE projects ADD COLUMN sparse_checkout_patterns TEXT;
+-- When set, directories the agent reads or edits outside the cone are added to the worktree
+ALTER TABLE projects ADD COLUMN sparse_auto_expand BOOLEAN NOT NULL DEFAULT FALSE;
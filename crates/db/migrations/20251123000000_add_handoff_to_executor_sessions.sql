@@ -0,0 +1,4 @@
+-- Record when an execution took over an attempt from a different executor
+-- handoff_from_executor is the previous executor profile, e.g. "CLAUDE_CODE:PLAN"
+ALTER TABLE executor_sessions ADD COLUMN handoff_from_executor TEXT;
+ALTER TABLE executor_sessions ADD COLUMN handoff_from_execution_process_id BLOB;
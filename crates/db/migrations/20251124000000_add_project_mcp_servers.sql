@@ -0,0 +1,16 @@
+PRAGMA foreign_keys = ON;
+
+-- MCP servers scoped to a project, layered over each agent's global config when
+-- an attempt runs. config holds one entry in the canonical mcpServers format.
+CREATE TABLE project_mcp_servers (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    config      TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE INDEX idx_project_mcp_servers_project_id ON project_mcp_servers(project_id);
@@ -0,0 +1,17 @@
+PRAGMA foreign_keys = ON;
+
+-- Named secrets scoped to a project. Only metadata lives here; the encrypted
+-- value is kept in secrets under provider 'project:<project_id>'.
+CREATE TABLE project_secrets (
+    id                 BLOB PRIMARY KEY,
+    project_id         BLOB NOT NULL,
+    name               TEXT NOT NULL,
+    expose_to_scripts  BOOLEAN NOT NULL DEFAULT 1,
+    expose_to_agents   BOOLEAN NOT NULL DEFAULT 0,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE INDEX idx_project_secrets_project_id ON project_secrets(project_id);
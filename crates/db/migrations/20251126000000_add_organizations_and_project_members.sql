@@ -0,0 +1,41 @@
+PRAGMA foreign_keys = ON;
+
+-- Teams that can share projects. Roles: owner, maintainer, contributor, viewer.
+CREATE TABLE organizations (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL,
+    created_by  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE TABLE organization_members (
+    organization_id  BLOB NOT NULL,
+    user_id          TEXT NOT NULL,
+    role             TEXT NOT NULL
+                     CHECK (role IN ('owner','maintainer','contributor','viewer')),
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (organization_id, user_id),
+    FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_organization_members_user_id ON organization_members(user_id);
+
+-- Members of an organization get their organization role on its projects
+ALTER TABLE projects ADD COLUMN organization_id BLOB REFERENCES organizations(id) ON DELETE SET NULL;
+CREATE INDEX idx_projects_organization_id ON projects(organization_id);
+
+-- Per-project grants, in addition to the project creator (projects.user_id) who is always owner
+CREATE TABLE project_members (
+    project_id  BLOB NOT NULL,
+    user_id     TEXT NOT NULL,
+    role        TEXT NOT NULL
+                CHECK (role IN ('owner','maintainer','contributor','viewer')),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, user_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_members_user_id ON project_members(user_id);
@@ -0,0 +1,19 @@
+PRAGMA foreign_keys = ON;
+
+-- Personal access tokens for scripts and CI. Only a SHA-256 hash of the token
+-- is stored; the plaintext is shown once when the token is created.
+CREATE TABLE api_tokens (
+    id            BLOB PRIMARY KEY,
+    user_id       TEXT NOT NULL,
+    github_id     INTEGER NOT NULL DEFAULT 0,
+    username      TEXT NOT NULL,
+    name          TEXT NOT NULL,
+    token_prefix  TEXT NOT NULL,
+    token_hash    TEXT NOT NULL UNIQUE,
+    scopes        TEXT NOT NULL DEFAULT '[]',
+    expires_at    TEXT,
+    last_used_at  TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_api_tokens_user_id ON api_tokens(user_id);
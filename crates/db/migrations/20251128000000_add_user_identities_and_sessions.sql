@@ -0,0 +1,30 @@
+PRAGMA foreign_keys = ON;
+
+-- External identities (OIDC subjects, linked GitHub accounts) mapped to the
+-- user_id used throughout the schema.
+CREATE TABLE user_identities (
+    id            BLOB PRIMARY KEY,
+    user_id       TEXT NOT NULL,
+    provider      TEXT NOT NULL,
+    subject       TEXT NOT NULL,
+    email         TEXT,
+    display_name  TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (provider, subject)
+);
+
+CREATE INDEX idx_user_identities_user_id ON user_identities(user_id);
+
+-- Browser sessions created by identity provider sign-in. Only a hash of the
+-- session cookie is stored.
+CREATE TABLE user_sessions (
+    id            BLOB PRIMARY KEY,
+    user_id       TEXT NOT NULL,
+    display_name  TEXT NOT NULL,
+    token_hash    TEXT NOT NULL UNIQUE,
+    expires_at    TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
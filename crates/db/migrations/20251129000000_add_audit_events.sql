@@ -0,0 +1,40 @@
+PRAGMA foreign_keys = ON;
+
+-- Append-only record of who did what. Target ids are not foreign keys so that
+-- events outlive the projects, tasks and attempts they refer to.
+CREATE TABLE audit_events (
+    id              BLOB PRIMARY KEY,
+    actor_type      TEXT NOT NULL CHECK (actor_type IN ('user', 'system')),
+    actor_id        TEXT,
+    actor_name      TEXT,
+    via_api_token   INTEGER NOT NULL DEFAULT 0,
+    action          TEXT NOT NULL,
+    project_id      BLOB,
+    task_id         BLOB,
+    attempt_id      BLOB,
+    target          TEXT,
+    request_method  TEXT,
+    request_path    TEXT,
+    ip_address      TEXT,
+    user_agent      TEXT,
+    before          TEXT,
+    after           TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_audit_events_created_at ON audit_events(created_at);
+CREATE INDEX idx_audit_events_actor_id ON audit_events(actor_id);
+CREATE INDEX idx_audit_events_project_id ON audit_events(project_id);
+CREATE INDEX idx_audit_events_attempt_id ON audit_events(attempt_id);
+
+CREATE TRIGGER audit_events_no_update
+BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
+
+CREATE TRIGGER audit_events_no_delete
+BEFORE DELETE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
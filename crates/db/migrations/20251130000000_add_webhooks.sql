@@ -0,0 +1,37 @@
+PRAGMA foreign_keys = ON;
+
+-- Outbound webhooks notified of a project's task and attempt lifecycle events.
+-- The signing secret is kept in the secret store, not here.
+CREATE TABLE webhooks (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    url         TEXT NOT NULL,
+    events      TEXT NOT NULL DEFAULT '[]',
+    enabled     INTEGER NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhooks_project_id ON webhooks(project_id);
+
+-- Delivery log: one row per event sent to a webhook, updated after every attempt
+CREATE TABLE webhook_deliveries (
+    id               BLOB PRIMARY KEY,
+    webhook_id       BLOB NOT NULL,
+    event            TEXT NOT NULL,
+    payload          TEXT NOT NULL,
+    status           TEXT NOT NULL DEFAULT 'pending'
+                        CHECK (status IN ('pending', 'succeeded', 'failed')),
+    attempts         INTEGER NOT NULL DEFAULT 0,
+    response_status  INTEGER,
+    response_body    TEXT,
+    error            TEXT,
+    next_attempt_at  TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, created_at);
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
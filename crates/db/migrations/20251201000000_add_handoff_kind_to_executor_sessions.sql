@@ -0,0 +1,5 @@
+-- Whether a handoff was requested by a user ('manual') or started by a fallback chain ('fallback')
+-- Earlier handoffs cannot be told apart; they were all followed as fallbacks
+ALTER TABLE executor_sessions ADD COLUMN handoff_kind TEXT
+    CHECK (handoff_kind IN ('manual', 'fallback'));
+UPDATE executor_sessions SET handoff_kind = 'fallback' WHERE handoff_from_executor IS NOT NULL;
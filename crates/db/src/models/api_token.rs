@@ -0,0 +1,177 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a personal API token may do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub enum TokenScope {
+    /// Read projects, tasks, attempts and logs
+    #[serde(rename = "read")]
+    Read,
+    /// Create, edit and delete tasks
+    #[serde(rename = "tasks:write")]
+    TasksWrite,
+    /// Start, follow up on and stop attempts
+    #[serde(rename = "attempts:run")]
+    AttemptsRun,
+    /// Merge, push, rebase and open pull requests
+    #[serde(rename = "merge")]
+    Merge,
+}
+
+/// A personal API token. The token itself is never stored, only its hash.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: String,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub github_id: i64,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub username: String,
+    pub name: String,
+    /// First characters of the token, to tell tokens apart
+    pub token_prefix: String,
+    #[ts(type = "Array<TokenScope>")]
+    pub scopes: Json<Vec<TokenScope>>,
+    #[ts(type = "Date | null")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date | null")]
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateApiToken {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// Days until the token expires (at most 3650); omit for a token that never expires
+    pub expires_in_days: Option<u32>,
+}
+
+impl ApiToken {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub async fn find_by_user(pool: &SqlitePool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      user_id,
+                      github_id,
+                      username,
+                      name,
+                      token_prefix,
+                      scopes as "scopes!: Json<Vec<TokenScope>>",
+                      expires_at as "expires_at: DateTime<Utc>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE user_id = $1
+               ORDER BY created_at DESC"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      user_id,
+                      github_id,
+                      username,
+                      name,
+                      token_prefix,
+                      scopes as "scopes!: Json<Vec<TokenScope>>",
+                      expires_at as "expires_at: DateTime<Utc>",
+                      last_used_at as "last_used_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &SqlitePool,
+        user_id: &str,
+        github_id: i64,
+        username: &str,
+        data: &CreateApiToken,
+        token_prefix: &str,
+        token_hash: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let scopes = Json(&data.scopes);
+        sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO api_tokens
+                   (id, user_id, github_id, username, name, token_prefix, token_hash, scopes, expires_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         user_id,
+                         github_id,
+                         username,
+                         name,
+                         token_prefix,
+                         scopes as "scopes!: Json<Vec<TokenScope>>",
+                         expires_at as "expires_at: DateTime<Utc>",
+                         last_used_at as "last_used_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            user_id,
+            github_id,
+            username,
+            data.name,
+            token_prefix,
+            token_hash,
+            scopes,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn touch(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE api_tokens SET last_used_at = $2 WHERE id = $1",
+            id,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Revoke one of the user's tokens
+    pub async fn delete(pool: &SqlitePool, id: Uuid, user_id: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM api_tokens WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
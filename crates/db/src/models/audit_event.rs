@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Who performed an audited action
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "audit_actor_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AuditActorType {
+    User,
+    System,
+}
+
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "audit_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    AttemptStarted,
+    AttemptFollowUp,
+    AttemptStopped,
+    AttemptMerged,
+    AttemptPushed,
+    AttemptRebased,
+    AttemptTargetBranchChanged,
+    AttemptBranchRenamed,
+    AttemptFileDeleted,
+    PullRequestCreated,
+    PullRequestAttached,
+    ProjectCreated,
+    ProjectUpdated,
+    ProjectDeleted,
+    ProjectRepositoryAdded,
+    ProjectRepositoryRemoved,
+    ProjectMcpServersUpdated,
+    ProjectSecretUpdated,
+    ProjectSecretDeleted,
+    ProjectMemberUpdated,
+    ProjectMemberRemoved,
+    ProjectOrganizationChanged,
+    ProjectWebhookCreated,
+    ProjectWebhookUpdated,
+    ProjectWebhookDeleted,
+    ProjectWebhookRedelivered,
+    ConfigUpdated,
+    McpConfigUpdated,
+    ExecutorProfilesUpdated,
+}
+
+/// An entry in the append-only audit log
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub actor_type: AuditActorType,
+    /// User id of the actor; null for system actions
+    pub actor_id: Option<String>,
+    pub actor_name: Option<String>,
+    /// Whether the request was authenticated with a personal API token
+    pub via_api_token: bool,
+    pub action: AuditAction,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub attempt_id: Option<Uuid>,
+    /// Other target of the action, such as a secret name, member or branch
+    pub target: Option<String>,
+    pub request_method: Option<String>,
+    pub request_path: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// Snapshot before the change, for updates and deletes
+    #[ts(type = "unknown")]
+    pub before: Option<Json<Value>>,
+    /// Snapshot after the change, for creates and updates
+    #[ts(type = "unknown")]
+    pub after: Option<Json<Value>>,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditEvent {
+    pub actor_type: AuditActorType,
+    pub actor_id: Option<String>,
+    pub actor_name: Option<String>,
+    pub via_api_token: bool,
+    pub action: AuditAction,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub attempt_id: Option<Uuid>,
+    pub target: Option<String>,
+    pub request_method: Option<String>,
+    pub request_path: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Filters for querying the audit log; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct AuditEventFilter {
+    pub actor_id: Option<String>,
+    pub action: Option<AuditAction>,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub attempt_id: Option<Uuid>,
+    /// Only events at or after this time
+    #[ts(type = "Date | null")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only events before this time
+    #[ts(type = "Date | null")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl AuditEvent {
+    pub async fn create(pool: &SqlitePool, data: &CreateAuditEvent) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let before = data.before.as_ref().map(Json);
+        let after = data.after.as_ref().map(Json);
+        sqlx::query_as!(
+            AuditEvent,
+            r#"INSERT INTO audit_events
+                   (id, actor_type, actor_id, actor_name, via_api_token, action, project_id, task_id,
+                    attempt_id, target, request_method, request_path, ip_address, user_agent,
+                    before, after)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+               RETURNING id as "id!: Uuid",
+                         actor_type as "actor_type!: AuditActorType",
+                         actor_id,
+                         actor_name,
+                         via_api_token as "via_api_token!: bool",
+                         action as "action!: AuditAction",
+                         project_id as "project_id: Uuid",
+                         task_id as "task_id: Uuid",
+                         attempt_id as "attempt_id: Uuid",
+                         target,
+                         request_method,
+                         request_path,
+                         ip_address,
+                         user_agent,
+                         before as "before: Json<Value>",
+                         after as "after: Json<Value>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.actor_type,
+            data.actor_id,
+            data.actor_name,
+            data.via_api_token,
+            data.action,
+            data.project_id,
+            data.task_id,
+            data.attempt_id,
+            data.target,
+            data.request_method,
+            data.request_path,
+            data.ip_address,
+            data.user_agent,
+            before,
+            after
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Matching events, newest first
+    pub async fn find(
+        pool: &SqlitePool,
+        filter: &AuditEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AuditEvent,
+            r#"SELECT id as "id!: Uuid",
+                      actor_type as "actor_type!: AuditActorType",
+                      actor_id,
+                      actor_name,
+                      via_api_token as "via_api_token!: bool",
+                      action as "action!: AuditAction",
+                      project_id as "project_id: Uuid",
+                      task_id as "task_id: Uuid",
+                      attempt_id as "attempt_id: Uuid",
+                      target,
+                      request_method,
+                      request_path,
+                      ip_address,
+                      user_agent,
+                      before as "before: Json<Value>",
+                      after as "after: Json<Value>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM audit_events
+               WHERE ($1 IS NULL OR actor_id = $1)
+                 AND ($2 IS NULL OR action = $2)
+                 AND ($3 IS NULL OR project_id = $3)
+                 AND ($4 IS NULL OR task_id = $4)
+                 AND ($5 IS NULL OR attempt_id = $5)
+                 AND ($6 IS NULL OR julianday(created_at) >= julianday($6))
+                 AND ($7 IS NULL OR julianday(created_at) < julianday($7))
+               ORDER BY created_at DESC, rowid DESC
+               LIMIT $8 OFFSET $9"#,
+            filter.actor_id,
+            filter.action,
+            filter.project_id,
+            filter.task_id,
+            filter.attempt_id,
+            filter.since,
+            filter.until,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -284,6 +284,15 @@ impl ExecutionProcess {
                  AND ep.run_reason = 'codingagent'
                  AND ep.dropped = FALSE
                  AND es.session_id IS NOT NULL
+                 -- Sessions from before the latest executor handoff belong to another executor
+                 AND ep.created_at >= COALESCE((
+                     SELECT MAX(hp.created_at)
+                     FROM execution_processes hp
+                     JOIN executor_sessions hs ON hp.id = hs.execution_process_id
+                     WHERE hp.task_attempt_id = $1
+                       AND hp.dropped = FALSE
+                       AND hs.handoff_from_executor IS NOT NULL
+                 ), ep.created_at)
                ORDER BY ep.created_at DESC
                LIMIT 1"#,
             task_attempt_id
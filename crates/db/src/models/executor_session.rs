@@ -1,9 +1,20 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
+/// How an execution came to take over an attempt from another executor
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "handoff_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum HandoffKind {
+    /// A user switched the attempt to another executor
+    Manual,
+    /// The previous executor failed and its fallback chain continued the run
+    Fallback,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutorSession {
     pub id: Uuid,
@@ -12,6 +23,11 @@ pub struct ExecutorSession {
     pub session_id: Option<String>, // External session ID from Claude/Amp
     pub prompt: Option<String>,     // The prompt sent to the executor
     pub summary: Option<String>,    // Final assistant message/summary
+    /// Executor profile this session took over the attempt from, e.g. "CLAUDE_CODE:PLAN"
+    pub handoff_from_executor: Option<String>,
+    /// Execution process whose conversation was handed over
+    pub handoff_from_execution_process_id: Option<Uuid>,
+    pub handoff_kind: Option<HandoffKind>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -44,6 +60,9 @@ impl ExecutorSession {
                 session_id, 
                 prompt,
                 summary,
+                handoff_from_executor,
+                handoff_from_execution_process_id as "handoff_from_execution_process_id: Uuid",
+                handoff_kind as "handoff_kind: HandoffKind",
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM executor_sessions 
@@ -68,6 +87,9 @@ impl ExecutorSession {
                 session_id,
                 prompt,
                 summary,
+                handoff_from_executor,
+                handoff_from_execution_process_id as "handoff_from_execution_process_id: Uuid",
+                handoff_kind as "handoff_kind: HandoffKind",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM executor_sessions
@@ -93,6 +115,9 @@ impl ExecutorSession {
                 session_id, 
                 prompt,
                 summary,
+                handoff_from_executor,
+                handoff_from_execution_process_id as "handoff_from_execution_process_id: Uuid",
+                handoff_kind as "handoff_kind: HandoffKind",
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM executor_sessions 
@@ -117,6 +142,9 @@ impl ExecutorSession {
                 session_id,
                 prompt,
                 summary,
+                handoff_from_executor,
+                handoff_from_execution_process_id as "handoff_from_execution_process_id: Uuid",
+                handoff_kind as "handoff_kind: HandoffKind",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM executor_sessions
@@ -158,6 +186,9 @@ impl ExecutorSession {
                 session_id,
                 prompt,
                 summary,
+                handoff_from_executor,
+                handoff_from_execution_process_id as "handoff_from_execution_process_id: Uuid",
+                handoff_kind as "handoff_kind: HandoffKind",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>""#,
             session_id,
@@ -237,6 +268,32 @@ impl ExecutorSession {
         Ok(())
     }
 
+    /// Mark the session of `execution_process_id` as taking over from another executor
+    pub async fn record_handoff(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        from_execution_process_id: Uuid,
+        from_executor: &str,
+        kind: HandoffKind,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE executor_sessions
+               SET handoff_from_executor = $1, handoff_from_execution_process_id = $2,
+                   handoff_kind = $3, updated_at = $4
+               WHERE execution_process_id = $5"#,
+            from_executor,
+            from_execution_process_id,
+            kind,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Delete executor sessions for a task attempt (cleanup)
     pub async fn delete_by_task_attempt_id(
         pool: &SqlitePool,
@@ -152,6 +152,19 @@ impl Image {
         .await
     }
 
+    /// Projects whose tasks reference the image
+    pub async fn find_project_ids(pool: &SqlitePool, id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT t.project_id as "project_id!: Uuid"
+               FROM task_images ti
+               JOIN tasks t ON t.id = ti.task_id
+               WHERE ti.image_id = $1"#,
+            id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_by_task_id(
         pool: &SqlitePool,
         task_id: Uuid,
@@ -1,11 +1,23 @@
+pub mod api_token;
+pub mod audit_event;
 pub mod draft;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod executor_session;
 pub mod image;
 pub mod merge;
+pub mod organization;
 pub mod project;
+pub mod project_mcp_server;
+pub mod project_member;
+pub mod project_repository;
+pub mod project_secret;
 pub mod secret;
 pub mod tag;
 pub mod task;
 pub mod task_attempt;
+pub mod task_attempt_repository;
+pub mod user_identity;
+pub mod user_session;
+pub mod webhook;
+pub mod webhook_delivery;
@@ -0,0 +1,237 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Role of a member in an organization or project, from least to most privileged
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Type,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    TS,
+    EnumString,
+    Display,
+)]
+#[sqlx(type_name = "member_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum MemberRole {
+    Viewer,
+    Contributor,
+    Maintainer,
+    Owner,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: String,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct OrganizationMember {
+    pub organization_id: Uuid,
+    pub user_id: String,
+    pub role: MemberRole,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateOrganization {
+    pub name: String,
+}
+
+/// Body for adding a member or changing their role
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateMemberRole {
+    pub role: MemberRole,
+}
+
+impl Organization {
+    /// Create an organization with `user_id` as its first owner
+    pub async fn create(pool: &SqlitePool, name: &str, user_id: &str) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let role = MemberRole::Owner;
+        let mut tx = pool.begin().await?;
+        let organization = sqlx::query_as!(
+            Organization,
+            r#"INSERT INTO organizations (id, name, created_by)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         created_by,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3)",
+            id,
+            user_id,
+            role
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(organization)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Organization,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      created_by,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM organizations
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Organizations the user is a member of
+    pub async fn find_by_user(pool: &SqlitePool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Organization,
+            r#"SELECT o.id as "id!: Uuid",
+                      o.name,
+                      o.created_by,
+                      o.created_at as "created_at!: DateTime<Utc>",
+                      o.updated_at as "updated_at!: DateTime<Utc>"
+               FROM organizations o
+               JOIN organization_members m ON m.organization_id = o.id
+               WHERE m.user_id = $1
+               ORDER BY o.name ASC"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM organizations WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl OrganizationMember {
+    pub async fn find_by_organization_id(
+        pool: &SqlitePool,
+        organization_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            OrganizationMember,
+            r#"SELECT organization_id as "organization_id!: Uuid",
+                      user_id,
+                      role as "role!: MemberRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM organization_members
+               WHERE organization_id = $1
+               ORDER BY user_id ASC"#,
+            organization_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn role_for(
+        pool: &SqlitePool,
+        organization_id: Uuid,
+        user_id: &str,
+    ) -> Result<Option<MemberRole>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT role as "role!: MemberRole"
+               FROM organization_members
+               WHERE organization_id = $1 AND user_id = $2"#,
+            organization_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        organization_id: Uuid,
+        user_id: &str,
+        role: MemberRole,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            OrganizationMember,
+            r#"INSERT INTO organization_members (organization_id, user_id, role)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(organization_id, user_id)
+               DO UPDATE SET role = excluded.role, updated_at = datetime('now', 'subsec')
+               RETURNING organization_id as "organization_id!: Uuid",
+                         user_id,
+                         role as "role!: MemberRole",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            organization_id,
+            user_id,
+            role
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        organization_id: Uuid,
+        user_id: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+            organization_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn count_owners(
+        pool: &SqlitePool,
+        organization_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let role = MemberRole::Owner;
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM organization_members
+               WHERE organization_id = $1 AND role = $2"#,
+            organization_id,
+            role
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
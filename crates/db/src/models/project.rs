@@ -30,6 +30,9 @@ pub struct Project {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub init_submodules: bool,
+    pub sparse_checkout_patterns: Option<String>,
+    pub sparse_auto_expand: bool,
 
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -46,6 +49,14 @@ pub struct CreateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    /// Initialise submodules in attempt worktrees (defaults to off)
+    #[ts(optional)]
+    pub init_submodules: Option<bool>,
+    /// Comma-separated directories for a cone-mode sparse checkout
+    #[ts(optional)]
+    pub sparse_checkout_patterns: Option<String>,
+    #[ts(optional)]
+    pub sparse_auto_expand: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -56,6 +67,14 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    /// Leaves the current setting unchanged when omitted
+    #[ts(optional)]
+    pub init_submodules: Option<bool>,
+    /// Leaves the current patterns unchanged when omitted; an empty string clears them
+    #[ts(optional)]
+    pub sparse_checkout_patterns: Option<String>,
+    #[ts(optional)]
+    pub sparse_auto_expand: Option<bool>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -84,7 +103,7 @@ impl Project {
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules as "init_submodules!: bool", sparse_checkout_patterns, sparse_auto_expand as "sparse_auto_expand!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
         )
         .fetch_all(pool)
         .await
@@ -104,6 +123,9 @@ impl Project {
                 dev_script,
                 cleanup_script,
                 copy_files,
+                init_submodules as "init_submodules!: bool",
+                sparse_checkout_patterns,
+                sparse_auto_expand as "sparse_auto_expand!: bool",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
             FROM projects
@@ -116,12 +138,84 @@ impl Project {
         .await
     }
 
+    /// Projects the user created, was added to, or can see through an organization
+    pub async fn find_accessible_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Project,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name,
+                git_repo_path,
+                setup_script,
+                dev_script,
+                cleanup_script,
+                copy_files,
+                init_submodules as "init_submodules!: bool",
+                sparse_checkout_patterns,
+                sparse_auto_expand as "sparse_auto_expand!: bool",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM projects
+            WHERE user_id = $1
+               OR id IN (SELECT project_id FROM project_members WHERE user_id = $1)
+               OR organization_id IN (
+                   SELECT organization_id FROM organization_members WHERE user_id = $1
+               )
+            ORDER BY updated_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// User who created the project; they are always its owner
+    pub async fn owner_id(pool: &SqlitePool, id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        let owner_id = sqlx::query_scalar!(
+            r#"SELECT user_id as "user_id?: String" FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(owner_id.flatten())
+    }
+
+    /// Organization the project is shared with, if any
+    pub async fn organization_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        let organization_id = sqlx::query_scalar!(
+            r#"SELECT organization_id as "organization_id?: Uuid" FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(organization_id.flatten())
+    }
+
+    pub async fn set_organization_id(
+        pool: &SqlitePool,
+        id: Uuid,
+        organization_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET organization_id = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            organization_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Find the most actively used projects based on recent task activity
     pub async fn find_most_active(pool: &SqlitePool, limit: i32) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
             r#"
-            SELECT p.id as "id!: Uuid", p.name, p.git_repo_path, p.setup_script, p.dev_script, p.cleanup_script, p.copy_files, 
+            SELECT p.id as "id!: Uuid", p.name, p.git_repo_path, p.setup_script, p.dev_script, p.cleanup_script, p.copy_files, p.init_submodules as "init_submodules!: bool", p.sparse_checkout_patterns, p.sparse_auto_expand as "sparse_auto_expand!: bool",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -143,7 +237,7 @@ impl Project {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules as "init_submodules!: bool", sparse_checkout_patterns, sparse_auto_expand as "sparse_auto_expand!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
             id
         )
         .fetch_optional(pool)
@@ -168,6 +262,9 @@ impl Project {
                 dev_script,
                 cleanup_script,
                 copy_files,
+                init_submodules as "init_submodules!: bool",
+                sparse_checkout_patterns,
+                sparse_auto_expand as "sparse_auto_expand!: bool",
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
             FROM projects
@@ -186,7 +283,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules as "init_submodules!: bool", sparse_checkout_patterns, sparse_auto_expand as "sparse_auto_expand!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
             git_repo_path
         )
         .fetch_optional(pool)
@@ -200,7 +297,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules as "init_submodules!: bool", sparse_checkout_patterns, sparse_auto_expand as "sparse_auto_expand!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
             git_repo_path,
             exclude_id
         )
@@ -218,9 +315,11 @@ impl Project {
     where
         E: sqlx::Executor<'e, Database = Sqlite>,
     {
+        let init_submodules = data.init_submodules.unwrap_or(false);
+        let sparse_auto_expand = data.sparse_auto_expand.unwrap_or(false);
         sqlx::query_as!(
             Project,
-            r#"INSERT INTO projects (id, user_id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO projects (id, user_id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules, sparse_checkout_patterns, sparse_auto_expand) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules as "init_submodules!: bool", sparse_checkout_patterns, sparse_auto_expand as "sparse_auto_expand!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             user_id,  // ✅ Added
             data.name,
@@ -228,7 +327,10 @@ impl Project {
             data.setup_script,
             data.dev_script,
             data.cleanup_script,
-            data.copy_files
+            data.copy_files,
+            init_submodules,
+            data.sparse_checkout_patterns,
+            sparse_auto_expand
         )
         .fetch_one(executor)
         .await
@@ -244,17 +346,23 @@ impl Project {
         dev_script: Option<String>,
         cleanup_script: Option<String>,
         copy_files: Option<String>,
+        init_submodules: bool,
+        sparse_checkout_patterns: Option<String>,
+        sparse_auto_expand: bool,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7, init_submodules = $8, sparse_checkout_patterns = $9, sparse_auto_expand = $10 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, init_submodules as "init_submodules!: bool", sparse_checkout_patterns, sparse_auto_expand as "sparse_auto_expand!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             git_repo_path,
             setup_script,
             dev_script,
             cleanup_script,
-            copy_files
+            copy_files,
+            init_submodules,
+            sparse_checkout_patterns,
+            sparse_auto_expand
         )
         .fetch_one(pool)
         .await
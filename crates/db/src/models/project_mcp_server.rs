@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use utils::secret_mask::SECRET_MASK;
+use uuid::Uuid;
+
+/// Definition fields whose values commonly hold credentials
+const CREDENTIAL_FIELDS: [&str; 2] = ["env", "headers"];
+
+/// An MCP server that agents only see when running attempts for one project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectMcpServer {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Server definition in the same format as the global MCP settings
+    #[ts(type = "JsonValue")]
+    pub config: Json<Value>,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectMcpServers {
+    #[ts(type = "{ [key in string]?: JsonValue }")]
+    pub servers: HashMap<String, Value>,
+}
+
+impl ProjectMcpServer {
+    /// The server with `env` and `headers` values replaced by [`SECRET_MASK`], for members
+    /// who can't change the definition
+    pub fn masked(mut self) -> Self {
+        for field in CREDENTIAL_FIELDS {
+            if let Some(Value::Object(values)) = self.config.0.get_mut(field) {
+                for value in values.values_mut() {
+                    *value = Value::String(SECRET_MASK.to_string());
+                }
+            }
+        }
+        self
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMcpServer,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      config as "config!: Json<Value>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_mcp_servers
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Servers keyed by name, ready to merge over an agent's global config
+    pub async fn servers_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Map<String, Value>, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .map(|server| (server.name, server.config.0))
+            .collect())
+    }
+
+    /// Replace every server of the project with `servers`
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        servers: &HashMap<String, Value>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM project_mcp_servers WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (name, config) in servers {
+            let id = Uuid::new_v4();
+            let config = Json(config);
+            sqlx::query!(
+                r#"INSERT INTO project_mcp_servers (id, project_id, name, config)
+                   VALUES ($1, $2, $3, $4)"#,
+                id,
+                project_id,
+                name,
+                config
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_project_id(pool, project_id).await
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::organization::MemberRole;
+
+/// A user granted a role on one project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectMember {
+    pub project_id: Uuid,
+    pub user_id: String,
+    pub role: MemberRole,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectMember {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMember,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      user_id,
+                      role as "role!: MemberRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_members
+               WHERE project_id = $1
+               ORDER BY user_id ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: &str,
+        role: MemberRole,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMember,
+            r#"INSERT INTO project_members (project_id, user_id, role)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(project_id, user_id)
+               DO UPDATE SET role = excluded.role, updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         user_id,
+                         role as "role!: MemberRole",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            user_id,
+            role
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_members WHERE project_id = $1 AND user_id = $2",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// The user's role on a project: owner for its creator, otherwise the higher of their
+    /// project grant and their role in the project's organization. None means no access.
+    pub async fn effective_role(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user_id: &str,
+    ) -> Result<Option<MemberRole>, sqlx::Error> {
+        let Some(row) = sqlx::query!(
+            r#"SELECT p.user_id as "owner_id?: String",
+                      pm.role as "project_role?: MemberRole",
+                      om.role as "organization_role?: MemberRole"
+               FROM projects p
+               LEFT JOIN project_members pm
+                   ON pm.project_id = p.id AND pm.user_id = $2
+               LEFT JOIN organization_members om
+                   ON om.organization_id = p.organization_id AND om.user_id = $2
+               WHERE p.id = $1"#,
+            project_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        if row.owner_id.as_deref() == Some(user_id) {
+            return Ok(Some(MemberRole::Owner));
+        }
+        Ok(row.project_role.max(row.organization_role))
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Metadata for a named project secret; the value itself is encrypted in the secret store
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectSecret {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Environment variable name the value is exposed as
+    pub name: String,
+    pub expose_to_scripts: bool,
+    pub expose_to_agents: bool,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProjectSecret {
+    /// New value; omit to keep the stored value and only change where it is exposed
+    pub value: Option<String>,
+    pub expose_to_scripts: bool,
+    pub expose_to_agents: bool,
+}
+
+impl ProjectSecret {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectSecret,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      expose_to_scripts as "expose_to_scripts!: bool",
+                      expose_to_agents as "expose_to_agents!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_secrets
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectSecret,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      expose_to_scripts as "expose_to_scripts!: bool",
+                      expose_to_agents as "expose_to_agents!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_secrets
+               WHERE project_id = $1 AND name = $2"#,
+            project_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+        expose_to_scripts: bool,
+        expose_to_agents: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ProjectSecret,
+            r#"INSERT INTO project_secrets (id, project_id, name, expose_to_scripts, expose_to_agents)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(project_id, name)
+               DO UPDATE SET
+                   expose_to_scripts = excluded.expose_to_scripts,
+                   expose_to_agents = excluded.expose_to_agents,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         expose_to_scripts as "expose_to_scripts!: bool",
+                         expose_to_agents as "expose_to_agents!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            name,
+            expose_to_scripts,
+            expose_to_agents
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_secrets WHERE project_id = $1 AND name = $2",
+            project_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
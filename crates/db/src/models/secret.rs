@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, Sqlite, SqlitePool};
 use uuid::Uuid;
 
 #[derive(Debug, FromRow, Clone)]
@@ -65,6 +65,21 @@ impl SecretRecord {
         Ok(())
     }
 
+    pub async fn delete_by_provider(
+        pool: &SqlitePool,
+        user_id: &str,
+        provider: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM secrets WHERE user_id = $1 AND provider = $2"#,
+            user_id,
+            provider
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn get(
         pool: &SqlitePool,
         user_id: &str,
@@ -93,4 +108,70 @@ impl SecretRecord {
         .fetch_optional(pool)
         .await
     }
+
+    /// Secrets encrypted with any key other than `key_version`
+    pub async fn find_not_at_key_version<'e, E>(
+        executor: E,
+        key_version: i64,
+    ) -> Result<Vec<Self>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as!(
+            SecretRecord,
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                user_id,
+                provider,
+                name,
+                key_version,
+                secret_blob,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+            FROM secrets
+            WHERE key_version != $1
+            "#,
+            key_version
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Replace the encrypted value without touching `updated_at`, which tracks value changes
+    pub async fn update_blob<'e, E>(
+        executor: E,
+        id: Uuid,
+        secret_blob: &[u8],
+        key_version: i64,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query!(
+            r#"UPDATE secrets SET secret_blob = $2, key_version = $3 WHERE id = $1"#,
+            id,
+            secret_blob,
+            key_version
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Number of stored secrets per key version
+    pub async fn count_by_key_version(pool: &SqlitePool) -> Result<Vec<(i64, i64)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT key_version, COUNT(*) as "count!: i64"
+               FROM secrets
+               GROUP BY key_version
+               ORDER BY key_version ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.key_version, row.count))
+            .collect())
+    }
 }
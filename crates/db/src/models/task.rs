@@ -226,6 +226,17 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Current status of every task
+    pub async fn find_all_statuses(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(Uuid, TaskStatus)>, sqlx::Error> {
+        let records =
+            sqlx::query!(r#"SELECT id as "id!: Uuid", status as "status!: TaskStatus" FROM tasks"#)
+                .fetch_all(pool)
+                .await?;
+        Ok(records.into_iter().map(|r| (r.id, r.status)).collect())
+    }
+
     /// Find tasks for a specific user (multi-user support)
     pub async fn find_by_user(pool: &SqlitePool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
@@ -447,6 +447,13 @@ impl TaskAttempt {
         .await?)
     }
 
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_attempts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn update_target_branch(
         pool: &SqlitePool,
         attempt_id: Uuid,
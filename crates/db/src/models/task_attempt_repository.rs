@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Worktree of an additional project repository for a single task attempt.
+/// The attempt's own `container_ref` still points at the primary repository.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAttemptRepository {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub project_repository_id: Uuid,
+    pub worktree_path: String,
+    pub target_branch: String,
+    pub merge_commit: Option<String>,
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TaskAttemptRepository {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptRepository,
+            r#"SELECT id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      project_repository_id as "project_repository_id!: Uuid",
+                      worktree_path,
+                      target_branch,
+                      merge_commit,
+                      pr_number,
+                      pr_url,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_attempt_repositories
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_repository(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        project_repository_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptRepository,
+            r#"SELECT id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      project_repository_id as "project_repository_id!: Uuid",
+                      worktree_path,
+                      target_branch,
+                      merge_commit,
+                      pr_number,
+                      pr_url,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_attempt_repositories
+               WHERE task_attempt_id = $1 AND project_repository_id = $2"#,
+            task_attempt_id,
+            project_repository_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record (or refresh) the worktree for a repository of an attempt
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        project_repository_id: Uuid,
+        worktree_path: &str,
+        target_branch: &str,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO task_attempt_repositories
+                   (id, task_attempt_id, project_repository_id, worktree_path, target_branch)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(task_attempt_id, project_repository_id)
+               DO UPDATE SET
+                   worktree_path = excluded.worktree_path,
+                   updated_at = datetime('now', 'subsec')"#,
+            id,
+            task_attempt_id,
+            project_repository_id,
+            worktree_path,
+            target_branch
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_merge_commit(
+        pool: &SqlitePool,
+        id: Uuid,
+        merge_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempt_repositories SET merge_commit = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            merge_commit,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_pr(
+        pool: &SqlitePool,
+        id: Uuid,
+        pr_number: i64,
+        pr_url: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempt_repositories SET pr_number = $1, pr_url = $2, updated_at = datetime('now', 'subsec') WHERE id = $3",
+            pr_number,
+            pr_url,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An account at an identity provider linked to a user
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct UserIdentity {
+    pub id: Uuid,
+    pub user_id: String,
+    /// `oidc`, `github`, ...
+    pub provider: String,
+    /// Stable id of the account at the provider
+    pub subject: String,
+    pub email: Option<String>,
+    pub display_name: String,
+
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserIdentity {
+    pub async fn find_by_subject(
+        pool: &SqlitePool,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            UserIdentity,
+            r#"SELECT id as "id!: Uuid",
+                      user_id,
+                      provider,
+                      subject,
+                      email,
+                      display_name,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM user_identities
+               WHERE provider = $1 AND subject = $2"#,
+            provider,
+            subject
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_user(pool: &SqlitePool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            UserIdentity,
+            r#"SELECT id as "id!: Uuid",
+                      user_id,
+                      provider,
+                      subject,
+                      email,
+                      display_name,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM user_identities
+               WHERE user_id = $1
+               ORDER BY created_at ASC"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Link a provider account to `user_id`, moving it if it was linked to someone else
+    pub async fn link(
+        pool: &SqlitePool,
+        user_id: &str,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+        display_name: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            UserIdentity,
+            r#"INSERT INTO user_identities (id, user_id, provider, subject, email, display_name)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(provider, subject) DO UPDATE SET
+                   user_id = excluded.user_id,
+                   email = excluded.email,
+                   display_name = excluded.display_name,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         user_id,
+                         provider,
+                         subject,
+                         email,
+                         display_name,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            user_id,
+            provider,
+            subject,
+            email,
+            display_name
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn unlink(
+        pool: &SqlitePool,
+        user_id: &str,
+        provider: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM user_identities WHERE user_id = $1 AND provider = $2",
+            user_id,
+            provider
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// A browser session created by identity provider sign-in
+#[derive(Debug, Clone, FromRow)]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: String,
+    pub display_name: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UserSession {
+    pub async fn create(
+        pool: &SqlitePool,
+        user_id: &str,
+        display_name: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            UserSession,
+            r#"INSERT INTO user_sessions (id, user_id, display_name, token_hash, expires_at)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         user_id,
+                         display_name,
+                         expires_at as "expires_at!: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            user_id,
+            display_name,
+            token_hash,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The unexpired session with this token hash
+    pub async fn find_by_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let session = sqlx::query_as!(
+            UserSession,
+            r#"SELECT id as "id!: Uuid",
+                      user_id,
+                      display_name,
+                      expires_at as "expires_at!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM user_sessions
+               WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(session.filter(|session| session.expires_at > Utc::now()))
+    }
+
+    pub async fn delete_by_hash(pool: &SqlitePool, token_hash: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM user_sessions WHERE token_hash = $1",
+            token_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_expired(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!("DELETE FROM user_sessions WHERE expires_at <= $1", now)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Lifecycle events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT")]
+pub enum WebhookEventType {
+    #[serde(rename = "task.created")]
+    #[sqlx(rename = "task.created")]
+    TaskCreated,
+    #[serde(rename = "task.status_changed")]
+    #[sqlx(rename = "task.status_changed")]
+    TaskStatusChanged,
+    /// A coding agent run started, for a new attempt or a follow-up
+    #[serde(rename = "attempt.started")]
+    #[sqlx(rename = "attempt.started")]
+    AttemptStarted,
+    /// A coding agent run completed
+    #[serde(rename = "attempt.finished")]
+    #[sqlx(rename = "attempt.finished")]
+    AttemptFinished,
+    /// A setup script or coding agent run failed
+    #[serde(rename = "attempt.failed")]
+    #[sqlx(rename = "attempt.failed")]
+    AttemptFailed,
+    #[serde(rename = "pr.opened")]
+    #[sqlx(rename = "pr.opened")]
+    PrOpened,
+    #[serde(rename = "pr.merged")]
+    #[sqlx(rename = "pr.merged")]
+    PrMerged,
+    /// An agent is waiting for a tool call to be approved
+    #[serde(rename = "approval.requested")]
+    #[sqlx(rename = "approval.requested")]
+    ApprovalRequested,
+}
+
+/// An outbound webhook of a project. Its signing secret is kept in the secret store.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub url: String,
+    #[ts(type = "Array<WebhookEventType>")]
+    pub events: Json<Vec<WebhookEventType>>,
+    pub enabled: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEventType>,
+    /// Signing secret; one is generated when omitted
+    pub secret: Option<String>,
+}
+
+/// Changes to a webhook; unset fields are left as they are
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEventType>>,
+    pub enabled: Option<bool>,
+    pub secret: Option<String>,
+}
+
+impl Webhook {
+    pub fn is_subscribed(&self, event: WebhookEventType) -> bool {
+        self.enabled && self.events.contains(&event)
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      url,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      url,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        project_id: Uuid,
+        url: &str,
+        events: &[WebhookEventType],
+    ) -> Result<Self, sqlx::Error> {
+        let events = Json(events);
+        sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, project_id, url, events)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         url,
+                         events as "events!: Json<Vec<WebhookEventType>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            url,
+            events
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        url: &str,
+        events: &[WebhookEventType],
+        enabled: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let events = Json(events);
+        sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET url = $2, events = $3, enabled = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         url,
+                         events as "events!: Json<Vec<WebhookEventType>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            url,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
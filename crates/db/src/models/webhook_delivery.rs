@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::webhook::WebhookEventType;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Succeeded,
+    /// Gave up after the last retry
+    Failed,
+}
+
+/// One event sent to a webhook, with the outcome of its latest attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEventType,
+    /// The JSON body sent to the webhook
+    #[ts(type = "unknown")]
+    pub payload: Json<Value>,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    /// Start of the response body, for debugging receivers
+    pub response_body: Option<String>,
+    /// Why the latest attempt failed, if it did
+    pub error: Option<String>,
+    #[ts(type = "Date | null")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of one delivery attempt
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    pub status: WebhookDeliveryStatus,
+    pub response_status: Option<i64>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    /// When to retry; None once the delivery succeeded or failed for good
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    /// Queue an event for a webhook, due right away
+    pub async fn create(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        event: WebhookEventType,
+        payload: &Value,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let payload = Json(payload);
+        let now = Utc::now();
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event, payload, next_attempt_at)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         webhook_id as "webhook_id!: Uuid",
+                         event as "event!: WebhookEventType",
+                         payload as "payload!: Json<Value>",
+                         status as "status!: WebhookDeliveryStatus",
+                         attempts as "attempts!: i64",
+                         response_status,
+                         response_body,
+                         error,
+                         next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event,
+            payload,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event as "event!: WebhookEventType",
+                      payload as "payload!: Json<Value>",
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      response_status,
+                      response_body,
+                      error,
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// A webhook's most recent deliveries, newest first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event as "event!: WebhookEventType",
+                      payload as "payload!: Json<Value>",
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      response_status,
+                      response_body,
+                      error,
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first
+    pub async fn find_due(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event as "event!: WebhookEventType",
+                      payload as "payload!: Json<Value>",
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      response_status,
+                      response_body,
+                      error,
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE status = 'pending' AND next_attempt_at <= $1
+               ORDER BY next_attempt_at ASC
+               LIMIT $2"#,
+            now,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn record_attempt(
+        pool: &SqlitePool,
+        id: Uuid,
+        attempt: &DeliveryAttempt,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = $2,
+                   attempts = attempts + 1,
+                   response_status = $3,
+                   response_body = $4,
+                   error = $5,
+                   next_attempt_at = $6,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            attempt.status,
+            attempt.response_status,
+            attempt.response_body,
+            attempt.error,
+            attempt.next_attempt_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -28,10 +28,12 @@ use services::services::{
     file_search_cache::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
+    forge::ForgeResolver,
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
     pr_monitor::PrMonitorService,
     secret_store::{SECRET_GITHUB_OAUTH, SECRET_GITHUB_PAT, SecretStore, SecretStoreError},
+    webhooks::WebhookService,
     worktree_manager::WorktreeError,
 };
 use sqlx::{Error as SqlxError, types::Uuid};
@@ -134,6 +136,14 @@ pub trait Deployment: Clone + Send + Sync + 'static {
     /// GitHub user cache for reducing GitHub API calls
     fn github_user_cache(&self) -> &services::services::github_user_cache::GitHubUserCache;
 
+    fn executor_health(&self) -> &services::services::executor_health::ExecutorHealthService;
+
+    /// Redirect-flow identity providers (OIDC) configured for sign-in
+    fn identity_providers(&self) -> &services::services::identity::IdentityProviders;
+
+    /// Outbound webhooks of projects, for emitting events that don't come from the event stream
+    fn webhooks(&self) -> &WebhookService;
+
     async fn github_token(&self) -> Result<Option<String>, SecretStoreError> {
         if let Some(pat) = self
             .secret_store()
@@ -152,6 +162,11 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         Ok(None)
     }
 
+    /// Resolves git remotes to GitHub, GitLab or Gitea and their stored tokens
+    fn forge_resolver(&self) -> ForgeResolver {
+        ForgeResolver::new(self.secret_store().clone(), self.user_id())
+    }
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -173,7 +188,13 @@ pub trait Deployment: Clone + Send + Sync + 'static {
             });
         let secret_store = self.secret_store().clone();
         let user_id = self.user_id().to_string();
-        PrMonitorService::spawn(db, analytics, secret_store, user_id).await
+        let webhooks = self.webhooks().clone();
+        PrMonitorService::spawn(db, analytics, secret_store, user_id, webhooks).await
+    }
+
+    fn spawn_webhook_service(&self) -> tokio::task::JoinHandle<()> {
+        self.webhooks()
+            .spawn(self.events().msg_store().clone(), self.approvals())
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -327,6 +348,9 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                         dev_script: None,
                         cleanup_script: None,
                         copy_files: None,
+                        init_submodules: None,
+                        sparse_checkout_patterns: None,
+                        sparse_auto_expand: None,
                     };
                     // Ensure existing repo has a main branch if it's empty
                     if let Err(e) = self.git().ensure_main_branch_exists(&repo.path) {
@@ -372,12 +396,14 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         }
     }
 
+    /// The event store's history then live events, resumed after `since` when given
     async fn stream_events(
         &self,
+        since: Option<u64>,
     ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
         self.events()
             .msg_store()
-            .history_plus_stream()
+            .resumable_stream(since)
             .map_ok(|m| m.to_sse_event())
             .boxed()
     }
@@ -7,12 +7,17 @@ use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use thiserror::Error;
 use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{
+    executors::ExecutorError,
+    profile::ExecutorConfigs,
+    tool_cache::{NpmPackage, ToolCache},
+};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -62,6 +67,25 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<Vec<String>>,
+    #[schemars(
+        title = "Version Pin",
+        description = "Run this version of the agent's npm package instead of the built-in one"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl CmdOverrides {
+    /// Base command after applying the override and version pin
+    pub fn resolve_base(&self, base: &str) -> String {
+        if let Some(ref base) = self.base_command_override {
+            return base.clone();
+        }
+        self.version
+            .as_deref()
+            .and_then(|version| NpmPackage::pin_npx_base(base, version))
+            .unwrap_or_else(|| base.to_string())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -153,11 +177,8 @@ fn split_command_line(input: &str) -> Result<Vec<String>, CommandBuildError> {
 }
 
 pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> CommandBuilder {
-    let builder = if let Some(ref base) = overrides.base_command_override {
-        builder.override_base(base.clone())
-    } else {
-        builder
-    };
+    let base = overrides.resolve_base(&builder.base);
+    let builder = builder.override_base(base);
     if let Some(ref extra) = overrides.additional_params {
         builder.extend_params(extra.clone())
     } else {
@@ -280,6 +301,11 @@ impl ExecutionCommand {
 #[async_trait]
 pub trait CommandRuntime: Send + Sync {
     async fn spawn(&self, command: ExecutionCommand) -> Result<AsyncGroupChild, ExecutorError>;
+
+    /// Extra MCP servers (`mcpServers` format) for agents that take them when creating a session
+    fn session_mcp_servers(&self) -> Option<&Map<String, Value>> {
+        None
+    }
 }
 
 pub struct HostCommandRuntime;
@@ -287,14 +313,29 @@ pub struct HostCommandRuntime;
 #[async_trait]
 impl CommandRuntime for HostCommandRuntime {
     async fn spawn(&self, command: ExecutionCommand) -> Result<AsyncGroupChild, ExecutorError> {
-        let executable = resolve_executable_path(command.program())
-            .await
-            .ok_or_else(|| ExecutorError::ExecutableNotFound {
-                program: command.program().to_string(),
-            })?;
+        // Prefer a pinned install from the tool cache over fetching the package through npx
+        let (executable, args) =
+            match ToolCache::global().resolve(command.program(), command.args_slice()) {
+                Some(cached) => cached,
+                None => {
+                    if !ExecutorConfigs::get_cached().tools.allow_npx_fallback
+                        && NpmPackage::is_npx(command.program())
+                    {
+                        return Err(ExecutorError::ToolNotCached {
+                            package: NpmPackage::npx_spec(command.args_slice()),
+                        });
+                    }
+                    let executable = resolve_executable_path(command.program())
+                        .await
+                        .ok_or_else(|| ExecutorError::ExecutableNotFound {
+                            program: command.program().to_string(),
+                        })?;
+                    (executable, command.args_slice().to_vec())
+                }
+            };
 
         let mut process = Command::new(executable);
-        process.args(command.args_slice());
+        process.args(args);
         process.current_dir(command.current_dir_path());
         apply_stdio(&mut process, command.stdin_config(), IoStream::Stdin);
         apply_stdio(&mut process, command.stdout_config(), IoStream::Stdout);
@@ -313,6 +354,59 @@ impl CommandRuntime for HostCommandRuntime {
     }
 }
 
+/// Runtime that adds arguments and environment variables to every command it spawns
+pub struct ExtendedCommandRuntime<'a> {
+    inner: &'a dyn CommandRuntime,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    mcp_servers: Option<Map<String, Value>>,
+}
+
+impl<'a> ExtendedCommandRuntime<'a> {
+    pub fn new(inner: &'a dyn CommandRuntime) -> Self {
+        Self {
+            inner,
+            args: Vec::new(),
+            env: Vec::new(),
+            mcp_servers: None,
+        }
+    }
+
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Self {
+        self.args.extend(args);
+        self
+    }
+
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_session_mcp_servers(mut self, servers: Map<String, Value>) -> Self {
+        self.mcp_servers.get_or_insert_default().extend(servers);
+        self
+    }
+}
+
+#[async_trait]
+impl CommandRuntime for ExtendedCommandRuntime<'_> {
+    async fn spawn(&self, mut command: ExecutionCommand) -> Result<AsyncGroupChild, ExecutorError> {
+        let mut args = command.args_slice().to_vec();
+        args.extend(self.args.iter().cloned());
+        command.args(&args);
+        for (key, value) in &self.env {
+            command.env(key, value);
+        }
+        self.inner.spawn(command).await
+    }
+
+    fn session_mcp_servers(&self) -> Option<&Map<String, Value>> {
+        self.mcp_servers
+            .as_ref()
+            .or_else(|| self.inner.session_mcp_servers())
+    }
+}
+
 #[derive(Clone, Copy)]
 enum IoStream {
     Stdin,
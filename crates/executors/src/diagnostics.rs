@@ -0,0 +1,54 @@
+//! What to check when diagnosing whether an executor can run
+
+use std::path::PathBuf;
+
+use crate::command::{CmdOverrides, CommandBuilder};
+
+/// A place an executor's credentials can come from
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthSource {
+    /// Environment variable holding an API key or token
+    EnvVar(String),
+    /// Credentials file written by the agent's own login flow
+    File(PathBuf),
+    /// Credentials the app stores for this provider (e.g. "claude")
+    StoredSecret(&'static str),
+}
+
+impl AuthSource {
+    /// File under the user's home directory
+    pub fn home_file(relative: &str) -> Option<Self> {
+        dirs::home_dir().map(|home| Self::File(home.join(relative)))
+    }
+
+    pub fn env(name: impl Into<String>) -> Self {
+        Self::EnvVar(name.into())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticProbe {
+    /// Command used to launch the agent, without per-run arguments
+    pub command: Option<CommandBuilder>,
+    /// Arguments that make `command` print its version
+    pub version_args: Option<Vec<String>>,
+    /// Any one present source counts as signed in; empty when no credentials are needed
+    pub auth_sources: Vec<AuthSource>,
+}
+
+impl DiagnosticProbe {
+    /// Probe for a CLI that prints its version with `--version`, honouring the base command
+    /// override and version pin
+    pub fn cli(base: &str, cmd: &CmdOverrides) -> Self {
+        Self {
+            command: Some(CommandBuilder::new(cmd.resolve_base(base))),
+            version_args: Some(vec!["--version".to_string()]),
+            auth_sources: Vec::new(),
+        }
+    }
+
+    pub fn with_auth(mut self, sources: impl IntoIterator<Item = Option<AuthSource>>) -> Self {
+        self.auth_sources.extend(sources.into_iter().flatten());
+        self
+    }
+}
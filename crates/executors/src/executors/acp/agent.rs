@@ -0,0 +1,261 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use agent_client_protocol as proto;
+use async_trait::async_trait;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, path::expand_tilde, shell::resolve_executable_path};
+
+use super::AcpAgentHarness;
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuilder, CommandRuntime, apply_overrides},
+    diagnostics::DiagnosticProbe,
+    executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+};
+
+/// Any agent speaking the Agent Client Protocol over stdio
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct Acp {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts the agent in ACP mode, e.g. `npx -y @zed-industries/claude-code-acp`"
+    )]
+    pub command: String,
+    #[schemars(
+        title = "Session Namespace",
+        description = "Directory name under which session history is stored, `acp_sessions` by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_namespace: Option<String>,
+    #[schemars(
+        title = "MCP Config Path",
+        description = "JSON file with an `mcpServers` map; its servers are passed to the agent when a session is created"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config_path: Option<String>,
+    #[schemars(
+        title = "Auto Approve",
+        description = "Grant permission requests without asking"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_approve: Option<bool>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl Acp {
+    fn build_command_builder(&self) -> CommandBuilder {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    fn harness(&self, runtime: &dyn CommandRuntime) -> AcpAgentHarness {
+        let namespace = self
+            .session_namespace
+            .clone()
+            .unwrap_or_else(|| "acp_sessions".to_string());
+        // Servers given for this run (e.g. the project's) take precedence over the config file
+        let mut servers = self.configured_mcp_servers();
+        if let Some(run_servers) = runtime.session_mcp_servers() {
+            servers.extend(run_servers.clone());
+        }
+        let mut harness = AcpAgentHarness::with_session_namespace(namespace)
+            .with_mcp_servers(to_acp_mcp_servers(&servers));
+        if !self.auto_approve.unwrap_or(false)
+            && let Some(approvals) = &self.approvals
+        {
+            harness = harness.with_approvals(approvals.clone());
+        }
+        harness
+    }
+
+    /// Servers from the MCP config file
+    fn configured_mcp_servers(&self) -> Map<String, Value> {
+        let Some(path) = self.default_mcp_config_path() else {
+            return Map::new();
+        };
+        let config = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str::<Value>(&content),
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to read MCP config {}: {err}", path.display());
+                }
+                return Map::new();
+            }
+        };
+        match config {
+            Ok(config) => config
+                .get("mcpServers")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+            Err(err) => {
+                tracing::warn!("Invalid MCP config {}: {err}", path.display());
+                Map::new()
+            }
+        }
+    }
+}
+
+/// Servers of an `mcpServers` map in ACP's session format
+fn to_acp_mcp_servers(servers: &Map<String, Value>) -> Vec<proto::McpServer> {
+    servers
+        .iter()
+        .filter_map(|(name, server)| {
+            let converted = to_acp_mcp_server(name, server)?;
+            match serde_json::from_value::<proto::McpServer>(converted) {
+                Ok(server) => Some(server),
+                Err(err) => {
+                    tracing::warn!("Skipping MCP server `{name}`: {err}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Convert an `mcpServers` entry to ACP's wire format, where env and headers are
+/// name/value lists and remote servers are tagged by transport
+fn to_acp_mcp_server(name: &str, server: &Value) -> Option<Value> {
+    let name_values = |key: &str| -> Vec<Value> {
+        server
+            .get(key)
+            .and_then(Value::as_object)
+            .map(|map| {
+                map.iter()
+                    .map(|(k, v)| json!({ "name": k, "value": v.as_str().unwrap_or_default() }))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    if let Some(command) = server.get("command").and_then(Value::as_str) {
+        return Some(json!({
+            "name": name,
+            "command": command,
+            "args": server.get("args").cloned().unwrap_or_else(|| json!([])),
+            "env": name_values("env"),
+        }));
+    }
+
+    let url = server.get("url").and_then(Value::as_str)?;
+    let transport = match server.get("type").and_then(Value::as_str) {
+        Some("sse") => "sse",
+        _ => "http",
+    };
+    Some(json!({
+        "type": transport,
+        "name": name,
+        "url": url,
+        "headers": name_values("headers"),
+    }))
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Acp {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command = self.build_command_builder().build_initial()?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness(runtime)
+            .spawn_with_command(current_dir, combined_prompt, command, runtime)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command = self.build_command_builder().build_follow_up(&[])?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        self.harness(runtime)
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                command,
+                runtime,
+            )
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        super::normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        self.mcp_config_path.as_deref().map(expand_tilde)
+    }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe {
+            command: Some(self.build_command_builder()),
+            ..Default::default()
+        }
+    }
+
+    async fn check_availability(&self) -> bool {
+        match self.build_command_builder().build_initial() {
+            Ok(parts) => resolve_executable_path(parts.program()).await.is_some(),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_mcp_servers_to_acp_format() {
+        let stdio = to_acp_mcp_server(
+            "files",
+            &json!({ "command": "npx", "args": ["-y", "server"], "env": { "TOKEN": "x" } }),
+        )
+        .unwrap();
+        assert_eq!(
+            stdio,
+            json!({
+                "name": "files",
+                "command": "npx",
+                "args": ["-y", "server"],
+                "env": [{ "name": "TOKEN", "value": "x" }],
+            })
+        );
+
+        let sse = to_acp_mcp_server(
+            "remote",
+            &json!({ "type": "sse", "url": "https://example.com/sse" }),
+        )
+        .unwrap();
+        assert_eq!(sse["type"], "sse");
+        assert_eq!(sse["headers"], json!([]));
+
+        assert!(to_acp_mcp_server("broken", &json!({ "args": [] })).is_none());
+    }
+}
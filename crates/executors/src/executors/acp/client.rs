@@ -1,25 +1,112 @@
+use std::{sync::Arc, time::Duration};
+
 use agent_client_protocol as acp;
 use async_trait::async_trait;
-use tokio::sync::mpsc;
+use tokio::{runtime::Handle, sync::mpsc};
 use tracing::{debug, warn};
+use workspace_utils::approvals::ApprovalStatus;
 
-use crate::executors::acp::AcpEvent;
+use crate::{approvals::ExecutorApprovalService, executors::acp::AcpEvent};
 
 /// ACP client that handles agent-client protocol communication
 pub struct AcpClient {
     event_tx: mpsc::UnboundedSender<AcpEvent>,
+    /// Approval service and the runtime it lives on; permission requests are
+    /// auto-approved when unset
+    approvals: Option<(Arc<dyn ExecutorApprovalService>, Handle)>,
 }
 
 impl AcpClient {
     /// Create a new ACP client
     pub fn new(event_tx: mpsc::UnboundedSender<AcpEvent>) -> Self {
-        Self { event_tx }
+        Self {
+            event_tx,
+            approvals: None,
+        }
+    }
+
+    /// Route permission requests through `approvals`, which is driven on `runtime`
+    pub fn with_approvals(
+        mut self,
+        approvals: Arc<dyn ExecutorApprovalService>,
+        runtime: Handle,
+    ) -> Self {
+        self.approvals = Some((approvals, runtime));
+        self
     }
 
     pub fn record_user_prompt_event(&self, prompt: &str) {
         self.send_event(AcpEvent::User(prompt.to_string()));
     }
 
+    /// Ask the approval service about a permission request and pick the matching option
+    async fn request_approval(
+        approvals: Arc<dyn ExecutorApprovalService>,
+        runtime: &Handle,
+        args: &acp::RequestPermissionRequest,
+    ) -> acp::RequestPermissionResponse {
+        let tool_call_id = args.tool_call.id.0.to_string();
+        let tool_name = args
+            .tool_call
+            .fields
+            .title
+            .clone()
+            .unwrap_or_else(|| "tool".to_string());
+        let tool_input = serde_json::to_value(&args.tool_call).unwrap_or_default();
+
+        // The client runs on its own single-threaded runtime; the approval service
+        // belongs to the server's runtime
+        let status = runtime
+            .spawn(async move {
+                // Give the log normalizer a moment to record the tool call the approval attaches to
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                approvals
+                    .request_tool_approval(&tool_name, tool_input, &tool_call_id)
+                    .await
+            })
+            .await;
+        let status = match status {
+            Ok(Ok(status)) => status,
+            Ok(Err(err)) => {
+                warn!("ACP permission request failed: {err}");
+                ApprovalStatus::Denied {
+                    reason: Some("approval service error".to_string()),
+                }
+            }
+            Err(err) => {
+                warn!("ACP permission request task failed: {err}");
+                ApprovalStatus::Denied {
+                    reason: Some("approval service error".to_string()),
+                }
+            }
+        };
+
+        let preferred: &[acp::PermissionOptionKind] = match status {
+            ApprovalStatus::Approved => &[
+                acp::PermissionOptionKind::AllowOnce,
+                acp::PermissionOptionKind::AllowAlways,
+            ],
+            _ => &[
+                acp::PermissionOptionKind::RejectOnce,
+                acp::PermissionOptionKind::RejectAlways,
+            ],
+        };
+        let chosen_option = preferred
+            .iter()
+            .find_map(|kind| args.options.iter().find(|o| o.kind == *kind));
+
+        let outcome = match chosen_option {
+            Some(opt) => acp::RequestPermissionOutcome::Selected {
+                option_id: opt.id.clone(),
+            },
+            None => acp::RequestPermissionOutcome::Cancelled,
+        };
+        acp::RequestPermissionResponse {
+            outcome,
+            meta: None,
+        }
+    }
+
     /// Send an event to the event channel
     fn send_event(&self, event: AcpEvent) {
         if let Err(e) = self.event_tx.send(event) {
@@ -37,6 +124,10 @@ impl acp::Client for AcpClient {
         // Forward the request as an event
         self.send_event(AcpEvent::RequestPermission(args.clone()));
 
+        if let Some((approvals, runtime)) = &self.approvals {
+            return Ok(Self::request_approval(approvals.clone(), runtime, &args).await);
+        }
+
         // Auto-approve with best available option
         let chosen_option = args
             .options
@@ -17,6 +17,7 @@ use workspace_utils::stream_lines::LinesStreamExt;
 
 use super::{AcpClient, SessionManager};
 use crate::{
+    approvals::ExecutorApprovalService,
     command::{CommandParts, CommandRuntime, ExecutionCommand, StdioConfig},
     executors::{ExecutorError, SpawnedChild, acp::AcpEvent},
 };
@@ -24,6 +25,8 @@ use crate::{
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
 pub struct AcpAgentHarness {
     session_namespace: String,
+    mcp_servers: Vec<proto::McpServer>,
+    approvals: Option<Arc<dyn ExecutorApprovalService>>,
 }
 
 impl Default for AcpAgentHarness {
@@ -36,18 +39,30 @@ impl Default for AcpAgentHarness {
 impl AcpAgentHarness {
     /// Create a harness with the default Gemini namespace
     pub fn new() -> Self {
-        Self {
-            session_namespace: "gemini_sessions".to_string(),
-        }
+        Self::with_session_namespace("gemini_sessions")
     }
 
     /// Create a harness with a custom session namespace (e.g. for Qwen)
     pub fn with_session_namespace(namespace: impl Into<String>) -> Self {
         Self {
             session_namespace: namespace.into(),
+            mcp_servers: vec![],
+            approvals: None,
         }
     }
 
+    /// MCP servers passed to the agent when creating a session
+    pub fn with_mcp_servers(mut self, mcp_servers: Vec<proto::McpServer>) -> Self {
+        self.mcp_servers = mcp_servers;
+        self
+    }
+
+    /// Route permission requests through `approvals` instead of auto-approving them
+    pub fn with_approvals(mut self, approvals: Arc<dyn ExecutorApprovalService>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
     pub async fn spawn_with_command(
         &self,
         current_dir: &Path,
@@ -66,13 +81,12 @@ impl AcpAgentHarness {
         let mut child = runtime.spawn(exec_command).await?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<()>();
-        Self::bootstrap_acp_connection(
+        self.bootstrap_acp_connection(
             &mut child,
             current_dir.to_path_buf(),
             None,
             prompt,
             Some(exit_tx),
-            self.session_namespace.clone(),
         )
         .await?;
 
@@ -101,13 +115,12 @@ impl AcpAgentHarness {
         let mut child = runtime.spawn(exec_command).await?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<()>();
-        Self::bootstrap_acp_connection(
+        self.bootstrap_acp_connection(
             &mut child,
             current_dir.to_path_buf(),
             Some(session_id.to_string()),
             prompt,
             Some(exit_tx),
-            self.session_namespace.clone(),
         )
         .await?;
 
@@ -118,13 +131,21 @@ impl AcpAgentHarness {
     }
 
     async fn bootstrap_acp_connection(
+        &self,
         child: &mut AsyncGroupChild,
         cwd: PathBuf,
         existing_session: Option<String>,
         prompt: String,
         exit_signal: Option<tokio::sync::oneshot::Sender<()>>,
-        session_namespace: String,
     ) -> Result<(), ExecutorError> {
+        let session_namespace = self.session_namespace.clone();
+        let mcp_servers = self.mcp_servers.clone();
+        // The ACP client runs on its own runtime; approvals are awaited on this one
+        let approvals = self
+            .approvals
+            .clone()
+            .map(|approvals| (approvals, tokio::runtime::Handle::current()));
+
         // Take child's stdio for ACP wiring
         let orig_stdout = child.inner().stdout.take().ok_or_else(|| {
             ExecutorError::Io(std::io::Error::new(
@@ -239,7 +260,10 @@ impl AcpAgentHarness {
                         let session_manager = std::sync::Arc::new(session_manager);
 
                         // Create ACP client
-                        let client = AcpClient::new(event_tx.clone());
+                        let mut client = AcpClient::new(event_tx.clone());
+                        if let Some((approvals, handle)) = approvals {
+                            client = client.with_approvals(approvals, handle);
+                        }
 
                         client.record_user_prompt_event(&prompt);
 
@@ -284,7 +308,7 @@ impl AcpAgentHarness {
 
                                 match conn
                                     .new_session(proto::NewSessionRequest {
-                                        mcp_servers: vec![],
+                                        mcp_servers: mcp_servers.clone(),
                                         cwd: cwd.clone(),
                                         meta,
                                     })
@@ -305,7 +329,7 @@ impl AcpAgentHarness {
                                 // New session
                                 match conn
                                     .new_session(proto::NewSessionRequest {
-                                        mcp_servers: vec![],
+                                        mcp_servers: mcp_servers.clone(),
                                         cwd: cwd.clone(),
                                         meta: None,
                                     })
@@ -1,3 +1,4 @@
+pub mod agent;
 pub mod client;
 pub mod harness;
 pub mod normalize_logs;
@@ -5,6 +6,7 @@ pub mod session;
 
 use std::{fmt::Display, str::FromStr};
 
+pub use agent::Acp;
 pub use client::AcpClient;
 pub use harness::AcpAgentHarness;
 pub use normalize_logs::*;
@@ -14,11 +14,14 @@ use workspace_utils::msg_store::MsgStore;
 
 pub use super::AcpAgentHarness;
 use super::AcpEvent;
-use crate::logs::{
-    ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolResult,
-    ToolResultValueType, ToolStatus as LogToolStatus,
-    stderr_processor::normalize_stderr_logs,
-    utils::{ConversationPatch, EntryIndexProvider},
+use crate::{
+    approvals::ToolCallMetadata,
+    logs::{
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
 };
 
 pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
@@ -232,7 +235,10 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     status: convert_tool_status(&tool_data.status),
                 },
                 content: get_tool_content(tool_data),
-                metadata: None,
+                metadata: serde_json::to_value(ToolCallMetadata {
+                    tool_call_id: tool_data.id.0.to_string(),
+                })
+                .ok(),
             };
             let patch = if is_new {
                 ConversationPatch::add_normalized_entry(tool_data.index, entry)
@@ -12,6 +12,7 @@ use crate::{
         CmdOverrides, CommandBuilder, CommandParts, CommandRuntime, ExecutionCommand, StdioConfig,
         apply_overrides,
     },
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         claude::{ClaudeLogProcessor, HistoryStrategy},
@@ -33,10 +34,11 @@ pub struct Amp {
     pub cmd: CmdOverrides,
 }
 
+const BASE_COMMAND: &str = "npx -y @sourcegraph/amp@0.0.1761050239-g36fe88";
+
 impl Amp {
     fn build_command_builder(&self) -> CommandBuilder {
-        let mut builder = CommandBuilder::new("npx -y @sourcegraph/amp@0.0.1761050239-g36fe88")
-            .params(["--execute", "--stream-json"]);
+        let mut builder = CommandBuilder::new(BASE_COMMAND).params(["--execute", "--stream-json"]);
         if self.dangerously_allow_all.unwrap_or(false) {
             builder = builder.extend_params(["--dangerously-allow-all"]);
         }
@@ -172,4 +174,11 @@ impl StandardCodingAgentExecutor for Amp {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".config").join("amp").join("settings.json"))
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(BASE_COMMAND, &self.cmd).with_auth([
+            Some(AuthSource::env("AMP_API_KEY")),
+            AuthSource::home_file(".local/share/amp/secrets.json"),
+        ])
+    }
 }
@@ -25,6 +25,7 @@ use crate::{
         CmdOverrides, CommandBuilder, CommandParts, CommandRuntime, ExecutionCommand, StdioConfig,
         apply_overrides,
     },
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         codex::client::LogWriter,
@@ -203,6 +204,19 @@ impl StandardCodingAgentExecutor for ClaudeCode {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".claude.json"))
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(
+            base_command(self.claude_code_router.unwrap_or(false)),
+            &self.cmd,
+        )
+        .with_auth([
+            Some(AuthSource::env("ANTHROPIC_API_KEY")),
+            Some(AuthSource::env("CLAUDE_CODE_OAUTH_TOKEN")),
+            AuthSource::home_file(".claude/.credentials.json"),
+            Some(AuthSource::StoredSecret("claude")),
+        ])
+    }
 }
 
 impl ClaudeCode {
@@ -1980,6 +1994,7 @@ mod tests {
             cmd: crate::command::CmdOverrides {
                 base_command_override: None,
                 additional_params: None,
+                version: None,
             },
             approvals_service: None,
         };
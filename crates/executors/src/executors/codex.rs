@@ -33,6 +33,7 @@ use crate::{
         CmdOverrides, CommandBuilder, CommandParts, CommandRuntime, ExecutionCommand, StdioConfig,
         apply_overrides,
     },
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         codex::{jsonrpc::ExitSignalSender, normalize_logs::Error},
@@ -178,11 +179,20 @@ impl StandardCodingAgentExecutor for Codex {
     fn default_mcp_config_path(&self) -> Option<PathBuf> {
         dirs::home_dir().map(|home| home.join(".codex").join("config.toml"))
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(BASE_COMMAND, &self.cmd).with_auth([
+            Some(AuthSource::env("OPENAI_API_KEY")),
+            AuthSource::home_file(".codex/auth.json"),
+        ])
+    }
 }
 
+const BASE_COMMAND: &str = "npx -y @openai/codex@0.55.0";
+
 impl Codex {
     fn build_command_builder(&self) -> CommandBuilder {
-        let mut builder = CommandBuilder::new("npx -y @openai/codex@0.55.0 app-server");
+        let mut builder = CommandBuilder::new(format!("{BASE_COMMAND} app-server"));
 
         if self.oss.unwrap_or(false) {
             builder = builder.extend_params(["--oss"]);
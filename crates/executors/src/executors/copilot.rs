@@ -22,6 +22,7 @@ use crate::{
         CmdOverrides, CommandBuilder, CommandRuntime, ExecutionCommand, StdioConfig,
         apply_overrides,
     },
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     logs::{
         NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
@@ -50,9 +51,11 @@ pub struct Copilot {
     pub cmd: CmdOverrides,
 }
 
+const BASE_COMMAND: &str = "npx -y @github/copilot@0.0.337";
+
 impl Copilot {
     fn build_command_builder(&self, log_dir: &str) -> CommandBuilder {
-        let mut builder = CommandBuilder::new("npx -y @github/copilot@0.0.337").params([
+        let mut builder = CommandBuilder::new(BASE_COMMAND).params([
             "--no-color",
             "--log-level",
             "debug",
@@ -195,6 +198,14 @@ impl StandardCodingAgentExecutor for Copilot {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".copilot").join("mcp-config.json"))
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(BASE_COMMAND, &self.cmd).with_auth([
+            Some(AuthSource::env("GH_TOKEN")),
+            Some(AuthSource::env("GITHUB_TOKEN")),
+            AuthSource::home_file(".copilot/config.json"),
+        ])
+    }
 }
 
 impl Copilot {
@@ -22,6 +22,7 @@ use crate::{
         CmdOverrides, CommandBuilder, CommandRuntime, ExecutionCommand, StdioConfig,
         apply_overrides,
     },
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
@@ -477,6 +478,13 @@ impl StandardCodingAgentExecutor for CursorAgent {
         dirs::home_dir().map(|home| home.join(".cursor").join("mcp.json"))
     }
 
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(Self::base_command(), &self.cmd).with_auth([
+            Some(AuthSource::env("CURSOR_API_KEY")),
+            AuthSource::home_file(".config/cursor/auth.json"),
+        ])
+    }
+
     async fn check_availability(&self) -> bool {
         resolve_executable_path("cursor-agent").await.is_some()
     }
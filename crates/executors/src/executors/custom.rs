@@ -0,0 +1,566 @@
+use std::{borrow::Cow, path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path};
+
+use crate::{
+    command::{
+        CmdOverrides, CommandBuilder, CommandParts, CommandRuntime, ExecutionCommand, StdioConfig,
+        apply_overrides,
+    },
+    diagnostics::DiagnosticProbe,
+    executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+};
+
+/// How the prompt is handed to the agent
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomPromptInput {
+    #[default]
+    Stdin,
+    /// Passed as the last command line argument
+    Argument,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomOutputFormat {
+    /// Each stdout line is matched as plain text
+    #[default]
+    Lines,
+    /// Each stdout line is parsed as a JSON object; rules may match on fields
+    JsonLines,
+}
+
+/// Conversation entry produced by a matching output rule
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomEntryKind {
+    #[default]
+    AssistantMessage,
+    Thinking,
+    SystemMessage,
+    ErrorMessage,
+    ToolUse,
+    /// Drop the line
+    Ignore,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomToolAction {
+    FileRead,
+    FileEdit,
+    CommandRun,
+    Search,
+    WebFetch,
+    #[default]
+    Other,
+}
+
+/// Maps matching stdout lines to a conversation entry.
+///
+/// Templates may reference `{line}`, regex capture groups (`{1}`, `{name}`) and,
+/// in `json_lines` mode, JSON pointers into the parsed line (`{/message/text}`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct CustomOutputRule {
+    /// JSON pointer of the value to match in `json_lines` mode, e.g. `/type`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Regex the line (or field) must match; every line matches when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub entry: CustomEntryKind,
+    /// Entry content template, `{line}` by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Tool name template for `tool_use` entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_action: Option<CustomToolAction>,
+    /// Template for the tool's path, command, query or URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct CustomAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that runs the agent, e.g. `my-agent --print`"
+    )]
+    pub command: String,
+    #[serde(default)]
+    pub prompt_input: CustomPromptInput,
+    #[schemars(
+        title = "Follow-up Arguments",
+        description = "Arguments appended to resume a session; `{session_id}` is substituted. Follow-ups are unsupported when unset"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_up_args: Option<Vec<String>>,
+    #[schemars(
+        title = "Session ID Pattern",
+        description = "Regex whose first capture group extracts the session id from stdout"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id_pattern: Option<String>,
+    #[serde(default)]
+    pub output_format: CustomOutputFormat,
+    #[schemars(
+        title = "Output Rules",
+        description = "Evaluated in order; the first matching rule decides the entry. Unmatched lines become assistant messages"
+    )]
+    #[serde(default)]
+    pub output_rules: Vec<CustomOutputRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config_path: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl CustomAgent {
+    fn build_command_builder(&self) -> CommandBuilder {
+        apply_overrides(CommandBuilder::new(self.command.clone()), &self.cmd)
+    }
+
+    async fn spawn_with(
+        &self,
+        command_parts: CommandParts,
+        current_dir: &Path,
+        prompt: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (program, mut args) = command_parts.into_owned();
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        if self.prompt_input == CustomPromptInput::Argument {
+            args.push(combined_prompt.clone());
+        }
+
+        let mut exec_command = ExecutionCommand::new(program, args, current_dir.to_path_buf());
+        exec_command.kill_on_drop(true);
+        exec_command.stdin(match self.prompt_input {
+            CustomPromptInput::Stdin => StdioConfig::piped(),
+            CustomPromptInput::Argument => StdioConfig::null(),
+        });
+        exec_command.stdout(StdioConfig::piped());
+        exec_command.stderr(StdioConfig::piped());
+
+        let mut child = runtime.spawn(exec_command).await?;
+
+        if self.prompt_input == CustomPromptInput::Stdin
+            && let Some(mut stdin) = child.inner().stdin.take()
+        {
+            stdin.write_all(combined_prompt.as_bytes()).await?;
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAgent {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self.build_command_builder().build_initial()?;
+        self.spawn_with(command_parts, current_dir, prompt, runtime)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let Some(follow_up_args) = &self.follow_up_args else {
+            return Err(ExecutorError::FollowUpNotSupported(
+                "custom agent has no follow_up_args".to_string(),
+            ));
+        };
+        let args = follow_up_args
+            .iter()
+            .map(|arg| arg.replace("{session_id}", session_id))
+            .collect::<Vec<_>>();
+        let command_parts = self.build_command_builder().build_follow_up(&args)?;
+        self.spawn_with(command_parts, current_dir, prompt, runtime)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+        let parser = CustomOutputParser::new(self);
+        tokio::spawn(async move {
+            let parser = match parser {
+                Ok(parser) => parser,
+                Err(e) => {
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::ErrorMessage {
+                            error_type: NormalizedEntryError::Other,
+                        },
+                        content: format!("Invalid custom agent output spec: {e}"),
+                        metadata: None,
+                    };
+                    msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                        entry_index_provider.next(),
+                        entry,
+                    ));
+                    CustomOutputParser::plain_text()
+                }
+            };
+
+            let mut stdout_lines = msg_store.stdout_lines_stream();
+            let mut session_id_found = false;
+            while let Some(Ok(line)) = stdout_lines.next().await {
+                let parsed = parser.parse_line(&line);
+                if !session_id_found && let Some(session_id) = parsed.session_id {
+                    msg_store.push_session_id(session_id);
+                    session_id_found = true;
+                }
+                if let Some(entry) = parsed.entry {
+                    msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                        entry_index_provider.next(),
+                        entry,
+                    ));
+                }
+            }
+        });
+    }
+
+    // MCP configuration methods
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        let path = self.mcp_config_path.as_deref()?;
+        match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None => Some(path.into()),
+        }
+    }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe {
+            command: Some(self.build_command_builder()),
+            ..Default::default()
+        }
+    }
+
+    async fn check_availability(&self) -> bool {
+        match self.build_command_builder().build_initial() {
+            Ok(parts) => resolve_executable_path(parts.program()).await.is_some(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Result of running one stdout line through the output spec
+#[derive(Debug, Default)]
+pub struct ParsedLine {
+    pub session_id: Option<String>,
+    pub entry: Option<NormalizedEntry>,
+}
+
+struct CompiledRule {
+    rule: CustomOutputRule,
+    regex: Option<Regex>,
+}
+
+/// Compiled form of a [`CustomAgent`]'s output spec
+pub struct CustomOutputParser {
+    format: CustomOutputFormat,
+    session_id: Option<Regex>,
+    rules: Vec<CompiledRule>,
+}
+
+impl CustomOutputParser {
+    pub fn new(agent: &CustomAgent) -> Result<Self, regex::Error> {
+        let session_id = agent
+            .session_id_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()?;
+        let rules = agent
+            .output_rules
+            .iter()
+            .map(|rule| {
+                Ok(CompiledRule {
+                    regex: rule.pattern.as_deref().map(Regex::new).transpose()?,
+                    rule: rule.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, regex::Error>>()?;
+        Ok(Self {
+            format: agent.output_format,
+            session_id,
+            rules,
+        })
+    }
+
+    /// Parser that turns every line into an assistant message
+    fn plain_text() -> Self {
+        Self {
+            format: CustomOutputFormat::Lines,
+            session_id: None,
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn parse_line(&self, line: &str) -> ParsedLine {
+        let line = strip_ansi_escapes::strip_str(line);
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            return ParsedLine::default();
+        }
+
+        let session_id = self
+            .session_id
+            .as_ref()
+            .and_then(|re| re.captures(line))
+            .and_then(|caps| caps.get(1).or_else(|| caps.get(0)))
+            .map(|m| m.as_str().to_string());
+
+        let json = match self.format {
+            CustomOutputFormat::JsonLines => serde_json::from_str::<Value>(line).ok(),
+            CustomOutputFormat::Lines => None,
+        };
+
+        let entry = self
+            .rules
+            .iter()
+            .find_map(|rule| Self::apply_rule(rule, line, json.as_ref()))
+            .unwrap_or_else(|| {
+                Some(Self::entry(
+                    NormalizedEntryType::AssistantMessage,
+                    line.to_string(),
+                ))
+            });
+
+        ParsedLine { session_id, entry }
+    }
+
+    /// `None` if the rule does not match, `Some(None)` if it matches but drops the line
+    fn apply_rule(
+        compiled: &CompiledRule,
+        line: &str,
+        json: Option<&Value>,
+    ) -> Option<Option<NormalizedEntry>> {
+        let rule = &compiled.rule;
+        let subject: Cow<'_, str> = match (&rule.field, json) {
+            (Some(pointer), Some(json)) => Cow::Owned(value_to_string(json.pointer(pointer)?)),
+            (Some(_), None) => return None,
+            (None, _) => Cow::Borrowed(line),
+        };
+        let captures = match &compiled.regex {
+            Some(regex) => Some(regex.captures(&subject)?),
+            None => None,
+        };
+        let render = |template: &str| render_template(template, line, captures.as_ref(), json);
+
+        let entry_type = match rule.entry {
+            CustomEntryKind::Ignore => return Some(None),
+            CustomEntryKind::AssistantMessage => NormalizedEntryType::AssistantMessage,
+            CustomEntryKind::Thinking => NormalizedEntryType::Thinking,
+            CustomEntryKind::SystemMessage => NormalizedEntryType::SystemMessage,
+            CustomEntryKind::ErrorMessage => NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other,
+            },
+            CustomEntryKind::ToolUse => {
+                let tool_name = rule
+                    .tool_name
+                    .as_deref()
+                    .map(render)
+                    .unwrap_or_else(|| "tool".to_string());
+                let target = rule.tool_target.as_deref().map(render).unwrap_or_default();
+                let action_type = match rule.tool_action.unwrap_or_default() {
+                    CustomToolAction::FileRead => ActionType::FileRead { path: target },
+                    CustomToolAction::FileEdit => ActionType::FileEdit {
+                        path: target,
+                        changes: vec![],
+                    },
+                    CustomToolAction::CommandRun => ActionType::CommandRun {
+                        command: target,
+                        result: None,
+                    },
+                    CustomToolAction::Search => ActionType::Search { query: target },
+                    CustomToolAction::WebFetch => ActionType::WebFetch { url: target },
+                    CustomToolAction::Other => ActionType::Other {
+                        description: target,
+                    },
+                };
+                NormalizedEntryType::ToolUse {
+                    tool_name,
+                    action_type,
+                    status: ToolStatus::Success,
+                }
+            }
+        };
+        let content = rule
+            .content
+            .as_deref()
+            .map(render)
+            .unwrap_or_else(|| line.to_string());
+        Some(Some(Self::entry(entry_type, content)))
+    }
+
+    fn entry(entry_type: NormalizedEntryType, content: String) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: None,
+        }
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Expand `{line}`, `{<capture>}` and `{/json/pointer}` placeholders
+fn render_template(
+    template: &str,
+    line: &str,
+    captures: Option<&Captures>,
+    json: Option<&Value>,
+) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = &after[..end];
+        let value = if key == "line" {
+            Some(line.to_string())
+        } else if key.starts_with('/') {
+            json.and_then(|json| json.pointer(key)).map(value_to_string)
+        } else {
+            captures
+                .and_then(|caps| match key.parse::<usize>() {
+                    Ok(idx) => caps.get(idx),
+                    Err(_) => caps.name(key),
+                })
+                .map(|m| m.as_str().to_string())
+        };
+        out.push_str(&value.unwrap_or_default());
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(output_format: CustomOutputFormat, rules: serde_json::Value) -> CustomAgent {
+        CustomAgent {
+            append_prompt: AppendPrompt::default(),
+            command: "my-agent".to_string(),
+            prompt_input: CustomPromptInput::Stdin,
+            follow_up_args: None,
+            session_id_pattern: Some(r#"session: (\S+)"#.to_string()),
+            output_format,
+            output_rules: serde_json::from_value(rules).unwrap(),
+            mcp_config_path: None,
+            cmd: CmdOverrides::default(),
+        }
+    }
+
+    #[test]
+    fn line_rules_map_to_entries() {
+        let agent = agent(
+            CustomOutputFormat::Lines,
+            serde_json::json!([
+                { "pattern": "^> read (?P<path>.+)$", "entry": "tool_use",
+                  "tool_name": "read", "tool_action": "file_read", "tool_target": "{path}" },
+                { "pattern": "^debug:", "entry": "ignore" },
+            ]),
+        );
+        let parser = CustomOutputParser::new(&agent).unwrap();
+
+        let parsed = parser.parse_line("session: abc123");
+        assert_eq!(parsed.session_id.as_deref(), Some("abc123"));
+
+        let entry = parser.parse_line("> read src/main.rs").entry.unwrap();
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type: ActionType::FileRead { path },
+                ..
+            } => {
+                assert_eq!(tool_name, "read");
+                assert_eq!(path, "src/main.rs");
+            }
+            other => panic!("unexpected entry {other:?}"),
+        }
+
+        assert!(parser.parse_line("debug: noise").entry.is_none());
+        assert!(matches!(
+            parser.parse_line("All done").entry.unwrap().entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+    }
+
+    #[test]
+    fn json_rules_match_fields() {
+        let agent = agent(
+            CustomOutputFormat::JsonLines,
+            serde_json::json!([
+                { "field": "/type", "pattern": "^text$", "content": "{/text}" },
+                { "field": "/type", "pattern": "^shell$", "entry": "tool_use",
+                  "tool_name": "bash", "tool_action": "command_run", "tool_target": "{/cmd}" },
+                { "field": "/type", "entry": "ignore" },
+            ]),
+        );
+        let parser = CustomOutputParser::new(&agent).unwrap();
+
+        let entry = parser
+            .parse_line(r#"{"type":"text","text":"Hello"}"#)
+            .entry
+            .unwrap();
+        assert_eq!(entry.content, "Hello");
+
+        let entry = parser
+            .parse_line(r#"{"type":"shell","cmd":"cargo test"}"#)
+            .entry
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { ref command, .. },
+                ..
+            } if command == "cargo test"
+        ));
+
+        assert!(parser.parse_line(r#"{"type":"usage"}"#).entry.is_none());
+    }
+}
@@ -9,6 +9,7 @@ use workspace_utils::msg_store::MsgStore;
 pub use super::acp::AcpAgentHarness;
 use crate::{
     command::{CmdOverrides, CommandBuilder, CommandRuntime, apply_overrides},
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
 };
 
@@ -104,4 +105,12 @@ impl StandardCodingAgentExecutor for Gemini {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".gemini").join("settings.json"))
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(self.model.base_command(), &self.cmd).with_auth([
+            Some(AuthSource::env("GEMINI_API_KEY")),
+            Some(AuthSource::env("GOOGLE_API_KEY")),
+            AuthSource::home_file(".gemini/oauth_creds.json"),
+        ])
+    }
 }
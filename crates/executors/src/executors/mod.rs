@@ -15,12 +15,15 @@ use workspace_utils::msg_store::MsgStore;
 use crate::{
     actions::ExecutorAction,
     approvals::ExecutorApprovalService,
-    command::{CommandBuildError, CommandRuntime},
+    command::{CmdOverrides, CommandBuildError, CommandRuntime},
+    diagnostics::DiagnosticProbe,
     executors::{
-        amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        acp::Acp, amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot,
+        cursor::CursorAgent, custom::CustomAgent, gemini::Gemini, model_api::ModelApi,
+        opencode::Opencode, qwen::QwenCode,
     },
     mcp_config::McpConfig,
+    tool_cache::NpmPackage,
 };
 
 pub mod acp;
@@ -29,7 +32,9 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod gemini;
+pub mod model_api;
 pub mod opencode;
 pub mod qwen;
 
@@ -64,6 +69,8 @@ pub enum ExecutorError {
     CommandBuild(#[from] CommandBuildError),
     #[error("Executable `{program}` not found in PATH")]
     ExecutableNotFound { program: String },
+    #[error("`{package}` is not in the tool cache and npx fallback is disabled")]
+    ToolNotCached { package: String },
     #[error("Setup helper not supported")]
     SetupHelperNotSupported,
 }
@@ -95,6 +102,9 @@ pub enum CodingAgent {
     CursorAgent,
     QwenCode,
     Copilot,
+    CustomAgent,
+    Acp,
+    ModelApi,
 }
 
 impl CodingAgent {
@@ -140,6 +150,34 @@ impl CodingAgent {
         self.default_mcp_config_path().is_some()
     }
 
+    /// Command overrides for executors that launch a CLI
+    pub fn cmd_overrides_mut(&mut self) -> Option<&mut CmdOverrides> {
+        match self {
+            Self::ClaudeCode(agent) => Some(&mut agent.cmd),
+            Self::Amp(agent) => Some(&mut agent.cmd),
+            Self::Gemini(agent) => Some(&mut agent.cmd),
+            Self::Codex(agent) => Some(&mut agent.cmd),
+            Self::Opencode(agent) => Some(&mut agent.cmd),
+            Self::CursorAgent(agent) => Some(&mut agent.cmd),
+            Self::QwenCode(agent) => Some(&mut agent.cmd),
+            Self::Copilot(agent) => Some(&mut agent.cmd),
+            Self::CustomAgent(agent) => Some(&mut agent.cmd),
+            Self::Acp(agent) => Some(&mut agent.cmd),
+            Self::ModelApi(_) => None,
+        }
+    }
+
+    /// npm package the agent is launched from, after overrides and version pins
+    pub async fn npm_package(&self) -> Option<NpmPackage> {
+        let parts = self
+            .diagnostic_probe()
+            .await
+            .command?
+            .build_initial()
+            .ok()?;
+        NpmPackage::from_npx(parts.program(), parts.args()).map(|(package, _)| package)
+    }
+
     pub fn capabilities(&self) -> Vec<BaseAgentCapability> {
         match self {
             Self::ClaudeCode(_) => vec![BaseAgentCapability::SessionFork],
@@ -147,8 +185,10 @@ impl CodingAgent {
             Self::Codex(_) => vec![BaseAgentCapability::SessionFork],
             Self::Gemini(_) => vec![BaseAgentCapability::SessionFork],
             Self::QwenCode(_) => vec![BaseAgentCapability::SessionFork],
+            Self::Acp(_) => vec![BaseAgentCapability::SessionFork],
+            Self::ModelApi(_) => vec![BaseAgentCapability::SessionFork],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Opencode(_) | Self::Copilot(_) => vec![],
+            Self::Opencode(_) | Self::Copilot(_) | Self::CustomAgent(_) => vec![],
         }
     }
 }
@@ -180,6 +220,11 @@ pub trait StandardCodingAgentExecutor {
         Err(ExecutorError::SetupHelperNotSupported)
     }
 
+    /// Installation and credential checks used by executor health diagnostics
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::default()
+    }
+
     async fn check_availability(&self) -> bool {
         self.default_mcp_config_path()
             .map(|path| path.exists())
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+
+use super::{
+    client::{ChatMessage, ModelClient, ToolCall},
+    session::SessionStore,
+    tools::{ToolContext, ToolInvocation, tool_specs},
+};
+use crate::logs::{
+    ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+};
+
+/// Line written to the executor's stdout and turned into conversation patches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModelApiEvent {
+    SessionStart {
+        session_id: String,
+    },
+    /// An entry replaces the earlier entry with the same key
+    Entry {
+        key: String,
+        entry: NormalizedEntry,
+    },
+}
+
+/// Request/tool-call loop that runs until the model answers without calling a tool
+pub struct AgentLoop {
+    pub client: ModelClient,
+    pub tools: ToolContext,
+    pub system_prompt: String,
+    pub max_turns: u32,
+    pub events: mpsc::UnboundedSender<ModelApiEvent>,
+    /// Turns true when the execution was stopped
+    pub cancelled: Option<watch::Receiver<bool>>,
+}
+
+impl AgentLoop {
+    /// Continue `messages` with `prompt`, persisting the conversation under `session_id`
+    /// after every turn. Returns the final conversation.
+    pub async fn run(
+        mut self,
+        store: &SessionStore,
+        session_id: &str,
+        mut messages: Vec<ChatMessage>,
+        prompt: String,
+    ) -> Vec<ChatMessage> {
+        self.emit(ModelApiEvent::SessionStart {
+            session_id: session_id.to_string(),
+        });
+        self.emit_entry("user", NormalizedEntryType::UserMessage, prompt.clone());
+        messages.push(ChatMessage::User { content: prompt });
+        self.save(store, session_id, &messages);
+
+        let specs = tool_specs();
+        for turn in 0..self.max_turns {
+            let request = self.client.complete(&self.system_prompt, &messages, &specs);
+            let response = match self.cancelled.clone() {
+                Some(mut cancelled) => tokio::select! {
+                    response = request => response,
+                    _ = cancelled.wait_for(|c| *c) => return messages,
+                },
+                None => request.await,
+            };
+            let response = match response {
+                Ok(response) => response,
+                Err(err) => {
+                    self.emit_entry(
+                        "error",
+                        NormalizedEntryType::ErrorMessage {
+                            error_type: NormalizedEntryError::Other,
+                        },
+                        err.to_string(),
+                    );
+                    return messages;
+                }
+            };
+
+            if let Some(text) = &response.content {
+                self.emit_entry(
+                    &format!("assistant-{turn}"),
+                    NormalizedEntryType::AssistantMessage,
+                    text.clone(),
+                );
+            }
+            messages.push(ChatMessage::Assistant {
+                content: response.content,
+                tool_calls: response.tool_calls.clone(),
+            });
+            self.save(store, session_id, &messages);
+
+            if response.tool_calls.is_empty() {
+                return messages;
+            }
+            for call in &response.tool_calls {
+                let output = self.run_tool(call).await;
+                messages.push(ChatMessage::Tool {
+                    tool_call_id: call.id.clone(),
+                    content: output,
+                });
+            }
+            self.save(store, session_id, &messages);
+        }
+
+        self.emit_entry(
+            "max-turns",
+            NormalizedEntryType::SystemMessage,
+            format!("Stopped after {} turns", self.max_turns),
+        );
+        messages
+    }
+
+    async fn run_tool(&mut self, call: &ToolCall) -> String {
+        let key = format!("tool-{}", call.id);
+        let invocation = match ToolInvocation::parse(&call.name, &call.arguments) {
+            Ok(invocation) => invocation,
+            Err(err) => {
+                let action = ActionType::Tool {
+                    tool_name: call.name.clone(),
+                    arguments: Some(call.arguments.clone()),
+                    result: None,
+                };
+                self.emit_tool(&key, &call.name, action, ToolStatus::Failed);
+                return err;
+            }
+        };
+
+        self.emit_tool(&key, &call.name, invocation.action(), ToolStatus::Created);
+        let outcome = self.tools.execute(invocation).await;
+        let status = if outcome.success {
+            ToolStatus::Success
+        } else {
+            ToolStatus::Failed
+        };
+        self.emit_tool(&key, &call.name, outcome.action, status);
+        outcome.output
+    }
+
+    fn emit_tool(&self, key: &str, tool_name: &str, action: ActionType, status: ToolStatus) {
+        let content = match &action {
+            ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => path.clone(),
+            ActionType::CommandRun { command, .. } => command.clone(),
+            ActionType::Search { query } => query.clone(),
+            _ => tool_name.to_string(),
+        };
+        self.emit_entry(
+            key,
+            NormalizedEntryType::ToolUse {
+                tool_name: tool_name.to_string(),
+                action_type: action,
+                status,
+            },
+            content,
+        );
+    }
+
+    fn emit_entry(&self, key: &str, entry_type: NormalizedEntryType, content: String) {
+        self.emit(ModelApiEvent::Entry {
+            key: key.to_string(),
+            entry: NormalizedEntry {
+                timestamp: None,
+                entry_type,
+                content,
+                metadata: None,
+            },
+        });
+    }
+
+    fn emit(&self, event: ModelApiEvent) {
+        let _ = self.events.send(event);
+    }
+
+    fn save(&self, store: &SessionStore, session_id: &str, messages: &[ChatMessage]) {
+        if let Err(err) = store.save(session_id, messages) {
+            tracing::warn!("Failed to persist model API session {session_id}: {err}");
+        }
+    }
+}
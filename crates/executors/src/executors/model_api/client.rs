@@ -0,0 +1,375 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use super::ModelApiProvider;
+
+#[derive(Debug, Error)]
+pub enum ModelClientError {
+    #[error("request to model API failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("model API returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("unexpected model API response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Provider-neutral conversation message, persisted between follow-ups
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "role", rename_all = "snake_case")]
+pub enum ChatMessage {
+    User {
+        content: String,
+    },
+    Assistant {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tool_calls: Vec<ToolCall>,
+    },
+    Tool {
+        tool_call_id: String,
+        content: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub parameters: Value,
+}
+
+/// One assistant turn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelResponse {
+    pub content: Option<String>,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Minimal chat-completions client for OpenAI- and Anthropic-compatible endpoints
+pub struct ModelClient {
+    http: reqwest::Client,
+    provider: ModelApiProvider,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    max_tokens: u32,
+}
+
+impl ModelClient {
+    pub fn new(
+        provider: ModelApiProvider,
+        base_url: String,
+        api_key: Option<String>,
+        model: String,
+        max_tokens: u32,
+    ) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()
+            .unwrap_or_default();
+        Self {
+            http,
+            provider,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+            max_tokens,
+        }
+    }
+
+    pub async fn complete(
+        &self,
+        system: &str,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+    ) -> Result<ModelResponse, ModelClientError> {
+        let request = match self.provider {
+            ModelApiProvider::Openai => {
+                let mut request = self
+                    .http
+                    .post(format!("{}/chat/completions", self.base_url))
+                    .json(&self.openai_body(system, messages, tools));
+                if let Some(key) = &self.api_key {
+                    request = request.bearer_auth(key);
+                }
+                request
+            }
+            ModelApiProvider::Anthropic => {
+                let mut request = self
+                    .http
+                    .post(format!("{}/v1/messages", self.base_url))
+                    .header("anthropic-version", "2023-06-01")
+                    .json(&self.anthropic_body(system, messages, tools));
+                if let Some(key) = &self.api_key {
+                    request = request.header("x-api-key", key);
+                }
+                request
+            }
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ModelClientError::Status {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        let body: Value = response.json().await?;
+        match self.provider {
+            ModelApiProvider::Openai => parse_openai_response(&body),
+            ModelApiProvider::Anthropic => parse_anthropic_response(&body),
+        }
+    }
+
+    fn openai_body(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Value {
+        let mut wire = vec![json!({ "role": "system", "content": system })];
+        for message in messages {
+            wire.push(match message {
+                ChatMessage::User { content } => json!({ "role": "user", "content": content }),
+                ChatMessage::Assistant {
+                    content,
+                    tool_calls,
+                } => {
+                    let mut msg = json!({ "role": "assistant", "content": content });
+                    if !tool_calls.is_empty() {
+                        msg["tool_calls"] = tool_calls
+                            .iter()
+                            .map(|call| {
+                                json!({
+                                    "id": call.id,
+                                    "type": "function",
+                                    "function": {
+                                        "name": call.name,
+                                        "arguments": call.arguments.to_string(),
+                                    },
+                                })
+                            })
+                            .collect();
+                    }
+                    msg
+                }
+                ChatMessage::Tool {
+                    tool_call_id,
+                    content,
+                } => json!({ "role": "tool", "tool_call_id": tool_call_id, "content": content }),
+            });
+        }
+
+        json!({
+            "model": self.model,
+            "max_tokens": self.max_tokens,
+            "messages": wire,
+            "tools": tools
+                .iter()
+                .map(|tool| json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters,
+                    },
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn anthropic_body(&self, system: &str, messages: &[ChatMessage], tools: &[ToolSpec]) -> Value {
+        // Tool results are user turns; consecutive results are merged into one message
+        let mut wire: Vec<Value> = Vec::new();
+        for message in messages {
+            match message {
+                ChatMessage::User { content } => {
+                    wire.push(json!({ "role": "user", "content": content }))
+                }
+                ChatMessage::Assistant {
+                    content,
+                    tool_calls,
+                } => {
+                    let mut blocks = Vec::new();
+                    if let Some(text) = content.as_deref().filter(|t| !t.is_empty()) {
+                        blocks.push(json!({ "type": "text", "text": text }));
+                    }
+                    for call in tool_calls {
+                        blocks.push(json!({
+                            "type": "tool_use",
+                            "id": call.id,
+                            "name": call.name,
+                            "input": call.arguments,
+                        }));
+                    }
+                    wire.push(json!({ "role": "assistant", "content": blocks }));
+                }
+                ChatMessage::Tool {
+                    tool_call_id,
+                    content,
+                } => {
+                    let block = json!({
+                        "type": "tool_result",
+                        "tool_use_id": tool_call_id,
+                        "content": content,
+                    });
+                    match wire.last_mut() {
+                        Some(last) if last["role"] == "user" && last["content"].is_array() => {
+                            if let Some(blocks) = last["content"].as_array_mut() {
+                                blocks.push(block);
+                            }
+                        }
+                        _ => wire.push(json!({ "role": "user", "content": [block] })),
+                    }
+                }
+            }
+        }
+
+        json!({
+            "model": self.model,
+            "max_tokens": self.max_tokens,
+            "system": system,
+            "messages": wire,
+            "tools": tools
+                .iter()
+                .map(|tool| json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "input_schema": tool.parameters,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn parse_openai_response(body: &Value) -> Result<ModelResponse, ModelClientError> {
+    let message = body
+        .pointer("/choices/0/message")
+        .ok_or_else(|| ModelClientError::InvalidResponse("missing choices[0].message".into()))?;
+
+    let content = message
+        .get("content")
+        .and_then(Value::as_str)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+    let tool_calls = message
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .map(|calls| {
+            calls
+                .iter()
+                .filter_map(|call| {
+                    let function = call.get("function")?;
+                    // Arguments are a JSON-encoded string; keep the raw text if it does not parse
+                    let arguments = match function.get("arguments") {
+                        Some(Value::String(raw)) => {
+                            serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone()))
+                        }
+                        Some(other) => other.clone(),
+                        None => json!({}),
+                    };
+                    Some(ToolCall {
+                        id: call.get("id")?.as_str()?.to_string(),
+                        name: function.get("name")?.as_str()?.to_string(),
+                        arguments,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ModelResponse {
+        content,
+        tool_calls,
+    })
+}
+
+fn parse_anthropic_response(body: &Value) -> Result<ModelResponse, ModelClientError> {
+    let blocks = body
+        .get("content")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ModelClientError::InvalidResponse("missing content".into()))?;
+
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(t) = block.get("text").and_then(Value::as_str) {
+                    text.push_str(t);
+                }
+            }
+            Some("tool_use") => {
+                if let (Some(id), Some(name)) = (
+                    block.get("id").and_then(Value::as_str),
+                    block.get("name").and_then(Value::as_str),
+                ) {
+                    tool_calls.push(ToolCall {
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        arguments: block.get("input").cloned().unwrap_or_else(|| json!({})),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ModelResponse {
+        content: (!text.is_empty()).then_some(text),
+        tool_calls,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anthropic_body_merges_tool_results() {
+        let client = ModelClient::new(
+            ModelApiProvider::Anthropic,
+            "http://localhost".into(),
+            None,
+            "model".into(),
+            100,
+        );
+        let call = |id: &str| ToolCall {
+            id: id.into(),
+            name: "read_file".into(),
+            arguments: json!({ "path": "a" }),
+        };
+        let messages = vec![
+            ChatMessage::User {
+                content: "hi".into(),
+            },
+            ChatMessage::Assistant {
+                content: None,
+                tool_calls: vec![call("1"), call("2")],
+            },
+            ChatMessage::Tool {
+                tool_call_id: "1".into(),
+                content: "a".into(),
+            },
+            ChatMessage::Tool {
+                tool_call_id: "2".into(),
+                content: "b".into(),
+            },
+        ];
+
+        let body = client.anthropic_body("sys", &messages, &[]);
+        let wire = body["messages"].as_array().unwrap();
+        assert_eq!(wire.len(), 3);
+        assert_eq!(wire[1]["content"][1]["type"], "tool_use");
+        assert_eq!(wire[2]["content"].as_array().unwrap().len(), 2);
+        assert_eq!(wire[2]["content"][1]["tool_use_id"], "2");
+    }
+}
@@ -0,0 +1,402 @@
+pub mod agent;
+pub mod client;
+pub mod session;
+pub mod tools;
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::mpsc};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::get_shell_command};
+
+use self::{
+    agent::{AgentLoop, ModelApiEvent},
+    client::ModelClient,
+    session::SessionStore,
+    tools::{ShellSession, ToolContext},
+};
+use crate::{
+    command::{CommandRuntime, ExecutionCommand, StdioConfig},
+    diagnostics::{AuthSource, DiagnosticProbe},
+    executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    logs::{
+        stderr_processor::normalize_stderr_logs,
+        utils::{ConversationPatch, EntryIndexProvider},
+    },
+    stdout_dup::create_stdout_pipe_writer,
+};
+
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a coding agent working in a git worktree. \
+Use the tools to inspect and change files and to run commands. \
+Paths are relative to the worktree root. When the task is done, reply with a short summary.";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelApiProvider {
+    // `POST {base_url}/chat/completions`
+    #[default]
+    Openai,
+    // `POST {base_url}/v1/messages`
+    Anthropic,
+}
+
+impl ModelApiProvider {
+    fn default_base_url(&self) -> &'static str {
+        match self {
+            Self::Openai => "https://api.openai.com/v1",
+            Self::Anthropic => "https://api.anthropic.com",
+        }
+    }
+
+    fn default_api_key_env(&self) -> &'static str {
+        match self {
+            Self::Openai => "OPENAI_API_KEY",
+            Self::Anthropic => "ANTHROPIC_API_KEY",
+        }
+    }
+}
+
+/// Built-in agent that calls a chat completions API directly and runs its tools in-process
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct ModelApi {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[serde(default)]
+    pub provider: ModelApiProvider,
+    pub model: String,
+    #[schemars(
+        title = "Base URL",
+        description = "API endpoint, e.g. a local OpenAI-compatible server. Defaults to the provider's public API"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[schemars(
+        title = "API Key Variable",
+        description = "Environment variable holding the API key, `OPENAI_API_KEY` or `ANTHROPIC_API_KEY` by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[schemars(
+        title = "Max Turns",
+        description = "Maximum number of model requests per prompt, 50 by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+}
+
+impl ModelApi {
+    fn api_key(&self) -> Option<String> {
+        let var = self
+            .api_key_env
+            .as_deref()
+            .unwrap_or(self.provider.default_api_key_env());
+        std::env::var(var).ok().filter(|key| !key.is_empty())
+    }
+
+    fn client(&self) -> ModelClient {
+        ModelClient::new(
+            self.provider,
+            self.base_url
+                .clone()
+                .unwrap_or_else(|| self.provider.default_base_url().to_string()),
+            self.api_key(),
+            self.model.clone(),
+            self.max_tokens.unwrap_or(8192),
+        )
+    }
+
+    async fn spawn_with(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        resume_from: Option<&str>,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let store = SessionStore::default_location().map_err(ExecutorError::Io)?;
+        let messages = match resume_from {
+            Some(session_id) => store.load(session_id).map_err(ExecutorError::Io)?,
+            None => vec![],
+        };
+
+        // The process is a shell that runs the agent's commands; the loop itself runs here.
+        // Commands are wrapped in POSIX syntax, so Windows needs an `sh` (e.g. Git's) on PATH.
+        let shell = if cfg!(windows) {
+            "sh".to_string()
+        } else {
+            get_shell_command().0
+        };
+        let mut exec_command = ExecutionCommand::new(shell, vec![], current_dir.to_path_buf());
+        exec_command.kill_on_drop(true);
+        exec_command.stdin(StdioConfig::piped());
+        exec_command.stdout(StdioConfig::piped());
+        exec_command.stderr(StdioConfig::piped());
+        let mut child = runtime.spawn(exec_command).await?;
+
+        let shell = ShellSession::attach(&mut child)?;
+        let cancelled = shell.closed();
+        let mut writer = create_stdout_pipe_writer(&mut child)?;
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<ModelApiEvent>();
+
+        // Signal completion only after every event reached the log
+        tokio::spawn(async move {
+            while let Some(event) = event_rx.recv().await {
+                let mut line = serde_json::to_string(&event).unwrap_or_default();
+                line.push('\n');
+                let _ = writer.write_all(line.as_bytes()).await;
+            }
+            let _ = writer.flush().await;
+            let _ = exit_tx.send(());
+        });
+
+        let agent = AgentLoop {
+            client: self.client(),
+            tools: ToolContext::new(current_dir.to_path_buf(), Some(shell)),
+            system_prompt: self
+                .system_prompt
+                .clone()
+                .unwrap_or_else(|| DEFAULT_SYSTEM_PROMPT.to_string()),
+            max_turns: self.max_turns.unwrap_or(50),
+            events: event_tx,
+            cancelled: Some(cancelled),
+        };
+        let prompt = self.append_prompt.combine_prompt(prompt);
+        let session_id = uuid::Uuid::new_v4().to_string();
+        tokio::spawn(async move {
+            agent.run(&store, &session_id, messages, prompt).await;
+        });
+
+        Ok(SpawnedChild {
+            child,
+            exit_signal: Some(exit_rx),
+        })
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for ModelApi {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_with(current_dir, prompt, None, runtime).await
+    }
+
+    /// Follow-ups continue a copy of the stored conversation under a new session id
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        runtime: &dyn CommandRuntime,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_with(current_dir, prompt, Some(session_id), runtime)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+        let entry_index = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+
+        tokio::spawn(async move {
+            let mut indices: HashMap<String, usize> = HashMap::new();
+            let mut lines = msg_store.stdout_lines_stream();
+            while let Some(Ok(line)) = lines.next().await {
+                let Ok(event) = serde_json::from_str::<ModelApiEvent>(&line) else {
+                    continue;
+                };
+                match event {
+                    ModelApiEvent::SessionStart { session_id } => {
+                        msg_store.push_session_id(session_id);
+                    }
+                    ModelApiEvent::Entry { key, entry } => match indices.get(&key) {
+                        Some(&index) => {
+                            msg_store.push_patch(ConversationPatch::replace(index, entry))
+                        }
+                        None => {
+                            let index = entry_index.next();
+                            indices.insert(key, index);
+                            msg_store
+                                .push_patch(ConversationPatch::add_normalized_entry(index, entry));
+                        }
+                    },
+                }
+            }
+        });
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        // A custom endpoint (e.g. a local server) may not need a key
+        let auth_sources = if self.base_url.is_some() {
+            Vec::new()
+        } else {
+            vec![AuthSource::env(
+                self.api_key_env
+                    .as_deref()
+                    .unwrap_or(self.provider.default_api_key_env()),
+            )]
+        };
+        DiagnosticProbe {
+            auth_sources,
+            ..Default::default()
+        }
+    }
+
+    async fn check_availability(&self) -> bool {
+        self.api_key().is_some() || self.base_url.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use axum::{Json, Router, extract::State, routing::post};
+    use serde_json::{Value, json};
+
+    use super::{client::ChatMessage, *};
+    use crate::logs::{NormalizedEntryType, ToolStatus};
+
+    #[derive(Clone, Default)]
+    struct MockLlm {
+        responses: Arc<Mutex<VecDeque<Value>>>,
+        requests: Arc<Mutex<Vec<Value>>>,
+    }
+
+    async fn chat_completions(State(mock): State<MockLlm>, Json(body): Json<Value>) -> Json<Value> {
+        mock.requests.lock().unwrap().push(body);
+        let message = mock.responses.lock().unwrap().pop_front().unwrap();
+        Json(json!({ "choices": [{ "index": 0, "message": message }] }))
+    }
+
+    async fn start_mock(responses: Vec<Value>) -> (MockLlm, String) {
+        let mock = MockLlm {
+            responses: Arc::new(Mutex::new(responses.into())),
+            ..Default::default()
+        };
+        let app = Router::new()
+            .route("/chat/completions", post(chat_completions))
+            .with_state(mock.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (mock, url)
+    }
+
+    fn agent_loop(
+        base_url: String,
+        root: &Path,
+    ) -> (AgentLoop, mpsc::UnboundedReceiver<ModelApiEvent>) {
+        let (events, rx) = mpsc::unbounded_channel();
+        let agent = AgentLoop {
+            client: ModelClient::new(
+                ModelApiProvider::Openai,
+                base_url,
+                None,
+                "mock".into(),
+                1024,
+            ),
+            tools: ToolContext::new(root.to_path_buf(), None),
+            system_prompt: "test".into(),
+            max_turns: 5,
+            events,
+            cancelled: None,
+        };
+        (agent, rx)
+    }
+
+    #[tokio::test]
+    async fn tool_loop_against_mock_server() {
+        let worktree = tempfile::tempdir().unwrap();
+        let sessions = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(sessions.path()).unwrap();
+        let (mock, url) = start_mock(vec![
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {
+                        "name": "write_file",
+                        "arguments": "{\"path\":\"src/hello.txt\",\"content\":\"hello\"}"
+                    }
+                }]
+            }),
+            json!({ "role": "assistant", "content": "Wrote the file." }),
+            json!({ "role": "assistant", "content": "It says hello." }),
+        ])
+        .await;
+
+        let (agent, mut events) = agent_loop(url.clone(), worktree.path());
+        let messages = agent
+            .run(&store, "first", vec![], "Create hello.txt".into())
+            .await;
+
+        assert_eq!(
+            std::fs::read_to_string(worktree.path().join("src/hello.txt")).unwrap(),
+            "hello"
+        );
+        assert_eq!(messages.len(), 4);
+        assert_eq!(store.load("first").unwrap(), messages);
+
+        let mut tool_statuses = vec![];
+        let mut last_assistant = None;
+        while let Ok(event) = events.try_recv() {
+            if let ModelApiEvent::Entry { key, entry } = event {
+                match entry.entry_type {
+                    NormalizedEntryType::ToolUse { status, .. } => {
+                        assert_eq!(key, "tool-call_1");
+                        tool_statuses.push(status);
+                    }
+                    NormalizedEntryType::AssistantMessage => last_assistant = Some(entry.content),
+                    _ => {}
+                }
+            }
+        }
+        assert!(matches!(
+            tool_statuses.as_slice(),
+            [ToolStatus::Created, ToolStatus::Success]
+        ));
+        assert_eq!(last_assistant.as_deref(), Some("Wrote the file."));
+
+        // The tool result is sent back in the second request
+        let requests = mock.requests.lock().unwrap().clone();
+        let second = requests[1]["messages"].as_array().unwrap();
+        assert_eq!(second.last().unwrap()["role"], "tool");
+        assert_eq!(second.last().unwrap()["tool_call_id"], "call_1");
+
+        // A follow-up resumes the stored conversation
+        let (agent, _events) = agent_loop(url, worktree.path());
+        let resumed = agent
+            .run(
+                &store,
+                "second",
+                store.load("first").unwrap(),
+                "What does it say?".into(),
+            )
+            .await;
+        assert_eq!(resumed.len(), 6);
+        assert!(matches!(
+            resumed.last(),
+            Some(ChatMessage::Assistant { content: Some(text), .. }) if text == "It says hello."
+        ));
+        let third = mock.requests.lock().unwrap()[2]["messages"].clone();
+        // system + 4 stored messages + new prompt
+        assert_eq!(third.as_array().unwrap().len(), 6);
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fs, io, path::PathBuf};
+
+use super::client::ChatMessage;
+
+/// Stores each session's conversation as a JSON file so follow-ups can resume it
+pub struct SessionStore {
+    base_dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(base_dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let base_dir = base_dir.into();
+        fs::create_dir_all(&base_dir)?;
+        Ok(Self { base_dir })
+    }
+
+    /// `~/.anyon/model_api_sessions`, under `dev/` in debug builds
+    pub fn default_location() -> io::Result<Self> {
+        let mut dir = dirs::home_dir()
+            .ok_or_else(|| io::Error::other("Could not determine home directory"))?
+            .join(".anyon");
+        if cfg!(debug_assertions) {
+            dir = dir.join("dev");
+        }
+        Self::new(dir.join("model_api_sessions"))
+    }
+
+    pub fn load(&self, session_id: &str) -> io::Result<Vec<ChatMessage>> {
+        let content = fs::read_to_string(self.session_file_path(session_id)?)?;
+        serde_json::from_str(&content).map_err(io::Error::other)
+    }
+
+    pub fn save(&self, session_id: &str, messages: &[ChatMessage]) -> io::Result<()> {
+        let content = serde_json::to_string(messages).map_err(io::Error::other)?;
+        fs::write(self.session_file_path(session_id)?, content)
+    }
+
+    fn session_file_path(&self, session_id: &str) -> io::Result<PathBuf> {
+        if session_id.is_empty()
+            || !session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid session id `{session_id}`"),
+            ));
+        }
+        Ok(self.base_dir.join(format!("{session_id}.json")))
+    }
+}
@@ -0,0 +1,547 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use command_group::AsyncGroupChild;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::ChildStdin,
+    sync::{mpsc, watch},
+};
+use workspace_utils::diff::create_unified_diff;
+
+use super::client::ToolSpec;
+use crate::{
+    executors::ExecutorError,
+    logs::{ActionType, CommandExitStatus, CommandRunResult, FileChange},
+};
+
+/// Longest tool output handed back to the model
+const MAX_OUTPUT_CHARS: usize = 30_000;
+const MAX_SEARCH_MATCHES: usize = 200;
+const MAX_SEARCH_FILE_BYTES: u64 = 1024 * 1024;
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+pub fn tool_specs() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: "read_file",
+            description: "Read a text file in the workspace. Returns the file with 1-based line numbers.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path relative to the workspace root" },
+                    "offset": { "type": "integer", "description": "First line to return (1-based)" },
+                    "limit": { "type": "integer", "description": "Maximum number of lines to return" }
+                },
+                "required": ["path"]
+            }),
+        },
+        ToolSpec {
+            name: "write_file",
+            description: "Create or overwrite a file in the workspace with the given content.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "content": { "type": "string" }
+                },
+                "required": ["path", "content"]
+            }),
+        },
+        ToolSpec {
+            name: "edit_file",
+            description: "Replace exactly one occurrence of `old_text` with `new_text` in a file.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "old_text": { "type": "string" },
+                    "new_text": { "type": "string" }
+                },
+                "required": ["path", "old_text", "new_text"]
+            }),
+        },
+        ToolSpec {
+            name: "run_command",
+            description: "Run a shell command in the workspace root and return its combined output and exit code.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string" }
+                },
+                "required": ["command"]
+            }),
+        },
+        ToolSpec {
+            name: "search",
+            description: "Search file contents in the workspace with a regular expression.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" },
+                    "path": { "type": "string", "description": "Directory to search, the workspace root by default" }
+                },
+                "required": ["pattern"]
+            }),
+        },
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "name", content = "arguments", rename_all = "snake_case")]
+pub enum ToolInvocation {
+    ReadFile {
+        path: String,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    },
+    WriteFile {
+        path: String,
+        content: String,
+    },
+    EditFile {
+        path: String,
+        old_text: String,
+        new_text: String,
+    },
+    RunCommand {
+        command: String,
+    },
+    Search {
+        pattern: String,
+        path: Option<String>,
+    },
+}
+
+impl ToolInvocation {
+    pub fn parse(name: &str, arguments: &Value) -> Result<Self, String> {
+        serde_json::from_value(json!({ "name": name, "arguments": arguments }))
+            .map_err(|err| format!("Invalid call to `{name}`: {err}"))
+    }
+
+    /// Action shown while the tool is running
+    pub fn action(&self) -> ActionType {
+        match self {
+            Self::ReadFile { path, .. } => ActionType::FileRead { path: path.clone() },
+            Self::WriteFile { path, content } => ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![FileChange::Write {
+                    content: content.clone(),
+                }],
+            },
+            Self::EditFile { path, .. } => ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![],
+            },
+            Self::RunCommand { command } => ActionType::CommandRun {
+                command: command.clone(),
+                result: None,
+            },
+            Self::Search { pattern, .. } => ActionType::Search {
+                query: pattern.clone(),
+            },
+        }
+    }
+}
+
+/// Result of a tool call: what the model sees and what the UI shows
+pub struct ToolOutcome {
+    pub output: String,
+    pub success: bool,
+    pub action: ActionType,
+}
+
+impl ToolOutcome {
+    fn failed(action: ActionType, output: impl Into<String>) -> Self {
+        Self {
+            output: output.into(),
+            success: false,
+            action,
+        }
+    }
+}
+
+/// Executes tool calls against a workspace
+pub struct ToolContext {
+    root: PathBuf,
+    shell: Option<ShellSession>,
+}
+
+impl ToolContext {
+    pub fn new(root: PathBuf, shell: Option<ShellSession>) -> Self {
+        Self { root, shell }
+    }
+
+    pub async fn execute(&mut self, invocation: ToolInvocation) -> ToolOutcome {
+        let action = invocation.action();
+        match invocation {
+            ToolInvocation::ReadFile {
+                path,
+                offset,
+                limit,
+            } => match self.resolve(&path).and_then(std::fs::read_to_string) {
+                Ok(content) => {
+                    let start = offset.unwrap_or(1).max(1);
+                    let lines = content
+                        .lines()
+                        .enumerate()
+                        .skip(start - 1)
+                        .take(limit.unwrap_or(usize::MAX))
+                        .map(|(i, line)| format!("{:>6}\t{line}", i + 1))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ToolOutcome {
+                        output: truncate(lines),
+                        success: true,
+                        action,
+                    }
+                }
+                Err(err) => ToolOutcome::failed(action, format!("Failed to read {path}: {err}")),
+            },
+            ToolInvocation::WriteFile { path, content } => {
+                let result = self.resolve(&path).and_then(|full| {
+                    if let Some(parent) = full.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(full, &content)
+                });
+                match result {
+                    Ok(()) => ToolOutcome {
+                        output: format!("Wrote {} bytes to {path}", content.len()),
+                        success: true,
+                        action,
+                    },
+                    Err(err) => {
+                        ToolOutcome::failed(action, format!("Failed to write {path}: {err}"))
+                    }
+                }
+            }
+            ToolInvocation::EditFile {
+                path,
+                old_text,
+                new_text,
+            } => self.edit_file(action, &path, &old_text, &new_text),
+            ToolInvocation::RunCommand { command } => self.run_command(&command).await,
+            ToolInvocation::Search { pattern, path } => {
+                self.search(action, &pattern, path.as_deref())
+            }
+        }
+    }
+
+    fn edit_file(
+        &self,
+        action: ActionType,
+        path: &str,
+        old_text: &str,
+        new_text: &str,
+    ) -> ToolOutcome {
+        let full = match self.resolve(path) {
+            Ok(full) => full,
+            Err(err) => return ToolOutcome::failed(action, err.to_string()),
+        };
+        let original = match std::fs::read_to_string(&full) {
+            Ok(content) => content,
+            Err(err) => {
+                return ToolOutcome::failed(action, format!("Failed to read {path}: {err}"));
+            }
+        };
+        match original.matches(old_text).count() {
+            0 => return ToolOutcome::failed(action, format!("`old_text` not found in {path}")),
+            1 => {}
+            n => {
+                return ToolOutcome::failed(
+                    action,
+                    format!("`old_text` occurs {n} times in {path}; include more context"),
+                );
+            }
+        }
+
+        let updated = original.replacen(old_text, new_text, 1);
+        if let Err(err) = std::fs::write(&full, &updated) {
+            return ToolOutcome::failed(action, format!("Failed to write {path}: {err}"));
+        }
+        ToolOutcome {
+            output: format!("Edited {path}"),
+            success: true,
+            action: ActionType::FileEdit {
+                path: path.to_string(),
+                changes: vec![FileChange::Edit {
+                    unified_diff: create_unified_diff(path, &original, &updated),
+                    has_line_numbers: true,
+                }],
+            },
+        }
+    }
+
+    async fn run_command(&mut self, command: &str) -> ToolOutcome {
+        let Some(shell) = self.shell.as_mut() else {
+            return ToolOutcome::failed(
+                ActionType::CommandRun {
+                    command: command.to_string(),
+                    result: None,
+                },
+                "Shell commands are unavailable",
+            );
+        };
+
+        match shell.run(command).await {
+            Ok((exit_code, output)) => {
+                let output = truncate(output);
+                ToolOutcome {
+                    output: format!("Exit code: {exit_code}\n{output}"),
+                    success: exit_code == 0,
+                    action: ActionType::CommandRun {
+                        command: command.to_string(),
+                        result: Some(CommandRunResult {
+                            exit_status: Some(CommandExitStatus::ExitCode { code: exit_code }),
+                            output: Some(output),
+                        }),
+                    },
+                }
+            }
+            Err(err) => ToolOutcome::failed(
+                ActionType::CommandRun {
+                    command: command.to_string(),
+                    result: None,
+                },
+                format!("Failed to run command: {err}"),
+            ),
+        }
+    }
+
+    fn search(&self, action: ActionType, pattern: &str, path: Option<&str>) -> ToolOutcome {
+        let regex = match Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(err) => return ToolOutcome::failed(action, format!("Invalid pattern: {err}")),
+        };
+        let start = match self.resolve(path.unwrap_or(".")) {
+            Ok(start) => start,
+            Err(err) => return ToolOutcome::failed(action, err.to_string()),
+        };
+
+        let mut matches = Vec::new();
+        let mut pending = vec![start];
+        while let Some(current) = pending.pop() {
+            if matches.len() >= MAX_SEARCH_MATCHES {
+                break;
+            }
+            let metadata = match std::fs::symlink_metadata(&current) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                if let Ok(entries) = std::fs::read_dir(&current) {
+                    let mut children: Vec<_> = entries
+                        .filter_map(Result::ok)
+                        .filter(|e| {
+                            !SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+                        })
+                        .map(|e| e.path())
+                        .collect();
+                    children.sort();
+                    pending.extend(children.into_iter().rev());
+                }
+                continue;
+            }
+            if !metadata.is_file() || metadata.len() > MAX_SEARCH_FILE_BYTES {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(&current) else {
+                continue;
+            };
+            let rel = current
+                .strip_prefix(&self.root)
+                .unwrap_or(&current)
+                .to_string_lossy()
+                .replace('\\', "/");
+            for (i, line) in content.lines().enumerate() {
+                if regex.is_match(line) {
+                    matches.push(format!("{rel}:{}: {}", i + 1, line.trim_end()));
+                    if matches.len() >= MAX_SEARCH_MATCHES {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let output = if matches.is_empty() {
+            "No matches".to_string()
+        } else {
+            truncate(matches.join("\n"))
+        };
+        ToolOutcome {
+            output,
+            success: true,
+            action,
+        }
+    }
+
+    /// Resolve a workspace-relative path, rejecting paths that escape the workspace
+    fn resolve(&self, path: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(path)
+            .strip_prefix(&self.root)
+            .unwrap_or(Path::new(path));
+        let mut resolved = self.root.clone();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => resolved.push(part),
+                Component::CurDir => {}
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        format!("{path} is outside the workspace"),
+                    ));
+                }
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+fn truncate(mut output: String) -> String {
+    if output.len() > MAX_OUTPUT_CHARS {
+        let mut end = MAX_OUTPUT_CHARS;
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        output.truncate(end);
+        output.push_str("\n[output truncated]");
+    }
+    output
+}
+
+/// Long-lived POSIX shell that runs the agent's commands.
+///
+/// The shell is the executor's child process, so commands run wherever the
+/// `CommandRuntime` placed it and stopping the execution kills them.
+pub struct ShellSession {
+    stdin: ChildStdin,
+    output: mpsc::UnboundedReceiver<String>,
+    closed: watch::Receiver<bool>,
+}
+
+impl ShellSession {
+    /// Take over the shell child's stdin and stdout
+    pub fn attach(child: &mut AsyncGroupChild) -> Result<Self, ExecutorError> {
+        let stdin = child.inner().stdin.take().ok_or_else(|| {
+            ExecutorError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Child process has no stdin",
+            ))
+        })?;
+        let stdout = child.inner().stdout.take().ok_or_else(|| {
+            ExecutorError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Child process has no stdout",
+            ))
+        })?;
+
+        let (output_tx, output) = mpsc::unbounded_channel();
+        let (closed_tx, closed) = watch::channel(false);
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if output_tx.send(line).is_err() {
+                    break;
+                }
+            }
+            let _ = closed_tx.send(true);
+        });
+
+        Ok(Self {
+            stdin,
+            output,
+            closed,
+        })
+    }
+
+    /// Receiver that turns true once the shell has exited
+    pub fn closed(&self) -> watch::Receiver<bool> {
+        self.closed.clone()
+    }
+
+    /// Run `command` in a subshell and return its exit code and combined output
+    pub async fn run(&mut self, command: &str) -> io::Result<(i32, String)> {
+        let marker = format!("__MODEL_API_DONE_{}__", uuid::Uuid::new_v4().simple());
+        let script =
+            format!("(\n{command}\n) </dev/null 2>&1; printf '\\n%s %s\\n' '{marker}' \"$?\"\n");
+        self.stdin.write_all(script.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        let mut output = Vec::new();
+        while let Some(line) = self.output.recv().await {
+            if let Some(code) = line.strip_prefix(&marker) {
+                let exit_code = code.trim().parse().unwrap_or(-1);
+                return Ok((exit_code, output.join("\n").trim_end().to_string()));
+            }
+            output.push(line);
+        }
+        Err(io::Error::new(
+            io::ErrorKind::BrokenPipe,
+            "shell exited before the command finished",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn edit_file_requires_unique_match() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\ntwo\n").unwrap();
+        let mut tools = ToolContext::new(dir.path().to_path_buf(), None);
+
+        let ambiguous = ToolInvocation::parse(
+            "edit_file",
+            &json!({ "path": "a.txt", "old_text": "two", "new_text": "three" }),
+        )
+        .unwrap();
+        assert!(!tools.execute(ambiguous).await.success);
+
+        let unique = ToolInvocation::parse(
+            "edit_file",
+            &json!({ "path": "a.txt", "old_text": "one", "new_text": "zero" }),
+        )
+        .unwrap();
+        let outcome = tools.execute(unique).await;
+        assert!(outcome.success);
+        assert!(
+            matches!(outcome.action, ActionType::FileEdit { ref changes, .. } if changes.len() == 1)
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "zero\ntwo\ntwo\n"
+        );
+
+        let escape = ToolInvocation::parse("read_file", &json!({ "path": "../secret" })).unwrap();
+        assert!(!tools.execute(escape).await.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shell_session_runs_commands_in_subshells() {
+        use std::process::Stdio;
+
+        use command_group::AsyncCommandGroup;
+
+        let mut child = tokio::process::Command::new("sh")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .group_spawn()
+            .unwrap();
+        let mut shell = ShellSession::attach(&mut child).unwrap();
+
+        assert_eq!(
+            shell.run("echo hi; echo err >&2; exit 3").await.unwrap(),
+            (3, "hi\nerr".to_string())
+        );
+        assert_eq!(shell.run("true").await.unwrap(), (0, String::new()));
+    }
+}
@@ -21,6 +21,7 @@ use crate::{
         CmdOverrides, CommandBuilder, CommandRuntime, ExecutionCommand, StdioConfig,
         apply_overrides,
     },
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         opencode::share_bridge::Bridge as ShareBridge,
@@ -107,9 +108,11 @@ pub struct Opencode {
     pub cmd: CmdOverrides,
 }
 
+const BASE_COMMAND: &str = "npx -y opencode-ai@0.15.8";
+
 impl Opencode {
     fn build_command_builder(&self) -> CommandBuilder {
-        let mut builder = CommandBuilder::new("npx -y opencode-ai@0.15.8 run").params([
+        let mut builder = CommandBuilder::new(format!("{BASE_COMMAND} run")).params([
             "--print-logs",
             "--log-level",
             "ERROR",
@@ -310,6 +313,14 @@ impl StandardCodingAgentExecutor for Opencode {
             dirs::config_dir().map(|config| config.join("opencode").join("opencode.json"))
         }
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(BASE_COMMAND, &self.cmd).with_auth([
+            AuthSource::home_file(".local/share/opencode/auth.json"),
+            Some(AuthSource::env("ANTHROPIC_API_KEY")),
+            Some(AuthSource::env("OPENAI_API_KEY")),
+        ])
+    }
 }
 impl Opencode {
     const SHARE_PREFIX: &'static str = "[oc-share] ";
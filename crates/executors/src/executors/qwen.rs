@@ -8,6 +8,7 @@ use workspace_utils::msg_store::MsgStore;
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, CommandRuntime, apply_overrides},
+    diagnostics::{AuthSource, DiagnosticProbe},
     executors::{
         AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
         gemini::AcpAgentHarness,
@@ -24,9 +25,11 @@ pub struct QwenCode {
     pub cmd: CmdOverrides,
 }
 
+const BASE_COMMAND: &str = "npx -y @qwen-code/qwen-code@0.0.14";
+
 impl QwenCode {
     fn build_command_builder(&self) -> CommandBuilder {
-        let mut builder = CommandBuilder::new("npx -y @qwen-code/qwen-code@0.0.14");
+        let mut builder = CommandBuilder::new(BASE_COMMAND);
 
         if self.yolo.unwrap_or(false) {
             builder = builder.extend_params(["--yolo"]);
@@ -81,4 +84,11 @@ impl StandardCodingAgentExecutor for QwenCode {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".qwen").join("settings.json"))
     }
+
+    async fn diagnostic_probe(&self) -> DiagnosticProbe {
+        DiagnosticProbe::cli(BASE_COMMAND, &self.cmd).with_auth([
+            AuthSource::home_file(".qwen/oauth_creds.json"),
+            Some(AuthSource::env("OPENAI_API_KEY")),
+        ])
+    }
 }
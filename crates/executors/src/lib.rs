@@ -1,8 +1,10 @@
 pub mod actions;
 pub mod approvals;
 pub mod command;
+pub mod diagnostics;
 pub mod executors;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
 pub mod stdout_dup;
+pub mod tool_cache;
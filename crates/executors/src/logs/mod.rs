@@ -51,6 +51,8 @@ pub struct NormalizedConversation {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum NormalizedEntryError {
     SetupRequired,
+    /// The provider reported a rate or usage limit
+    RateLimited,
     Other,
 }
 
@@ -81,6 +83,23 @@ pub enum NormalizedEntryType {
     },
 }
 
+impl NormalizedEntryType {
+    /// Short label for transcripts, e.g. `assistant` or `tool:Bash`; None for entries that
+    /// are only UI state
+    pub fn label(&self) -> Option<String> {
+        Some(match self {
+            Self::UserMessage => "user".to_string(),
+            Self::UserFeedback { .. } => "feedback".to_string(),
+            Self::AssistantMessage => "assistant".to_string(),
+            Self::ToolUse { tool_name, .. } => format!("tool:{tool_name}"),
+            Self::SystemMessage => "system".to_string(),
+            Self::ErrorMessage { .. } => "error".to_string(),
+            Self::Thinking => "thinking".to_string(),
+            Self::Loading | Self::NextAction { .. } => return None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct NormalizedEntry {
     pub timestamp: Option<String>,
@@ -1,7 +1,8 @@
 //! Standard stderr log processor for executors
 //!
 //! Uses `PlainTextLogProcessor` with a 2-second `latency_threshold` to split stderr streams into entries.
-//! Each entry is normalized as `ErrorMessage` and emitted as JSON patches to the message store.
+//! Each entry is normalized as `ErrorMessage`, typed `RateLimited` when it reports a rate limit,
+//! and emitted as JSON patches to the message store.
 //!
 //! Example:
 //! ```rust,ignore
@@ -11,6 +12,8 @@
 use std::{sync::Arc, time::Duration};
 
 use futures::StreamExt;
+use lazy_static::lazy_static;
+use regex::Regex;
 use workspace_utils::msg_store::MsgStore;
 
 use super::{
@@ -19,6 +22,28 @@ use super::{
 };
 use crate::logs::utils::EntryIndexProvider;
 
+lazy_static! {
+    /// Provider error types and API error lines, not any mention of a limit: agents print
+    /// file names, counts and tool output on stderr too
+    static ref RATE_LIMIT_RE: Regex = Regex::new(
+        r"(?ix)
+        \b(rate_limit_error|rate_limit_exceeded|overloaded_error|resource_exhausted)\b
+        | \b429\s+too\s+many\s+requests\b
+        | \b(api\s+error|status(\s+code)?|http(/[\d.]+)?)[\s:=]+(429|529)\b
+        | \busage\s+limit\s+reached\b
+        | \brate\s+limit\s+(reached|exceeded)\b
+        | \bexceeded\s+your\s+current\s+quota\b
+        | \bquota\s+exceeded\s+for\s+quota\s+metric\b
+        "
+    )
+    .unwrap();
+}
+
+/// Whether a stderr chunk reports that the provider is rate limiting the agent
+pub fn is_rate_limit_message(content: &str) -> bool {
+    RATE_LIMIT_RE.is_match(content)
+}
+
 /// Standard stderr log normalizer that uses PlainTextLogProcessor to stream error logs.
 ///
 /// Splits stderr output into discrete entries based on a latency threshold (2s) to group
@@ -42,13 +67,19 @@ pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: Ent
 
         // Create a processor with time-based emission for stderr
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content: strip_ansi_escapes::strip_str(&content),
-                metadata: None,
+            .normalized_entry_producer(Box::new(|content: String| {
+                let content = strip_ansi_escapes::strip_str(&content);
+                let error_type = if is_rate_limit_message(&content) {
+                    NormalizedEntryError::RateLimited
+                } else {
+                    NormalizedEntryError::Other
+                };
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage { error_type },
+                    content,
+                    metadata: None,
+                }
             }))
             .time_gap(Duration::from_secs(2)) // Break messages if they are 2 seconds apart
             .index_provider(entry_index_provider)
@@ -61,3 +92,39 @@ pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: Ent
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_rate_limit_message;
+
+    #[test]
+    fn provider_rate_limit_errors_are_detected() {
+        for line in [
+            r#"API Error: 429 {"type":"error","error":{"type":"rate_limit_error"}}"#,
+            r#"API Error: 529 {"type":"error","error":{"type":"overloaded_error"}}"#,
+            "Claude AI usage limit reached|1760000000",
+            "ERROR: exceeded retry limit, last status: 429 Too Many Requests",
+            "You exceeded your current quota, please check your plan and billing details.",
+            r#"{"error":{"code":429,"status":"RESOURCE_EXHAUSTED"}}"#,
+            "Quota exceeded for quota metric 'Generate Content API requests per minute'",
+            "Rate limit reached for gpt-5 on tokens per min",
+        ] {
+            assert!(is_rate_limit_message(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn ordinary_stderr_is_not_a_rate_limit() {
+        for line in [
+            "Compiling 429 crates",
+            "warning: unused import in src/rate_limit.rs",
+            "test rate_limiter::tests::refills ... ok",
+            "The build machine is overloaded, this may take a while",
+            "npm WARN deprecated usage-limit@1.0.0",
+            "Retrying request (attempt 2 of 5)",
+            "error: line 429: expected `;`",
+        ] {
+            assert!(!is_rate_limit_message(line), "{line}");
+        }
+    }
+}
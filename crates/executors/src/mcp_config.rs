@@ -2,11 +2,15 @@
 //!
 //! These helpers abstract over JSON vs TOML formats used by different agents.
 
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use tokio::fs;
+use tokio::{fs, process::Command};
 use ts_rs::TS;
 
 use crate::executors::{CodingAgent, ExecutorError};
@@ -288,20 +292,452 @@ fn apply_adapter(adapter: Adapter, canonical: Value) -> Value {
     }
 }
 
+/// How an executor picks up MCP servers that only apply to a single run, without touching
+/// the user's global agent config
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunMcpServers {
+    /// Arguments appended to the agent command
+    Args(Vec<String>),
+    /// Environment variable holding extra agent config
+    Env { key: &'static str, value: String },
+    /// Config file the agent reads from its working directory
+    WorkspaceFile { path: &'static str, content: Value },
+    /// Servers handed to the agent when it creates a session, in `mcpServers` format
+    Session(Map<String, Value>),
+}
+
+impl RunMcpServers {
+    /// Write the run's servers into a workspace config file, keeping whatever else the file
+    /// holds. Servers written by an earlier run are replaced, so a server removed from the
+    /// project disappears from the file too. The file is kept out of the attempt's commits:
+    /// an untracked one through the repository's `info/exclude`, a tracked one by marking it
+    /// skip-worktree. Returns false when an existing file isn't a JSON object and was left
+    /// in place.
+    pub async fn write_workspace_file(
+        current_dir: &Path,
+        path: &str,
+        content: &Value,
+    ) -> Result<bool, ExecutorError> {
+        let file = current_dir.join(path);
+        let existing = match fs::read_to_string(&file).await {
+            Ok(existing) => Some(existing),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        let git_paths = match WorktreeGitPaths::resolve(current_dir).await {
+            Ok(git_paths) => Some(git_paths),
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to locate the git directory of {}: {err}",
+                    current_dir.display()
+                );
+                None
+            }
+        };
+        let mut injected = match &git_paths {
+            Some(git_paths) => git_paths.read_injected().await,
+            None => Map::new(),
+        };
+        let entry = git_paths
+            .as_ref()
+            .map_or_else(|| path.to_string(), |git_paths| git_paths.entry(path));
+
+        let merged = match existing {
+            None => content.clone(),
+            Some(existing) => {
+                let Ok(Value::Object(mut merged)) = serde_json::from_str::<Value>(&existing) else {
+                    return Ok(false);
+                };
+                let previous = injected.get(&entry).cloned().unwrap_or_default();
+                merge_servers(&mut merged, &previous, content);
+                Value::Object(merged)
+            }
+        };
+
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&file, serde_json::to_string_pretty(&merged)?).await?;
+
+        if let Some(git_paths) = &git_paths {
+            if let Err(err) = git_paths.keep_out_of_commits(current_dir, path).await {
+                tracing::warn!(
+                    "Failed to keep {} out of git in {}: {err}",
+                    path,
+                    current_dir.display()
+                );
+            }
+            injected.insert(entry, server_names(content));
+            if let Err(err) = git_paths.write_injected(&injected).await {
+                tracing::warn!("Failed to record the MCP servers written to {path}: {err}");
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Merge the server maps of `content` into `file`, dropping the servers listed in `previous`
+/// (written by an earlier run) first. `previous` maps each key of `content` to server names.
+fn merge_servers(file: &mut Map<String, Value>, previous: &Value, content: &Value) {
+    let Some(content) = content.as_object() else {
+        return;
+    };
+    for (key, servers) in content {
+        let existing = file
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        match (existing, servers) {
+            (Value::Object(existing), Value::Object(servers)) => {
+                let stale = previous.get(key).and_then(Value::as_array);
+                for name in stale.into_iter().flatten().filter_map(Value::as_str) {
+                    existing.remove(name);
+                }
+                existing.extend(servers.clone());
+            }
+            (existing, servers) => *existing = servers.clone(),
+        }
+    }
+}
+
+/// Names of the servers under each key of `content`
+fn server_names(content: &Value) -> Value {
+    let names = content
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, servers)| {
+            let names = servers.as_object()?.keys().cloned().map(Value::String);
+            Some((key.clone(), Value::Array(names.collect())))
+        });
+    Value::Object(names.collect())
+}
+
+/// Paths git uses for the worktree containing a directory
+struct WorktreeGitPaths {
+    /// The directory relative to the worktree root, ending in `/` unless empty
+    prefix: String,
+    /// `info/exclude`, shared by all worktrees of the repository
+    exclude: PathBuf,
+    /// Servers written into each workspace file, kept per worktree
+    injected: PathBuf,
+}
+
+impl WorktreeGitPaths {
+    async fn resolve(current_dir: &Path) -> std::io::Result<Self> {
+        let stdout = git(
+            current_dir,
+            &[
+                "rev-parse",
+                "--show-prefix",
+                "--git-path",
+                "info/exclude",
+                "--git-path",
+                "anyon-mcp-servers.json",
+            ],
+        )
+        .await?;
+        let mut lines = stdout.lines();
+        let (Some(prefix), Some(exclude), Some(injected)) =
+            (lines.next(), lines.next(), lines.next())
+        else {
+            return Err(std::io::Error::other("unexpected git rev-parse output"));
+        };
+        Ok(Self {
+            prefix: prefix.to_string(),
+            exclude: current_dir.join(exclude),
+            injected: current_dir.join(injected),
+        })
+    }
+
+    /// `path` (relative to the directory) relative to the worktree root
+    fn entry(&self, path: &str) -> String {
+        format!("{}{path}", self.prefix)
+    }
+
+    async fn read_injected(&self) -> Map<String, Value> {
+        match fs::read_to_string(&self.injected).await {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_default(),
+            Err(_) => Map::new(),
+        }
+    }
+
+    async fn write_injected(&self, injected: &Map<String, Value>) -> std::io::Result<()> {
+        fs::write(&self.injected, serde_json::to_string_pretty(injected)?).await
+    }
+
+    async fn keep_out_of_commits(&self, current_dir: &Path, path: &str) -> std::io::Result<()> {
+        let tracked = git(current_dir, &["ls-files", "--error-unmatch", "--", path])
+            .await
+            .is_ok();
+        if tracked {
+            git(
+                current_dir,
+                &["update-index", "--skip-worktree", "--", path],
+            )
+            .await?;
+            return Ok(());
+        }
+        self.exclude(path).await
+    }
+
+    /// Append `path` to `info/exclude`
+    async fn exclude(&self, path: &str) -> std::io::Result<()> {
+        let entry = format!("/{}", self.entry(path));
+        let existing = match fs::read_to_string(&self.exclude).await {
+            Ok(existing) => existing,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        if existing.lines().any(|line| line.trim() == entry) {
+            return Ok(());
+        }
+        if let Some(parent) = self.exclude.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let mut updated = existing;
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&entry);
+        updated.push('\n');
+        fs::write(&self.exclude, updated).await
+    }
+}
+
+/// Run git in `current_dir` and return its stdout
+async fn git(current_dir: &Path, args: &[&str]) -> std::io::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(current_dir)
+        .args(args)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Flatten a server map into Codex `-c key=value` overrides, which take TOML values
+fn codex_config_overrides(servers: &Value) -> Vec<String> {
+    fn flatten(prefix: String, value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    flatten(format!("{prefix}.{}", toml_key(key)), value, out);
+                }
+            }
+            Value::Null => {}
+            value => {
+                if let Ok(value) = serde_json::from_value::<toml::Value>(value.clone()) {
+                    out.push("-c".to_string());
+                    out.push(format!("{prefix}={value}"));
+                }
+            }
+        }
+    }
+
+    fn toml_key(key: &str) -> String {
+        if !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            key.to_string()
+        } else {
+            toml::Value::String(key.to_string()).to_string()
+        }
+    }
+
+    let mut out = Vec::new();
+    flatten("mcp_servers".to_string(), servers, &mut out);
+    out
+}
+
 impl CodingAgent {
-    pub fn preconfigured_mcp(&self) -> Value {
+    /// Inject `servers` (canonical `mcpServers` format) for one run, or None when the
+    /// executor has no way to take extra servers
+    pub fn run_mcp_servers(&self, servers: &Map<String, Value>) -> Option<RunMcpServers> {
+        if servers.is_empty() {
+            return None;
+        }
+        let adapted = apply_adapter(self.mcp_adapter(), Value::Object(servers.clone()));
+
+        match self {
+            CodingAgent::ClaudeCode(_) => Some(RunMcpServers::Args(vec![
+                "--mcp-config".to_string(),
+                serde_json::json!({ "mcpServers": adapted }).to_string(),
+            ])),
+            CodingAgent::Copilot(_) => Some(RunMcpServers::Args(vec![
+                "--additional-mcp-config".to_string(),
+                serde_json::json!({ "mcpServers": adapted }).to_string(),
+            ])),
+            CodingAgent::Amp(_) => Some(RunMcpServers::Args(vec![
+                "--mcp-config".to_string(),
+                adapted.to_string(),
+            ])),
+            CodingAgent::Codex(_) => Some(RunMcpServers::Args(codex_config_overrides(&adapted))),
+            CodingAgent::Opencode(_) => Some(RunMcpServers::Env {
+                key: "OPENCODE_CONFIG_CONTENT",
+                value: serde_json::json!({ "mcp": adapted }).to_string(),
+            }),
+            CodingAgent::Gemini(_) => Some(RunMcpServers::WorkspaceFile {
+                path: ".gemini/settings.json",
+                content: serde_json::json!({ "mcpServers": adapted }),
+            }),
+            CodingAgent::QwenCode(_) => Some(RunMcpServers::WorkspaceFile {
+                path: ".qwen/settings.json",
+                content: serde_json::json!({ "mcpServers": adapted }),
+            }),
+            CodingAgent::CursorAgent(_) => Some(RunMcpServers::WorkspaceFile {
+                path: ".cursor/mcp.json",
+                content: serde_json::json!({ "mcpServers": adapted }),
+            }),
+            CodingAgent::Acp(_) => adapted.as_object().cloned().map(RunMcpServers::Session),
+            CodingAgent::CustomAgent(_) | CodingAgent::ModelApi(_) => None,
+        }
+    }
+
+    fn mcp_adapter(&self) -> Adapter {
         use Adapter::*;
 
-        let adapter = match self {
-            CodingAgent::ClaudeCode(_) | CodingAgent::Amp(_) => Passthrough,
+        match self {
+            CodingAgent::ClaudeCode(_)
+            | CodingAgent::Amp(_)
+            | CodingAgent::CustomAgent(_)
+            | CodingAgent::Acp(_)
+            | CodingAgent::ModelApi(_) => Passthrough,
             CodingAgent::QwenCode(_) | CodingAgent::Gemini(_) => Gemini,
             CodingAgent::CursorAgent(_) => Cursor,
             CodingAgent::Codex(_) => Codex,
             CodingAgent::Opencode(_) => Opencode,
             CodingAgent::Copilot(..) => Copilot,
-        };
+        }
+    }
 
+    pub fn preconfigured_mcp(&self) -> Value {
         let canonical = PRECONFIGURED_MCP_SERVERS.clone();
-        apply_adapter(adapter, canonical)
+        apply_adapter(self.mcp_adapter(), canonical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn codex_servers_become_config_overrides() {
+        let servers = json!({
+            "docs": { "command": "npx", "args": ["-y", "docs-mcp"], "env": { "API_KEY": "x" } }
+        });
+        assert_eq!(
+            codex_config_overrides(&servers),
+            vec![
+                "-c",
+                r#"mcp_servers.docs.command="npx""#,
+                "-c",
+                r#"mcp_servers.docs.args=["-y", "docs-mcp"]"#,
+                "-c",
+                r#"mcp_servers.docs.env.API_KEY="x""#,
+            ]
+        );
+    }
+
+    fn git_repo() -> (tempfile::TempDir, impl Fn(&[&str]) -> String) {
+        let repo = tempfile::TempDir::new().unwrap();
+        let path = repo.path().to_path_buf();
+        let git = move |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&path)
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        git(&["init", "-q"]);
+        (repo, git)
+    }
+
+    #[tokio::test]
+    async fn workspace_files_are_excluded_from_git() {
+        let (repo, git) = git_repo();
+        let current_dir = repo.path().join("app");
+        std::fs::create_dir_all(current_dir.join(".gemini")).unwrap();
+        std::fs::write(current_dir.join(".gemini/.gitignore"), "cache/\n").unwrap();
+        git(&["add", "-A"]);
+
+        let written = RunMcpServers::write_workspace_file(
+            &current_dir,
+            ".gemini/settings.json",
+            &json!({ "mcpServers": {} }),
+        )
+        .await
+        .unwrap();
+        assert!(written);
+
+        let exclude = std::fs::read_to_string(repo.path().join(".git/info/exclude")).unwrap();
+        assert!(
+            exclude
+                .lines()
+                .any(|line| line == "/app/.gemini/settings.json")
+        );
+        assert_eq!(
+            git(&["status", "--porcelain", "--untracked-files=all"]),
+            "A  app/.gemini/.gitignore\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn workspace_files_are_rewritten_around_user_servers() {
+        let (repo, git) = git_repo();
+        let file = repo.path().join(".cursor/mcp.json");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(
+            &file,
+            json!({ "mcpServers": { "mine": { "command": "mine" } }, "theme": "dark" }).to_string(),
+        )
+        .unwrap();
+        git(&["add", "-A"]);
+        git(&[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-qm",
+            "init",
+        ]);
+
+        for servers in [
+            json!({ "docs": { "command": "docs" } }),
+            json!({ "search": { "command": "search" } }),
+        ] {
+            let written = RunMcpServers::write_workspace_file(
+                repo.path(),
+                ".cursor/mcp.json",
+                &json!({ "mcpServers": servers }),
+            )
+            .await
+            .unwrap();
+            assert!(written);
+        }
+
+        let content: Value =
+            serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(
+            content,
+            json!({
+                "mcpServers": { "mine": { "command": "mine" }, "search": { "command": "search" } },
+                "theme": "dark",
+            })
+        );
+        // The tracked file's injected servers never reach a commit
+        assert_eq!(git(&["status", "--porcelain"]), "");
     }
 }
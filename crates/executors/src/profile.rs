@@ -6,7 +6,11 @@ use serde::{Deserialize, Deserializer, Serialize, de::Error as DeError};
 use thiserror::Error;
 use ts_rs::TS;
 
-use crate::executors::{BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor};
+use crate::{
+    executors::{BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType},
+    tool_cache::NpmPackage,
+};
 
 /// Return the canonical form for variant keys.
 /// – "DEFAULT" is kept as-is  
@@ -166,9 +170,88 @@ impl ExecutorConfig {
     }
 }
 
+/// Condition under which a failed coding agent run moves on to the next executor in its chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FallbackTrigger {
+    /// The executor process exited with a non-zero code
+    NonZeroExit,
+    /// The provider reported a rate or usage limit
+    RateLimit,
+    /// The conversation contains an error entry of this type
+    Error { error_type: NormalizedEntryError },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct FallbackChain {
+    /// Executors to try, in order, once the primary executor fails
+    pub chain: Vec<ExecutorProfileId>,
+    /// Conditions that hand the attempt over to the next executor
+    #[serde(default = "FallbackChain::default_triggers")]
+    pub triggers: Vec<FallbackTrigger>,
+}
+
+impl FallbackChain {
+    fn default_triggers() -> Vec<FallbackTrigger> {
+        vec![FallbackTrigger::NonZeroExit, FallbackTrigger::RateLimit]
+    }
+
+    /// Return the first configured trigger matched by a finished run
+    pub fn triggered_by(
+        &self,
+        exit_code: Option<i64>,
+        entries: &[NormalizedEntry],
+    ) -> Option<&FallbackTrigger> {
+        let has_error = |wanted: &NormalizedEntryError| {
+            entries.iter().any(|entry| {
+                matches!(
+                    &entry.entry_type,
+                    NormalizedEntryType::ErrorMessage { error_type } if error_type == wanted
+                )
+            })
+        };
+        self.triggers.iter().find(|trigger| match trigger {
+            FallbackTrigger::NonZeroExit => exit_code.is_some_and(|code| code != 0),
+            FallbackTrigger::RateLimit => has_error(&NormalizedEntryError::RateLimited),
+            FallbackTrigger::Error { error_type } => has_error(error_type),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct ToolCacheConfig {
+    /// Run agents through npx when their pinned version isn't in the tool cache. Off by default
+    /// so agents never download code from the npm registry unless this is turned on.
+    #[serde(default = "ToolCacheConfig::default_allow_npx_fallback")]
+    pub allow_npx_fallback: bool,
+}
+
+impl ToolCacheConfig {
+    fn default_allow_npx_fallback() -> bool {
+        false
+    }
+
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl Default for ToolCacheConfig {
+    fn default() -> Self {
+        Self {
+            allow_npx_fallback: Self::default_allow_npx_fallback(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ExecutorConfigs {
     pub executors: HashMap<BaseCodingAgent, ExecutorConfig>,
+    /// Fallback chains keyed by executor profile ("CLAUDE_CODE" or "CLAUDE_CODE:PLAN")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fallbacks: HashMap<String, FallbackChain>,
+    #[serde(default, skip_serializing_if = "ToolCacheConfig::is_default")]
+    pub tools: ToolCacheConfig,
 }
 
 impl ExecutorConfigs {
@@ -277,6 +360,9 @@ impl ExecutorConfigs {
                 }
             }
         }
+        // Fallback chains are replaced as a whole
+        defaults.fallbacks.extend(overrides.fallbacks);
+        defaults.tools = overrides.tools;
         defaults
     }
 
@@ -284,6 +370,8 @@ impl ExecutorConfigs {
     fn compute_overrides(defaults: &Self, current: &Self) -> Result<Self, ProfileError> {
         let mut overrides = Self {
             executors: HashMap::new(),
+            fallbacks: HashMap::new(),
+            tools: current.tools.clone(),
         };
 
         // Fast scan for any illegal deletions BEFORE allocating/cloning
@@ -343,6 +431,14 @@ impl ExecutorConfigs {
             }
         }
 
+        for (profile_key, chain) in &current.fallbacks {
+            if defaults.fallbacks.get(profile_key) != Some(chain) {
+                overrides
+                    .fallbacks
+                    .insert(profile_key.clone(), chain.clone());
+            }
+        }
+
         Ok(overrides)
     }
 
@@ -372,6 +468,17 @@ impl ExecutorConfigs {
                 }
             }
         }
+
+        // Every fallback target must resolve to a configured executor
+        for (profile_key, fallback) in &merged.fallbacks {
+            for target in &fallback.chain {
+                if merged.get_coding_agent(target).is_none() {
+                    return Err(ProfileError::Validation(format!(
+                        "Fallback '{target}' for '{profile_key}' is not a configured executor profile"
+                    )));
+                }
+            }
+        }
         Ok(())
     }
 
@@ -397,6 +504,38 @@ impl ExecutorConfigs {
             .cloned()
     }
 
+    /// Pin (or unpin, with `None`) the npm package version a profile launches
+    pub fn pin_version(
+        &mut self,
+        executor_profile_id: &ExecutorProfileId,
+        version: Option<String>,
+    ) -> Result<(), ProfileError> {
+        if let Some(version) = &version
+            && !NpmPackage::is_valid_version(version)
+        {
+            return Err(ProfileError::Validation(format!(
+                "'{version}' is not an exact semver version"
+            )));
+        }
+        let variant = executor_profile_id.variant.as_deref().unwrap_or("DEFAULT");
+        let agent = self
+            .executors
+            .get_mut(&executor_profile_id.executor)
+            .and_then(|executor| executor.configurations.get_mut(variant))
+            .ok_or_else(|| {
+                ProfileError::Validation(format!(
+                    "Unknown executor profile '{executor_profile_id}'"
+                ))
+            })?;
+        let cmd = agent.cmd_overrides_mut().ok_or_else(|| {
+            ProfileError::Validation(format!(
+                "Executor profile '{executor_profile_id}' does not launch a CLI"
+            ))
+        })?;
+        cmd.version = version;
+        Ok(())
+    }
+
     pub fn get_coding_agent_or_default(
         &self,
         executor_profile_id: &ExecutorProfileId,
@@ -409,6 +548,20 @@ impl ExecutorConfigs {
                     .expect("No default variant found")
             })
     }
+
+    /// Fallback chain for a profile, falling back to the chain of its executor's default variant
+    pub fn fallback_chain(
+        &self,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Option<&FallbackChain> {
+        self.fallbacks
+            .get(&executor_profile_id.to_string())
+            .or_else(|| {
+                self.fallbacks
+                    .get(&to_default_variant(executor_profile_id).to_string())
+            })
+    }
+
     /// Get the first available executor profile for new users
     pub async fn get_recommended_executor_profile(
         &self,
@@ -432,3 +585,57 @@ pub fn to_default_variant(id: &ExecutorProfileId) -> ExecutorProfileId {
         variant: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_entry(error_type: NormalizedEntryError) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ErrorMessage { error_type },
+            content: String::new(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn fallback_triggers_match_failures() {
+        let chain: FallbackChain =
+            serde_json::from_str(r#"{ "chain": [{ "executor": "CODEX" }] }"#).unwrap();
+        assert_eq!(chain.triggers, FallbackChain::default_triggers());
+
+        assert_eq!(chain.triggered_by(Some(0), &[]), None);
+        assert_eq!(
+            chain.triggered_by(Some(1), &[]),
+            Some(&FallbackTrigger::NonZeroExit)
+        );
+        assert_eq!(
+            chain.triggered_by(Some(0), &[error_entry(NormalizedEntryError::RateLimited)]),
+            Some(&FallbackTrigger::RateLimit)
+        );
+        assert_eq!(
+            chain.triggered_by(Some(0), &[error_entry(NormalizedEntryError::SetupRequired)]),
+            None
+        );
+    }
+
+    #[test]
+    fn fallback_chain_uses_default_variant() {
+        let mut configs = ExecutorConfigs::from_defaults();
+        let chain = FallbackChain {
+            chain: vec![ExecutorProfileId::new(BaseCodingAgent::Codex)],
+            triggers: vec![FallbackTrigger::RateLimit],
+        };
+        configs
+            .fallbacks
+            .insert("CLAUDE_CODE".to_string(), chain.clone());
+
+        let plan = ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLAN".into());
+        assert_eq!(configs.fallback_chain(&plan), Some(&chain));
+        assert_eq!(
+            configs.fallback_chain(&ExecutorProfileId::new(BaseCodingAgent::Amp)),
+            None
+        );
+    }
+}
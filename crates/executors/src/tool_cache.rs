@@ -0,0 +1,396 @@
+//! Pinned agent CLIs installed ahead of time so executors can run without the npm registry
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use workspace_utils::shell::resolve_executable_path;
+
+#[derive(Debug, Error)]
+pub enum ToolCacheError {
+    #[error("npm was not found on PATH")]
+    NpmNotFound,
+    #[error("npm install {package} failed: {message}")]
+    InstallFailed { package: String, message: String },
+    #[error("{0} does not provide an executable")]
+    MissingBinary(String),
+    #[error("'{0}' is not a valid npm package version")]
+    InvalidVersion(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// An exact npm package version, e.g. `@anthropic-ai/claude-code@2.0.31`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+pub struct NpmPackage {
+    pub name: String,
+    pub version: String,
+}
+
+impl NpmPackage {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+
+    /// Parse `name@version`; specs without a version are rejected because they can't be pinned,
+    /// and so are aliases (`npm:other@1.0.0`), URLs and paths
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (name, version) = spec.rsplit_once('@')?;
+        if !is_valid_name(name) || !Self::is_valid_version(version) {
+            return None;
+        }
+        Some(Self::new(name, version))
+    }
+
+    /// An exact semver version, such as `2.0.31` or `1.0.0-beta.2+build`. Dist-tags like
+    /// `latest` move to new releases, so they can't pin anything. Versions become part of npm
+    /// specs and cache paths, so anything else is refused.
+    pub fn is_valid_version(version: &str) -> bool {
+        let is_identifiers = |text: &str| {
+            text.split('.').all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+        };
+        let (version, build) = version
+            .split_once('+')
+            .map_or((version, None), |(version, build)| (version, Some(build)));
+        let (core, pre) = version
+            .split_once('-')
+            .map_or((version, None), |(core, pre)| (core, Some(pre)));
+        let core: Vec<_> = core.split('.').collect();
+        core.len() == 3
+            && core
+                .iter()
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+            && pre.is_none_or(is_identifiers)
+            && build.is_none_or(is_identifiers)
+    }
+
+    fn is_valid(&self) -> bool {
+        is_valid_name(&self.name) && Self::is_valid_version(&self.version)
+    }
+
+    /// Whether `program` is npx, which downloads what it runs from the npm registry
+    pub fn is_npx(program: &str) -> bool {
+        Path::new(program)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            == Some("npx")
+    }
+
+    /// Package spec of an npx invocation as written, pinned or not
+    pub fn npx_spec(args: &[String]) -> String {
+        args.iter()
+            .find(|arg| !arg.starts_with('-'))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Package launched by an `npx [-y] <spec> ...` invocation, with the index of the spec in `args`
+    pub fn from_npx(program: &str, args: &[String]) -> Option<(Self, usize)> {
+        if !Self::is_npx(program) {
+            return None;
+        }
+        for (index, arg) in args.iter().enumerate() {
+            match arg.as_str() {
+                "-y" | "--yes" => continue,
+                // The executable comes from a different package than the spec, so we can't map it
+                "-p" | "--package" | "-c" | "--call" => return None,
+                spec => return Self::parse(spec).map(|package| (package, index)),
+            }
+        }
+        None
+    }
+
+    /// Same npx invocation with the package version replaced
+    pub fn pin_npx_base(base: &str, version: &str) -> Option<String> {
+        if !Self::is_valid_version(version) {
+            return None;
+        }
+        let parts = shlex::split(base)?;
+        let (program, args) = parts.split_first()?;
+        let (package, index) = Self::from_npx(program, args)?;
+        let mut args = args.to_vec();
+        args[index] = Self::new(package.name, version).to_string();
+        let mut pinned = vec![program.clone()];
+        pinned.extend(args);
+        shlex::try_join(pinned.iter().map(String::as_str)).ok()
+    }
+
+    fn dir_name(&self) -> String {
+        format!("{}@{}", self.name.replace('/', "+"), self.version)
+    }
+
+    fn from_dir_name(dir_name: &str) -> Option<Self> {
+        Self::parse(&dir_name.replace('+', "/"))
+    }
+}
+
+impl fmt::Display for NpmPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+/// Directory of npm packages installed side by side, one prefix per package version
+#[derive(Debug, Clone)]
+pub struct ToolCache {
+    root: PathBuf,
+}
+
+impl ToolCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// `ANYON_TOOL_CACHE_DIR`, or the `tools` folder of the data directory
+    pub fn global() -> Self {
+        match std::env::var_os("ANYON_TOOL_CACHE_DIR") {
+            Some(dir) => Self::new(dir),
+            None => Self::new(workspace_utils::assets::asset_dir().join("tools")),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn install_dir(&self, package: &NpmPackage) -> Result<PathBuf, ToolCacheError> {
+        if !package.is_valid() {
+            return Err(ToolCacheError::InvalidVersion(package.to_string()));
+        }
+        Ok(self.root.join(package.dir_name()))
+    }
+
+    /// Installed executable for the package, if it has been cached
+    pub fn binary(&self, package: &NpmPackage) -> Option<PathBuf> {
+        let prefix = self.install_dir(package).ok()?;
+        let bin = bin_name(&prefix, package)?;
+        let bin_dir = prefix.join("node_modules").join(".bin");
+        let path = if cfg!(windows) {
+            bin_dir.join(format!("{bin}.cmd"))
+        } else {
+            bin_dir.join(bin)
+        };
+        path.exists().then_some(path)
+    }
+
+    pub fn installed(&self) -> Vec<NpmPackage> {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+        let mut packages: Vec<NpmPackage> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| NpmPackage::from_dir_name(entry.file_name().to_str()?))
+            .filter(|package| self.binary(package).is_some())
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name).then(a.version.cmp(&b.version)));
+        packages
+    }
+
+    /// Install a package version with npm, replacing any previous install of that version
+    pub async fn install(&self, package: &NpmPackage) -> Result<PathBuf, ToolCacheError> {
+        let target = self.install_dir(package)?;
+        let npm = resolve_executable_path("npm")
+            .await
+            .ok_or(ToolCacheError::NpmNotFound)?;
+
+        // Install into a scratch prefix so a failed install never leaves a half-populated entry
+        let staging = self.root.join(format!(".{}.partial", package.dir_name()));
+        if staging.exists() {
+            tokio::fs::remove_dir_all(&staging).await?;
+        }
+        tokio::fs::create_dir_all(&staging).await?;
+
+        let output = Command::new(npm)
+            .arg("install")
+            .arg("--prefix")
+            .arg(&staging)
+            .args(["--no-audit", "--no-fund", "--no-save"])
+            .arg(package.to_string())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        if !output.status.success() {
+            let _ = tokio::fs::remove_dir_all(&staging).await;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ToolCacheError::InstallFailed {
+                package: package.to_string(),
+                message: stderr
+                    .lines()
+                    .rev()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or("no output")
+                    .trim()
+                    .to_string(),
+            });
+        }
+
+        if target.exists() {
+            tokio::fs::remove_dir_all(&target).await?;
+        }
+        tokio::fs::rename(&staging, &target).await?;
+        self.binary(package)
+            .ok_or_else(|| ToolCacheError::MissingBinary(package.to_string()))
+    }
+
+    pub async fn remove(&self, package: &NpmPackage) -> Result<(), ToolCacheError> {
+        let dir = self.install_dir(package)?;
+        if dir.exists() {
+            tokio::fs::remove_dir_all(dir).await?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite an `npx <package>` invocation to run the cached binary directly.
+    ///
+    /// Returns `None` when the command doesn't launch an npm package or the package isn't cached.
+    pub fn resolve(&self, program: &str, args: &[String]) -> Option<(PathBuf, Vec<String>)> {
+        let (package, index) = NpmPackage::from_npx(program, args)?;
+        let binary = self.binary(&package)?;
+        Some((binary, args[index + 1..].to_vec()))
+    }
+}
+
+/// An npm package name, optionally scoped: `pkg` or `@scope/pkg`
+fn is_valid_name(name: &str) -> bool {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+    };
+    match name.strip_prefix('@') {
+        Some(scoped) => scoped
+            .split_once('/')
+            .is_some_and(|(scope, package)| valid_part(scope) && valid_part(package)),
+        None => valid_part(name),
+    }
+}
+
+/// Executable name declared in the installed package's `package.json`
+fn bin_name(prefix: &Path, package: &NpmPackage) -> Option<String> {
+    let unscoped = package.name.rsplit('/').next().unwrap_or(&package.name);
+    let manifest = prefix
+        .join("node_modules")
+        .join(&package.name)
+        .join("package.json");
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(manifest).ok()?).ok()?;
+
+    match manifest.get("bin")? {
+        serde_json::Value::String(_) => Some(unscoped.to_string()),
+        serde_json::Value::Object(bins) if bins.contains_key(unscoped) => {
+            Some(unscoped.to_string())
+        }
+        // npx only picks a bin automatically when there is exactly one
+        serde_json::Value::Object(bins) if bins.len() == 1 => bins.keys().next().cloned(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_install(cache: &ToolCache, package: &NpmPackage, bin: &str) {
+        let prefix = cache.install_dir(package).unwrap();
+        let package_dir = prefix.join("node_modules").join(&package.name);
+        std::fs::create_dir_all(&package_dir).unwrap();
+        std::fs::write(
+            package_dir.join("package.json"),
+            format!(r#"{{"bin": {{"{bin}": "cli.js"}}}}"#),
+        )
+        .unwrap();
+        let bin_dir = prefix.join("node_modules").join(".bin");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        let bin_file = if cfg!(windows) {
+            format!("{bin}.cmd")
+        } else {
+            bin.to_string()
+        };
+        std::fs::write(bin_dir.join(bin_file), "").unwrap();
+    }
+
+    #[test]
+    fn parses_npx_invocations() {
+        let args = ["-y", "@anthropic-ai/claude-code@2.0.31", "-p"].map(String::from);
+        let (package, index) = NpmPackage::from_npx("npx", &args).unwrap();
+        assert_eq!(
+            package,
+            NpmPackage::new("@anthropic-ai/claude-code", "2.0.31")
+        );
+        assert_eq!(index, 1);
+
+        assert!(NpmPackage::from_npx("npx", &["-y".into(), "@scope/pkg".into()]).is_none());
+        assert!(NpmPackage::from_npx("node", &["cli.js".into()]).is_none());
+        assert!(NpmPackage::parse("@openai/codex@npm:other-pkg@1.0.0").is_none());
+        assert_eq!(
+            NpmPackage::pin_npx_base("npx -y @openai/codex@0.55.0", "0.60.1").unwrap(),
+            "npx -y @openai/codex@0.60.1"
+        );
+    }
+
+    #[test]
+    fn rejects_versions_that_are_not_exact_semver() {
+        for version in ["2.0.31", "1.0.0-beta.2+build.5", "0.0.1761050239-g36fe88"] {
+            assert!(NpmPackage::is_valid_version(version), "{version}");
+        }
+        for version in [
+            "",
+            "latest",
+            "next",
+            "v1.0.0",
+            "1.0",
+            "1.0.0-",
+            "1.0.0-beta..1",
+            "../../x",
+            "1.0/..",
+            "npm:other-pkg@1.0.0",
+            "file:x",
+            "^1.0",
+        ] {
+            assert!(!NpmPackage::is_valid_version(version), "{version}");
+        }
+        assert!(NpmPackage::pin_npx_base("npx -y @openai/codex@0.55.0", "../../x").is_none());
+    }
+
+    #[tokio::test]
+    async fn refuses_to_touch_paths_outside_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ToolCache::new(dir.path().join("tools"));
+        let package = NpmPackage::new("@openai/codex", "../../x");
+        assert!(matches!(
+            cache.install(&package).await,
+            Err(ToolCacheError::InvalidVersion(_))
+        ));
+        assert!(cache.remove(&package).await.is_err());
+        assert!(cache.binary(&package).is_none());
+    }
+
+    #[test]
+    fn resolves_cached_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ToolCache::new(dir.path());
+        let package = NpmPackage::new("@musistudio/claude-code-router", "1.0.58");
+        let args = ["-y", "@musistudio/claude-code-router@1.0.58", "code"].map(String::from);
+        assert!(cache.resolve("npx", &args).is_none());
+
+        fake_install(&cache, &package, "ccr");
+        let (binary, rest) = cache.resolve("npx", &args).unwrap();
+        assert!(binary.ends_with(if cfg!(windows) { "ccr.cmd" } else { "ccr" }));
+        assert_eq!(rest, vec!["code".to_string()]);
+        assert_eq!(cache.installed(), vec![package]);
+    }
+}
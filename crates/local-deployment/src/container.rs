@@ -20,23 +20,28 @@ use db::{
         image::TaskImage,
         merge::Merge,
         project::Project,
+        project_mcp_server::ProjectMcpServer,
+        project_repository::ProjectRepository,
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
+        task_attempt_repository::TaskAttemptRepository,
     },
 };
 use deployment::DeploymentError;
 use executors::{
-    actions::{Executable, ExecutorAction},
+    actions::{Executable, ExecutorAction, ExecutorActionType},
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
-    command::CommandRuntime,
+    command::{CommandRuntime, ExtendedCommandRuntime},
     executors::BaseCodingAgent,
     logs::{
-        NormalizedEntryType,
+        ActionType, NormalizedEntryType,
         utils::{
             ConversationPatch,
             patch::{escape_json_pointer_segment, extract_normalized_entry_from_patch},
         },
     },
+    mcp_config::RunMcpServers,
+    profile::ExecutorConfigs,
 };
 use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use serde_json::json;
@@ -47,15 +52,19 @@ use services::services::{
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{Commit, DiffTarget, GitService},
+    git_cli::GitCli,
     image::ImageService,
     notification::NotificationService,
-    worktree_manager::WorktreeManager,
+    project_secrets::{ProjectSecretsService, SecretTarget},
+    sparse_checkout::SparseCone,
+    worktree_manager::{WorktreeManager, WorktreeOptions},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    secret_mask::SecretMasker,
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
@@ -72,6 +81,7 @@ pub struct LocalContainerService {
     image_service: ImageService,
     analytics: Option<AnalyticsContext>,
     approvals: Approvals,
+    project_secrets: ProjectSecretsService,
 }
 
 impl LocalContainerService {
@@ -83,6 +93,7 @@ impl LocalContainerService {
         image_service: ImageService,
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
+        project_secrets: ProjectSecretsService,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
 
@@ -95,6 +106,7 @@ impl LocalContainerService {
             image_service,
             analytics,
             approvals,
+            project_secrets,
         }
     }
 
@@ -208,6 +220,12 @@ impl LocalContainerService {
                 continue;
             }
 
+            // Multi-repository workspaces hold the primary worktree one level down
+            if WorktreeManager::is_workspace_dir(&path) {
+                self.cleanup_orphaned_workspace(&path).await;
+                continue;
+            }
+
             let worktree_path_str = path.to_string_lossy().to_string();
             if let Ok(false) =
                 TaskAttempt::container_ref_exists(&self.db().pool, &worktree_path_str).await
@@ -230,6 +248,86 @@ impl LocalContainerService {
         }
     }
 
+    /// Delete a workspace directory if none of its worktrees belongs to a task attempt
+    async fn cleanup_orphaned_workspace(&self, workspace_path: &Path) {
+        let Ok(entries) = std::fs::read_dir(workspace_path) else {
+            return;
+        };
+        let worktrees: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        for worktree in &worktrees {
+            let worktree_path_str = worktree.to_string_lossy().to_string();
+            if !matches!(
+                TaskAttempt::container_ref_exists(&self.db().pool, &worktree_path_str).await,
+                Ok(false)
+            ) {
+                return;
+            }
+        }
+
+        tracing::info!("Found orphaned workspace: {}", workspace_path.display());
+        for worktree in &worktrees {
+            if let Err(e) = WorktreeManager::cleanup_worktree(worktree, None).await {
+                tracing::error!(
+                    "Failed to remove orphaned worktree {}: {}",
+                    worktree.display(),
+                    e
+                );
+            }
+        }
+        if let Err(e) = WorktreeManager::cleanup_workspace_dir(workspace_path).await {
+            tracing::error!(
+                "Failed to remove orphaned workspace {}: {}",
+                workspace_path.display(),
+                e
+            );
+        }
+    }
+
+    /// Remove the worktrees of a project's additional repositories along with the
+    /// workspace directory that holds them next to the primary worktree
+    async fn cleanup_attempt_repositories(db: &DBService, attempt_id: Uuid, worktree_path: &Path) {
+        let repositories = TaskAttemptRepository::find_by_task_attempt_id(&db.pool, attempt_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to load repositories for attempt {attempt_id}: {e}");
+                Vec::new()
+            });
+        for repository in repositories {
+            let git_repo_path =
+                ProjectRepository::find_by_id(&db.pool, repository.project_repository_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|r| r.git_repo_path);
+            WorktreeManager::cleanup_worktree(
+                Path::new(&repository.worktree_path),
+                git_repo_path.as_deref(),
+            )
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to clean up worktree {} for attempt {}: {}",
+                    repository.worktree_path,
+                    attempt_id,
+                    e
+                );
+            });
+        }
+        if let Some(workspace_path) = WorktreeManager::workspace_root(worktree_path)
+            && let Err(e) = WorktreeManager::cleanup_workspace_dir(&workspace_path).await
+        {
+            tracing::warn!(
+                "Failed to remove workspace {} for attempt {}: {}",
+                workspace_path.display(),
+                attempt_id,
+                e
+            );
+        }
+    }
+
     pub async fn cleanup_expired_attempt(
         db: &DBService,
         attempt_id: Uuid,
@@ -237,6 +335,7 @@ impl LocalContainerService {
         git_repo_path: PathBuf,
     ) -> Result<(), DeploymentError> {
         WorktreeManager::cleanup_worktree(&worktree_path, Some(&git_repo_path)).await?;
+        Self::cleanup_attempt_repositories(db, attempt_id, &worktree_path).await;
         // Mark worktree as deleted in database after successful cleanup
         TaskAttempt::mark_worktree_deleted(&db.pool, attempt_id).await?;
         tracing::info!("Successfully marked worktree as deleted for attempt {attempt_id}",);
@@ -374,7 +473,16 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                // A fallback executor continues the attempt, so nothing is committed or finalized yet
+                let fallback_started = match container.try_start_fallback(&ctx).await {
+                    Ok(started) => started,
+                    Err(e) => {
+                        tracing::error!("Failed to start fallback executor: {}", e);
+                        false
+                    }
+                };
+
+                if !fallback_started && (success || cleanup_done) {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -410,7 +518,7 @@ impl LocalContainerService {
                     }
                 }
 
-                if Self::should_finalize(&ctx) {
+                if !fallback_started && Self::should_finalize(&ctx) {
                     Self::finalize_task(&db, &config, &ctx).await;
                     // After finalization, check if a queued follow-up exists and start it
                     if let Err(e) = container.try_consume_queued_followup(&ctx).await {
@@ -514,7 +622,281 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(attempt_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
+    /// Directory name of the primary repository inside a multi-repository workspace
+    fn primary_repo_dir_name(project: &Project) -> String {
+        project
+            .git_repo_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "primary".to_string())
+    }
+
+    /// Worktree options for the project's primary repository
+    fn worktree_options(project: &Project) -> WorktreeOptions {
+        WorktreeOptions {
+            init_submodules: project.init_submodules,
+            sparse_cone: project
+                .sparse_checkout_patterns
+                .as_deref()
+                .and_then(SparseCone::parse),
+        }
+    }
+
+    /// Sparse patterns are specific to the primary repository, so additional ones are full
+    fn repository_worktree_options(project: &Project) -> WorktreeOptions {
+        WorktreeOptions {
+            init_submodules: project.init_submodules,
+            sparse_cone: None,
+        }
+    }
+
+    /// Runtime for one execution: the project's secrets as environment variables plus, for
+    /// coding agents, its MCP servers. Also returns a masker for the exposed secret values.
+    async fn execution_runtime<'a>(
+        &self,
+        task_attempt: &TaskAttempt,
+        executor_action: &ExecutorAction,
+        current_dir: &Path,
+        runtime: &'a dyn CommandRuntime,
+    ) -> Result<(ExtendedCommandRuntime<'a>, SecretMasker), ContainerError> {
+        let mut extended = ExtendedCommandRuntime::new(runtime);
+        let Some(task) = task_attempt.parent_task(&self.db.pool).await? else {
+            return Ok((extended, SecretMasker::default()));
+        };
+
+        let target = match executor_action.typ() {
+            ExecutorActionType::ScriptRequest(_) => SecretTarget::Script,
+            _ => SecretTarget::CodingAgent,
+        };
+        let env = self
+            .project_secrets
+            .env_for(task.project_id, target)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!("Failed to load project secrets: {e}")))?;
+        let masker = SecretMasker::new(env.iter().map(|(_, value)| value));
+        for (key, value) in env {
+            extended = extended.with_env(key, value);
+        }
+
+        let extended = self
+            .with_project_mcp_servers(task.project_id, executor_action, current_dir, extended)
+            .await?;
+        Ok((extended, masker))
+    }
+
+    /// Layer the project's MCP servers over the agent's global config for one coding agent run,
+    /// on the command line, in files written to the worktree or in the agent's new session.
+    async fn with_project_mcp_servers<'a>(
+        &self,
+        project_id: Uuid,
+        executor_action: &ExecutorAction,
+        current_dir: &Path,
+        runtime: ExtendedCommandRuntime<'a>,
+    ) -> Result<ExtendedCommandRuntime<'a>, ContainerError> {
+        let executor_profile_id = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::ScriptRequest(_) => return Ok(runtime),
+        };
+        let servers = ProjectMcpServer::servers_for_project(&self.db.pool, project_id).await?;
+        if servers.is_empty() {
+            return Ok(runtime);
+        }
+        let Some(agent) = ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)
+        else {
+            return Ok(runtime);
+        };
+
+        match agent.run_mcp_servers(&servers) {
+            Some(RunMcpServers::Args(args)) => Ok(runtime.with_args(args)),
+            Some(RunMcpServers::Env { key, value }) => Ok(runtime.with_env(key, value)),
+            Some(RunMcpServers::Session(servers)) => Ok(runtime.with_session_mcp_servers(servers)),
+            Some(RunMcpServers::WorkspaceFile { path, content }) => {
+                if !RunMcpServers::write_workspace_file(current_dir, path, &content).await? {
+                    tracing::warn!(
+                        "Not injecting project MCP servers: {} in {} is not a JSON object",
+                        path,
+                        current_dir.display()
+                    );
+                }
+                Ok(runtime)
+            }
+            None => {
+                tracing::warn!(
+                    "{} does not support project MCP servers; using its global config only",
+                    executor_profile_id
+                );
+                Ok(runtime)
+            }
+        }
+    }
+
+    /// Watch an execution's normalized logs and add directories the agent reads or edits
+    /// outside the sparse cone of `worktree_path` to the checkout.
+    fn spawn_sparse_expander(
+        &self,
+        execution_id: Uuid,
+        worktree_path: PathBuf,
+        current_dir: PathBuf,
+    ) {
+        // Read the live cone so expansions from earlier executions are kept
+        let Some(mut cone) = GitCli::new()
+            .sparse_checkout_list(&worktree_path)
+            .ok()
+            .and_then(|dirs| SparseCone::parse(&dirs.join(",")))
+        else {
+            return;
+        };
+        let msg_stores = self.msg_stores.clone();
+
+        tokio::spawn(async move {
+            let Some(store) = msg_stores.read().await.get(&execution_id).cloned() else {
+                return;
+            };
+            let mut stream = store.history_plus_stream();
+            while let Some(Ok(msg)) = stream.next().await {
+                let patch = match msg {
+                    LogMsg::JsonPatch(patch) => patch,
+                    LogMsg::Finished => break,
+                    _ => continue,
+                };
+                let Some((_, entry)) = extract_normalized_entry_from_patch(&patch) else {
+                    continue;
+                };
+                let path = match entry.entry_type {
+                    NormalizedEntryType::ToolUse {
+                        action_type:
+                            ActionType::FileRead { path } | ActionType::FileEdit { path, .. },
+                        ..
+                    } => PathBuf::from(path),
+                    _ => continue,
+                };
+                let path = if path.is_absolute() {
+                    path
+                } else {
+                    current_dir.join(path)
+                };
+                let Ok(rel_path) = path.strip_prefix(&worktree_path) else {
+                    continue;
+                };
+                let Some(dir) = cone.expansion_for(&rel_path.to_string_lossy()) else {
+                    continue;
+                };
+
+                let worktree_path = worktree_path.clone();
+                let dirs = vec![dir.clone()];
+                let result = tokio::task::spawn_blocking(move || {
+                    GitCli::new().sparse_checkout_add(&worktree_path, &dirs)
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => {
+                        tracing::info!(
+                            "Expanded sparse checkout of execution {} with {}",
+                            execution_id,
+                            dir
+                        );
+                        cone.add(&dir);
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!("Failed to expand sparse checkout with {}: {}", dir, e)
+                    }
+                    Err(e) => tracing::warn!("Sparse checkout expansion task failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Create a worktree on the attempt branch for every additional project repository
+    async fn create_repository_worktrees(
+        &self,
+        task_attempt: &TaskAttempt,
+        repositories: &[ProjectRepository],
+        workspace_path: &Path,
+        options: &WorktreeOptions,
+    ) -> Result<(), ContainerError> {
+        for (index, repository) in repositories.iter().enumerate() {
+            if let Err(err) = self
+                .create_repository_worktree(task_attempt, repository, workspace_path, options)
+                .await
+            {
+                // Leave no partial workspace behind; the failed worktree may be half created
+                for created in repositories[..=index].iter().rev() {
+                    let worktree_path = workspace_path.join(&created.name);
+                    WorktreeManager::cleanup_worktree(&worktree_path, Some(&created.git_repo_path))
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::warn!(
+                                "Failed to roll back worktree {} for task attempt {}: {}",
+                                worktree_path.display(),
+                                task_attempt.id,
+                                e
+                            );
+                        });
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    async fn create_repository_worktree(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository: &ProjectRepository,
+        workspace_path: &Path,
+        options: &WorktreeOptions,
+    ) -> Result<(), ContainerError> {
+        let target_branch = match &repository.default_target_branch {
+            Some(branch) => branch.clone(),
+            None => self
+                .git()
+                .get_current_branch(&repository.git_repo_path)
+                .map_err(|e| {
+                    ContainerError::Other(anyhow!(
+                        "Failed to read current branch of {}: {e}",
+                        repository.git_repo_path.display()
+                    ))
+                })?,
+        };
+        let worktree_path = workspace_path.join(&repository.name);
+
+        WorktreeManager::create_worktree(
+            &repository.git_repo_path,
+            &task_attempt.branch,
+            &worktree_path,
+            &target_branch,
+            true, // create new branch
+            options,
+        )
+        .await
+        .map_err(|err| {
+            let context_message = format!(
+                "Failed to prepare repository '{}' at {} for task attempt {}",
+                repository.name,
+                worktree_path.display(),
+                task_attempt.id
+            );
+            ContainerError::Other(anyhow::Error::new(err).context(context_message))
+        })?;
+
+        TaskAttemptRepository::upsert(
+            &self.db.pool,
+            task_attempt.id,
+            repository.id,
+            &worktree_path.to_string_lossy(),
+            &target_branch,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        masker: SecretMasker,
+    ) {
         let store = Arc::new(MsgStore::new());
 
         let out = child.inner().stdout.take().expect("no stdout");
@@ -532,7 +914,9 @@ impl LocalContainerService {
 
         // Merge and forward into the store
         let merged = select(out, err); // Stream<Item = Result<LogMsg, io::Error>>
-        let debounced = utils::stream_ext::debounce_logs(merged);
+        // Mask secrets before anything reaches the store, which also persists the logs
+        let masked = utils::secret_mask::mask_logs(merged, masker);
+        let debounced = utils::stream_ext::debounce_logs(Box::pin(masked));
         store.clone().spawn_forwarder(debounced);
 
         let mut map = self.msg_stores().write().await;
@@ -662,7 +1046,9 @@ impl ContainerService for LocalContainerService {
     }
 
     fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf {
-        PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default())
+        let worktree_path = PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default());
+        // Agents for multi-repository projects run in the directory holding every worktree
+        WorktreeManager::workspace_root(&worktree_path).unwrap_or(worktree_path)
     }
     /// Create a container
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError> {
@@ -673,19 +1059,31 @@ impl ContainerService for LocalContainerService {
 
         let worktree_dir_name =
             LocalContainerService::dir_name_from_task_attempt(&task_attempt.id, &task.title);
-        let worktree_path = WorktreeManager::get_worktree_base_dir().join(&worktree_dir_name);
 
         let project = task
             .parent_project(&self.db.pool)
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
+        // Projects with additional repositories get a workspace directory holding one
+        // worktree per repository; the primary worktree remains the container ref
+        let repositories = ProjectRepository::find_by_project_id(&self.db.pool, project.id).await?;
+        let workspace_path = WorktreeManager::get_worktree_base_dir().join(&worktree_dir_name);
+        let worktree_path = if repositories.is_empty() {
+            workspace_path.clone()
+        } else {
+            WorktreeManager::create_workspace_dir(&workspace_path).await?;
+            workspace_path.join(Self::primary_repo_dir_name(&project))
+        };
+
+        let worktree_options = Self::worktree_options(&project);
         WorktreeManager::create_worktree(
             &project.git_repo_path,
             &task_attempt.branch,
             &worktree_path,
             &task_attempt.target_branch,
             true, // create new branch
+            &worktree_options,
         )
         .await
         .map_err(|err| {
@@ -727,6 +1125,39 @@ If the project directory has moved or been deleted, update the project's reposit
             tracing::warn!("Failed to copy task images to worktree: {}", e);
         }
 
+        if !repositories.is_empty()
+            && let Err(err) = self
+                .create_repository_worktrees(
+                    task_attempt,
+                    &repositories,
+                    &workspace_path,
+                    &Self::repository_worktree_options(&project),
+                )
+                .await
+        {
+            // The attempt has no container until every repository is ready
+            WorktreeManager::cleanup_worktree(&worktree_path, Some(&project.git_repo_path))
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Failed to roll back worktree for task attempt {}: {}",
+                        task_attempt.id,
+                        e
+                    );
+                });
+            WorktreeManager::cleanup_workspace_dir(&workspace_path)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Failed to remove workspace {} for task attempt {}: {}",
+                        workspace_path.display(),
+                        task_attempt.id,
+                        e
+                    );
+                });
+            return Err(err);
+        }
+
         // Update both container_ref and branch in the database
         TaskAttempt::update_container_ref(
             &self.db.pool,
@@ -752,18 +1183,17 @@ If the project directory has moved or been deleted, update the project's reposit
                 None
             }
         };
-        WorktreeManager::cleanup_worktree(
-            &PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default()),
-            git_repo_path.as_deref(),
-        )
-        .await
-        .unwrap_or_else(|e| {
-            tracing::warn!(
-                "Failed to clean up worktree for task attempt {}: {}",
-                task_attempt.id,
-                e
-            );
-        });
+        let worktree_path = PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default());
+        WorktreeManager::cleanup_worktree(&worktree_path, git_repo_path.as_deref())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to clean up worktree for task attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+            });
+        Self::cleanup_attempt_repositories(&self.db, task_attempt.id, &worktree_path).await;
         Ok(())
     }
 
@@ -787,17 +1217,53 @@ If the project directory has moved or been deleted, update the project's reposit
         })?;
         let worktree_path = PathBuf::from(container_ref);
 
+        let worktree_options = Self::worktree_options(&project);
+        let repository_options = Self::repository_worktree_options(&project);
         WorktreeManager::ensure_worktree_exists(
             &project.git_repo_path,
             &task_attempt.branch,
             &worktree_path,
+            &worktree_options,
         )
         .await?;
 
+        let repositories =
+            TaskAttemptRepository::find_by_task_attempt_id(&self.db.pool, task_attempt.id).await?;
+        if !repositories.is_empty()
+            && let Some(workspace_path) = worktree_path.parent()
+            && !WorktreeManager::is_workspace_dir(workspace_path)
+        {
+            WorktreeManager::create_workspace_dir(workspace_path).await?;
+        }
+        for repository in repositories {
+            let Some(project_repository) =
+                ProjectRepository::find_by_id(&self.db.pool, repository.project_repository_id)
+                    .await?
+            else {
+                continue;
+            };
+            WorktreeManager::ensure_worktree_exists(
+                &project_repository.git_repo_path,
+                &task_attempt.branch,
+                Path::new(&repository.worktree_path),
+                &repository_options,
+            )
+            .await?;
+        }
+
         Ok(container_ref.to_string())
     }
 
     async fn is_container_clean(&self, task_attempt: &TaskAttempt) -> Result<bool, ContainerError> {
+        let repositories =
+            TaskAttemptRepository::find_by_task_attempt_id(&self.db.pool, task_attempt.id).await?;
+        for repository in repositories {
+            let path = Path::new(&repository.worktree_path);
+            if path.exists() && !self.git().is_worktree_clean(path)? {
+                return Ok(false);
+            }
+        }
+
         if let Some(container_ref) = &task_attempt.container_ref {
             // If container_ref is set, check if the worktree exists
             let path = PathBuf::from(container_ref);
@@ -818,14 +1284,12 @@ If the project directory has moved or been deleted, update the project's reposit
         executor_action: &ExecutorAction,
         runtime: &dyn CommandRuntime,
     ) -> Result<(), ContainerError> {
-        // Get the worktree path
-        let container_ref = task_attempt
-            .container_ref
-            .as_ref()
-            .ok_or(ContainerError::Other(anyhow!(
+        if task_attempt.container_ref.is_none() {
+            return Err(ContainerError::Other(anyhow!(
                 "Container ref not found for task attempt"
-            )))?;
-        let current_dir = PathBuf::from(container_ref);
+            )));
+        }
+        let current_dir = self.task_attempt_to_current_dir(task_attempt);
 
         let approvals_service: Arc<dyn ExecutorApprovalService> =
             match executor_action.base_executor() {
@@ -839,14 +1303,32 @@ If the project directory has moved or been deleted, update the project's reposit
                 _ => Arc::new(NoopExecutorApprovalService {}),
             };
 
+        let (runtime, masker) = self
+            .execution_runtime(task_attempt, executor_action, &current_dir, runtime)
+            .await?;
+
         // Create the child and stream, add to execution tracker
         let mut spawned = executor_action
-            .spawn(&current_dir, approvals_service, runtime)
+            .spawn(&current_dir, approvals_service, &runtime)
             .await?;
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child, masker)
             .await;
 
+        // Coding agents may wander outside a sparse checkout; follow them when enabled
+        if executor_action.base_executor().is_some()
+            && let Some(task) = task_attempt.parent_task(&self.db.pool).await?
+            && let Some(project) = task.parent_project(&self.db.pool).await?
+            && project.sparse_auto_expand
+            && let Some(container_ref) = &task_attempt.container_ref
+        {
+            self.spawn_sparse_expander(
+                execution_process.id,
+                PathBuf::from(container_ref),
+                current_dir.clone(),
+            );
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -972,6 +1454,50 @@ If the project directory has moved or been deleted, update the project's reposit
         Ok(Box::pin(wrapper))
     }
 
+    async fn stream_repository_diff(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository: &TaskAttemptRepository,
+        stats_only: bool,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
+    {
+        let repo_path =
+            ProjectRepository::find_by_id(&self.db.pool, repository.project_repository_id)
+                .await?
+                .ok_or(ContainerError::Other(anyhow!(
+                    "Project repository not found"
+                )))?
+                .git_repo_path;
+        let worktree_path = PathBuf::from(&repository.worktree_path);
+
+        let is_ahead = self
+            .git()
+            .get_branch_status(&repo_path, &task_attempt.branch, &repository.target_branch)
+            .map(|(ahead, _)| ahead > 0)
+            .unwrap_or(false);
+        let is_clean = !worktree_path.exists() || self.git().is_worktree_clean(&worktree_path)?;
+
+        if let Some(commit) = &repository.merge_commit
+            && is_clean
+            && !is_ahead
+        {
+            let wrapper = self.create_merged_diff_stream(&repo_path, commit, stats_only)?;
+            return Ok(Box::pin(wrapper));
+        }
+
+        self.ensure_container_exists(task_attempt).await?;
+        let base_commit = self.git().get_base_commit(
+            &repo_path,
+            &task_attempt.branch,
+            &repository.target_branch,
+        )?;
+
+        let wrapper = self
+            .create_live_diff_stream(&worktree_path, &base_commit, stats_only)
+            .await?;
+        Ok(Box::pin(wrapper))
+    }
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
         if !matches!(
             ctx.execution_process.run_reason,
@@ -1036,7 +1562,16 @@ If the project directory has moved or been deleted, update the project's reposit
             message
         );
 
-        let changes_committed = self.git().commit(Path::new(container_ref), &message)?;
+        let mut changes_committed = self.git().commit(Path::new(container_ref), &message)?;
+
+        let repositories =
+            TaskAttemptRepository::find_by_task_attempt_id(&self.db.pool, ctx.task_attempt.id)
+                .await?;
+        for repository in repositories {
+            changes_committed |= self
+                .git()
+                .commit(Path::new(&repository.worktree_path), &message)?;
+        }
         Ok(changes_committed)
     }
 
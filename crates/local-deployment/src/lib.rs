@@ -17,12 +17,16 @@ use services::services::{
     container::ContainerService,
     drafts::DraftsService,
     events::EventService,
+    executor_health::ExecutorHealthService,
     file_search_cache::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
     github_user_cache::GitHubUserCache,
+    identity::IdentityProviders,
     image::ImageService,
+    project_secrets::ProjectSecretsService,
     secret_store::{SECRET_GITHUB_OAUTH, SECRET_GITHUB_PAT, SecretStore},
+    webhooks::WebhookService,
 };
 use tokio::sync::RwLock;
 use utils::{assets::config_path, msg_store::MsgStore};
@@ -54,6 +58,9 @@ pub struct LocalDeployment {
     claude_auth: ClaudeAuthManager,
     claude_auth_pty: services::services::claude_auth_pty::ClaudePtyManager,
     github_user_cache: GitHubUserCache,
+    executor_health: ExecutorHealthService,
+    identity_providers: IdentityProviders,
+    webhooks: WebhookService,
 }
 
 #[async_trait]
@@ -143,6 +150,7 @@ impl Deployment for LocalDeployment {
             image.clone(),
             analytics_ctx,
             approvals.clone(),
+            ProjectSecretsService::new(secret_store.clone()),
         );
         container.spawn_worktree_cleanup().await;
 
@@ -150,6 +158,10 @@ impl Deployment for LocalDeployment {
         let drafts = DraftsService::new(db.clone(), image.clone());
         let file_search_cache = Arc::new(FileSearchCache::new());
         let github_user_cache = GitHubUserCache::new();
+        let executor_health = ExecutorHealthService::new(secret_store.clone(), user_id.clone());
+        let identity_providers =
+            IdentityProviders::from_env().map_err(|e| DeploymentError::Other(e.into()))?;
+        let webhooks = WebhookService::new(secret_store.clone());
 
         Ok(Self {
             config,
@@ -170,6 +182,9 @@ impl Deployment for LocalDeployment {
             claude_auth,
             claude_auth_pty,
             github_user_cache,
+            executor_health,
+            identity_providers,
+            webhooks,
         })
     }
 
@@ -247,6 +262,18 @@ impl Deployment for LocalDeployment {
     fn github_user_cache(&self) -> &GitHubUserCache {
         &self.github_user_cache
     }
+
+    fn executor_health(&self) -> &ExecutorHealthService {
+        &self.executor_health
+    }
+
+    fn identity_providers(&self) -> &IdentityProviders {
+        &self.identity_providers
+    }
+
+    fn webhooks(&self) -> &WebhookService {
+        &self.webhooks
+    }
 }
 
 impl LocalDeployment {
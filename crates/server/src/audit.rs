@@ -0,0 +1,246 @@
+//! Recording audit events from route handlers
+//!
+//! Handlers take an [`AuditContext`] extractor, which captures the actor, request metadata and
+//! whatever project, task or attempt the model loaders resolved, then record what they did:
+//!
+//! ```ignore
+//! audit
+//!     .event(AuditAction::AttemptMerged)
+//!     .target(&task_attempt.target_branch)
+//!     .record(&deployment)
+//!     .await;
+//! ```
+
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, OriginalUri},
+    http::{HeaderMap, request::Parts},
+};
+use db::models::{
+    audit_event::{AuditAction, AuditActorType, AuditEvent, CreateAuditEvent},
+    task::Task,
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, auth::AuthenticatedUser, middleware::authorization::ProjectAccess};
+
+/// Who is making a request and from where
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    user: Option<AuthenticatedUser>,
+    project_id: Option<Uuid>,
+    task_id: Option<Uuid>,
+    attempt_id: Option<Uuid>,
+    method: String,
+    path: String,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+}
+
+/// Reverse proxies allowed to report the client address, as comma-separated IP addresses
+fn trusted_proxies() -> Vec<IpAddr> {
+    std::env::var("ANYON_TRUSTED_PROXIES")
+        .map(|proxies| {
+            proxies
+                .split(',')
+                .filter_map(|proxy| proxy.trim().parse::<IpAddr>().ok())
+                .map(|proxy| proxy.to_canonical())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The peer address. When the peer is a trusted proxy, the last `X-Forwarded-For` hop that
+/// isn't one is used instead, else `X-Real-IP`: anything left of that hop was sent by the
+/// client and can't be trusted.
+fn client_ip(peer: Option<IpAddr>, headers: &HeaderMap, trusted: &[IpAddr]) -> Option<String> {
+    let peer = peer?.to_canonical();
+    if !trusted.contains(&peer) {
+        return Some(peer.to_string());
+    }
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let forwarded = header("x-forwarded-for").and_then(|value| {
+        value
+            .rsplit(',')
+            .filter_map(|hop| hop.trim().parse::<IpAddr>().ok())
+            .map(|hop| hop.to_canonical())
+            .find(|hop| !trusted.contains(hop))
+    });
+    let real_ip = || {
+        header("x-real-ip")
+            .and_then(|value| value.trim().parse::<IpAddr>().ok())
+            .map(|ip| ip.to_canonical())
+    };
+    Some(forwarded.or_else(real_ip).unwrap_or(peer).to_string())
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AuditContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let attempt = parts.extensions.get::<TaskAttempt>();
+        let path = parts
+            .extensions
+            .get::<OriginalUri>()
+            .map(|uri| uri.path().to_string())
+            .unwrap_or_else(|| parts.uri.path().to_string());
+        Ok(Self {
+            user: parts.extensions.get::<AuthenticatedUser>().cloned(),
+            project_id: parts
+                .extensions
+                .get::<ProjectAccess>()
+                .map(|access| access.project_id),
+            task_id: attempt
+                .map(|attempt| attempt.task_id)
+                .or_else(|| parts.extensions.get::<Task>().map(|task| task.id)),
+            attempt_id: attempt.map(|attempt| attempt.id),
+            method: parts.method.to_string(),
+            path,
+            ip_address: client_ip(
+                parts
+                    .extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| addr.ip()),
+                &parts.headers,
+                &trusted_proxies(),
+            ),
+            user_agent: parts
+                .headers
+                .get(axum::http::header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        })
+    }
+}
+
+impl AuditContext {
+    /// Start an event for this request; actions without a signed-in user are the system's
+    pub fn event(&self, action: AuditAction) -> AuditEntry {
+        let (actor_type, actor_id, actor_name, via_api_token) = match &self.user {
+            Some(user) => (
+                AuditActorType::User,
+                Some(user.user_id.clone()),
+                Some(user.username.clone()),
+                user.token_scopes.is_some(),
+            ),
+            None => (AuditActorType::System, None, None, false),
+        };
+        AuditEntry(CreateAuditEvent {
+            actor_type,
+            actor_id,
+            actor_name,
+            via_api_token,
+            action,
+            project_id: self.project_id,
+            task_id: self.task_id,
+            attempt_id: self.attempt_id,
+            target: None,
+            request_method: Some(self.method.clone()),
+            request_path: Some(self.path.clone()),
+            ip_address: self.ip_address.clone(),
+            user_agent: self.user_agent.clone(),
+            before: None,
+            after: None,
+        })
+    }
+}
+
+/// An audit event being built by a handler
+#[must_use = "audit events are only written by `record`"]
+pub struct AuditEntry(CreateAuditEvent);
+
+fn snapshot(value: &impl Serialize) -> Option<serde_json::Value> {
+    serde_json::to_value(value)
+        .inspect_err(|err| tracing::error!("Failed to snapshot audit event state: {}", err))
+        .ok()
+}
+
+impl AuditEntry {
+    pub fn project(mut self, project_id: Uuid) -> Self {
+        self.0.project_id = Some(project_id);
+        self
+    }
+
+    pub fn attempt(mut self, attempt: &TaskAttempt) -> Self {
+        self.0.attempt_id = Some(attempt.id);
+        self.0.task_id = Some(attempt.task_id);
+        self
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.0.target = Some(target.into());
+        self
+    }
+
+    pub fn before(mut self, value: &impl Serialize) -> Self {
+        self.0.before = snapshot(value);
+        self
+    }
+
+    pub fn after(mut self, value: &impl Serialize) -> Self {
+        self.0.after = snapshot(value);
+        self
+    }
+
+    /// Append the event. The action has already happened, so failures are logged rather than
+    /// returned.
+    pub async fn record(mut self, deployment: &DeploymentImpl) {
+        let pool = &deployment.db().pool;
+        if self.0.project_id.is_none()
+            && let Some(task_id) = self.0.task_id
+        {
+            match Task::find_by_id(pool, task_id).await {
+                Ok(task) => self.0.project_id = task.map(|task| task.project_id),
+                Err(err) => {
+                    tracing::warn!("Failed to resolve project of task {}: {}", task_id, err)
+                }
+            }
+        }
+        if let Err(err) = AuditEvent::create(pool, &self.0).await {
+            tracing::error!("Failed to record audit event {}: {}", self.0.action, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn forwarding_headers_only_count_from_trusted_proxies() {
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+        let trusted = [peer];
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.9, 203.0.113.7"),
+        );
+        headers.insert("x-real-ip", HeaderValue::from_static("192.0.2.1"));
+
+        assert_eq!(client_ip(None, &headers, &trusted), None);
+        // A client talking to the server directly can't pick the recorded address
+        assert_eq!(
+            client_ip(Some(peer), &headers, &[]).as_deref(),
+            Some("10.0.0.1")
+        );
+        // The hop the proxy appended, not the one the client sent
+        assert_eq!(
+            client_ip(Some(peer), &headers, &trusted).as_deref(),
+            Some("203.0.113.7")
+        );
+        headers.remove("x-forwarded-for");
+        assert_eq!(
+            client_ip(Some(peer), &headers, &trusted).as_deref(),
+            Some("192.0.2.1")
+        );
+    }
+}
@@ -2,15 +2,33 @@
 ///
 /// This module provides the AuthenticatedUser type which is injected
 /// into API handlers via Axum's Extension mechanism.
+use db::models::{
+    api_token::{ApiToken, TokenScope},
+    user_session::UserSession,
+};
+use deployment::Deployment;
 use serde::{Deserialize, Serialize};
 
+use crate::DeploymentImpl;
+
+pub mod session;
+
+/// Whether the server is shared: single sign-on is configured or `ANYON_MULTI_USER` is set.
+/// Shared servers require every request to carry a session or API token, rather than acting
+/// as the server's own GitHub (or dev) login.
+pub fn multi_user_mode(deployment: &DeploymentImpl) -> bool {
+    !deployment.identity_providers().is_empty()
+        || std::env::var("ANYON_MULTI_USER")
+            .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"))
+}
+
 /// Represents an authenticated user from GitHub OAuth
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuthenticatedUser {
-    /// Internal user ID (format: "github_{github_id}")
+    /// Internal user ID ("github_{github_id}", or "oidc_{uuid}" for single sign-on users)
     pub user_id: String,
 
-    /// GitHub user ID (numeric)
+    /// GitHub user ID (numeric); 0 for single sign-on users
     pub github_id: i64,
 
     /// GitHub username (login)
@@ -18,6 +36,14 @@ pub struct AuthenticatedUser {
 
     /// GitHub avatar URL
     pub avatar_url: Option<String>,
+
+    /// Scopes of the personal API token used; None for interactive sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_scopes: Option<Vec<TokenScope>>,
+
+    /// Whether the request was authenticated as the single-user server's own login
+    #[serde(skip)]
+    pub server_login: bool,
 }
 
 impl AuthenticatedUser {
@@ -28,7 +54,52 @@ impl AuthenticatedUser {
             github_id,
             username,
             avatar_url,
+            token_scopes: None,
+            server_login: false,
+        }
+    }
+
+    /// Create for a request authenticated with a personal API token
+    pub fn from_api_token(token: &ApiToken) -> Self {
+        Self {
+            user_id: token.user_id.clone(),
+            github_id: token.github_id,
+            username: token.username.clone(),
+            avatar_url: None,
+            token_scopes: Some(token.scopes.0.clone()),
+            server_login: false,
+        }
+    }
+
+    /// Create for a single sign-on session
+    pub fn from_session(session: &UserSession) -> Self {
+        Self {
+            user_id: session.user_id.clone(),
+            github_id: 0,
+            username: session.display_name.clone(),
+            avatar_url: None,
+            token_scopes: None,
+            server_login: false,
+        }
+    }
+
+    /// Interactive sessions hold every scope; token requests only those granted
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.token_scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.contains(&scope))
+    }
+
+    /// Server admins change global settings and executors, browse the host filesystem and read
+    /// the whole audit log: the server's own login on a single-user server, and the users listed
+    /// in `ANYON_ADMINS` (comma-separated user IDs) on a shared one. API tokens never qualify.
+    pub fn is_server_admin(&self) -> bool {
+        if self.server_login {
+            return true;
         }
+        self.token_scopes.is_none()
+            && std::env::var("ANYON_ADMINS")
+                .is_ok_and(|admins| admins.split(',').any(|admin| admin.trim() == self.user_id))
     }
 
     /// Create a development-mode user (no GitHub account)
@@ -39,6 +110,8 @@ impl AuthenticatedUser {
             github_id: 0,
             username,
             avatar_url: None,
+            token_scopes: None,
+            server_login: false,
         }
     }
 }
@@ -0,0 +1,142 @@
+//! Session cookies for users signed in through an identity provider
+
+use axum::http::{HeaderMap, header};
+use chrono::{Duration, Utc};
+use db::models::user_session::UserSession;
+use deployment::Deployment;
+use services::services::{api_tokens, identity::new_session_token};
+
+use super::AuthenticatedUser;
+use crate::DeploymentImpl;
+
+pub const SESSION_COOKIE: &str = "anyon_session";
+const SESSION_TTL_DAYS: i64 = 30;
+/// Holds a hash of the `state` of the single sign-on login the browser started
+pub const LOGIN_STATE_COOKIE: &str = "anyon_sso_state";
+const LOGIN_STATE_TTL_SECS: i64 = 10 * 60;
+
+/// Only mark cookies `Secure` when the server is reached over HTTPS
+fn secure_attribute() -> &'static str {
+    match std::env::var("ANYON_OIDC_REDIRECT_URL") {
+        Ok(url) if url.starts_with("https://") => "; Secure",
+        _ => "",
+    }
+}
+
+fn cookie_value(headers: &HeaderMap, cookie: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == cookie)
+        .map(|(_, value)| value.to_string())
+}
+
+/// The session cookie value sent with the request, if any
+pub fn session_token(headers: &HeaderMap) -> Option<String> {
+    cookie_value(headers, SESSION_COOKIE)
+}
+
+/// `Set-Cookie` header value tying a single sign-on login's `state` to this browser, so a
+/// callback started by someone else is refused
+pub fn login_state_cookie(state: &str) -> String {
+    format!(
+        "{LOGIN_STATE_COOKIE}={}; Path=/api/auth/sso; HttpOnly; SameSite=Lax; Max-Age={LOGIN_STATE_TTL_SECS}{}",
+        api_tokens::hash(state),
+        secure_attribute()
+    )
+}
+
+/// `Set-Cookie` header value clearing the login state cookie
+pub fn clear_login_state_cookie() -> String {
+    format!(
+        "{LOGIN_STATE_COOKIE}=; Path=/api/auth/sso; HttpOnly; SameSite=Lax; Max-Age=0{}",
+        secure_attribute()
+    )
+}
+
+/// Whether the request comes from the browser that started the login with `state`
+pub fn login_state_matches(headers: &HeaderMap, state: &str) -> bool {
+    cookie_value(headers, LOGIN_STATE_COOKIE).is_some_and(|hash| hash == api_tokens::hash(state))
+}
+
+/// Start a session and return the `Set-Cookie` header value for it
+pub async fn create_session(
+    deployment: &DeploymentImpl,
+    user_id: &str,
+    display_name: &str,
+) -> Result<String, sqlx::Error> {
+    let pool = &deployment.db().pool;
+    if let Err(e) = UserSession::delete_expired(pool).await {
+        tracing::warn!("Failed to delete expired sessions: {}", e);
+    }
+    let (token, token_hash) = new_session_token();
+    let expires_at = Utc::now() + Duration::days(SESSION_TTL_DAYS);
+    UserSession::create(pool, user_id, display_name, &token_hash, expires_at).await?;
+    Ok(format!(
+        "{SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+        SESSION_TTL_DAYS * 24 * 60 * 60,
+        secure_attribute()
+    ))
+}
+
+/// End the request's session and return the `Set-Cookie` header value clearing it
+pub async fn end_session(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+) -> Result<String, sqlx::Error> {
+    if let Some(token) = session_token(headers) {
+        UserSession::delete_by_hash(&deployment.db().pool, &api_tokens::hash(&token)).await?;
+    }
+    Ok(format!(
+        "{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0{}",
+        secure_attribute()
+    ))
+}
+
+/// The user of a valid session cookie sent with the request
+pub async fn session_user(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+) -> Result<Option<AuthenticatedUser>, sqlx::Error> {
+    let Some(token) = session_token(headers) else {
+        return Ok(None);
+    };
+    let session =
+        UserSession::find_by_hash(&deployment.db().pool, &api_tokens::hash(&token)).await?;
+    Ok(session.map(|session| AuthenticatedUser::from_session(&session)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn finds_session_cookie_among_others() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; anyon_session=abc123; other=1"),
+        );
+        assert_eq!(session_token(&headers).as_deref(), Some("abc123"));
+
+        headers.insert(header::COOKIE, HeaderValue::from_static("theme=dark"));
+        assert_eq!(session_token(&headers), None);
+    }
+
+    #[test]
+    fn login_state_cookie_matches_only_its_state() {
+        let set_cookie = login_state_cookie("state-1");
+        let pair = set_cookie.split(';').next().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_str(pair).unwrap());
+
+        assert!(login_state_matches(&headers, "state-1"));
+        assert!(!login_state_matches(&headers, "state-2"));
+        assert!(!login_state_matches(&HeaderMap::new(), "state-1"));
+    }
+}
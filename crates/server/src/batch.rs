@@ -0,0 +1,566 @@
+//! Headless batch mode: run one task end-to-end without serving the API
+//!
+//! The `anyon_batch` binary boots a deployment against a throwaway data directory, keeping the
+//! local install's executor profiles and tool cache, creates a project for the repository and a
+//! task from the spec, starts an attempt through [`ContainerService::start_attempt`], prints its
+//! normalized logs, then optionally runs a test command and pushes the branch or opens a pull
+//! request.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow, bail};
+use clap::Args;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    project::{CreateProject, Project},
+    task::{CreateTask, Task, TaskStatus},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use futures_util::StreamExt;
+use git2::BranchType;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    github_service::CreatePrRequest,
+    secret_store::{SECRET_GITHUB_PAT, SecretKeys, SecretStoreError},
+};
+use utils::{log_msg::LogMsg, shell::get_shell_command};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    cli::{OutputMode, logs::LogPrinter, parse_executor},
+};
+
+/// How long to wait for the next process of the attempt's chain to start
+const NEXT_PROCESS_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Exit code when the test command fails; agent failures and errors exit with 1
+pub const TESTS_FAILED_EXIT_CODE: u8 = 2;
+
+/// Task to run, read from YAML
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchSpec {
+    /// Git repository to work in; defaults to the current directory
+    pub repo: Option<PathBuf>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Coding agent, e.g. CLAUDE_CODE; defaults to the recommended profile
+    pub executor: Option<String>,
+    pub variant: Option<String>,
+    /// Defaults to the repository's current branch
+    pub base_branch: Option<String>,
+    /// Runs in the worktree before the agent
+    pub setup_script: Option<String>,
+    /// Runs in the worktree after the agent succeeds; failing it fails the run
+    pub test_command: Option<String>,
+    /// Push the attempt's branch once tests pass
+    #[serde(default)]
+    pub push: bool,
+    /// Open a pull request once tests pass; implies `push`
+    pub pr: Option<BatchPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchPullRequest {
+    pub title: String,
+    pub body: Option<String>,
+    /// Defaults to the attempt's base branch
+    pub target_branch: Option<String>,
+}
+
+/// Command-line options; each one overrides the same field of `--file`
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    /// YAML task spec
+    #[arg(long, short)]
+    pub file: Option<PathBuf>,
+    #[arg(long)]
+    pub repo: Option<PathBuf>,
+    #[arg(long)]
+    pub title: Option<String>,
+    #[arg(long)]
+    pub description: Option<String>,
+    #[arg(long)]
+    pub executor: Option<String>,
+    #[arg(long)]
+    pub variant: Option<String>,
+    #[arg(long)]
+    pub base_branch: Option<String>,
+    #[arg(long)]
+    pub setup_script: Option<String>,
+    #[arg(long)]
+    pub test_command: Option<String>,
+    #[arg(long)]
+    pub push: bool,
+    /// Open a pull request with this title
+    #[arg(long)]
+    pub pr_title: Option<String>,
+    #[arg(long, requires = "pr_title")]
+    pub pr_body: Option<String>,
+    #[arg(long, requires = "pr_title")]
+    pub pr_target_branch: Option<String>,
+}
+
+impl BatchArgs {
+    pub fn into_spec(self) -> anyhow::Result<BatchSpec> {
+        let mut spec = match &self.file {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                serde_yaml::from_str(&contents)
+                    .with_context(|| format!("Invalid task spec {}", path.display()))?
+            }
+            None => BatchSpec::default(),
+        };
+        spec.repo = self.repo.or(spec.repo);
+        spec.title = self.title.or(spec.title);
+        spec.description = self.description.or(spec.description);
+        spec.executor = self.executor.or(spec.executor);
+        spec.variant = self.variant.or(spec.variant);
+        spec.base_branch = self.base_branch.or(spec.base_branch);
+        spec.setup_script = self.setup_script.or(spec.setup_script);
+        spec.test_command = self.test_command.or(spec.test_command);
+        spec.push |= self.push;
+        if let Some(title) = self.pr_title {
+            spec.pr = Some(BatchPullRequest {
+                title,
+                body: self.pr_body,
+                target_branch: self.pr_target_branch,
+            });
+        }
+        if spec
+            .title
+            .as_deref()
+            .is_none_or(|title| title.trim().is_empty())
+        {
+            bail!("A task title is required (--title or `title:` in the spec)");
+        }
+        Ok(spec)
+    }
+}
+
+/// What a batch run did
+#[derive(Debug, Serialize)]
+pub struct BatchReport {
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+    pub branch: String,
+    pub agent_succeeded: bool,
+    /// `None` when no test command ran
+    pub tests_passed: Option<bool>,
+    pub pushed: bool,
+    pub pr_url: Option<String>,
+}
+
+impl BatchReport {
+    pub fn exit_code(&self) -> u8 {
+        if !self.agent_succeeded {
+            1
+        } else if self.tests_passed == Some(false) {
+            TESTS_FAILED_EXIT_CODE
+        } else {
+            0
+        }
+    }
+}
+
+/// Point the deployment's database, config and worktrees at `data_dir`, and generate a secret
+/// key for it if none is configured. Must run before any other thread starts.
+pub fn use_data_dir(data_dir: &Path) {
+    let needs_key = matches!(SecretKeys::load(), Err(SecretStoreError::MissingKey));
+    // std::env::set_var is only sound while no other thread reads the environment.
+    unsafe {
+        std::env::set_var("ANYON_ASSET_DIR", data_dir);
+        std::env::set_var("ANYON_WORKTREE_DIR", data_dir.join("worktrees"));
+        if needs_key {
+            std::env::set_var("ANYON_SECRET_KEY", SecretKeys::generate_key());
+        }
+    }
+}
+
+/// [`use_data_dir`] for a batch run, which still uses the agent setup of the local install:
+/// its executor profiles (or `profiles`, if given) are copied into `data_dir`, and its tool
+/// cache is used in place. Must run before any other thread starts.
+pub fn use_batch_data_dir(data_dir: &Path, profiles: Option<&Path>) -> anyhow::Result<()> {
+    let user_dir = utils::assets::resolve_asset_dir();
+    let profiles = match profiles {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(user_dir.join("profiles.json")).filter(|path| path.exists()),
+    };
+    if let Some(path) = profiles {
+        std::fs::copy(&path, data_dir.join("profiles.json"))
+            .with_context(|| format!("Failed to read profiles from {}", path.display()))?;
+    }
+    if std::env::var_os("ANYON_TOOL_CACHE_DIR").is_none() {
+        // std::env::set_var is only sound while no other thread reads the environment.
+        unsafe { std::env::set_var("ANYON_TOOL_CACHE_DIR", user_dir.join("tools")) };
+    }
+    use_data_dir(data_dir);
+    Ok(())
+}
+
+fn print_status(mode: OutputMode, message: &str) {
+    // Keep stdout to log entries and the report in JSON mode
+    match mode {
+        OutputMode::Human => println!("==> {message}"),
+        OutputMode::Json => eprintln!("==> {message}"),
+    }
+}
+
+/// Run `spec` to completion on a freshly booted deployment
+pub async fn run(
+    deployment: &DeploymentImpl,
+    spec: BatchSpec,
+    mode: OutputMode,
+) -> anyhow::Result<BatchReport> {
+    let pool = &deployment.db().pool;
+    let user_id = deployment.user_id().to_string();
+
+    let repo = match &spec.repo {
+        Some(repo) => repo.clone(),
+        None => std::env::current_dir()?,
+    };
+    let repo = std::path::absolute(&repo)?;
+    if !repo.join(".git").exists() {
+        bail!("{} is not a git repository", repo.display());
+    }
+
+    // The data directory is new, so the token has to come from the environment
+    if let Ok(token) = std::env::var("GITHUB_TOKEN")
+        && !token.trim().is_empty()
+    {
+        deployment
+            .secret_store()
+            .put_secret(&user_id, SECRET_GITHUB_PAT, token.trim().as_bytes())
+            .await?;
+    }
+
+    let project = Project::create(
+        pool,
+        &CreateProject {
+            name: repo
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "batch".to_string()),
+            git_repo_path: repo.to_string_lossy().into_owned(),
+            use_existing_repo: true,
+            setup_script: spec.setup_script.clone(),
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            init_submodules: None,
+            sparse_checkout_patterns: None,
+            sparse_auto_expand: None,
+        },
+        Uuid::new_v4(),
+        &user_id,
+    )
+    .await?;
+
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            project.id,
+            spec.title.clone().unwrap_or_default(),
+            spec.description.clone(),
+        ),
+        Uuid::new_v4(),
+        &user_id,
+    )
+    .await?;
+
+    let executor_profile_id = match &spec.executor {
+        Some(executor) => ExecutorProfileId {
+            executor: parse_executor(executor).map_err(|err| anyhow!(err))?,
+            variant: spec.variant.clone(),
+        },
+        None => {
+            let mut profile = deployment.config().read().await.executor_profile.clone();
+            if spec.variant.is_some() {
+                profile.variant = spec.variant.clone();
+            }
+            profile
+        }
+    };
+    let base_branch = match &spec.base_branch {
+        Some(branch) => branch.clone(),
+        None => deployment.git().get_current_branch(&repo)?,
+    };
+
+    let attempt_id = Uuid::new_v4();
+    let branch = deployment
+        .container()
+        .git_branch_from_task_attempt(&attempt_id, &task.title)
+        .await;
+    let attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: executor_profile_id.executor,
+            base_branch: base_branch.clone(),
+            branch,
+        },
+        attempt_id,
+        task.id,
+        &user_id,
+    )
+    .await?;
+
+    print_status(
+        mode,
+        &format!(
+            "Running {} on {} from {}",
+            executor_profile_id, attempt.branch, base_branch
+        ),
+    );
+    let first = deployment
+        .container()
+        .start_attempt(&attempt, executor_profile_id)
+        .await?;
+    follow_processes(deployment, attempt.id, first, mode).await?;
+
+    let attempt = TaskAttempt::find_by_id(pool, attempt.id)
+        .await?
+        .ok_or_else(|| anyhow!("Attempt {} disappeared", attempt.id))?;
+    let mut report = BatchReport {
+        task_id: task.id,
+        attempt_id: attempt.id,
+        branch: attempt.branch.clone(),
+        agent_succeeded: agent_succeeded(deployment, attempt.id).await?,
+        tests_passed: None,
+        pushed: false,
+        pr_url: None,
+    };
+    if !report.agent_succeeded {
+        print_status(mode, "Agent run failed");
+        return Ok(report);
+    }
+
+    let worktree = PathBuf::from(
+        attempt
+            .container_ref
+            .as_deref()
+            .ok_or_else(|| anyhow!("Attempt has no worktree"))?,
+    );
+    if let Some(command) = &spec.test_command {
+        print_status(mode, &format!("Running tests: {command}"));
+        let passed = run_test_command(&worktree, command, mode).await?;
+        report.tests_passed = Some(passed);
+        if !passed {
+            print_status(mode, "Tests failed");
+            return Ok(report);
+        }
+    }
+
+    if spec.push || spec.pr.is_some() {
+        report.pr_url = publish(deployment, &project, &attempt, &worktree, spec.pr).await?;
+        report.pushed = true;
+        print_status(mode, &format!("Pushed {}", attempt.branch));
+    }
+    Ok(report)
+}
+
+/// Print each process of the attempt's chain (setup, agent, cleanup) until none follows
+async fn follow_processes(
+    deployment: &DeploymentImpl,
+    attempt_id: Uuid,
+    mut process: ExecutionProcess,
+    mode: OutputMode,
+) -> anyhow::Result<()> {
+    loop {
+        if let Some(mut stream) = deployment
+            .container()
+            .stream_normalized_logs(&process.id)
+            .await
+        {
+            let mut printer = LogPrinter::new(mode, process.id).without_approval_hints();
+            while let Some(msg) = stream.next().await {
+                match msg? {
+                    LogMsg::JsonPatch(patch) => {
+                        if let Some(ops) = serde_json::to_value(&patch)?.as_array() {
+                            printer.apply_patch(ops);
+                        }
+                    }
+                    LogMsg::Finished => break,
+                    _ => {}
+                }
+            }
+            printer.finish();
+        }
+        match next_process(deployment, attempt_id, &process).await? {
+            Some(next) => process = next,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// The process started after `current`, once `current` has exited
+async fn next_process(
+    deployment: &DeploymentImpl,
+    attempt_id: Uuid,
+    current: &ExecutionProcess,
+) -> anyhow::Result<Option<ExecutionProcess>> {
+    let pool = &deployment.db().pool;
+    let mut waited = Duration::ZERO;
+    loop {
+        let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt_id, false).await?;
+        let still_running = processes
+            .iter()
+            .any(|p| p.id == current.id && p.status == ExecutionProcessStatus::Running);
+        if let Some(next) = processes
+            .into_iter()
+            .filter(|p| p.id != current.id && p.started_at >= current.started_at)
+            .min_by_key(|p| p.started_at)
+        {
+            return Ok(Some(next));
+        }
+        if !still_running {
+            // A finished chain moves the task to review; a failed step just stops
+            let task = TaskAttempt::find_by_id(pool, attempt_id)
+                .await?
+                .ok_or_else(|| anyhow!("Attempt {attempt_id} disappeared"))?
+                .parent_task(pool)
+                .await?;
+            if task.is_some_and(|task| task.status == TaskStatus::InReview)
+                || waited >= NEXT_PROCESS_TIMEOUT
+            {
+                return Ok(None);
+            }
+            waited += POLL_INTERVAL;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Whether the attempt's latest agent run completed
+async fn agent_succeeded(deployment: &DeploymentImpl, attempt_id: Uuid) -> anyhow::Result<bool> {
+    let processes =
+        ExecutionProcess::find_by_task_attempt_id(&deployment.db().pool, attempt_id, false).await?;
+    Ok(processes
+        .iter()
+        .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .max_by_key(|p| p.started_at)
+        .is_some_and(|p| p.status == ExecutionProcessStatus::Completed))
+}
+
+async fn run_test_command(
+    worktree: &Path,
+    command: &str,
+    mode: OutputMode,
+) -> anyhow::Result<bool> {
+    let (shell, flag) = get_shell_command();
+    let mut cmd = tokio::process::Command::new(shell);
+    cmd.arg(flag).arg(command).current_dir(worktree);
+    if mode == OutputMode::Json {
+        cmd.stdout(Stdio::from(std::io::stderr()));
+    }
+    let status = cmd
+        .status()
+        .await
+        .with_context(|| format!("Failed to run test command '{command}'"))?;
+    Ok(status.success())
+}
+
+/// Push the attempt's branch and open a pull request if one was asked for
+async fn publish(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    attempt: &TaskAttempt,
+    worktree: &Path,
+    pr: Option<BatchPullRequest>,
+) -> anyhow::Result<Option<String>> {
+    let remote_url = deployment.git().get_remote_url(&project.git_repo_path)?;
+    let forge = deployment
+        .forge_resolver()
+        .connect(&remote_url)
+        .await
+        .context("Set GITHUB_TOKEN to push from batch mode")?;
+    deployment.git().push_to_remote(
+        worktree,
+        &forge.repo_info.clone_url(),
+        &attempt.branch,
+        &forge.token,
+    )?;
+
+    let Some(pr) = pr else {
+        return Ok(None);
+    };
+    let target_branch = pr
+        .target_branch
+        .unwrap_or_else(|| attempt.target_branch.clone());
+    // Remote branches are `{remote}/{branch}` locally, but the PR API wants just the branch
+    let base_branch = if matches!(
+        deployment
+            .git()
+            .find_branch_type(&project.git_repo_path, &target_branch)?,
+        BranchType::Remote
+    ) {
+        let remote = deployment
+            .git()
+            .get_remote_name_from_branch_name(worktree, &target_branch)?;
+        target_branch
+            .strip_prefix(&format!("{remote}/"))
+            .unwrap_or(&target_branch)
+            .to_string()
+    } else {
+        target_branch
+    };
+    let pr_info = forge
+        .service
+        .create_pr(
+            &forge.repo_info,
+            &CreatePrRequest {
+                title: pr.title,
+                body: pr.body,
+                head_branch: attempt.branch.clone(),
+                base_branch,
+            },
+        )
+        .await?;
+    Ok(Some(pr_info.url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_override_spec_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("task.yaml");
+        std::fs::write(
+            &file,
+            "title: Fix the flaky test\nexecutor: claude-code\ntest_command: cargo test\npr:\n  title: Fix flaky test\n",
+        )
+        .unwrap();
+        let args = BatchArgs {
+            file: Some(file),
+            repo: None,
+            title: None,
+            description: None,
+            executor: None,
+            variant: Some("PLAN".to_string()),
+            base_branch: None,
+            setup_script: None,
+            test_command: Some("npm test".to_string()),
+            push: false,
+            pr_title: None,
+            pr_body: None,
+            pr_target_branch: None,
+        };
+        let spec = args.into_spec().unwrap();
+        assert_eq!(spec.title.as_deref(), Some("Fix the flaky test"));
+        assert_eq!(spec.variant.as_deref(), Some("PLAN"));
+        assert_eq!(spec.test_command.as_deref(), Some("npm test"));
+        assert_eq!(spec.pr.unwrap().title, "Fix flaky test");
+    }
+}
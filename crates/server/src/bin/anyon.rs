@@ -0,0 +1,10 @@
+//! Command-line client for a running server; see `anyon --help`.
+
+use clap::Parser;
+use server::cli::{Cli, run};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _ = dotenvy::dotenv();
+    run(Cli::parse()).await
+}
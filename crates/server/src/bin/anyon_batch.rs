@@ -0,0 +1,83 @@
+//! Run one task end-to-end without the server, for CI.
+//!
+//! Boots a deployment against a temporary data directory, with the local install's executor
+//! profiles and tool cache, runs the agent on the task given by `--file` and/or flags, then the
+//! test command, push and pull request. Exits with 1 when the agent fails and 2 when the tests
+//! fail.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use server::{
+    DeploymentImpl,
+    batch::{self, BatchArgs},
+    cli::OutputMode,
+};
+use services::services::container::ContainerService;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "anyon_batch",
+    version,
+    about = "Run one task end-to-end without the server"
+)]
+struct Cli {
+    #[command(flatten)]
+    args: BatchArgs,
+    /// Print log entries and the final report as JSON lines
+    #[arg(long)]
+    json: bool,
+    /// Keep the temporary database and worktree, and print where they are
+    #[arg(long)]
+    keep_data: bool,
+    /// Executor profiles to use instead of the local install's profiles.json
+    #[arg(long)]
+    profiles: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let _ = dotenvy::dotenv();
+    let cli = Cli::parse();
+    let spec = cli.args.into_spec()?;
+    let mode = if cli.json {
+        OutputMode::Json
+    } else {
+        OutputMode::Human
+    };
+
+    let data_dir = tempfile::Builder::new().prefix("anyon-batch-").tempdir()?;
+    // Before the runtime starts any threads
+    batch::use_batch_data_dir(data_dir.path(), cli.profiles.as_deref())?;
+    let runtime = tokio::runtime::Runtime::new()?;
+
+    let report = runtime.block_on(async {
+        let deployment = DeploymentImpl::new().await?;
+        let report = batch::run(&deployment, spec, mode).await;
+        if !cli.keep_data
+            && let Ok(report) = &report
+            && let Ok(Some(attempt)) =
+                TaskAttempt::find_by_id(&deployment.db().pool, report.attempt_id).await
+            && let Err(err) = deployment.container().delete(&attempt).await
+        {
+            // The branch stays in the repository either way
+            eprintln!("Failed to remove the attempt's worktree: {err}");
+        }
+        report
+    })?;
+
+    match mode {
+        OutputMode::Json => println!("{}", serde_json::json!({ "report": report })),
+        OutputMode::Human => {
+            println!("Attempt {} on branch {}", report.attempt_id, report.branch);
+            if let Some(url) = &report.pr_url {
+                println!("Pull request: {url}");
+            }
+        }
+    }
+    if cli.keep_data {
+        eprintln!("Kept data in {}", data_dir.keep().display());
+    }
+    Ok(ExitCode::from(report.exit_code()))
+}
@@ -17,6 +17,34 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::project_repository::ProjectRepository::decl(),
+        db::models::project_repository::CreateProjectRepository::decl(),
+        db::models::project_mcp_server::ProjectMcpServer::decl(),
+        db::models::project_mcp_server::UpdateProjectMcpServers::decl(),
+        db::models::project_secret::ProjectSecret::decl(),
+        db::models::project_secret::UpsertProjectSecret::decl(),
+        db::models::organization::MemberRole::decl(),
+        db::models::organization::Organization::decl(),
+        db::models::organization::OrganizationMember::decl(),
+        db::models::organization::CreateOrganization::decl(),
+        db::models::organization::UpdateMemberRole::decl(),
+        db::models::project_member::ProjectMember::decl(),
+        db::models::api_token::TokenScope::decl(),
+        db::models::api_token::ApiToken::decl(),
+        db::models::api_token::CreateApiToken::decl(),
+        db::models::audit_event::AuditActorType::decl(),
+        db::models::audit_event::AuditAction::decl(),
+        db::models::audit_event::AuditEvent::decl(),
+        db::models::audit_event::AuditEventFilter::decl(),
+        db::models::webhook::WebhookEventType::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        db::models::webhook_delivery::WebhookDeliveryStatus::decl(),
+        db::models::webhook_delivery::WebhookDelivery::decl(),
+        db::models::user_identity::UserIdentity::decl(),
+        services::services::identity::IdentityProviderInfo::decl(),
+        db::models::task_attempt_repository::TaskAttemptRepository::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -45,7 +73,16 @@ fn generate_types_content() -> String {
         server::routes::config::McpServerQuery::decl(),
         server::routes::config::UpdateMcpServersBody::decl(),
         server::routes::config::GetMcpServerResponse::decl(),
+        services::services::executor_health::CheckStatus::decl(),
+        services::services::executor_health::HealthCheck::decl(),
+        services::services::executor_health::ExecutorHealth::decl(),
+        services::services::executor_health::ExecutorHealthReport::decl(),
+        server::routes::executors::AgentTool::decl(),
+        server::routes::executors::AgentToolsResponse::decl(),
+        server::routes::executors::InstallAgentToolRequest::decl(),
+        server::routes::executors::UpdateToolSettingsRequest::decl(),
         server::routes::task_attempts::CreateFollowUpAttempt::decl(),
+        server::routes::task_attempts::HandoffAttemptRequest::decl(),
         services::services::drafts::DraftResponse::decl(),
         services::services::drafts::UpdateFollowUpDraftRequest::decl(),
         services::services::drafts::UpdateRetryFollowUpDraftRequest::decl(),
@@ -54,6 +91,11 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::RenameBranchRequest::decl(),
         server::routes::task_attempts::RenameBranchResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::projects::ProjectMembers::decl(),
+        server::routes::projects::UpdateProjectOrganization::decl(),
+        server::routes::api_tokens::CreatedApiToken::decl(),
+        server::routes::audit_events::AuditEventPage::decl(),
+        server::routes::webhooks::CreatedWebhook::decl(),
         server::routes::task_attempts::CreateGitHubPrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         services::services::github_service::GitHubServiceError::decl(),
@@ -67,8 +109,12 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::auth::DeviceFlowStartResponse::decl(),
+        server::routes::auth::DevicePollRequest::decl(),
         server::routes::auth::DevicePollStatus::decl(),
         server::routes::auth::CheckTokenResponse::decl(),
+        services::services::forge::ForgeProvider::decl(),
+        server::routes::auth::ForgeTokenRequest::decl(),
+        server::routes::auth::ForgeHostQuery::decl(),
         server::routes::auth::ClaudeSessionResponse::decl(),
         services::services::git::GitBranch::decl(),
         utils::diff::Diff::decl(),
@@ -77,6 +123,10 @@ fn generate_types_content() -> String {
         executors::command::CommandBuilder::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
+        executors::profile::FallbackChain::decl(),
+        executors::profile::FallbackTrigger::decl(),
+        executors::profile::ToolCacheConfig::decl(),
+        executors::tool_cache::NpmPackage::decl(),
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
@@ -90,6 +140,15 @@ fn generate_types_content() -> String {
         executors::executors::codex::ReasoningSummaryFormat::decl(),
         executors::executors::cursor::CursorAgent::decl(),
         executors::executors::copilot::Copilot::decl(),
+        executors::executors::custom::CustomAgent::decl(),
+        executors::executors::custom::CustomPromptInput::decl(),
+        executors::executors::custom::CustomOutputFormat::decl(),
+        executors::executors::custom::CustomEntryKind::decl(),
+        executors::executors::custom::CustomToolAction::decl(),
+        executors::executors::custom::CustomOutputRule::decl(),
+        executors::executors::acp::Acp::decl(),
+        executors::executors::model_api::ModelApi::decl(),
+        executors::executors::model_api::ModelApiProvider::decl(),
         executors::executors::opencode::Opencode::decl(),
         executors::executors::qwen::QwenCode::decl(),
         executors::executors::AppendPrompt::decl(),
@@ -103,7 +162,12 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::ReplaceProcessRequest::decl(),
         server::routes::task_attempts::CommitInfo::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
+        server::routes::task_attempts::repositories::AttemptRepositoryStatus::decl(),
         services::services::git::ConflictOp::decl(),
+        server::routes::task_attempts::patch::PatchFormat::decl(),
+        server::routes::task_attempts::patch::CreateTaskAttemptFromPatchBody::decl(),
+        server::routes::task_attempts::patch::PatchImportResult::decl(),
+        services::services::git::PatchConflict::decl(),
         db::models::task_attempt::TaskAttempt::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
@@ -203,6 +267,18 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "copilot",
             generate_json_schema::<executors::executors::copilot::Copilot>()?,
         ),
+        (
+            "custom_agent",
+            generate_json_schema::<executors::executors::custom::CustomAgent>()?,
+        ),
+        (
+            "acp",
+            generate_json_schema::<executors::executors::acp::Acp>()?,
+        ),
+        (
+            "model_api",
+            generate_json_schema::<executors::executors::model_api::ModelApi>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
@@ -52,7 +52,11 @@ fn main() -> anyhow::Result<()> {
                 url
             };
 
+            let token = std::env::var("ANYON_TOKEN")
+                .ok()
+                .filter(|t| !t.trim().is_empty());
             let service = TaskServer::new(&base_url)
+                .with_token(token)
                 .serve(stdio())
                 .await
                 .map_err(|e| {
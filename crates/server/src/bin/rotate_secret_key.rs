@@ -0,0 +1,41 @@
+//! Re-encrypt stored secrets with the current SecretStore key.
+//!
+//! Run after making a new key current while keeping the old one as a retired key. With
+//! `--check` it only reports how many secrets use each key version.
+
+use db::{DBService, models::secret::SecretRecord};
+use services::services::secret_store::SecretStore;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _ = dotenvy::dotenv();
+    let check = std::env::args().any(|arg| arg == "--check");
+
+    let db = DBService::new().await?;
+    let store = SecretStore::new(db.clone())?;
+
+    let counts = SecretRecord::count_by_key_version(&db.pool).await?;
+    println!("Current key version: {}", store.key_version());
+    for (version, count) in &counts {
+        println!("  version {version}: {count} secret(s)");
+    }
+
+    if check {
+        let stale: i64 = counts
+            .iter()
+            .filter(|(version, _)| *version != store.key_version())
+            .map(|(_, count)| count)
+            .sum();
+        if stale > 0 {
+            anyhow::bail!("{stale} secret(s) still use a retired key");
+        }
+        return Ok(());
+    }
+
+    let rotation = store.rotate().await?;
+    println!(
+        "Re-encrypted {} secret(s) with key version {}",
+        rotation.rotated, rotation.key_version
+    );
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use anyhow::{Context, anyhow, bail};
+use reqwest::{Method, RequestBuilder, header};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, handshake::client::Request};
+use utils::{port_file::read_port_file, response::ApiResponse};
+
+/// Client for a running server's HTTP API
+pub struct ApiClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+/// `--url`, then `ANYON_URL`, then `HOST` with `BACKEND_PORT`/`PORT` or the port file the
+/// server writes on startup
+pub async fn resolve_base_url(url: Option<String>) -> anyhow::Result<String> {
+    if let Some(url) = url.or_else(|| std::env::var("ANYON_URL").ok()) {
+        return Ok(url.trim_end_matches('/').to_string());
+    }
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = match std::env::var("BACKEND_PORT").or_else(|_| std::env::var("PORT")) {
+        Ok(port) => port
+            .parse::<u16>()
+            .with_context(|| format!("Invalid port value '{port}'"))?,
+        Err(_) => read_port_file("anyon")
+            .await
+            .context("No running server found; start it or pass --url")?,
+    };
+    Ok(format!("http://{host}:{port}"))
+}
+
+impl ApiClient {
+    pub fn new(base_url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            token,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let rb = self.client.request(
+            method,
+            format!("{}/api/{}", self.base_url, path.trim_start_matches('/')),
+        );
+        match &self.token {
+            Some(token) => rb.bearer_auth(token),
+            None => rb,
+        }
+    }
+
+    /// Unwrap the `ApiResponse` envelope, turning `success: false` into an error
+    async fn send<T: DeserializeOwned>(&self, rb: RequestBuilder) -> anyhow::Result<Option<T>> {
+        let response = rb.send().await.context("Failed to reach the server")?;
+        let status = response.status();
+        let body = response.text().await?;
+        let envelope: ApiResponse<T, Value> = serde_json::from_str(&body).map_err(|_| {
+            anyhow!(
+                "Server returned {status}{}",
+                if body.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {body}")
+                }
+            )
+        })?;
+        if !envelope.is_success() {
+            let message = envelope.message().map(str::to_string);
+            bail!(message.unwrap_or_else(|| format!("Request failed with status {status}")));
+        }
+        Ok(envelope.into_data())
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.send(self.request(Method::GET, path))
+            .await?
+            .ok_or_else(|| anyhow!("Server response is missing data"))
+    }
+
+    pub async fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        self.send(self.request(Method::POST, path).json(body))
+            .await?
+            .ok_or_else(|| anyhow!("Server response is missing data"))
+    }
+
+    /// POST to an endpoint that returns no data
+    pub async fn post_empty<B: Serialize>(&self, path: &str, body: &B) -> anyhow::Result<()> {
+        self.send::<Value>(self.request(Method::POST, path).json(body))
+            .await
+            .map(|_| ())
+    }
+
+    /// GET a response that is not wrapped in an `ApiResponse`, such as a patch download
+    pub async fn get_text(&self, path: &str) -> anyhow::Result<String> {
+        let response = self
+            .request(Method::GET, path)
+            .send()
+            .await
+            .context("Failed to reach the server")?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            bail!("Server returned {status}: {body}");
+        }
+        Ok(body)
+    }
+
+    /// POST returning plain JSON rather than an `ApiResponse`
+    pub async fn post_raw<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        let response = self
+            .request(Method::POST, path)
+            .json(body)
+            .send()
+            .await
+            .context("Failed to reach the server")?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("Server returned {status}");
+        }
+        Ok(response.json().await?)
+    }
+
+    /// WebSocket handshake request for a streaming endpoint
+    pub fn ws_request(&self, path: &str) -> anyhow::Result<Request> {
+        let url = format!(
+            "{}/api/{}",
+            self.base_url
+                .replacen("http://", "ws://", 1)
+                .replacen("https://", "wss://", 1),
+            path.trim_start_matches('/')
+        );
+        let mut request = url.into_client_request()?;
+        if let Some(token) = &self.token {
+            request
+                .headers_mut()
+                .insert(header::AUTHORIZATION, format!("Bearer {token}").parse()?);
+        }
+        Ok(request)
+    }
+}
@@ -0,0 +1,212 @@
+//! Tail an execution process's normalized logs in the terminal
+//!
+//! The stream is a series of JSON patches against a list of entries, and the latest entry is
+//! replaced while an agent streams its message. An entry is printed once a later entry
+//! arrives, or when the process finishes, so each one is shown once in its final form.
+
+use std::collections::{BTreeMap, HashSet};
+
+use executors::logs::{NormalizedEntry, NormalizedEntryType, ToolStatus};
+use futures_util::StreamExt;
+use serde_json::Value;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use uuid::Uuid;
+
+use super::{OutputMode, client::ApiClient};
+
+/// One entry of the conversation
+enum LogEntry {
+    Normalized(NormalizedEntry),
+    Output(String),
+}
+
+pub(crate) struct LogPrinter {
+    mode: OutputMode,
+    process_id: Uuid,
+    entries: BTreeMap<usize, LogEntry>,
+    printed: usize,
+    announced_approvals: HashSet<String>,
+    approval_hints: bool,
+}
+
+impl LogPrinter {
+    pub(crate) fn new(mode: OutputMode, process_id: Uuid) -> Self {
+        Self {
+            mode,
+            process_id,
+            entries: BTreeMap::new(),
+            printed: 0,
+            announced_approvals: HashSet::new(),
+            approval_hints: true,
+        }
+    }
+
+    /// Don't announce pending approvals, for runs where nobody can answer them
+    pub(crate) fn without_approval_hints(mut self) -> Self {
+        self.approval_hints = false;
+        self
+    }
+
+    pub(crate) fn apply_patch(&mut self, ops: &[Value]) {
+        for op in ops {
+            let Some(index) = op
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(|path| path.strip_prefix("/entries/"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if op.get("op").and_then(Value::as_str) == Some("remove") {
+                self.entries.remove(&index);
+                continue;
+            }
+            let Some(value) = op.get("value") else {
+                continue;
+            };
+            let content = value.get("content").cloned().unwrap_or(Value::Null);
+            let entry = match value.get("type").and_then(Value::as_str) {
+                Some("NORMALIZED_ENTRY") => match serde_json::from_value(content) {
+                    Ok(entry) => LogEntry::Normalized(entry),
+                    Err(_) => continue,
+                },
+                Some("STDOUT") | Some("STDERR") => {
+                    LogEntry::Output(content.as_str().unwrap_or_default().to_string())
+                }
+                _ => continue,
+            };
+            self.announce_approval(&entry);
+            self.entries.insert(index, entry);
+            self.flush_before(index);
+        }
+    }
+
+    /// Pending approvals are shown right away, since the agent waits on them
+    fn announce_approval(&mut self, entry: &LogEntry) {
+        let LogEntry::Normalized(NormalizedEntry {
+            entry_type:
+                NormalizedEntryType::ToolUse {
+                    tool_name,
+                    status: ToolStatus::PendingApproval { approval_id, .. },
+                    ..
+                },
+            ..
+        }) = entry
+        else {
+            return;
+        };
+        if !self.approval_hints || !self.announced_approvals.insert(approval_id.clone()) {
+            return;
+        }
+        match self.mode {
+            OutputMode::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "approval_id": approval_id,
+                    "execution_process_id": self.process_id,
+                    "tool_name": tool_name,
+                })
+            ),
+            OutputMode::Human => eprintln!(
+                "Approval needed for {tool_name}: anyon approve {approval_id} --process {} [--deny]",
+                self.process_id
+            ),
+        }
+    }
+
+    fn flush_before(&mut self, end: usize) {
+        while self.printed < end {
+            if let Some(entry) = self.entries.get(&self.printed) {
+                self.print(entry);
+            }
+            self.printed += 1;
+        }
+    }
+
+    pub(crate) fn finish(&mut self) {
+        let end = self.entries.keys().next_back().map_or(0, |last| last + 1);
+        self.flush_before(end);
+    }
+
+    fn print(&self, entry: &LogEntry) {
+        match (self.mode, entry) {
+            (OutputMode::Json, LogEntry::Normalized(entry)) => {
+                println!("{}", serde_json::to_string(entry).unwrap_or_default())
+            }
+            (OutputMode::Json, LogEntry::Output(line)) => {
+                println!("{}", serde_json::json!({ "output": line }))
+            }
+            (OutputMode::Human, LogEntry::Normalized(entry)) => {
+                if let Some(label) = entry.entry_type.label() {
+                    println!("[{label}] {}", entry.content.trim_end());
+                }
+            }
+            (OutputMode::Human, LogEntry::Output(line)) => print!("{line}"),
+        }
+    }
+}
+
+/// Follow a process's normalized logs until it finishes
+pub async fn tail(client: &ApiClient, mode: OutputMode, process_id: Uuid) -> anyhow::Result<()> {
+    let request = client.ws_request(&format!(
+        "execution-processes/{process_id}/normalized-logs/ws"
+    ))?;
+    let (mut socket, _) = connect_async(request).await?;
+    let mut printer = LogPrinter::new(mode, process_id);
+
+    while let Some(message) = socket.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let Ok(msg) = serde_json::from_str::<Value>(text.as_str()) else {
+            continue;
+        };
+        if msg.get("finished").is_some() {
+            break;
+        }
+        if let Some(ops) = msg.get("JsonPatch").and_then(Value::as_array) {
+            printer.apply_patch(ops);
+        }
+    }
+    printer.finish();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn assistant(content: &str) -> Value {
+        json!({
+            "type": "NORMALIZED_ENTRY",
+            "content": {
+                "timestamp": null,
+                "entry_type": { "type": "assistant_message" },
+                "content": content,
+            }
+        })
+    }
+
+    #[test]
+    fn entries_print_once_final() {
+        let mut printer = LogPrinter::new(OutputMode::Json, Uuid::nil());
+        printer.apply_patch(&[
+            json!({ "op": "add", "path": "/entries/0", "value": assistant("Hel") }),
+        ]);
+        assert_eq!(printer.printed, 0);
+        printer.apply_patch(&[
+            json!({ "op": "replace", "path": "/entries/0", "value": assistant("Hello") }),
+        ]);
+        assert_eq!(printer.printed, 0);
+        printer.apply_patch(&[
+            json!({ "op": "add", "path": "/entries/1", "value": assistant("Done") }),
+        ]);
+        assert_eq!(printer.printed, 1);
+        printer.finish();
+        assert_eq!(printer.printed, 2);
+    }
+}
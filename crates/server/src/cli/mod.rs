@@ -0,0 +1,454 @@
+//! `anyon` command-line client for a running server
+//!
+//! Every command talks to the server's HTTP API, so it sees the same projects, permissions and
+//! audit trail as the web UI. `--json` switches output to machine-readable JSON.
+
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow};
+use clap::{Args, Parser, Subcommand};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
+    task::{CreateTask, Task, TaskWithAttemptStatus},
+    task_attempt::TaskAttempt,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use serde::Serialize;
+use serde_json::Value;
+use services::services::git::GitBranch;
+use utils::approvals::{ApprovalResponse, ApprovalStatus};
+use uuid::Uuid;
+
+use crate::routes::{
+    task_attempts::{CreateGitHubPrRequest, CreateTaskAttemptBody},
+    tasks::CreateAndStartTaskRequest,
+};
+
+pub mod client;
+pub mod logs;
+
+use client::ApiClient;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "anyon",
+    version,
+    about = "Command-line client for a running Anyon server"
+)]
+pub struct Cli {
+    /// Server URL; defaults to ANYON_URL or the local server's port file
+    #[arg(long, global = true)]
+    pub url: Option<String>,
+    /// Personal API token, sent as a bearer credential
+    #[arg(long, global = true, env = "ANYON_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+    /// Print JSON instead of human-readable output
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List projects
+    Projects,
+    /// List and create tasks
+    #[command(subcommand)]
+    Tasks(TasksCommand),
+    /// List and start attempts
+    #[command(subcommand)]
+    Attempts(AttemptsCommand),
+    /// Follow an attempt's agent logs until the run finishes
+    Logs {
+        attempt_id: Uuid,
+        /// Follow this execution process instead of the attempt's latest agent run
+        #[arg(long)]
+        process: Option<Uuid>,
+    },
+    /// Answer a pending tool approval
+    Approve {
+        approval_id: String,
+        /// Execution process that asked for approval, as shown by `anyon logs`
+        #[arg(long)]
+        process: Uuid,
+        /// Deny instead of approving
+        #[arg(long)]
+        deny: bool,
+        /// Reason given to the agent when denying
+        #[arg(long, requires = "deny")]
+        reason: Option<String>,
+    },
+    /// Print an attempt's changes as a unified diff
+    Diff {
+        attempt_id: Uuid,
+        /// Print the attempt's commits as a `git format-patch` mbox instead
+        #[arg(long)]
+        mbox: bool,
+    },
+    /// Merge an attempt into its target branch
+    Merge { attempt_id: Uuid },
+    /// Push an attempt's branch and open a pull request
+    Pr {
+        attempt_id: Uuid,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        body: Option<String>,
+        /// Base branch; defaults to the attempt's target branch
+        #[arg(long)]
+        target_branch: Option<String>,
+    },
+    /// Stop an attempt's running processes
+    Stop { attempt_id: Uuid },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TasksCommand {
+    /// List a project's tasks
+    List {
+        #[arg(long)]
+        project: Uuid,
+    },
+    /// Create a task, optionally starting an attempt right away
+    Create {
+        #[arg(long)]
+        project: Uuid,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        description: Option<String>,
+        /// Start an attempt for the new task
+        #[arg(long)]
+        start: bool,
+        #[command(flatten)]
+        profile: ProfileArgs,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AttemptsCommand {
+    /// List a task's attempts
+    List {
+        #[arg(long)]
+        task: Uuid,
+    },
+    /// Start an attempt for a task
+    Start {
+        #[arg(long)]
+        task: Uuid,
+        #[command(flatten)]
+        profile: ProfileArgs,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ProfileArgs {
+    /// Coding agent, e.g. CLAUDE_CODE; defaults to the server's configured profile
+    #[arg(long, value_parser = parse_executor)]
+    pub executor: Option<BaseCodingAgent>,
+    /// Profile variant, e.g. PLAN
+    #[arg(long)]
+    pub variant: Option<String>,
+    /// Branch to base the attempt on; defaults to the repository's current branch
+    #[arg(long)]
+    pub base_branch: Option<String>,
+}
+
+pub(crate) fn parse_executor(value: &str) -> Result<BaseCodingAgent, String> {
+    BaseCodingAgent::from_str(&value.trim().to_uppercase().replace('-', "_"))
+        .map_err(|_| format!("unknown executor '{value}'"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    Human,
+    Json,
+}
+
+impl OutputMode {
+    /// Print `value` as JSON, or the human-readable `lines`
+    fn print<T: Serialize>(self, value: &T, lines: impl FnOnce() -> Vec<String>) {
+        match self {
+            Self::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(value).unwrap_or_else(|_| "null".to_string())
+            ),
+            Self::Human => lines().iter().for_each(|line| println!("{line}")),
+        }
+    }
+}
+
+fn short_id(id: &Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+async fn resolve_profile(
+    client: &ApiClient,
+    project_id: Uuid,
+    args: ProfileArgs,
+) -> anyhow::Result<(ExecutorProfileId, String)> {
+    let executor_profile_id = match args.executor {
+        Some(executor) => ExecutorProfileId {
+            executor,
+            variant: args.variant,
+        },
+        None => {
+            let info: Value = client.get("info").await?;
+            let mut profile: ExecutorProfileId =
+                serde_json::from_value(info["config"]["executor_profile"].clone())
+                    .context("Server has no default executor profile; pass --executor")?;
+            if args.variant.is_some() {
+                profile.variant = args.variant;
+            }
+            profile
+        }
+    };
+    let base_branch = match args.base_branch {
+        Some(branch) => branch,
+        None => {
+            let branches: Vec<GitBranch> = client
+                .get(&format!("projects/{project_id}/branches"))
+                .await?;
+            branches
+                .into_iter()
+                .find(|branch| branch.is_current)
+                .map(|branch| branch.name)
+                .ok_or_else(|| anyhow!("Could not find the current branch; pass --base-branch"))?
+        }
+    };
+    Ok((executor_profile_id, base_branch))
+}
+
+fn attempt_lines(attempts: &[TaskAttempt]) -> Vec<String> {
+    attempts
+        .iter()
+        .map(|attempt| {
+            format!(
+                "{}  {:<14} {} -> {}",
+                attempt.id, attempt.executor, attempt.branch, attempt.target_branch
+            )
+        })
+        .collect()
+}
+
+/// Latest coding agent run of an attempt
+async fn latest_agent_process(client: &ApiClient, attempt_id: Uuid) -> anyhow::Result<Uuid> {
+    let processes: Vec<ExecutionProcess> = client
+        .get(&format!("execution-processes?task_attempt_id={attempt_id}"))
+        .await?;
+    processes
+        .into_iter()
+        .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .max_by_key(|process| process.created_at)
+        .map(|process| process.id)
+        .ok_or_else(|| anyhow!("Attempt {attempt_id} has no agent runs yet"))
+}
+
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let base_url = client::resolve_base_url(cli.url).await?;
+    let client = ApiClient::new(base_url, cli.token);
+    let mode = if cli.json {
+        OutputMode::Json
+    } else {
+        OutputMode::Human
+    };
+
+    match cli.command {
+        Command::Projects => {
+            let projects: Vec<Project> = client.get("projects").await?;
+            mode.print(&projects, || {
+                projects
+                    .iter()
+                    .map(|project| {
+                        format!(
+                            "{}  {:<24} {}",
+                            project.id,
+                            project.name,
+                            project.git_repo_path.display()
+                        )
+                    })
+                    .collect()
+            });
+        }
+        Command::Tasks(TasksCommand::List { project }) => {
+            let tasks: Vec<TaskWithAttemptStatus> =
+                client.get(&format!("tasks?project_id={project}")).await?;
+            mode.print(&tasks, || {
+                tasks
+                    .iter()
+                    .map(|task| {
+                        let marker = if task.has_in_progress_attempt {
+                            " (running)"
+                        } else if task.last_attempt_failed {
+                            " (failed)"
+                        } else {
+                            ""
+                        };
+                        format!("{}  {:<10} {}{marker}", task.id, task.status, task.title)
+                    })
+                    .collect()
+            });
+        }
+        Command::Tasks(TasksCommand::Create {
+            project,
+            title,
+            description,
+            start,
+            profile,
+        }) => {
+            let task = CreateTask::from_title_description(project, title, description);
+            let created: Task = if start {
+                let (executor_profile_id, base_branch) =
+                    resolve_profile(&client, project, profile).await?;
+                client
+                    .post(
+                        "tasks/create-and-start",
+                        &CreateAndStartTaskRequest {
+                            task,
+                            executor_profile_id,
+                            base_branch,
+                        },
+                    )
+                    .await?
+            } else {
+                client.post("tasks", &task).await?
+            };
+            mode.print(&created, || {
+                vec![format!("Created task {} \"{}\"", created.id, created.title)]
+            });
+        }
+        Command::Attempts(AttemptsCommand::List { task }) => {
+            let attempts: Vec<TaskAttempt> =
+                client.get(&format!("task-attempts?task_id={task}")).await?;
+            mode.print(&attempts, || attempt_lines(&attempts));
+        }
+        Command::Attempts(AttemptsCommand::Start { task, profile }) => {
+            let parent: Task = client.get(&format!("tasks/{task}")).await?;
+            let (executor_profile_id, base_branch) =
+                resolve_profile(&client, parent.project_id, profile).await?;
+            let attempt: TaskAttempt = client
+                .post(
+                    "task-attempts",
+                    &CreateTaskAttemptBody {
+                        task_id: task,
+                        executor_profile_id,
+                        base_branch,
+                    },
+                )
+                .await?;
+            mode.print(&attempt, || {
+                vec![
+                    format!("Started attempt {} on {}", attempt.id, attempt.branch),
+                    format!("Follow it with: anyon logs {}", attempt.id),
+                ]
+            });
+        }
+        Command::Logs {
+            attempt_id,
+            process,
+        } => {
+            let process_id = match process {
+                Some(process_id) => process_id,
+                None => latest_agent_process(&client, attempt_id).await?,
+            };
+            if mode == OutputMode::Human {
+                eprintln!("Following run {}", short_id(&process_id));
+            }
+            logs::tail(&client, mode, process_id).await?;
+        }
+        Command::Approve {
+            approval_id,
+            process,
+            deny,
+            reason,
+        } => {
+            let status = if deny {
+                ApprovalStatus::Denied { reason }
+            } else {
+                ApprovalStatus::Approved
+            };
+            let result: ApprovalStatus = client
+                .post_raw(
+                    &format!("approvals/{approval_id}/respond"),
+                    &ApprovalResponse {
+                        execution_process_id: process,
+                        status,
+                    },
+                )
+                .await?;
+            mode.print(&result, || {
+                vec![format!("Approval {approval_id}: {result:?}")]
+            });
+        }
+        Command::Diff { attempt_id, mbox } => {
+            let format = if mbox { "mbox" } else { "diff" };
+            let patch = client
+                .get_text(&format!("task-attempts/{attempt_id}/patch?format={format}"))
+                .await?;
+            mode.print(
+                &serde_json::json!({ "format": format, "patch": patch }),
+                || vec![patch.trim_end().to_string()],
+            );
+        }
+        Command::Merge { attempt_id } => {
+            client
+                .post_empty(&format!("task-attempts/{attempt_id}/merge"), &())
+                .await?;
+            mode.print(&serde_json::json!({ "merged": true }), || {
+                vec![format!("Merged attempt {}", short_id(&attempt_id))]
+            });
+        }
+        Command::Pr {
+            attempt_id,
+            title,
+            body,
+            target_branch,
+        } => {
+            let url: String = client
+                .post(
+                    &format!("task-attempts/{attempt_id}/pr"),
+                    &CreateGitHubPrRequest {
+                        title,
+                        body,
+                        target_branch,
+                    },
+                )
+                .await?;
+            mode.print(&serde_json::json!({ "url": url }), || {
+                vec![format!("Opened {url}")]
+            });
+        }
+        Command::Stop { attempt_id } => {
+            client
+                .post_empty(&format!("task-attempts/{attempt_id}/stop"), &())
+                .await?;
+            mode.print(&serde_json::json!({ "stopped": true }), || {
+                vec![format!("Stopped attempt {}", short_id(&attempt_id))]
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn executor_names_are_case_insensitive() {
+        assert_eq!(
+            parse_executor("claude-code").unwrap(),
+            BaseCodingAgent::ClaudeCode
+        );
+        assert!(parse_executor("nope").is_err());
+    }
+}
@@ -12,8 +12,9 @@ use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
     auth::AuthError, config::ConfigError, container::ContainerError, drafts::DraftsServiceError,
-    git::GitServiceError, github_service::GitHubServiceError, image::ImageError,
-    secret_store::SecretStoreError, worktree_manager::WorktreeError,
+    forge::ForgeError, git::GitServiceError, github_service::GitHubServiceError,
+    identity::IdentityError, image::ImageError, project_secrets::ProjectSecretError,
+    secret_store::SecretStoreError, webhooks::WebhookError, worktree_manager::WorktreeError,
 };
 use thiserror::Error;
 use utils::response::ApiResponse;
@@ -32,6 +33,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    Forge(#[from] ForgeError),
+    #[error(transparent)]
     Auth(#[from] AuthError),
     #[error(transparent)]
     Deployment(#[from] DeploymentError),
@@ -51,12 +54,22 @@ pub enum ApiError {
     Drafts(#[from] DraftsServiceError),
     #[error(transparent)]
     SecretStore(#[from] SecretStoreError),
+    #[error(transparent)]
+    ProjectSecret(#[from] ProjectSecretError),
+    #[error(transparent)]
+    Identity(#[from] IdentityError),
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Bad request: {0}")]
+    BadRequest(String),
     #[error("Conflict: {0}")]
     Conflict(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
 }
 
 impl From<Git2Error> for ApiError {
@@ -87,9 +100,26 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::Forge(forge_err) => match forge_err {
+                ForgeError::UnsupportedRemote(_) | ForgeError::PrivateAddress(_) => {
+                    (StatusCode::BAD_REQUEST, "ForgeError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ForgeError"),
+            },
+            ApiError::Auth(AuthError::DeviceFlowNotStarted) => {
+                (StatusCode::BAD_REQUEST, "AuthError")
+            }
             ApiError::Auth(_) => (StatusCode::INTERNAL_SERVER_ERROR, "AuthError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
-            ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            ApiError::Container(container_err) => match container_err {
+                ContainerError::UnconfiguredProfile(_) => {
+                    (StatusCode::BAD_REQUEST, "ContainerError")
+                }
+                ContainerError::NothingToHandOff | ContainerError::AlreadyRunsOn(_) => {
+                    (StatusCode::CONFLICT, "ContainerError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            },
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
             ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
@@ -114,9 +144,38 @@ impl IntoResponse for ApiError {
                 }
             },
             ApiError::SecretStore(_) => (StatusCode::INTERNAL_SERVER_ERROR, "SecretStoreError"),
+            ApiError::ProjectSecret(secret_err) => match secret_err {
+                ProjectSecretError::InvalidName(_) | ProjectSecretError::MissingValue(_) => {
+                    (StatusCode::BAD_REQUEST, "ProjectSecretError")
+                }
+                ProjectSecretError::NotFound(_) => (StatusCode::NOT_FOUND, "ProjectSecretError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectSecretError"),
+            },
+            ApiError::Identity(identity_err) => match identity_err {
+                IdentityError::UnknownProvider(_) => (StatusCode::NOT_FOUND, "IdentityError"),
+                IdentityError::InvalidState
+                | IdentityError::Provider(_)
+                | IdentityError::InvalidIdToken(_) => (StatusCode::UNAUTHORIZED, "IdentityError"),
+                IdentityError::Http(_) | IdentityError::Parse(_) => {
+                    (StatusCode::BAD_GATEWAY, "IdentityError")
+                }
+                IdentityError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IdentityError"),
+            },
+            ApiError::Webhook(webhook_err) => match webhook_err {
+                WebhookError::InvalidUrl(_)
+                | WebhookError::UnresolvableHost(_)
+                | WebhookError::PrivateAddress(_)
+                | WebhookError::NoEvents => (StatusCode::BAD_REQUEST, "WebhookError"),
+                WebhookError::NotFound | WebhookError::DeliveryNotFound => {
+                    (StatusCode::NOT_FOUND, "WebhookError")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "WebhookError"),
+            },
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "MultipartError"),
+            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequestError"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
+            ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
         };
 
         let error_message = match &self {
@@ -140,7 +199,10 @@ impl IntoResponse for ApiError {
                 _ => format!("{}: {}", error_type, self),
             },
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
+            ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
+            ApiError::Forbidden(msg) => msg.clone(),
+            ApiError::Identity(identity_err) => identity_err.to_string(),
             ApiError::Drafts(drafts_err) => match drafts_err {
                 DraftsServiceError::Conflict(msg) => msg.clone(),
                 DraftsServiceError::Database(_) => format!("{}: {}", error_type, drafts_err),
@@ -1,4 +1,7 @@
+pub mod audit;
 pub mod auth;
+pub mod batch;
+pub mod cli;
 pub mod error;
 pub mod mcp;
 pub mod middleware;
@@ -1,24 +1,43 @@
-use std::{future::Future, path::PathBuf, str::FromStr};
+use std::{future::Future, net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
 
+use axum::{
+    Router,
+    body::Body,
+    extract::ConnectInfo,
+    http::{HeaderMap, Method, StatusCode, header, request::Parts},
+};
 use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     project::Project,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     task_attempt::TaskAttempt,
 };
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{executors::BaseCodingAgent, logs::NormalizedEntry, profile::ExecutorProfileId};
 use rmcp::{
-    ErrorData, ServerHandler,
+    ErrorData, RoleServer, ServerHandler,
     handler::server::tool::{Parameters, ToolRouter},
     model::{
         CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
     },
-    schemars, tool, tool_handler, tool_router,
+    schemars,
+    service::RequestContext,
+    tool, tool_handler, tool_router,
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use tower::ServiceExt;
 use uuid::Uuid;
 
-use crate::routes::task_attempts::CreateTaskAttemptBody;
+use crate::routes::task_attempts::{
+    CreateFollowUpAttempt, CreateGitHubPrRequest, CreateTaskAttemptBody,
+};
+
+const DEFAULT_CONVERSATION_ENTRIES: usize = 20;
+/// Entries are cut to this many characters so tool results stay readable
+const MAX_ENTRY_CHARS: usize = 2000;
+const DEFAULT_WAIT_SECONDS: u64 = 600;
+const MAX_WAIT_SECONDS: u64 = 3600;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskRequest {
@@ -234,21 +253,230 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AttemptRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ProcessSummary {
+    pub id: String,
+    #[schemars(description = "'setupscript', 'codingagent', 'cleanupscript' or 'devserver'")]
+    pub run_reason: String,
+    #[schemars(description = "'running', 'completed', 'failed' or 'killed'")]
+    pub status: String,
+    pub exit_code: Option<i64>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+}
+
+impl ProcessSummary {
+    fn from_process(process: &ExecutionProcess) -> Self {
+        Self {
+            id: process.id.to_string(),
+            run_reason: enum_name(&process.run_reason),
+            status: enum_name(&process.status),
+            exit_code: process.exit_code,
+            started_at: process.started_at.to_rfc3339(),
+            completed_at: process.completed_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AttemptStatusResponse {
+    pub attempt_id: String,
+    pub task_id: String,
+    pub branch: String,
+    pub target_branch: String,
+    pub executor: String,
+    #[schemars(description = "Whether any process of the attempt is still running")]
+    pub is_running: bool,
+    #[schemars(description = "The latest coding agent run, if any")]
+    pub latest_agent_run: Option<ProcessSummary>,
+    pub processes: Vec<ProcessSummary>,
+    #[schemars(description = "Commits ahead/behind the target branch, merges and open PRs")]
+    pub branch_status: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetAttemptConversationRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Return only the last N entries (default: 20)")]
+    pub last: Option<usize>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ConversationEntry {
+    #[schemars(
+        description = "'user', 'assistant', 'tool:<name>', 'system', 'error', 'thinking' or 'feedback'"
+    )]
+    pub kind: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptConversationResponse {
+    pub attempt_id: String,
+    pub execution_process_id: String,
+    pub status: String,
+    #[schemars(description = "Number of entries in the whole conversation")]
+    pub total_entries: usize,
+    pub entries: Vec<ConversationEntry>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetAttemptDiffResponse {
+    pub attempt_id: String,
+    #[schemars(description = "Unified diff of the attempt's changes against its base")]
+    pub diff: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FollowUpRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Instructions for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Optional executor variant for this run")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct FollowUpResponse {
+    pub attempt_id: String,
+    pub execution_process_id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AttemptActionResponse {
+    pub attempt_id: String,
+    pub success: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreatePrRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Title of the pull request")]
+    pub title: String,
+    #[schemars(description = "Optional body of the pull request")]
+    pub body: Option<String>,
+    #[schemars(description = "Base branch; defaults to the attempt's target branch")]
+    pub target_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct CreatePrResponse {
+    pub attempt_id: String,
+    pub pr_url: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WaitForAttemptRequest {
+    #[schemars(description = "The ID of the task attempt")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "Seconds to wait before giving up (default: 600, max: 3600)")]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WaitForAttemptResponse {
+    #[schemars(description = "True if the attempt was still running when the timeout hit")]
+    pub timed_out: bool,
+    pub status: AttemptStatusResponse,
+}
+
+/// Serde name of a lowercase enum, such as a process status
+fn enum_name(value: &impl Serialize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn truncate(content: &str) -> String {
+    match content.char_indices().nth(MAX_ENTRY_CHARS) {
+        Some((end, _)) => format!("{}…", &content[..end]),
+        None => content.to_string(),
+    }
+}
+
+/// Where tools send their API calls
+#[derive(Debug, Clone)]
+enum ApiTarget {
+    /// A running server's HTTP API
+    Remote {
+        client: reqwest::Client,
+        base_url: String,
+        token: Option<String>,
+    },
+    /// This server's own API router, called in-process with the MCP request's credentials
+    InProcess(Router),
+}
+
+/// Headers of an MCP request passed on to in-process API calls, so they authenticate and are
+/// audited as the MCP client. The client's address goes along as [`ConnectInfo`].
+const FORWARDED_HEADERS: [header::HeaderName; 5] = [
+    header::AUTHORIZATION,
+    header::COOKIE,
+    header::USER_AGENT,
+    header::HeaderName::from_static("x-forwarded-for"),
+    header::HeaderName::from_static("x-real-ip"),
+];
+
+/// One API call made by a tool
+struct ApiRequest {
+    method: Method,
+    path: String,
+    body: Option<serde_json::Value>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
+}
+
+impl ApiRequest {
+    fn json<T: Serialize>(mut self, body: &T) -> Self {
+        self.body = serde_json::to_value(body).ok();
+        self
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
-    client: reqwest::Client,
-    base_url: String,
+    target: ApiTarget,
     tool_router: ToolRouter<TaskServer>,
 }
 
 impl TaskServer {
+    /// Tools call the server at `base_url` over HTTP
     pub fn new(base_url: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
-            base_url: base_url.to_string(),
+            target: ApiTarget::Remote {
+                client: reqwest::Client::new(),
+                base_url: base_url.to_string(),
+                token: None,
+            },
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Tools call `api`, a router serving the API under `/api`, without leaving the process
+    pub fn in_process(api: Router) -> Self {
+        Self {
+            target: ApiTarget::InProcess(api),
             tool_router: Self::tool_router(),
         }
     }
+
+    /// Call the API with a personal API token, so tools run with its owner's permissions
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        if let ApiTarget::Remote { token: target, .. } = &mut self.target {
+            *target = token;
+        }
+        self
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -281,23 +509,71 @@ impl TaskServer {
         Self::err_value(v)
     }
 
-    async fn send_json<T: DeserializeOwned>(
-        &self,
-        rb: reqwest::RequestBuilder,
-    ) -> Result<T, CallToolResult> {
-        let resp = rb
-            .send()
+    /// Status and body of an API call
+    async fn send(&self, request: ApiRequest) -> Result<(StatusCode, String), String> {
+        match &self.target {
+            ApiTarget::Remote {
+                client,
+                base_url,
+                token,
+            } => {
+                let url = format!(
+                    "{}/{}",
+                    base_url.trim_end_matches('/'),
+                    request.path.trim_start_matches('/')
+                );
+                let mut rb = client.request(request.method, url);
+                if let Some(token) = token {
+                    rb = rb.bearer_auth(token);
+                }
+                if let Some(body) = &request.body {
+                    rb = rb.json(body);
+                }
+                let resp = rb.send().await.map_err(|e| e.to_string())?;
+                let status = resp.status();
+                Ok((status, resp.text().await.unwrap_or_default()))
+            }
+            ApiTarget::InProcess(router) => {
+                let mut builder = axum::http::Request::builder()
+                    .method(request.method)
+                    .uri(&request.path);
+                for (name, value) in &request.headers {
+                    builder = builder.header(name, value);
+                }
+                if let Some(peer) = request.peer {
+                    builder = builder.extension(peer);
+                }
+                let body = match &request.body {
+                    Some(body) => {
+                        builder = builder.header(header::CONTENT_TYPE, "application/json");
+                        Body::from(serde_json::to_vec(body).map_err(|e| e.to_string())?)
+                    }
+                    None => Body::empty(),
+                };
+                let req = builder.body(body).map_err(|e| e.to_string())?;
+                let Ok(resp) = router.clone().oneshot(req).await;
+                let status = resp.status();
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok((status, String::from_utf8_lossy(&bytes).into_owned()))
+            }
+        }
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, rb: ApiRequest) -> Result<T, CallToolResult> {
+        let (status, body) = self
+            .send(rb)
             .await
-            .map_err(|e| Self::err("Failed to connect to VK API", Some(&e.to_string())).unwrap())?;
+            .map_err(|e| Self::err("Failed to connect to VK API", Some(&e)).unwrap())?;
 
-        if !resp.status().is_success() {
-            let status = resp.status();
+        if !status.is_success() {
             return Err(
                 Self::err(format!("VK API returned error status: {}", status), None).unwrap(),
             );
         }
 
-        let api_response = resp.json::<ApiResponseEnvelope<T>>().await.map_err(|e| {
+        let api_response = serde_json::from_str::<ApiResponseEnvelope<T>>(&body).map_err(|e| {
             Self::err("Failed to parse VK API response", Some(&e.to_string())).unwrap()
         })?;
 
@@ -311,15 +587,123 @@ impl TaskServer {
             .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
     }
 
-    fn url(&self, path: &str) -> String {
-        format!(
-            "{}/{}",
-            self.base_url.trim_end_matches('/'),
-            path.trim_start_matches('/')
-        )
+    /// Succeeds when the API does, for endpoints that return no data
+    async fn send_ok(&self, rb: ApiRequest) -> Result<(), CallToolResult> {
+        let (status, body) = self
+            .send(rb)
+            .await
+            .map_err(|e| Self::err("Failed to connect to VK API", Some(&e)).unwrap())?;
+        let api_response = serde_json::from_str::<ApiResponseEnvelope<serde_json::Value>>(&body)
+            .map_err(|_| {
+                Self::err(format!("VK API returned error status: {}", status), None).unwrap()
+            })?;
+        if !api_response.success {
+            let msg = api_response.message.as_deref().unwrap_or("Unknown error");
+            return Err(Self::err("VK API returned error", Some(msg)).unwrap());
+        }
+        Ok(())
+    }
+
+    /// Body of an endpoint that doesn't use the API envelope
+    async fn send_text(&self, rb: ApiRequest) -> Result<String, CallToolResult> {
+        let (status, body) = self
+            .send(rb)
+            .await
+            .map_err(|e| Self::err("Failed to connect to VK API", Some(&e)).unwrap())?;
+        if !status.is_success() {
+            return Err(Self::err(
+                format!("VK API returned error status: {}", status),
+                Some(body),
+            )
+            .unwrap());
+        }
+        Ok(body)
+    }
+
+    fn request(
+        &self,
+        context: &RequestContext<RoleServer>,
+        method: Method,
+        path: &str,
+    ) -> ApiRequest {
+        let mut headers = HeaderMap::new();
+        let mut peer = None;
+        // Streamable HTTP puts the MCP request's HTTP parts in the context; stdio has none
+        if let Some(parts) = context.extensions.get::<Parts>() {
+            for name in FORWARDED_HEADERS {
+                if let Some(value) = parts.headers.get(&name) {
+                    headers.insert(name, value.clone());
+                }
+            }
+            peer = parts.extensions.get::<ConnectInfo<SocketAddr>>().cloned();
+        }
+        ApiRequest {
+            method,
+            path: path.to_string(),
+            body: None,
+            headers,
+            peer,
+        }
+    }
+
+    async fn attempt_status(
+        &self,
+        context: &RequestContext<RoleServer>,
+        attempt_id: Uuid,
+    ) -> Result<AttemptStatusResponse, CallToolResult> {
+        let attempt: TaskAttempt = self
+            .send_json(self.request(
+                context,
+                Method::GET,
+                &format!("/api/task-attempts/{attempt_id}"),
+            ))
+            .await?;
+        let processes = self.attempt_processes(context, attempt_id).await?;
+        // Branch status needs the worktree, which may have been cleaned up
+        let branch_status = self
+            .send_json(self.request(
+                context,
+                Method::GET,
+                &format!("/api/task-attempts/{attempt_id}/branch-status"),
+            ))
+            .await
+            .ok();
+        Ok(AttemptStatusResponse {
+            attempt_id: attempt.id.to_string(),
+            task_id: attempt.task_id.to_string(),
+            branch: attempt.branch,
+            target_branch: attempt.target_branch,
+            executor: attempt.executor,
+            is_running: processes
+                .iter()
+                .any(|p| p.status == ExecutionProcessStatus::Running),
+            latest_agent_run: latest_agent_run(&processes).map(ProcessSummary::from_process),
+            processes: processes.iter().map(ProcessSummary::from_process).collect(),
+            branch_status,
+        })
+    }
+
+    async fn attempt_processes(
+        &self,
+        context: &RequestContext<RoleServer>,
+        attempt_id: Uuid,
+    ) -> Result<Vec<ExecutionProcess>, CallToolResult> {
+        self.send_json(self.request(
+            context,
+            Method::GET,
+            &format!("/api/execution-processes?task_attempt_id={attempt_id}"),
+        ))
+        .await
     }
 }
 
+fn latest_agent_run(processes: &[ExecutionProcess]) -> Option<&ExecutionProcess> {
+    processes
+        .iter()
+        .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .max_by_key(|p| p.started_at)
+}
+
 #[tool_router]
 impl TaskServer {
     #[tool(
@@ -332,18 +716,12 @@ impl TaskServer {
             title,
             description,
         }): Parameters<CreateTaskRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        let url = self.url("/api/tasks");
         let task: Task = match self
-            .send_json(
-                self.client
-                    .post(&url)
-                    .json(&CreateTask::from_title_description(
-                        project_id,
-                        title,
-                        description,
-                    )),
-            )
+            .send_json(self.request(&context, Method::POST, "/api/tasks").json(
+                &CreateTask::from_title_description(project_id, title, description),
+            ))
             .await
         {
             Ok(t) => t,
@@ -356,9 +734,14 @@ impl TaskServer {
     }
 
     #[tool(description = "List all the available projects")]
-    async fn list_projects(&self) -> Result<CallToolResult, ErrorData> {
-        let url = self.url("/api/projects");
-        let projects: Vec<Project> = match self.send_json(self.client.get(&url)).await {
+    async fn list_projects(
+        &self,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let projects: Vec<Project> = match self
+            .send_json(self.request(&context, Method::GET, "/api/projects"))
+            .await
+        {
             Ok(ps) => ps,
             Err(e) => return Ok(e),
         };
@@ -386,6 +769,7 @@ impl TaskServer {
             status,
             limit,
         }): Parameters<ListTasksRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let status_filter = if let Some(ref status_str) = status {
             match TaskStatus::from_str(status_str) {
@@ -401,12 +785,17 @@ impl TaskServer {
             None
         };
 
-        let url = self.url(&format!("/api/tasks?project_id={}", project_id));
-        let all_tasks: Vec<TaskWithAttemptStatus> =
-            match self.send_json(self.client.get(&url)).await {
-                Ok(t) => t,
-                Err(e) => return Ok(e),
-            };
+        let all_tasks: Vec<TaskWithAttemptStatus> = match self
+            .send_json(self.request(
+                &context,
+                Method::GET,
+                &format!("/api/tasks?project_id={}", project_id),
+            ))
+            .await
+        {
+            Ok(t) => t,
+            Err(e) => return Ok(e),
+        };
 
         let task_limit = limit.unwrap_or(50).max(0) as usize;
         let filtered = all_tasks.into_iter().filter(|t| {
@@ -445,6 +834,7 @@ impl TaskServer {
             variant,
             base_branch,
         }): Parameters<StartTaskAttemptRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_branch = base_branch.trim().to_string();
         if base_branch.is_empty() {
@@ -487,8 +877,12 @@ impl TaskServer {
             base_branch,
         };
 
-        let url = self.url("/api/task-attempts");
-        let attempt: TaskAttempt = match self.send_json(self.client.post(&url).json(&payload)).await
+        let attempt: TaskAttempt = match self
+            .send_json(
+                self.request(&context, Method::POST, "/api/task-attempts")
+                    .json(&payload),
+            )
+            .await
         {
             Ok(attempt) => attempt,
             Err(e) => return Ok(e),
@@ -513,6 +907,7 @@ impl TaskServer {
             description,
             status,
         }): Parameters<UpdateTaskRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let status = if let Some(ref status_str) = status {
             match TaskStatus::from_str(status_str) {
@@ -535,8 +930,13 @@ impl TaskServer {
             parent_task_attempt: None,
             image_ids: None,
         };
-        let url = self.url(&format!("/api/tasks/{}", task_id));
-        let updated_task: Task = match self.send_json(self.client.put(&url).json(&payload)).await {
+        let updated_task: Task = match self
+            .send_json(
+                self.request(&context, Method::PUT, &format!("/api/tasks/{}", task_id))
+                    .json(&payload),
+            )
+            .await
+        {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
@@ -552,10 +952,14 @@ impl TaskServer {
     async fn delete_task(
         &self,
         Parameters(DeleteTaskRequest { task_id }): Parameters<DeleteTaskRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        let url = self.url(&format!("/api/tasks/{}", task_id));
         if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.delete(&url))
+            .send_json::<serde_json::Value>(self.request(
+                &context,
+                Method::DELETE,
+                &format!("/api/tasks/{}", task_id),
+            ))
             .await
         {
             return Ok(e);
@@ -574,9 +978,12 @@ impl TaskServer {
     async fn get_task(
         &self,
         Parameters(GetTaskRequest { task_id }): Parameters<GetTaskRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        let url = self.url(&format!("/api/tasks/{}", task_id));
-        let task: Task = match self.send_json(self.client.get(&url)).await {
+        let task: Task = match self
+            .send_json(self.request(&context, Method::GET, &format!("/api/tasks/{}", task_id)))
+            .await
+        {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
@@ -586,6 +993,267 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+    #[tool(
+        description = "Get a task attempt's status: its branch, whether it is still running, its execution processes and how its branch compares to the target branch. `attempt_id` is required!"
+    )]
+    async fn get_attempt_status(
+        &self,
+        Parameters(AttemptRequest { attempt_id }): Parameters<AttemptRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.attempt_status(&context, attempt_id).await {
+            Ok(status) => TaskServer::success(&status),
+            Err(e) => Ok(e),
+        }
+    }
+
+    #[tool(
+        description = "Read the conversation of a task attempt's latest coding agent run: messages, tool calls and errors. Long entries are truncated. `attempt_id` is required!"
+    )]
+    async fn get_attempt_conversation(
+        &self,
+        Parameters(GetAttemptConversationRequest { attempt_id, last }): Parameters<
+            GetAttemptConversationRequest,
+        >,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let processes = match self.attempt_processes(&context, attempt_id).await {
+            Ok(processes) => processes,
+            Err(e) => return Ok(e),
+        };
+        let Some(process) = latest_agent_run(&processes) else {
+            return Self::err(
+                "The attempt has no coding agent runs yet.".to_string(),
+                None,
+            );
+        };
+        let entries: Vec<NormalizedEntry> = match self
+            .send_json(self.request(
+                &context,
+                Method::GET,
+                &format!("/api/execution-processes/{}/conversation", process.id),
+            ))
+            .await
+        {
+            Ok(entries) => entries,
+            Err(e) => return Ok(e),
+        };
+
+        let total_entries = entries.len();
+        let last = last.unwrap_or(DEFAULT_CONVERSATION_ENTRIES);
+        let entries = entries
+            .into_iter()
+            .skip(total_entries.saturating_sub(last))
+            .filter_map(|entry| {
+                Some(ConversationEntry {
+                    kind: entry.entry_type.label()?,
+                    content: truncate(&entry.content),
+                })
+            })
+            .collect();
+
+        TaskServer::success(&GetAttemptConversationResponse {
+            attempt_id: attempt_id.to_string(),
+            execution_process_id: process.id.to_string(),
+            status: enum_name(&process.status),
+            total_entries,
+            entries,
+        })
+    }
+
+    #[tool(
+        description = "Get the unified diff of a task attempt's changes against the commit it branched from. `attempt_id` is required!"
+    )]
+    async fn get_attempt_diff(
+        &self,
+        Parameters(AttemptRequest { attempt_id }): Parameters<AttemptRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let diff = match self
+            .send_text(self.request(
+                &context,
+                Method::GET,
+                &format!("/api/task-attempts/{attempt_id}/patch?format=diff"),
+            ))
+            .await
+        {
+            Ok(diff) => diff,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&GetAttemptDiffResponse {
+            attempt_id: attempt_id.to_string(),
+            diff,
+        })
+    }
+
+    #[tool(
+        description = "Send follow-up instructions to the coding agent of a finished task attempt, continuing its session. `attempt_id` and `prompt` are required!"
+    )]
+    async fn follow_up(
+        &self,
+        Parameters(FollowUpRequest {
+            attempt_id,
+            prompt,
+            variant,
+        }): Parameters<FollowUpRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if prompt.trim().is_empty() {
+            return Self::err("Prompt must not be empty.".to_string(), None::<String>);
+        }
+        let payload = CreateFollowUpAttempt {
+            prompt,
+            variant,
+            image_ids: None,
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+        };
+        let process: ExecutionProcess = match self
+            .send_json(
+                self.request(
+                    &context,
+                    Method::POST,
+                    &format!("/api/task-attempts/{attempt_id}/follow-up"),
+                )
+                .json(&payload),
+            )
+            .await
+        {
+            Ok(process) => process,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&FollowUpResponse {
+            attempt_id: attempt_id.to_string(),
+            execution_process_id: process.id.to_string(),
+        })
+    }
+
+    #[tool(description = "Stop all running processes of a task attempt. `attempt_id` is required!")]
+    async fn stop_attempt(
+        &self,
+        Parameters(AttemptRequest { attempt_id }): Parameters<AttemptRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self
+            .send_ok(self.request(
+                &context,
+                Method::POST,
+                &format!("/api/task-attempts/{attempt_id}/stop"),
+            ))
+            .await
+        {
+            return Ok(e);
+        }
+
+        TaskServer::success(&AttemptActionResponse {
+            attempt_id: attempt_id.to_string(),
+            success: true,
+        })
+    }
+
+    #[tool(
+        description = "Merge a task attempt's branch into its target branch in the local repository. `attempt_id` is required!"
+    )]
+    async fn merge_attempt(
+        &self,
+        Parameters(AttemptRequest { attempt_id }): Parameters<AttemptRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Err(e) = self
+            .send_ok(self.request(
+                &context,
+                Method::POST,
+                &format!("/api/task-attempts/{attempt_id}/merge"),
+            ))
+            .await
+        {
+            return Ok(e);
+        }
+
+        TaskServer::success(&AttemptActionResponse {
+            attempt_id: attempt_id.to_string(),
+            success: true,
+        })
+    }
+
+    #[tool(
+        description = "Push a task attempt's branch and open a pull request for it. `attempt_id` and `title` are required!"
+    )]
+    async fn create_pr(
+        &self,
+        Parameters(CreatePrRequest {
+            attempt_id,
+            title,
+            body,
+            target_branch,
+        }): Parameters<CreatePrRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = CreateGitHubPrRequest {
+            title,
+            body,
+            target_branch,
+        };
+        let pr_url: String = match self
+            .send_json(
+                self.request(
+                    &context,
+                    Method::POST,
+                    &format!("/api/task-attempts/{attempt_id}/pr"),
+                )
+                .json(&payload),
+            )
+            .await
+        {
+            Ok(url) => url,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&CreatePrResponse {
+            attempt_id: attempt_id.to_string(),
+            pr_url,
+        })
+    }
+
+    #[tool(
+        description = "Wait until a task attempt has no running processes, or until the timeout. Returns the attempt's status. `attempt_id` is required!"
+    )]
+    async fn wait_for_attempt(
+        &self,
+        Parameters(WaitForAttemptRequest {
+            attempt_id,
+            timeout_seconds,
+        }): Parameters<WaitForAttemptRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let timeout = Duration::from_secs(
+            timeout_seconds
+                .unwrap_or(DEFAULT_WAIT_SECONDS)
+                .min(MAX_WAIT_SECONDS),
+        );
+        let deadline = tokio::time::Instant::now() + timeout;
+        // The next step of a run (e.g. the cleanup script) starts just after the previous one
+        // exits, so only an idle state seen twice in a row counts as finished
+        let mut idle_polls = 0;
+        loop {
+            let status = match self.attempt_status(&context, attempt_id).await {
+                Ok(status) => status,
+                Err(e) => return Ok(e),
+            };
+            idle_polls = if status.is_running { 0 } else { idle_polls + 1 };
+            let timed_out = tokio::time::Instant::now() >= deadline;
+            if idle_polls >= 2 || timed_out {
+                return TaskServer::success(&WaitForAttemptResponse {
+                    timed_out: timed_out && status.is_running,
+                    status,
+                });
+            }
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        }
+    }
 }
 
 #[tool_handler]
@@ -600,7 +1268,7 @@ impl ServerHandler for TaskServer {
                 name: "anyon".to_string(),
                 version: "1.0.0".to_string(),
             },
-            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
+            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', 'get_attempt_status', 'get_attempt_conversation', 'get_attempt_diff', 'follow_up', 'stop_attempt', 'merge_attempt', 'create_pr', 'wait_for_attempt'. After starting an attempt, call `wait_for_attempt` and then `get_attempt_conversation` and `get_attempt_diff` to review the result. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
         }
     }
 }
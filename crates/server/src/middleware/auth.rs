@@ -1,28 +1,126 @@
 //! Authentication middleware for multi-user support
 //!
-//! This middleware checks if the user has a valid GitHub token and injects
-//! the authenticated user information into the request.
+//! This middleware checks if the user has a valid GitHub token, or a personal API token in
+//! an `Authorization: Bearer` header, and injects the authenticated user information into the
+//! request.
 
 use axum::{
     body::Body,
-    extract::State,
-    http::{Request, StatusCode},
+    extract::{OriginalUri, State},
+    http::{Method, Request, StatusCode, header},
     middleware::Next,
     response::Response,
 };
+use db::models::api_token::TokenScope;
 use deployment::Deployment;
 use services::services::{
+    api_tokens,
     github_service::{GitHubService, GitHubServiceError},
     secret_store::SECRET_GITHUB_OAUTH,
 };
 
 #[cfg(not(feature = "cloud"))]
 use crate::routes::auth::DEV_LOGIN_PLACEHOLDER_TOKEN;
-use crate::{DeploymentImpl, auth::AuthenticatedUser};
+use crate::{
+    DeploymentImpl,
+    auth::{AuthenticatedUser, multi_user_mode, session},
+};
+
+/// API paths where personal API tokens may make changes. Every write under them is guarded by
+/// a project permission, which also checks the token's scopes.
+const TOKEN_WRITE_PATHS: &[&str] = &[
+    "/api/approvals",
+    "/api/projects/",
+    "/api/tasks",
+    "/api/task-attempts",
+    "/api/execution-processes",
+    // Each MCP tool call is checked again by the route it calls in-process
+    "/api/mcp",
+];
+
+/// Authenticate a request carrying a personal API token
+async fn authenticate_api_token(
+    deployment: &DeploymentImpl,
+    mut req: Request<Body>,
+    next: Next,
+    token: &str,
+) -> Result<Response, StatusCode> {
+    let api_token = api_tokens::authenticate(&deployment.db().pool, token)
+        .await
+        .map_err(|err| {
+            tracing::error!("Failed to look up API token: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or_else(|| {
+            tracing::debug!("Unknown or expired API token");
+            StatusCode::UNAUTHORIZED
+        })?;
+
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let allowed = if matches!(*req.method(), Method::GET | Method::HEAD) {
+        api_token.has_scope(TokenScope::Read)
+    } else {
+        TOKEN_WRITE_PATHS
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    };
+    if !allowed {
+        tracing::debug!(
+            "API token {} may not {} {}",
+            api_token.token_prefix,
+            req.method(),
+            path
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    req.extensions_mut()
+        .insert(AuthenticatedUser::from_api_token(&api_token));
+    Ok(next.run(req).await)
+}
+
+fn bearer_token(req: &Request<Body>) -> Option<String> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string())
+}
+
+/// Like [`require_auth`], but only accepts a personal API token or a single sign-on session,
+/// never the server's own login. For endpoints reachable by other clients on the machine
+/// without a browser login, such as MCP over HTTP.
+pub async fn require_credentials(
+    State(deployment): State<DeploymentImpl>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Some(token) = bearer_token(&req) {
+        return authenticate_api_token(&deployment, req, next, &token).await;
+    }
+    match session::session_user(&deployment, req.headers()).await {
+        Ok(Some(user)) => {
+            req.extensions_mut().insert(user);
+            Ok(next.run(req).await)
+        }
+        Ok(None) => Err(StatusCode::UNAUTHORIZED),
+        Err(err) => {
+            tracing::error!("Failed to look up session: {}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
 
 /// Middleware that requires GitHub authentication
 ///
-/// This middleware:
+/// Requests with an `Authorization: Bearer <personal API token>` header are authenticated as
+/// the token's owner, limited to the token's scopes, and requests with a single sign-on session
+/// cookie as the session's user. On a shared server (see [`multi_user_mode`]) any other request
+/// is rejected with 401. Otherwise this middleware:
 /// 1. Retrieves the GitHub token from SecretStore
 /// 2. Validates the token with GitHub API (using cache)
 /// 3. Injects AuthenticatedUser into the request extensions
@@ -39,6 +137,29 @@ pub async fn require_auth(
     mut req: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    if let Some(token) = bearer_token(&req) {
+        return authenticate_api_token(&deployment, req, next, &token).await;
+    }
+
+    // Users signed in through single sign-on carry a session cookie
+    match session::session_user(&deployment, req.headers()).await {
+        Ok(Some(user)) => {
+            req.extensions_mut().insert(user);
+            return Ok(next.run(req).await);
+        }
+        Ok(None) => {}
+        Err(err) => {
+            tracing::error!("Failed to look up session: {}", err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    // Only a single-user server may act as its own login for requests without credentials
+    if multi_user_mode(&deployment) {
+        tracing::debug!("Rejecting request without a session or API token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
     // Step 1: Get GitHub token from SecretStore
     // "이 사용자가 GitHub 로그인 했을 때 받은 토큰을 가져옴"
     let token = deployment
@@ -66,7 +187,9 @@ pub async fn require_auth(
             .clone()
             .unwrap_or_else(|| "Dev User".to_string());
 
-        let dev_user = AuthenticatedUser::from_dev_user(deployment.user_id().to_string(), username);
+        let mut dev_user =
+            AuthenticatedUser::from_dev_user(deployment.user_id().to_string(), username);
+        dev_user.server_login = true;
 
         req.extensions_mut().insert(dev_user);
         return Ok(next.run(req).await);
@@ -112,8 +235,9 @@ pub async fn require_auth(
 
     // Step 4: Create AuthenticatedUser and inject into request
     // "검증된 사용자 정보를 Request에 추가 → 핸들러에서 사용 가능"
-    let authenticated_user =
+    let mut authenticated_user =
         AuthenticatedUser::from_github_user(user.id, user.login, user.avatar_url);
+    authenticated_user.server_login = true;
 
     tracing::debug!(
         "Authenticated user: {} ({})",
@@ -0,0 +1,228 @@
+//! Role-based access control for shared projects
+//!
+//! Loaders resolve the user's role on the project a request targets and insert it as a
+//! [`ProjectAccess`] extension; [`require_project_permission`] then guards individual routes.
+//!
+//! # Example
+//! ```ignore
+//! .route(
+//!     "/merge",
+//!     post(merge_task_attempt).route_layer(from_fn_with_state(
+//!         ProjectPermission::Merge,
+//!         require_project_permission,
+//!     )),
+//! )
+//! ```
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use db::models::{
+    api_token::TokenScope, organization::MemberRole, project_member::ProjectMember, task::Task,
+    task_attempt::TaskAttempt,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{auth::AuthenticatedUser, error::ApiError};
+
+/// Actions guarded by project roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectPermission {
+    /// Read the project, its tasks, attempts and logs
+    View,
+    /// Create, edit and delete tasks
+    EditTasks,
+    /// Start, follow up on and stop attempts and scripts
+    RunAttempts,
+    /// Merge, push, rebase and open pull requests
+    Merge,
+    /// Change project settings, secrets, repositories and members
+    Manage,
+    /// Delete the project or change the organization it is shared with
+    Delete,
+}
+
+impl ProjectPermission {
+    /// Least privileged role holding this permission
+    pub fn min_role(self) -> MemberRole {
+        match self {
+            Self::View => MemberRole::Viewer,
+            Self::EditTasks | Self::RunAttempts => MemberRole::Contributor,
+            Self::Merge | Self::Manage => MemberRole::Maintainer,
+            Self::Delete => MemberRole::Owner,
+        }
+    }
+
+    /// API token scope granting this permission; settings and membership need a session
+    pub fn token_scope(self) -> Option<TokenScope> {
+        match self {
+            Self::View => Some(TokenScope::Read),
+            Self::EditTasks => Some(TokenScope::TasksWrite),
+            Self::RunAttempts => Some(TokenScope::AttemptsRun),
+            Self::Merge => Some(TokenScope::Merge),
+            Self::Manage | Self::Delete => None,
+        }
+    }
+
+    /// Whether the user's credentials (session or API token scopes) cover this permission
+    pub fn allowed_for(self, user: &AuthenticatedUser) -> bool {
+        user.token_scopes.is_none()
+            || self
+                .token_scope()
+                .is_some_and(|scope| user.has_scope(scope))
+    }
+}
+
+/// The requesting user's role on the project a request targets
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectAccess {
+    pub project_id: Uuid,
+    pub role: MemberRole,
+}
+
+impl ProjectAccess {
+    /// None when the user has no role on the project
+    pub async fn resolve(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user: &AuthenticatedUser,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Ok(
+            ProjectMember::effective_role(pool, project_id, &user.user_id)
+                .await?
+                .map(|role| Self { project_id, role }),
+        )
+    }
+
+    pub fn allows(&self, permission: ProjectPermission) -> bool {
+        self.role >= permission.min_role()
+    }
+
+    /// Check a permission in handlers that take the project from the body or query
+    pub async fn require(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        user: &AuthenticatedUser,
+        permission: ProjectPermission,
+    ) -> Result<Self, ApiError> {
+        if !permission.allowed_for(user) {
+            return Err(ApiError::Forbidden(
+                "This API token does not have the required scope".to_string(),
+            ));
+        }
+        match Self::resolve(pool, project_id, user).await? {
+            Some(access) if access.allows(permission) => Ok(access),
+            Some(access) => Err(ApiError::Forbidden(format!(
+                "Your role ({}) does not allow this in this project",
+                access.role
+            ))),
+            None => Err(ApiError::Forbidden(
+                "You are not a member of this project".to_string(),
+            )),
+        }
+    }
+
+    /// [`Self::require`] for the project owning a task attempt
+    pub async fn require_for_attempt(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        user: &AuthenticatedUser,
+        permission: ProjectPermission,
+    ) -> Result<Self, ApiError> {
+        let attempt = TaskAttempt::find_by_id(pool, task_attempt_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Self::require_for_task(pool, attempt.task_id, user, permission).await
+    }
+
+    /// [`Self::require`] for the project owning a task
+    pub async fn require_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        user: &AuthenticatedUser,
+        permission: ProjectPermission,
+    ) -> Result<Self, ApiError> {
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Self::require(pool, task.project_id, user, permission).await
+    }
+}
+
+/// Reject the request unless the user's role (and API token scopes, if any) grant
+/// `permission`. Must run inside a loader that inserted [`ProjectAccess`].
+pub async fn require_project_permission(
+    State(permission): State<ProjectPermission>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let access = request
+        .extensions()
+        .get::<ProjectAccess>()
+        .ok_or(StatusCode::FORBIDDEN)?;
+    let scoped = request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .is_some_and(|user| permission.allowed_for(user));
+    if !scoped || !access.allows(permission) {
+        tracing::debug!(
+            "Denied {:?} on project {} to a {}",
+            permission,
+            access.project_id,
+            access.role
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(next.run(request).await)
+}
+
+/// Reject the request unless the user is a server admin (see
+/// [`AuthenticatedUser::is_server_admin`]). Guards global settings that no project role covers.
+/// Must run inside `require_auth`.
+pub async fn require_server_admin(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let admin = request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .is_some_and(AuthenticatedUser::is_server_admin);
+    if !admin {
+        tracing::debug!(
+            "Denied {} to a user who is not a server admin",
+            request.uri()
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_grant_increasing_permissions() {
+        let access = |role| ProjectAccess {
+            project_id: Uuid::nil(),
+            role,
+        };
+
+        assert!(access(MemberRole::Viewer).allows(ProjectPermission::View));
+        assert!(!access(MemberRole::Viewer).allows(ProjectPermission::RunAttempts));
+        assert!(access(MemberRole::Contributor).allows(ProjectPermission::RunAttempts));
+        assert!(!access(MemberRole::Contributor).allows(ProjectPermission::Merge));
+        assert!(access(MemberRole::Maintainer).allows(ProjectPermission::Manage));
+        assert!(!access(MemberRole::Maintainer).allows(ProjectPermission::Delete));
+        assert!(access(MemberRole::Owner).allows(ProjectPermission::Delete));
+    }
+
+    #[test]
+    fn only_the_server_login_is_admin_without_anyon_admins() {
+        let mut login = AuthenticatedUser::from_github_user(1, "octocat".to_string(), None);
+        assert!(!login.is_server_admin());
+        login.server_login = true;
+        assert!(login.is_server_admin());
+    }
+}
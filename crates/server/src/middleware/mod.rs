@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod authorization;
 pub mod model_loaders;
 
 pub use model_loaders::*;
@@ -11,7 +11,57 @@ use db::models::{
 use deployment::Deployment;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, auth::AuthenticatedUser};
+use crate::{DeploymentImpl, auth::AuthenticatedUser, middleware::authorization::ProjectAccess};
+
+/// The authenticated user's role on a project. Users without one get 404 rather than 403 so
+/// ids of other people's projects are not confirmed.
+async fn project_access(
+    deployment: &DeploymentImpl,
+    request: &Request,
+    project_id: Uuid,
+) -> Result<ProjectAccess, StatusCode> {
+    let user = request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    match ProjectAccess::resolve(&deployment.db().pool, project_id, user).await {
+        Ok(Some(access)) => Ok(access),
+        Ok(None) => {
+            tracing::warn!(
+                "User {} has no access to project {}",
+                user.user_id,
+                project_id
+            );
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to resolve access to project {}: {}", project_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Project of the task an attempt belongs to
+async fn attempt_project_id(
+    deployment: &DeploymentImpl,
+    attempt: &TaskAttempt,
+) -> Result<Uuid, StatusCode> {
+    match Task::find_by_id(&deployment.db().pool, attempt.task_id).await {
+        Ok(Some(task)) => Ok(task.project_id),
+        Ok(None) => {
+            tracing::warn!(
+                "Task {} of attempt {} not found",
+                attempt.task_id,
+                attempt.id
+            );
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task {}: {}", attempt.task_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
 
 pub async fn load_project_middleware(
     State(deployment): State<DeploymentImpl>,
@@ -32,9 +82,12 @@ pub async fn load_project_middleware(
         }
     };
 
+    let access = project_access(&deployment, &request, project.id).await?;
+
     // Insert the project as an extension
     let mut request = request;
     request.extensions_mut().insert(project);
+    request.extensions_mut().insert(access);
 
     // Continue with the next middleware/handler
     Ok(next.run(request).await)
@@ -59,9 +112,12 @@ pub async fn load_task_middleware(
         }
     };
 
+    let access = project_access(&deployment, &request, task.project_id).await?;
+
     // Insert both models as extensions
     let mut request = request;
     request.extensions_mut().insert(task);
+    request.extensions_mut().insert(access);
 
     // Continue with the next middleware/handler
     Ok(next.run(request).await)
@@ -73,28 +129,11 @@ pub async fn load_task_attempt_middleware(
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    // Get authenticated user from request extensions
-    let user = request
-        .extensions()
-        .get::<AuthenticatedUser>()
-        .ok_or(StatusCode::UNAUTHORIZED)?
-        .clone();
-
-    // Load the TaskAttempt from the database with user validation
-    let attempt = match TaskAttempt::find_by_id_for_user(
-        &deployment.db().pool,
-        task_attempt_id,
-        &user.user_id,
-    )
-    .await
-    {
+    // Load the TaskAttempt from the database; access comes from the user's project role
+    let attempt = match TaskAttempt::find_by_id(&deployment.db().pool, task_attempt_id).await {
         Ok(Some(a)) => a,
         Ok(None) => {
-            tracing::warn!(
-                "TaskAttempt {} not found or access denied for user {}",
-                task_attempt_id,
-                user.user_id
-            );
+            tracing::warn!("TaskAttempt {} not found", task_attempt_id);
             return Err(StatusCode::NOT_FOUND);
         }
         Err(e) => {
@@ -102,9 +141,12 @@ pub async fn load_task_attempt_middleware(
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
+    let project_id = attempt_project_id(&deployment, &attempt).await?;
+    let access = project_access(&deployment, &request, project_id).await?;
 
     // Insert the attempt into extensions
     request.extensions_mut().insert(attempt);
+    request.extensions_mut().insert(access);
 
     // Continue on
     Ok(next.run(request).await)
@@ -130,8 +172,34 @@ pub async fn load_execution_process_middleware(
             }
         };
 
+    let attempt =
+        match TaskAttempt::find_by_id(&deployment.db().pool, execution_process.task_attempt_id)
+            .await
+        {
+            Ok(Some(attempt)) => attempt,
+            Ok(None) => {
+                tracing::warn!(
+                    "TaskAttempt {} of execution process {} not found",
+                    execution_process.task_attempt_id,
+                    process_id
+                );
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch TaskAttempt {}: {}",
+                    execution_process.task_attempt_id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+    let project_id = attempt_project_id(&deployment, &attempt).await?;
+    let access = project_access(&deployment, &request, project_id).await?;
+
     // Inject the execution process into the request
     request.extensions_mut().insert(execution_process);
+    request.extensions_mut().insert(access);
 
     // Continue to the next middleware/handler
     Ok(next.run(request).await)
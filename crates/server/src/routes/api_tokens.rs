@@ -0,0 +1,115 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use chrono::{Duration, Utc};
+use db::models::api_token::{ApiToken, CreateApiToken};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::api_tokens;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, auth::AuthenticatedUser, error::ApiError, middleware::auth::require_auth,
+};
+
+/// Longest expiry a token can be issued with
+const MAX_EXPIRY_DAYS: u32 = 3650;
+
+/// A newly issued token. `token` is only ever returned here.
+#[derive(Debug, Serialize, TS)]
+pub struct CreatedApiToken {
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+pub async fn get_api_tokens(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiToken>>>, ApiError> {
+    let tokens = ApiToken::find_by_user(&deployment.db().pool, &user.user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(tokens)))
+}
+
+pub async fn create_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateApiToken>,
+) -> Result<ResponseJson<ApiResponse<CreatedApiToken>>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Token name cannot be empty",
+        )));
+    }
+    if payload.scopes.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Select at least one scope",
+        )));
+    }
+    if payload.expires_in_days == Some(0) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Expiry must be at least one day",
+        )));
+    }
+    if payload
+        .expires_in_days
+        .is_some_and(|days| days > MAX_EXPIRY_DAYS)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Expiry cannot be more than {MAX_EXPIRY_DAYS} days"
+        )));
+    }
+
+    let generated = api_tokens::generate();
+    let expires_at = payload
+        .expires_in_days
+        .map(|days| Utc::now() + Duration::days(i64::from(days)));
+    let api_token = ApiToken::create(
+        &deployment.db().pool,
+        &user.user_id,
+        user.github_id,
+        &user.username,
+        &payload,
+        &generated.prefix,
+        &generated.hash,
+        expires_at,
+    )
+    .await?;
+
+    tracing::info!(
+        "User {} created API token {} ({})",
+        user.username,
+        api_token.name,
+        api_token.token_prefix
+    );
+
+    Ok(ResponseJson(ApiResponse::success(CreatedApiToken {
+        api_token,
+        token: generated.token,
+    })))
+}
+
+pub async fn revoke_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(token_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if ApiToken::delete(&deployment.db().pool, token_id, &user.user_id).await? == 0 {
+        return Ok(ResponseJson(ApiResponse::error("Token not found")));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_api_tokens).post(create_api_token))
+        .route("/{token_id}", delete(revoke_api_token))
+        .layer(from_fn_with_state(deployment.clone(), require_auth));
+
+    Router::new().nest("/tokens", inner)
+}
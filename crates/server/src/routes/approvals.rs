@@ -1,20 +1,58 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, State},
     http::StatusCode,
     middleware::from_fn_with_state,
+    response::{IntoResponse, Response},
     routing::post,
 };
+use db::models::execution_process::ExecutionProcess;
 use deployment::Deployment;
 use utils::approvals::{ApprovalResponse, ApprovalStatus};
 
-use crate::{DeploymentImpl, middleware::auth::require_auth};
+use crate::{
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission},
+    },
+};
+
+/// Responding to an approval steers the attempt, so it needs RunAttempts on its project
+async fn authorize_approval(
+    deployment: &DeploymentImpl,
+    user: &AuthenticatedUser,
+    id: &str,
+) -> Result<(), Response> {
+    let pool = &deployment.db().pool;
+    let execution_process_id = deployment
+        .approvals()
+        .pending_execution_process(id)
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+    let process = ExecutionProcess::find_by_id(pool, execution_process_id)
+        .await
+        .map_err(|err| ApiError::from(err).into_response())?
+        .ok_or_else(|| StatusCode::NOT_FOUND.into_response())?;
+    ProjectAccess::require_for_attempt(
+        pool,
+        process.task_attempt_id,
+        user,
+        ProjectPermission::RunAttempts,
+    )
+    .await
+    .map_err(IntoResponse::into_response)?;
+    Ok(())
+}
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
     Path(id): Path<String>,
     Json(request): Json<ApprovalResponse>,
-) -> Result<Json<ApprovalStatus>, StatusCode> {
+) -> Result<Json<ApprovalStatus>, Response> {
+    authorize_approval(&deployment, &user, &id).await?;
     let service = deployment.approvals();
 
     match service.respond(&deployment.db().pool, &id, request).await {
@@ -35,7 +73,7 @@ pub async fn respond_to_approval(
         }
         Err(e) => {
             tracing::error!("Failed to respond to approval: {:?}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
         }
     }
 }
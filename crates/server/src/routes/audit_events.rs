@@ -0,0 +1,145 @@
+use axum::{
+    Extension, Router,
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::header,
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+};
+use chrono::Utc;
+use db::models::audit_event::{AuditEvent, AuditEventFilter};
+use deployment::Deployment;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission},
+    },
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditEventPageQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AuditEventPage {
+    pub events: Vec<AuditEvent>,
+    /// Whether more events match after this page
+    pub has_more: bool,
+}
+
+/// A project's events need Manage on it; the whole log is limited to server admins
+async fn authorize(
+    deployment: &DeploymentImpl,
+    user: &AuthenticatedUser,
+    filter: &AuditEventFilter,
+) -> Result<(), ApiError> {
+    if let Some(project_id) = filter.project_id {
+        ProjectAccess::require(
+            &deployment.db().pool,
+            project_id,
+            user,
+            ProjectPermission::Manage,
+        )
+        .await?;
+        return Ok(());
+    }
+    if !user.is_server_admin() {
+        return Err(ApiError::Forbidden(
+            "Filter by project_id to see a project's audit events".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /audit-events
+pub async fn get_audit_events(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<AuditEventFilter>,
+    Query(page): Query<AuditEventPageQuery>,
+) -> Result<ResponseJson<ApiResponse<AuditEventPage>>, ApiError> {
+    authorize(&deployment, &user, &filter).await?;
+    let limit = page
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = page.offset.unwrap_or(0).max(0);
+
+    // One extra row tells whether another page exists
+    let mut events = AuditEvent::find(&deployment.db().pool, &filter, limit + 1, offset).await?;
+    let has_more = events.len() as i64 > limit;
+    events.truncate(limit as usize);
+    Ok(ResponseJson(ApiResponse::success(AuditEventPage {
+        events,
+        has_more,
+    })))
+}
+
+/// GET /audit-events/export
+/// Every matching event as JSON lines, newest first
+pub async fn export_audit_events(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(mut filter): Query<AuditEventFilter>,
+) -> Result<Response, ApiError> {
+    authorize(&deployment, &user, &filter).await?;
+    // Events recorded during the export would otherwise shift the pages
+    filter.until = Some(
+        filter
+            .until
+            .map_or(Utc::now(), |until| until.min(Utc::now())),
+    );
+    let pool = deployment.db().pool.clone();
+
+    let lines = stream::try_unfold(Some(0i64), move |offset| {
+        let pool = pool.clone();
+        let filter = filter.clone();
+        async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let events = AuditEvent::find(&pool, &filter, MAX_PAGE_SIZE, offset).await?;
+            let next = (events.len() as i64 == MAX_PAGE_SIZE).then_some(offset + MAX_PAGE_SIZE);
+            let mut chunk = Vec::new();
+            for event in &events {
+                serde_json::to_writer(&mut chunk, event)
+                    .map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+                chunk.push(b'\n');
+            }
+            Ok::<_, sqlx::Error>(Some((Bytes::from(chunk), next)))
+        }
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"audit-events.jsonl\"",
+            ),
+        ],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/audit-events", get(get_audit_events))
+        .route("/audit-events/export", get(export_audit_events))
+        .layer(from_fn_with_state(deployment.clone(), require_auth))
+}
@@ -1,16 +1,16 @@
 use axum::{
     Json, Router,
     extract::{
-        Path, Request, State,
+        Path, Query, Request, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
-    middleware::{Next, from_fn_with_state},
+    http::{HeaderMap, StatusCode},
+    middleware::{Next, from_fn, from_fn_with_state},
     response::{
         Json as ResponseJson, Response, Sse,
         sse::{Event, KeepAlive},
     },
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
     draft::Draft, image::Image, project::Project, tag::Tag, task::Task, task_attempt::TaskAttempt,
@@ -23,13 +23,20 @@ use services::services::{
     auth::{AuthError, DeviceFlowStartResponse},
     claude_auth_pty::ClaudePtyLogEntry,
     config::save_config_to_file,
+    forge::{ForgeError, ForgeProvider, ensure_public_base_url, forge_service},
     github_service::{GitHubService, GitHubServiceError},
     secret_store::{SECRET_CLAUDE_ACCESS, SECRET_GITHUB_OAUTH},
 };
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    auth::{AuthenticatedUser, multi_user_mode, session},
+    error::ApiError,
+    middleware::{auth::require_auth, authorization::require_server_admin},
+    routes::identity::link_github_account,
+};
 
 #[cfg(not(feature = "cloud"))]
 pub const DEV_LOGIN_PLACEHOLDER_TOKEN: &str = "dev-login-placeholder-token";
@@ -158,6 +165,15 @@ async fn dev_login(
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // Forge tokens are used for every user's pull requests
+    let forge_token_router = Router::new()
+        .route(
+            "/auth/forge/token",
+            put(forge_token_store).delete(forge_token_delete),
+        )
+        .layer(from_fn(require_server_admin))
+        .layer(from_fn_with_state(deployment.clone(), require_auth));
+
     let mut router = Router::new()
         .route("/auth/github/device/start", post(device_start))
         .route("/auth/github/device/poll", post(device_poll))
@@ -181,6 +197,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/auth/claude/pty/session/{session_id}/log",
             get(claude_pty_session_log),
         )
+        .merge(forge_token_router)
         .layer(from_fn_with_state(
             deployment.clone(),
             sentry_user_context_middleware,
@@ -195,14 +212,39 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     router
 }
 
+/// The signed-in user a device flow belongs to, or None for the server's own login, which
+/// only a single-user server accepts without credentials
+async fn device_flow_owner(
+    deployment: &DeploymentImpl,
+    headers: &HeaderMap,
+) -> Result<Option<AuthenticatedUser>, ApiError> {
+    match session::session_user(deployment, headers).await? {
+        Some(user) => Ok(Some(user)),
+        None if multi_user_mode(deployment) => Err(ApiError::Forbidden(
+            "Sign in before connecting a GitHub account".to_string(),
+        )),
+        None => Ok(None),
+    }
+}
+
 /// POST /auth/github/device/start
 async fn device_start(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<ApiResponse<DeviceFlowStartResponse>>, ApiError> {
-    let device_start_response = deployment.auth().device_start().await?;
+    let owner = device_flow_owner(&deployment, &headers).await?;
+    let device_start_response = deployment
+        .auth()
+        .device_start(owner.map(|user| user.user_id))
+        .await?;
     Ok(ResponseJson(ApiResponse::success(device_start_response)))
 }
 
+#[derive(Deserialize, ts_rs::TS)]
+pub struct DevicePollRequest {
+    pub flow_id: String,
+}
+
 #[derive(Serialize, Deserialize, ts_rs::TS)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[ts(use_ts_enum)]
@@ -220,6 +262,21 @@ pub enum CheckTokenResponse {
     Invalid,
 }
 
+/// Token for a GitLab or Gitea instance, keyed by host
+#[derive(Deserialize, ts_rs::TS)]
+pub struct ForgeTokenRequest {
+    pub provider: ForgeProvider,
+    /// Host name (`gitlab.example.com`) or base URL (`http://gitea.local:3000`)
+    pub host: String,
+    pub token: String,
+}
+
+#[derive(Deserialize, ts_rs::TS)]
+pub struct ForgeHostQuery {
+    pub provider: ForgeProvider,
+    pub host: String,
+}
+
 #[derive(Serialize, ts_rs::TS)]
 pub struct ClaudeSessionResponse {
     #[ts(type = "string")]
@@ -239,10 +296,22 @@ pub struct ClaudePtySessionLogResponse {
 }
 
 /// POST /auth/github/device/poll
+/// For single sign-on users this links the GitHub account to them instead of signing the
+/// server in to GitHub
 async fn device_poll(
     State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+    Json(request): Json<DevicePollRequest>,
 ) -> Result<ResponseJson<ApiResponse<DevicePollStatus>>, ApiError> {
-    let user_info = match deployment.auth().device_poll().await {
+    let owner = device_flow_owner(&deployment, &headers).await?;
+    let user_info = match deployment
+        .auth()
+        .device_poll(
+            &request.flow_id,
+            owner.as_ref().map(|user| user.user_id.as_str()),
+        )
+        .await
+    {
         Ok(info) => info,
         Err(AuthError::Pending(Continue::SlowDown)) => {
             return Ok(ResponseJson(ApiResponse::success(
@@ -256,6 +325,18 @@ async fn device_poll(
         }
         Err(e) => return Err(e.into()),
     };
+    if let Some(user) = owner {
+        link_github_account(
+            &deployment,
+            &user,
+            &user_info.token,
+            user_info.primary_email.as_deref(),
+        )
+        .await?;
+        return Ok(ResponseJson(ApiResponse::success(
+            DevicePollStatus::Success,
+        )));
+    }
     // Save to config
     {
         let config_path = utils::assets::config_path();
@@ -469,6 +550,66 @@ async fn github_check_token(
     }
 }
 
+/// Split user input into the instance base URL and the host name used as the token key
+fn forge_base_url_and_host(host: &str) -> (String, String) {
+    let trimmed = host.trim().trim_end_matches('/');
+    let (base_url, authority) = if let Some(rest) = trimmed.strip_prefix("http://") {
+        (trimmed.to_string(), rest)
+    } else if let Some(rest) = trimmed.strip_prefix("https://") {
+        (trimmed.to_string(), rest)
+    } else {
+        (format!("https://{trimmed}"), trimmed)
+    };
+    let host_name = authority
+        .split(['/', ':'])
+        .next()
+        .unwrap_or(authority)
+        .to_ascii_lowercase();
+    (base_url, host_name)
+}
+
+/// PUT /auth/forge/token
+/// Verifies the token against the instance before storing it
+async fn forge_token_store(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ForgeTokenRequest>,
+) -> Result<ResponseJson<ApiResponse<CheckTokenResponse>>, ApiError> {
+    let (base_url, host) = forge_base_url_and_host(&payload.host);
+    ensure_public_base_url(&base_url).await?;
+    let service = forge_service(payload.provider, &base_url, &payload.token)?;
+    match service.check_token().await {
+        Ok(()) => {}
+        Err(ForgeError::TokenInvalid(_)) => {
+            return Ok(ResponseJson(ApiResponse::success(
+                CheckTokenResponse::Invalid,
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    deployment
+        .forge_resolver()
+        .store_host_token(payload.provider, &base_url, &host, &payload.token)
+        .await?;
+    tracing::info!("Stored {} token for {}", payload.provider, host);
+    Ok(ResponseJson(ApiResponse::success(
+        CheckTokenResponse::Valid,
+    )))
+}
+
+/// DELETE /auth/forge/token?provider=gitlab&host=gitlab.example.com
+async fn forge_token_delete(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ForgeHostQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let (_, host) = forge_base_url_and_host(&query.host);
+    deployment
+        .forge_resolver()
+        .delete_host_token(query.provider, &host)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 async fn claude_session_start(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<ClaudeSessionResponse>>, ApiError> {
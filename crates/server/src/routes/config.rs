@@ -5,9 +5,11 @@ use axum::{
     body::Body,
     extract::{Path, Query, State},
     http,
+    middleware::{from_fn, from_fn_with_state},
     response::{Json as ResponseJson, Response},
     routing::{get, put},
 };
+use db::models::audit_event::AuditAction;
 use deployment::{Deployment, DeploymentError};
 use executors::{
     executors::{BaseAgentCapability, BaseCodingAgent, StandardCodingAgentExecutor},
@@ -26,15 +28,26 @@ use tokio::fs;
 use ts_rs::TS;
 use utils::{assets::config_path, response::ApiResponse};
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    audit::AuditContext,
+    error::ApiError,
+    middleware::{auth::require_auth, authorization::require_server_admin},
+};
 
-pub fn router() -> Router<DeploymentImpl> {
-    Router::new()
-        .route("/info", get(get_user_system_info))
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // Global settings apply to every user of the server
+    let settings_router = Router::new()
         .route("/config", put(update_config))
-        .route("/sounds/{sound}", get(get_sound))
         .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
         .route("/profiles", get(get_profiles).put(update_profiles))
+        .layer(from_fn(require_server_admin))
+        .layer(from_fn_with_state(deployment.clone(), require_auth));
+
+    Router::new()
+        .route("/info", get(get_user_system_info))
+        .route("/sounds/{sound}", get(get_sound))
+        .merge(settings_router)
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -137,6 +150,7 @@ async fn get_user_system_info(
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(mut new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
     let config_path = config_path();
@@ -167,6 +181,14 @@ async fn update_config(
             // Track config events when fields transition from false → true and run side effects
             handle_config_events(&deployment, &old_config, &new_config).await;
 
+            // GitHub tokens were moved to the secret store above, so snapshots hold no secrets
+            audit
+                .event(AuditAction::ConfigUpdated)
+                .before(&old_config)
+                .after(&new_config)
+                .record(&deployment)
+                .await;
+
             ResponseJson(ApiResponse::success(new_config))
         }
         Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
@@ -364,8 +386,9 @@ async fn get_mcp_servers(
 }
 
 async fn update_mcp_servers(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Query(query): Query<McpServerQuery>,
+    audit: AuditContext,
     Json(payload): Json<UpdateMcpServersBody>,
 ) -> Result<ResponseJson<ApiResponse<String>>, ApiError> {
     let profiles = ExecutorConfigs::get_cached();
@@ -392,8 +415,19 @@ async fn update_mcp_servers(
     };
 
     let mcpc = agent.get_mcp_config();
+    // Definitions can hold credentials in `env`, so only server names are audited
+    let mut server_names: Vec<_> = payload.servers.keys().cloned().collect();
+    server_names.sort();
     match update_mcp_servers_in_config(&config_path, &mcpc, payload.servers).await {
-        Ok(message) => Ok(ResponseJson(ApiResponse::success(message))),
+        Ok(message) => {
+            audit
+                .event(AuditAction::McpConfigUpdated)
+                .target(query.executor.to_string())
+                .after(&server_names)
+                .record(&deployment)
+                .await;
+            Ok(ResponseJson(ApiResponse::success(message)))
+        }
         Err(e) => Ok(ResponseJson(ApiResponse::error(&format!(
             "Failed to update MCP servers: {}",
             e
@@ -518,18 +552,26 @@ async fn get_profiles(
 }
 
 async fn update_profiles(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     body: String,
 ) -> ResponseJson<ApiResponse<String>> {
     // Try to parse as ExecutorProfileConfigs format
     match serde_json::from_str::<ExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
+            let previous = ExecutorConfigs::get_cached();
             // Save the profiles to file
             match executor_profiles.save_overrides() {
                 Ok(_) => {
                     tracing::info!("Executor profiles saved successfully");
                     // Reload the cached profiles
                     ExecutorConfigs::reload();
+                    audit
+                        .event(AuditAction::ExecutorProfilesUpdated)
+                        .before(&previous)
+                        .after(&executor_profiles)
+                        .record(&deployment)
+                        .await;
                     ResponseJson(ApiResponse::success(
                         "Executor profiles updated successfully".to_string(),
                     ))
@@ -1,5 +1,5 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{Query, State},
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
@@ -12,7 +12,15 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::auth::require_auth};
+use crate::{
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission},
+    },
+};
 
 #[derive(Debug, Serialize, TS)]
 pub struct ContainerInfo {
@@ -30,6 +38,7 @@ pub struct ContainerQuery {
 pub async fn get_container_info(
     Query(query): Query<ContainerQuery>,
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
 ) -> Result<ResponseJson<ApiResponse<ContainerInfo>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -41,6 +50,8 @@ pub async fn get_container_info(
                 _ => ApiError::Database(e),
             })?;
 
+    ProjectAccess::require(pool, project_id, &user, ProjectPermission::View).await?;
+
     let container_info = ContainerInfo {
         attempt_id,
         task_id,
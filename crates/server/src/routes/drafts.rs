@@ -8,13 +8,21 @@ use axum::{
     response::IntoResponse,
     routing::get,
 };
-use db::models::project::Project;
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, auth::AuthenticatedUser, middleware::auth::require_auth};
+use crate::{
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission},
+    },
+    routes::events::ResumeQuery,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct DraftsQuery {
@@ -26,38 +34,33 @@ pub async fn stream_project_drafts_ws(
     State(deployment): State<DeploymentImpl>,
     Extension(user): Extension<AuthenticatedUser>,
     Query(query): Query<DraftsQuery>,
-) -> impl IntoResponse {
-    // Validate that the project belongs to the user
-    let project_result =
-        Project::find_by_id_for_user(&deployment.db().pool, query.project_id, &user.user_id).await;
-
-    ws.on_upgrade(move |socket| async move {
-        // Check project ownership before processing
-        match project_result {
-            Ok(Some(_project)) => {
-                if let Err(e) = handle_project_drafts_ws(socket, deployment, query.project_id).await
-                {
-                    tracing::warn!("drafts WS closed: {}", e);
-                }
-            }
-            Ok(None) => {
-                tracing::warn!("Project {} not found or access denied", query.project_id);
-            }
-            Err(e) => {
-                tracing::error!("Failed to validate project access: {}", e);
-            }
+    Query(resume): Query<ResumeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    ProjectAccess::require(
+        &deployment.db().pool,
+        query.project_id,
+        &user,
+        ProjectPermission::View,
+    )
+    .await?;
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) =
+            handle_project_drafts_ws(socket, deployment, query.project_id, resume.since).await
+        {
+            tracing::warn!("drafts WS closed: {}", e);
         }
-    })
+    }))
 }
 
 async fn handle_project_drafts_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
     project_id: Uuid,
+    since: Option<u64>,
 ) -> anyhow::Result<()> {
     let mut stream = deployment
         .events()
-        .stream_drafts_for_project_raw(project_id)
+        .stream_drafts_for_project_raw(project_id, since)
         .await?
         .map_ok(|msg| msg.to_ws_message_unchecked());
 
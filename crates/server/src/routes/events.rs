@@ -1,6 +1,7 @@
 use axum::{
-    BoxError, Router,
-    extract::State,
+    BoxError, Extension, Router,
+    extract::{Query, State},
+    http::HeaderMap,
     middleware::from_fn_with_state,
     response::{
         Sse,
@@ -9,16 +10,63 @@ use axum::{
     routing::get,
 };
 use deployment::Deployment;
-use futures_util::TryStreamExt;
+use futures_util::{FutureExt, StreamExt, TryStreamExt};
+use serde::Deserialize;
 
-use crate::{DeploymentImpl, middleware::auth::require_auth};
+use crate::{
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission},
+    },
+};
+
+/// Where a client left off in an event stream
+#[derive(Debug, Deserialize)]
+pub struct ResumeQuery {
+    /// Sequence number of the last message the client received
+    pub since: Option<u64>,
+}
 
 pub async fn events(
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(resume): Query<ResumeQuery>,
+    headers: HeaderMap,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, axum::http::StatusCode>
 {
-    // Ask the container service for a combined "history + live" stream
-    let stream = deployment.stream_events().await;
+    // EventSource sends the id of the last event it saw when it reconnects
+    let since = resume.since.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+    });
+    // Ask the container service for a combined "history + live" stream; users other than
+    // server admins only see the projects they can view
+    let stream = if user.is_server_admin() {
+        deployment.stream_events(since).await
+    } else {
+        let pool = deployment.db().pool.clone();
+        deployment
+            .events()
+            .stream_events_for_projects(since, move |project_id| {
+                let pool = pool.clone();
+                let user = user.clone();
+                async move {
+                    ProjectPermission::View.allowed_for(&user)
+                        && ProjectAccess::resolve(&pool, project_id, &user)
+                            .await
+                            .ok()
+                            .flatten()
+                            .is_some_and(|access| access.allows(ProjectPermission::View))
+                }
+                .boxed()
+            })
+            .map_ok(|msg| msg.to_sse_event())
+            .boxed()
+    };
     Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
 }
 
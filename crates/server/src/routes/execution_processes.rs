@@ -13,6 +13,7 @@ use db::models::execution_process::{
     ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus,
 };
 use deployment::Deployment;
+use executors::logs::NormalizedEntry;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::container::ContainerService;
@@ -21,8 +22,14 @@ use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
+    auth::AuthenticatedUser,
     error::ApiError,
-    middleware::{auth::require_auth, load_execution_process_middleware},
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission, require_project_permission},
+        load_execution_process_middleware,
+    },
+    routes::events::ResumeQuery,
 };
 
 #[derive(Debug, Deserialize)]
@@ -35,9 +42,12 @@ pub struct ExecutionProcessQuery {
 
 pub async fn get_execution_processes(
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
     Query(query): Query<ExecutionProcessQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
     let pool = &deployment.db().pool;
+    ProjectAccess::require_for_attempt(pool, query.task_attempt_id, &user, ProjectPermission::View)
+        .await?;
     let execution_processes = ExecutionProcess::find_by_task_attempt_id(
         pool,
         query.task_attempt_id,
@@ -55,6 +65,19 @@ pub async fn get_execution_process_by_id(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// GET /execution-processes/{id}/conversation
+/// The process's normalized entries in their final form, for clients that don't stream
+pub async fn get_normalized_conversation(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<NormalizedEntry>>>, ApiError> {
+    let entries = deployment
+        .container()
+        .normalized_conversation(&execution_process.id)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
 pub async fn stream_raw_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -199,20 +222,30 @@ pub async fn stop_execution_process(
 pub async fn stream_execution_processes_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
     Query(query): Query<ExecutionProcessQuery>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| async move {
+    Query(resume): Query<ResumeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    ProjectAccess::require_for_attempt(
+        &deployment.db().pool,
+        query.task_attempt_id,
+        &user,
+        ProjectPermission::View,
+    )
+    .await?;
+    Ok(ws.on_upgrade(move |socket| async move {
         if let Err(e) = handle_execution_processes_ws(
             socket,
             deployment,
             query.task_attempt_id,
             query.show_soft_deleted.unwrap_or(false),
+            resume.since,
         )
         .await
         {
             tracing::warn!("execution processes WS closed: {}", e);
         }
-    })
+    }))
 }
 
 async fn handle_execution_processes_ws(
@@ -220,11 +253,12 @@ async fn handle_execution_processes_ws(
     deployment: DeploymentImpl,
     task_attempt_id: uuid::Uuid,
     show_soft_deleted: bool,
+    since: Option<u64>,
 ) -> anyhow::Result<()> {
     // Get the raw stream and convert LogMsg to WebSocket messages
     let mut stream = deployment
         .events()
-        .stream_execution_processes_for_attempt_raw(task_attempt_id, show_soft_deleted)
+        .stream_execution_processes_for_attempt_raw(task_attempt_id, show_soft_deleted, since)
         .await?
         .map_ok(|msg| msg.to_ws_message_unchecked());
 
@@ -254,7 +288,14 @@ async fn handle_execution_processes_ws(
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
-        .route("/stop", post(stop_execution_process))
+        .route(
+            "/stop",
+            post(stop_execution_process).route_layer(from_fn_with_state(
+                ProjectPermission::RunAttempts,
+                require_project_permission,
+            )),
+        )
+        .route("/conversation", get(get_normalized_conversation))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
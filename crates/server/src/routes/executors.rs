@@ -0,0 +1,185 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    middleware::{from_fn, from_fn_with_state},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use deployment::Deployment;
+use executors::{
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    tool_cache::{NpmPackage, ToolCache},
+};
+use serde::{Deserialize, Serialize};
+use services::services::executor_health::ExecutorHealthReport;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{auth::require_auth, authorization::require_server_admin},
+};
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    // Installing tools and changing tool settings affect every user of the server
+    let admin_router = Router::new()
+        .route("/executors/tools/install", post(install_agent_tool))
+        .route("/executors/tools/settings", put(update_tool_settings))
+        .layer(from_fn(require_server_admin));
+
+    Router::new()
+        .route("/executors/health", get(get_executor_health))
+        .route("/executors/tools", get(get_agent_tools))
+        .merge(admin_router)
+        .layer(from_fn_with_state(deployment.clone(), require_auth))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutorHealthQuery {
+    /// Re-run the checks instead of returning the cached report
+    #[serde(default)]
+    refresh: bool,
+}
+
+async fn get_executor_health(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutorHealthQuery>,
+) -> Result<ResponseJson<ApiResponse<ExecutorHealthReport>>, ApiError> {
+    let report = deployment.executor_health().report(query.refresh).await;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct AgentTool {
+    pub executor_profile_id: ExecutorProfileId,
+    /// Package and version the profile launches, after any version pin
+    pub package: NpmPackage,
+    pub installed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct AgentToolsResponse {
+    pub allow_npx_fallback: bool,
+    pub cache_dir: String,
+    pub tools: Vec<AgentTool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct InstallAgentToolRequest {
+    pub executor_profile_id: ExecutorProfileId,
+    /// Pin the profile to this version before installing; omit to install the current version
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateToolSettingsRequest {
+    pub allow_npx_fallback: bool,
+}
+
+async fn agent_tool(
+    cache: &ToolCache,
+    configs: &ExecutorConfigs,
+    executor_profile_id: ExecutorProfileId,
+) -> Option<AgentTool> {
+    let package = configs
+        .get_coding_agent(&executor_profile_id)?
+        .npm_package()
+        .await?;
+    Some(AgentTool {
+        installed: cache.binary(&package).is_some(),
+        executor_profile_id,
+        package,
+    })
+}
+
+async fn get_agent_tools(
+    State(_deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<AgentToolsResponse>> {
+    let cache = ToolCache::global();
+    let configs = ExecutorConfigs::get_cached();
+
+    let mut profile_ids: Vec<ExecutorProfileId> = configs
+        .executors
+        .iter()
+        .flat_map(|(executor, config)| {
+            config.configurations.keys().map(|variant| {
+                if variant == "DEFAULT" {
+                    ExecutorProfileId::new(*executor)
+                } else {
+                    ExecutorProfileId::with_variant(*executor, variant.clone())
+                }
+            })
+        })
+        .collect();
+    profile_ids.sort_by_key(|id| id.to_string());
+
+    let mut tools = Vec::new();
+    for profile_id in profile_ids {
+        if let Some(tool) = agent_tool(&cache, &configs, profile_id).await {
+            tools.push(tool);
+        }
+    }
+
+    ResponseJson(ApiResponse::success(AgentToolsResponse {
+        allow_npx_fallback: configs.tools.allow_npx_fallback,
+        cache_dir: cache.root().to_string_lossy().to_string(),
+        tools,
+    }))
+}
+
+async fn install_agent_tool(
+    State(_deployment): State<DeploymentImpl>,
+    Json(payload): Json<InstallAgentToolRequest>,
+) -> ResponseJson<ApiResponse<AgentTool>> {
+    let mut configs = ExecutorConfigs::get_cached();
+    if let Some(version) = payload.version {
+        if let Err(e) = configs
+            .pin_version(&payload.executor_profile_id, Some(version))
+            .and_then(|_| configs.save_overrides())
+        {
+            return ResponseJson(ApiResponse::error(&format!("Failed to pin version: {e}")));
+        }
+        ExecutorConfigs::reload();
+    }
+
+    let cache = ToolCache::global();
+    let Some(tool) = agent_tool(&cache, &configs, payload.executor_profile_id).await else {
+        return ResponseJson(ApiResponse::error(
+            "This executor profile does not run an npm package",
+        ));
+    };
+
+    match cache.install(&tool.package).await {
+        Ok(path) => {
+            tracing::info!("Installed {} at {}", tool.package, path.display());
+            ResponseJson(ApiResponse::success(AgentTool {
+                installed: true,
+                ..tool
+            }))
+        }
+        Err(e) => {
+            tracing::error!("Failed to install {}: {}", tool.package, e);
+            ResponseJson(ApiResponse::error(&e.to_string()))
+        }
+    }
+}
+
+async fn update_tool_settings(
+    State(_deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateToolSettingsRequest>,
+) -> ResponseJson<ApiResponse<String>> {
+    let mut configs = ExecutorConfigs::get_cached();
+    configs.tools.allow_npx_fallback = payload.allow_npx_fallback;
+    match configs.save_overrides() {
+        Ok(_) => {
+            ExecutorConfigs::reload();
+            ResponseJson(ApiResponse::success(
+                "Tool settings updated successfully".to_string(),
+            ))
+        }
+        Err(e) => ResponseJson(ApiResponse::error(&format!(
+            "Failed to save tool settings: {e}"
+        ))),
+    }
+}
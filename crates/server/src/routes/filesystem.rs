@@ -1,7 +1,7 @@
 use axum::{
     Router,
     extract::{Query, State},
-    middleware::from_fn_with_state,
+    middleware::{from_fn, from_fn_with_state},
     response::Json as ResponseJson,
     routing::get,
 };
@@ -10,7 +10,11 @@ use serde::Deserialize;
 use services::services::filesystem::{DirectoryEntry, DirectoryListResponse, FilesystemError};
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::auth::require_auth};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{auth::require_auth, authorization::require_server_admin},
+};
 
 #[derive(Debug, Deserialize)]
 pub struct ListDirectoryQuery {
@@ -76,5 +80,6 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route("/filesystem/directory", get(list_directory))
         .route("/filesystem/git-repos", get(list_git_repos))
+        .layer(from_fn(require_server_admin))
         .layer(from_fn_with_state(deployment.clone(), require_auth))
 }
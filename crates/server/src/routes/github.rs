@@ -196,6 +196,9 @@ pub async fn create_project_from_github(
         dev_script: payload.dev_script,
         cleanup_script: payload.cleanup_script,
         copy_files: None,
+        init_submodules: None,
+        sparse_checkout_patterns: None,
+        sparse_auto_expand: None,
     };
 
     let project_id = Uuid::new_v4();
@@ -0,0 +1,245 @@
+//! Single sign-on through configured identity providers and linked accounts
+
+use axum::{
+    Extension, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, header},
+    middleware::from_fn_with_state,
+    response::{AppendHeaders, IntoResponse, Json as ResponseJson, Redirect, Response},
+    routing::{delete, get, post},
+};
+use db::models::user_identity::UserIdentity;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    github_service::GitHubService,
+    identity::{IdentityError, IdentityProviderInfo},
+    secret_store::SECRET_GITHUB_OAUTH,
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    auth::{AuthenticatedUser, session},
+    error::ApiError,
+    middleware::auth::require_auth,
+};
+
+pub const GITHUB_IDENTITY_PROVIDER: &str = "github";
+
+#[derive(Debug, Deserialize)]
+pub struct SsoLoginQuery {
+    /// Path in the app to return to after signing in
+    pub return_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SsoCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// Only allow redirects back into the app, never to another origin
+fn safe_return_to(return_to: Option<String>) -> String {
+    return_to
+        .filter(|path| path.starts_with('/') && !path.starts_with("//") && !path.contains('\\'))
+        .unwrap_or_else(|| "/".to_string())
+}
+
+/// GET /auth/providers
+pub async fn get_identity_providers(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<IdentityProviderInfo>>> {
+    ResponseJson(ApiResponse::success(deployment.identity_providers().list()))
+}
+
+/// GET /auth/sso/{provider}/login
+pub async fn sso_login(
+    State(deployment): State<DeploymentImpl>,
+    Path(provider): Path<String>,
+    Query(query): Query<SsoLoginQuery>,
+) -> Result<Response, ApiError> {
+    let provider = deployment.identity_providers().get(&provider)?;
+    let url = provider
+        .begin_login(Some(safe_return_to(query.return_to)))
+        .await?;
+    let state = reqwest::Url::parse(&url)
+        .ok()
+        .and_then(|url| {
+            url.query_pairs()
+                .find(|(name, _)| name == "state")
+                .map(|(_, state)| state.into_owned())
+        })
+        .ok_or(IdentityError::InvalidState)?;
+    Ok((
+        [(header::SET_COOKIE, session::login_state_cookie(&state))],
+        Redirect::to(&url),
+    )
+        .into_response())
+}
+
+/// GET /auth/sso/{provider}/callback
+pub async fn sso_callback(
+    State(deployment): State<DeploymentImpl>,
+    Path(provider): Path<String>,
+    Query(query): Query<SsoCallbackQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if let Some(error) = query.error {
+        return Err(IdentityError::Provider(query.error_description.unwrap_or(error)).into());
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return Err(IdentityError::InvalidState.into());
+    };
+    // Only the browser that started the login may finish it
+    if !session::login_state_matches(&headers, &state) {
+        return Err(IdentityError::InvalidState.into());
+    }
+    let provider = deployment.identity_providers().get(&provider)?;
+    let login = provider.complete_login(&code, &state).await?;
+    let identity = login.identity;
+
+    let pool = &deployment.db().pool;
+    let user_id =
+        match UserIdentity::find_by_subject(pool, &identity.provider, &identity.subject).await? {
+            Some(existing) => existing.user_id,
+            None => format!("{}_{}", identity.provider, Uuid::new_v4().simple()),
+        };
+    let display_name = identity.display_name();
+    UserIdentity::link(
+        pool,
+        &user_id,
+        &identity.provider,
+        &identity.subject,
+        identity.email.as_deref(),
+        &display_name,
+    )
+    .await?;
+
+    let cookie = session::create_session(&deployment, &user_id, &display_name).await?;
+    tracing::info!("User {} signed in through {}", user_id, identity.provider);
+    Ok((
+        AppendHeaders([
+            (header::SET_COOKIE, cookie),
+            (header::SET_COOKIE, session::clear_login_state_cookie()),
+        ]),
+        Redirect::to(&safe_return_to(login.return_to)),
+    )
+        .into_response())
+}
+
+/// POST /auth/logout
+pub async fn logout(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let cookie = session::end_session(&deployment, &headers).await?;
+    Ok((
+        [(header::SET_COOKIE, cookie)],
+        ResponseJson(ApiResponse::<()>::success(())),
+    )
+        .into_response())
+}
+
+/// GET /auth/identities
+pub async fn get_identities(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<ResponseJson<ApiResponse<Vec<UserIdentity>>>, ApiError> {
+    let identities = UserIdentity::find_by_user(&deployment.db().pool, &user.user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(identities)))
+}
+
+/// DELETE /auth/identities/github
+/// Forget the GitHub account linked to a single sign-on user and its token
+pub async fn unlink_github(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if user.user_id == deployment.user_id() {
+        return Err(ApiError::Conflict(
+            "The server's own GitHub login cannot be unlinked here".to_string(),
+        ));
+    }
+    UserIdentity::unlink(
+        &deployment.db().pool,
+        &user.user_id,
+        GITHUB_IDENTITY_PROVIDER,
+    )
+    .await?;
+    deployment
+        .secret_store()
+        .delete_secret(&user.user_id, SECRET_GITHUB_OAUTH)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Attach a GitHub token from the device flow to a single sign-on user, so pull requests are
+/// opened with their GitHub account
+pub async fn link_github_account(
+    deployment: &DeploymentImpl,
+    user: &AuthenticatedUser,
+    token: &str,
+    primary_email: Option<&str>,
+) -> Result<(), ApiError> {
+    let gh = GitHubService::new(token)?;
+    let github_user = deployment
+        .github_user_cache()
+        .get_or_fetch(token, &gh)
+        .await?;
+    deployment
+        .secret_store()
+        .put_secret(&user.user_id, SECRET_GITHUB_OAUTH, token.as_bytes())
+        .await?;
+    UserIdentity::link(
+        &deployment.db().pool,
+        &user.user_id,
+        GITHUB_IDENTITY_PROVIDER,
+        &github_user.id.to_string(),
+        primary_email,
+        &github_user.login,
+    )
+    .await?;
+    tracing::info!(
+        "Linked GitHub account {} to {}",
+        github_user.login,
+        user.user_id
+    );
+    Ok(())
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let authenticated = Router::new()
+        .route("/auth/identities", get(get_identities))
+        .route("/auth/identities/github", delete(unlink_github))
+        .layer(from_fn_with_state(deployment.clone(), require_auth));
+
+    Router::new()
+        .route("/auth/providers", get(get_identity_providers))
+        .route("/auth/sso/{provider}/login", get(sso_login))
+        .route("/auth/sso/{provider}/callback", get(sso_callback))
+        .route("/auth/logout", post(logout))
+        .merge(authenticated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn return_to_stays_in_the_app() {
+        assert_eq!(
+            safe_return_to(Some("/projects/1".to_string())),
+            "/projects/1"
+        );
+        assert_eq!(safe_return_to(Some("//evil.example".to_string())), "/");
+        assert_eq!(
+            safe_return_to(Some("https://evil.example".to_string())),
+            "/"
+        );
+        assert_eq!(safe_return_to(None), "/");
+    }
+}
@@ -8,14 +8,10 @@ use axum::{
     routing::{delete, get, post},
 };
 use chrono::{DateTime, Utc};
-use db::models::{
-    image::{Image, TaskImage},
-    task::Task,
-};
+use db::models::image::{Image, TaskImage};
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
 use services::services::image::ImageError;
-use sqlx::Error as SqlxError;
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
 use ts_rs::TS;
@@ -23,7 +19,13 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, auth::AuthenticatedUser, error::ApiError, middleware::auth::require_auth,
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission},
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -116,9 +118,13 @@ pub async fn upload_task_image(
     Extension(user): Extension<AuthenticatedUser>,
     multipart: Multipart,
 ) -> Result<ResponseJson<ApiResponse<ImageResponse>>, ApiError> {
-    Task::find_by_id(&deployment.db().pool, task_id)
-        .await?
-        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    ProjectAccess::require_for_task(
+        &deployment.db().pool,
+        task_id,
+        &user,
+        ProjectPermission::EditTasks,
+    )
+    .await?;
 
     let image_response =
         process_image_upload(&deployment, multipart, Some(task_id), &user.user_id).await?;
@@ -129,12 +135,14 @@ pub async fn upload_task_image(
 pub async fn serve_image(
     Path(image_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
 ) -> Result<Response, ApiError> {
     let image_service = deployment.image();
     let image = image_service
         .get_image(image_id)
         .await?
         .ok_or_else(|| ApiError::Image(ImageError::NotFound))?;
+    ensure_can_view_image(&deployment, image_id, &user).await?;
     let file_path = image_service.get_absolute_path(&image);
 
     let file = File::open(&file_path).await?;
@@ -169,10 +177,42 @@ pub async fn delete_image(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// The uploader can always see an image; anyone else needs View on a project whose
+/// tasks reference it
+async fn ensure_can_view_image(
+    deployment: &DeploymentImpl,
+    image_id: Uuid,
+    user: &AuthenticatedUser,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    if Image::find_by_id_for_user(pool, image_id, &user.user_id)
+        .await?
+        .is_some()
+    {
+        return Ok(());
+    }
+    let mut denied = ApiError::Image(ImageError::NotFound);
+    for project_id in Image::find_project_ids(pool, image_id).await? {
+        match ProjectAccess::require(pool, project_id, user, ProjectPermission::View).await {
+            Ok(_) => return Ok(()),
+            Err(e) => denied = e,
+        }
+    }
+    Err(denied)
+}
+
 pub async fn get_task_images(
     Path(task_id): Path<Uuid>,
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
 ) -> Result<ResponseJson<ApiResponse<Vec<ImageResponse>>>, ApiError> {
+    ProjectAccess::require_for_task(
+        &deployment.db().pool,
+        task_id,
+        &user,
+        ProjectPermission::View,
+    )
+    .await?;
     let images = Image::find_by_task_id(&deployment.db().pool, task_id).await?;
     let image_responses = images.into_iter().map(ImageResponse::from_image).collect();
     Ok(ResponseJson(ApiResponse::success(image_responses)))
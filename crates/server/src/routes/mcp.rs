@@ -0,0 +1,21 @@
+use axum::{Router, middleware::from_fn_with_state};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpService, session::local::LocalSessionManager,
+};
+
+use crate::{DeploymentImpl, mcp::task_server::TaskServer, middleware::auth::require_credentials};
+
+/// The MCP task server over streamable HTTP. `api` is the server's own API, nested at `/api`,
+/// which tools call in-process with the caller's credentials so route permissions still apply.
+/// Callers need an API token or session; the server's own login is never assumed.
+pub fn router(deployment: &DeploymentImpl, api: Router) -> Router {
+    let service = StreamableHttpService::new(
+        move || Ok(TaskServer::in_process(api.clone())),
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+
+    Router::new()
+        .nest_service("/mcp", service)
+        .layer(from_fn_with_state(deployment.clone(), require_credentials))
+}
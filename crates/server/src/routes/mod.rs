@@ -1,31 +1,39 @@
+use std::net::SocketAddr;
+
 use axum::{
-    Router,
-    middleware::from_fn_with_state,
-    routing::{IntoMakeService, get},
+    Router, extract::connect_info::IntoMakeServiceWithConnectInfo, middleware::from_fn_with_state,
+    routing::get,
 };
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::DeploymentImpl;
 
+pub mod api_tokens;
 pub mod approvals;
+pub mod audit_events;
 pub mod auth;
 pub mod config;
 pub mod containers;
 pub mod drafts;
 pub mod events;
 pub mod execution_processes;
+pub mod executors;
 pub mod filesystem;
 pub mod frontend;
 #[cfg(feature = "cloud")]
 pub mod github;
 pub mod health;
+pub mod identity;
 pub mod images;
+pub mod mcp;
+pub mod organizations;
 pub mod projects;
 pub mod tags;
 pub mod task_attempts;
 pub mod tasks;
+pub mod webhooks;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(deployment: DeploymentImpl) -> IntoMakeServiceWithConnectInfo<Router, SocketAddr> {
     // Configure CORS
     // Default: Allow all origins for development
     // Set CORS_ALLOWED_ORIGINS env var to restrict (comma-separated list)
@@ -52,15 +60,20 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let routes = Router::new()
         .route("/health", get(health::health_check))
-        .merge(config::router())
+        .merge(config::router(&deployment))
+        .merge(executors::router(&deployment))
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
+        .merge(organizations::router(&deployment))
         .merge(drafts::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(auth::router(&deployment))
+        .merge(api_tokens::router(&deployment))
+        .merge(audit_events::router(&deployment))
+        .merge(identity::router(&deployment))
         .merge(filesystem::router(&deployment))
         .merge(events::router(&deployment))
         .merge(approvals::router(&deployment))
@@ -76,12 +89,17 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     #[cfg(not(feature = "cloud"))]
     let routes = routes;
 
-    let base_routes = routes.with_state(deployment);
+    let base_routes = routes.with_state(deployment.clone());
+    // MCP tools call the rest of the API in-process
+    let base_routes = base_routes.clone().merge(mcp::router(
+        &deployment,
+        Router::new().nest("/api", base_routes),
+    ));
 
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
         .nest("/api", base_routes)
         .layer(cors)
-        .into_make_service()
+        .into_make_service_with_connect_info::<SocketAddr>()
 }
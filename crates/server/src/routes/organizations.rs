@@ -0,0 +1,165 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{delete, get, put},
+};
+use db::models::organization::{
+    CreateOrganization, MemberRole, Organization, OrganizationMember, UpdateMemberRole,
+};
+use deployment::Deployment;
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, auth::AuthenticatedUser, error::ApiError, middleware::auth::require_auth,
+};
+
+/// The user's role in the organization, or Forbidden when they are not a member
+async fn organization_role(
+    pool: &SqlitePool,
+    organization_id: Uuid,
+    user: &AuthenticatedUser,
+) -> Result<MemberRole, ApiError> {
+    OrganizationMember::role_for(pool, organization_id, &user.user_id)
+        .await?
+        .ok_or_else(|| ApiError::Forbidden("You are not a member of this organization".to_string()))
+}
+
+pub async fn get_organizations(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<ResponseJson<ApiResponse<Vec<Organization>>>, ApiError> {
+    let organizations = Organization::find_by_user(&deployment.db().pool, &user.user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(organizations)))
+}
+
+pub async fn create_organization(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateOrganization>,
+) -> Result<ResponseJson<ApiResponse<Organization>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Organization name cannot be empty",
+        )));
+    }
+    let organization = Organization::create(&deployment.db().pool, name, &user.user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(organization)))
+}
+
+pub async fn delete_organization(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if organization_role(pool, organization_id, &user).await? != MemberRole::Owner {
+        return Err(ApiError::Forbidden(
+            "Only owners can delete an organization".to_string(),
+        ));
+    }
+    // Projects stay with their creators; ON DELETE SET NULL unshares them
+    Organization::delete(pool, organization_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn get_organization_members(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<OrganizationMember>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    organization_role(pool, organization_id, &user).await?;
+    let members = OrganizationMember::find_by_organization_id(pool, organization_id).await?;
+    Ok(ResponseJson(ApiResponse::success(members)))
+}
+
+/// Check that a maintainer or owner with `role` may give `member_id` the role `new_role`
+/// (None when removing them)
+async fn check_member_change(
+    pool: &SqlitePool,
+    organization_id: Uuid,
+    role: MemberRole,
+    member_id: &str,
+    new_role: Option<MemberRole>,
+) -> Result<(), ApiError> {
+    if role < MemberRole::Maintainer {
+        return Err(ApiError::Forbidden(
+            "Only maintainers and owners can manage members".to_string(),
+        ));
+    }
+    if new_role.is_some_and(|new_role| new_role > role) {
+        return Err(ApiError::Forbidden(
+            "You cannot grant a role above your own".to_string(),
+        ));
+    }
+    let current = OrganizationMember::role_for(pool, organization_id, member_id).await?;
+    if current.is_some_and(|current| current > role) {
+        return Err(ApiError::Forbidden(
+            "You cannot change a member whose role is above your own".to_string(),
+        ));
+    }
+    if current == Some(MemberRole::Owner)
+        && new_role != Some(MemberRole::Owner)
+        && OrganizationMember::count_owners(pool, organization_id).await? <= 1
+    {
+        return Err(ApiError::Conflict(
+            "An organization needs at least one owner".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn upsert_organization_member(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((organization_id, member_id)): Path<(Uuid, String)>,
+    Json(payload): Json<UpdateMemberRole>,
+) -> Result<ResponseJson<ApiResponse<OrganizationMember>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let role = organization_role(pool, organization_id, &user).await?;
+    check_member_change(pool, organization_id, role, &member_id, Some(payload.role)).await?;
+
+    let member =
+        OrganizationMember::upsert(pool, organization_id, &member_id, payload.role).await?;
+    Ok(ResponseJson(ApiResponse::success(member)))
+}
+
+pub async fn remove_organization_member(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((organization_id, member_id)): Path<(Uuid, String)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let role = organization_role(pool, organization_id, &user).await?;
+    // Anyone may leave; removing others needs a managing role
+    let role = if member_id == user.user_id {
+        MemberRole::Owner
+    } else {
+        role
+    };
+    check_member_change(pool, organization_id, role, &member_id, None).await?;
+
+    if OrganizationMember::delete(pool, organization_id, &member_id).await? == 0 {
+        return Ok(ResponseJson(ApiResponse::error("Member not found")));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_organizations).post(create_organization))
+        .route("/{organization_id}", delete(delete_organization))
+        .route("/{organization_id}/members", get(get_organization_members))
+        .route(
+            "/{organization_id}/members/{user_id}",
+            put(upsert_organization_member).delete(remove_organization_member),
+        )
+        .layer(from_fn_with_state(deployment.clone(), require_auth));
+
+    Router::new().nest("/organizations", inner)
+}
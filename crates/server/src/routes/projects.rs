@@ -5,28 +5,47 @@ use std::{
 
 use axum::{
     Extension, Json, Router,
-    extract::{Query, State},
+    extract::{Path as AxumPath, Query, State},
+    handler::Handler,
     http::StatusCode,
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
-    routing::get,
+    routing::{delete, get, put},
 };
-use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
+use db::models::{
+    audit_event::AuditAction,
+    organization::{MemberRole, OrganizationMember, UpdateMemberRole},
+    project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
+    project_mcp_server::{ProjectMcpServer, UpdateProjectMcpServers},
+    project_member::ProjectMember,
+    project_repository::{CreateProjectRepository, ProjectRepository},
+    project_secret::{ProjectSecret, UpsertProjectSecret},
 };
 use deployment::Deployment;
 use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
 use services::services::{
     file_ranker::FileRanker,
     file_search_cache::{CacheError, SearchMode, SearchQuery},
     git::GitBranch,
+    project_secrets::ProjectSecretsService,
+    sparse_checkout::SparseCone,
 };
 use tokio::fs;
+use ts_rs::TS;
 use utils::{path::expand_tilde, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, auth::AuthenticatedUser, error::ApiError, middleware::load_project_middleware,
+    DeploymentImpl,
+    audit::AuditContext,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        authorization::{ProjectAccess, ProjectPermission, require_project_permission},
+        load_project_middleware,
+    },
+    routes::webhooks,
 };
 
 pub(crate) const INVALID_PROJECT_NAME_CHARS: &[char] =
@@ -50,8 +69,8 @@ pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
     Extension(user): Extension<AuthenticatedUser>, // ✅ 추가
 ) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
-    // ✅ find_by_user로 변경 (이 사용자의 프로젝트만)
-    let projects = Project::find_by_user(&deployment.db().pool, &user.user_id).await?;
+    // Own projects plus those shared directly or through an organization
+    let projects = Project::find_accessible_by_user(&deployment.db().pool, &user.user_id).await?;
 
     tracing::debug!(
         "User {} retrieved {} projects",
@@ -80,6 +99,7 @@ pub async fn get_project_branches(
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
     Extension(user): Extension<AuthenticatedUser>, // ✅ 추가
+    audit: AuditContext,
     Json(payload): Json<CreateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
     let id = Uuid::new_v4();
@@ -91,6 +111,9 @@ pub async fn create_project(
         cleanup_script,
         copy_files,
         use_existing_repo,
+        init_submodules,
+        sparse_checkout_patterns,
+        sparse_auto_expand,
     } = payload;
     tracing::debug!("Creating project '{}'", name);
 
@@ -193,6 +216,9 @@ pub async fn create_project(
         dev_script,
         cleanup_script,
         copy_files,
+        init_submodules,
+        sparse_checkout_patterns: sparse_checkout_patterns.filter(|p| !p.trim().is_empty()),
+        sparse_auto_expand,
     };
 
     match Project::create(&mut *tx, &create_payload, id, &user.user_id).await {
@@ -212,6 +238,13 @@ pub async fn create_project(
                 )
                 .await;
 
+            audit
+                .event(AuditAction::ProjectCreated)
+                .project(project.id)
+                .after(&project)
+                .record(&deployment)
+                .await;
+
             Ok(ResponseJson(ApiResponse::success(project)))
         }
         Err(e) => {
@@ -233,8 +266,13 @@ pub async fn create_project(
 pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    let audit_entry = audit
+        .event(AuditAction::ProjectUpdated)
+        .before(&existing_project);
+
     // Destructure payload to handle field updates.
     // This allows us to treat `None` from the payload as an explicit `null` to clear a field,
     // as the frontend currently sends all fields on update.
@@ -245,6 +283,9 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
+        init_submodules,
+        sparse_checkout_patterns,
+        sparse_auto_expand,
     } = payload;
     let name = match name {
         Some(value) => {
@@ -296,10 +337,19 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
+        init_submodules.unwrap_or(existing_project.init_submodules),
+        match sparse_checkout_patterns {
+            Some(patterns) => Some(patterns).filter(|p| !p.trim().is_empty()),
+            None => existing_project.sparse_checkout_patterns,
+        },
+        sparse_auto_expand.unwrap_or(existing_project.sparse_auto_expand),
     )
     .await
     {
-        Ok(project) => Ok(ResponseJson(ApiResponse::success(project))),
+        Ok(project) => {
+            audit_entry.after(&project).record(&deployment).await;
+            Ok(ResponseJson(ApiResponse::success(project)))
+        }
         Err(e) => {
             tracing::error!("Failed to update project: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -310,12 +360,30 @@ pub async fn update_project(
 pub async fn delete_project(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
 ) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
     match Project::delete(&deployment.db().pool, project.id).await {
         Ok(rows_affected) => {
             if rows_affected == 0 {
                 Err(StatusCode::NOT_FOUND)
             } else {
+                if let Err(e) = ProjectSecretsService::new(deployment.secret_store().clone())
+                    .delete_all(project.id)
+                    .await
+                {
+                    tracing::error!("Failed to delete secrets of project {}: {}", project.id, e);
+                }
+                if let Err(e) = deployment
+                    .webhooks()
+                    .delete_secrets_for_project(project.id)
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to delete webhook secrets of project {}: {}",
+                        project.id,
+                        e
+                    );
+                }
                 deployment
                     .track_if_analytics_allowed(
                         "project_deleted",
@@ -325,6 +393,12 @@ pub async fn delete_project(
                     )
                     .await;
 
+                audit
+                    .event(AuditAction::ProjectDeleted)
+                    .before(&project)
+                    .record(&deployment)
+                    .await;
+
                 Ok(ResponseJson(ApiResponse::success(())))
             }
         }
@@ -335,6 +409,349 @@ pub async fn delete_project(
     }
 }
 
+/// Additional repositories checked out next to the project's primary repository
+pub async fn get_project_repositories(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectRepository>>>, ApiError> {
+    let repositories =
+        ProjectRepository::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(repositories)))
+}
+
+pub async fn add_project_repository(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
+    Json(payload): Json<CreateProjectRepository>,
+) -> Result<ResponseJson<ApiResponse<ProjectRepository>>, ApiError> {
+    let name = payload.name.trim().to_string();
+    if name.is_empty() || name.starts_with('.') {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Repository name is required",
+        )));
+    }
+    if contains_invalid_project_name_chars(&name) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Repository name cannot contain / \\ : * ? \" < > | or control characters.",
+        )));
+    }
+    // Each repository becomes a sibling directory of the primary worktree
+    if project
+        .git_repo_path
+        .file_name()
+        .is_some_and(|primary| primary.to_string_lossy() == name)
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Repository name must differ from the primary repository directory",
+        )));
+    }
+
+    let path = std::path::absolute(expand_tilde(&payload.git_repo_path))?;
+    if path == project.git_repo_path {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The primary repository is already part of this project",
+        )));
+    }
+    if !path.is_dir() || !path.join(".git").exists() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The specified directory is not a git repository",
+        )));
+    }
+    if let Some(branch) = payload.default_target_branch.as_deref()
+        && !deployment.git().check_branch_exists(&path, branch)?
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Branch '{branch}' does not exist in {}",
+            path.display()
+        ))));
+    }
+
+    let create = CreateProjectRepository {
+        name,
+        git_repo_path: path.to_string_lossy().to_string(),
+        default_target_branch: payload
+            .default_target_branch
+            .filter(|b| !b.trim().is_empty()),
+    };
+    match ProjectRepository::create(&deployment.db().pool, project.id, &create).await {
+        Ok(repository) => {
+            audit
+                .event(AuditAction::ProjectRepositoryAdded)
+                .target(&repository.name)
+                .after(&repository)
+                .record(&deployment)
+                .await;
+            Ok(ResponseJson(ApiResponse::success(repository)))
+        }
+        Err(sqlx::Error::Database(db_err)) if db_err.message().contains("UNIQUE constraint") => {
+            Ok(ResponseJson(ApiResponse::error(
+                "A repository with this name or path already belongs to the project",
+            )))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn remove_project_repository(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((_, repository_id)): AxumPath<(Uuid, Uuid)>,
+    audit: AuditContext,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected =
+        ProjectRepository::delete(&deployment.db().pool, project.id, repository_id).await?;
+    if rows_affected == 0 {
+        return Ok(ResponseJson(ApiResponse::error("Repository not found")));
+    }
+    audit
+        .event(AuditAction::ProjectRepositoryRemoved)
+        .target(repository_id.to_string())
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// MCP servers added to every agent run for this project, on top of the global ones.
+/// Credentials in their definitions are only shown to members who can manage the project.
+pub async fn get_project_mcp_servers(
+    Extension(project): Extension<Project>,
+    Extension(access): Extension<ProjectAccess>,
+    Extension(user): Extension<AuthenticatedUser>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMcpServer>>>, ApiError> {
+    let mut servers =
+        ProjectMcpServer::find_by_project_id(&deployment.db().pool, project.id).await?;
+    let can_manage =
+        access.allows(ProjectPermission::Manage) && ProjectPermission::Manage.allowed_for(&user);
+    if !can_manage {
+        servers = servers.into_iter().map(ProjectMcpServer::masked).collect();
+    }
+    Ok(ResponseJson(ApiResponse::success(servers)))
+}
+
+pub async fn update_project_mcp_servers(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
+    Json(payload): Json<UpdateProjectMcpServers>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMcpServer>>>, ApiError> {
+    for (name, server) in &payload.servers {
+        if name.trim().is_empty() || name == "meta" {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Invalid MCP server name '{name}'"
+            ))));
+        }
+        let has_target = ["command", "url"]
+            .iter()
+            .any(|key| server.get(key).is_some_and(|value| value.is_string()));
+        if !has_target {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "MCP server '{name}' needs a `command` or a `url`"
+            ))));
+        }
+    }
+
+    // Definitions can hold credentials in `env`, so only server names are audited
+    let server_names =
+        |servers: &[ProjectMcpServer]| servers.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+    let previous = ProjectMcpServer::find_by_project_id(&deployment.db().pool, project.id).await?;
+    let servers =
+        ProjectMcpServer::replace_for_project(&deployment.db().pool, project.id, &payload.servers)
+            .await?;
+    audit
+        .event(AuditAction::ProjectMcpServersUpdated)
+        .before(&server_names(&previous))
+        .after(&server_names(&servers))
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(servers)))
+}
+
+/// Secrets exposed to this project's scripts and agents; values are never returned
+pub async fn get_project_secrets(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectSecret>>>, ApiError> {
+    let secrets = ProjectSecretsService::new(deployment.secret_store().clone())
+        .list(project.id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(secrets)))
+}
+
+pub async fn upsert_project_secret(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((_, name)): AxumPath<(Uuid, String)>,
+    audit: AuditContext,
+    Json(payload): Json<UpsertProjectSecret>,
+) -> Result<ResponseJson<ApiResponse<ProjectSecret>>, ApiError> {
+    let previous = ProjectSecret::find_by_name(&deployment.db().pool, project.id, &name).await?;
+    let secret = ProjectSecretsService::new(deployment.secret_store().clone())
+        .upsert(project.id, &name, &payload)
+        .await?;
+    // Only metadata is snapshotted; values never reach the audit log
+    audit
+        .event(AuditAction::ProjectSecretUpdated)
+        .target(&secret.name)
+        .before(&previous)
+        .after(&secret)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+pub async fn delete_project_secret(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((_, name)): AxumPath<(Uuid, String)>,
+    audit: AuditContext,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectSecretsService::new(deployment.secret_store().clone())
+        .delete(project.id, &name)
+        .await?;
+    audit
+        .event(AuditAction::ProjectSecretDeleted)
+        .target(&name)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Who can access a project and the requesting user's own role
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectMembers {
+    pub owner_id: Option<String>,
+    pub organization_id: Option<Uuid>,
+    pub members: Vec<ProjectMember>,
+    pub your_role: MemberRole,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectOrganization {
+    /// None stops sharing the project with its organization
+    pub organization_id: Option<Uuid>,
+}
+
+pub async fn get_project_members(
+    Extension(project): Extension<Project>,
+    Extension(access): Extension<ProjectAccess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectMembers>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Ok(ResponseJson(ApiResponse::success(ProjectMembers {
+        owner_id: Project::owner_id(pool, project.id).await?,
+        organization_id: Project::organization_id(pool, project.id).await?,
+        members: ProjectMember::find_by_project_id(pool, project.id).await?,
+        your_role: access.role,
+    })))
+}
+
+/// Maintainers can manage members up to their own role; the creator cannot be changed
+async fn check_project_member_change(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    access: &ProjectAccess,
+    member_id: &str,
+    new_role: Option<MemberRole>,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::owner_id(pool, project.id).await?.as_deref() == Some(member_id) {
+        return Err(ApiError::Conflict(
+            "The project creator always remains its owner".to_string(),
+        ));
+    }
+    if new_role.is_some_and(|new_role| new_role > access.role) {
+        return Err(ApiError::Forbidden(
+            "You cannot grant a role above your own".to_string(),
+        ));
+    }
+    let members = ProjectMember::find_by_project_id(pool, project.id).await?;
+    if members
+        .iter()
+        .any(|member| member.user_id == member_id && member.role > access.role)
+    {
+        return Err(ApiError::Forbidden(
+            "You cannot change a member whose role is above your own".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn upsert_project_member(
+    Extension(project): Extension<Project>,
+    Extension(access): Extension<ProjectAccess>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((_, member_id)): AxumPath<(Uuid, String)>,
+    audit: AuditContext,
+    Json(payload): Json<UpdateMemberRole>,
+) -> Result<ResponseJson<ApiResponse<ProjectMember>>, ApiError> {
+    check_project_member_change(
+        &deployment,
+        &project,
+        &access,
+        &member_id,
+        Some(payload.role),
+    )
+    .await?;
+    let member =
+        ProjectMember::upsert(&deployment.db().pool, project.id, &member_id, payload.role).await?;
+    audit
+        .event(AuditAction::ProjectMemberUpdated)
+        .target(&member_id)
+        .after(&member)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(member)))
+}
+
+pub async fn remove_project_member(
+    Extension(project): Extension<Project>,
+    Extension(access): Extension<ProjectAccess>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((_, member_id)): AxumPath<(Uuid, String)>,
+    audit: AuditContext,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    check_project_member_change(&deployment, &project, &access, &member_id, None).await?;
+    if ProjectMember::delete(&deployment.db().pool, project.id, &member_id).await? == 0 {
+        return Ok(ResponseJson(ApiResponse::error("Member not found")));
+    }
+    audit
+        .event(AuditAction::ProjectMemberRemoved)
+        .target(&member_id)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Share the project with an organization the requester maintains, or stop sharing it
+pub async fn update_project_organization(
+    Extension(project): Extension<Project>,
+    Extension(user): Extension<AuthenticatedUser>,
+    State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
+    Json(payload): Json<UpdateProjectOrganization>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(organization_id) = payload.organization_id {
+        let role = OrganizationMember::role_for(pool, organization_id, &user.user_id).await?;
+        if !role.is_some_and(|role| role >= MemberRole::Maintainer) {
+            return Err(ApiError::Forbidden(
+                "Only organization maintainers and owners can share projects with it".to_string(),
+            ));
+        }
+    }
+    let previous = Project::organization_id(pool, project.id).await?;
+    Project::set_organization_id(pool, project.id, payload.organization_id).await?;
+    audit
+        .event(AuditAction::ProjectOrganizationChanged)
+        .before(&serde_json::json!({ "organization_id": previous }))
+        .after(&serde_json::json!({ "organization_id": payload.organization_id }))
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub async fn search_project_files(
     State(deployment): State<DeploymentImpl>,
     Extension(project): Extension<Project>,
@@ -351,10 +768,14 @@ pub async fn search_project_files(
 
     let repo_path = &project.git_repo_path;
     let file_search_cache = deployment.file_search_cache();
+    let sparse_cone = project
+        .sparse_checkout_patterns
+        .as_deref()
+        .and_then(SparseCone::parse);
 
     // Try cache first
     match file_search_cache
-        .search(repo_path, query, mode.clone())
+        .search(repo_path, query, mode.clone(), sparse_cone.as_ref())
         .await
     {
         Ok(results) => {
@@ -374,7 +795,13 @@ pub async fn search_project_files(
                 query,
                 mode
             );
-            match search_files_in_repo(&project.git_repo_path.to_string_lossy(), query, mode).await
+            match search_files_in_repo(
+                &project.git_repo_path.to_string_lossy(),
+                query,
+                mode,
+                sparse_cone.as_ref(),
+            )
+            .await
             {
                 Ok(results) => Ok(ResponseJson(ApiResponse::success(results))),
                 Err(e) => {
@@ -386,7 +813,13 @@ pub async fn search_project_files(
         Err(CacheError::BuildError(e)) => {
             tracing::error!("Cache build error for repo {:?}: {}", repo_path, e);
             // Fall back to filesystem search
-            match search_files_in_repo(&project.git_repo_path.to_string_lossy(), query, mode).await
+            match search_files_in_repo(
+                &project.git_repo_path.to_string_lossy(),
+                query,
+                mode,
+                sparse_cone.as_ref(),
+            )
+            .await
             {
                 Ok(results) => Ok(ResponseJson(ApiResponse::success(results))),
                 Err(e) => {
@@ -402,6 +835,7 @@ async fn search_files_in_repo(
     repo_path: &str,
     query: &str,
     mode: SearchMode,
+    sparse_cone: Option<&SparseCone>,
 ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
     let repo_path = Path::new(repo_path);
 
@@ -457,6 +891,11 @@ async fn search_files_in_repo(
         }
 
         let relative_path = path.strip_prefix(repo_path)?;
+        if let Some(cone) = sparse_cone
+            && !cone.contains_path(&relative_path.to_string_lossy(), path.is_file())
+        {
+            continue;
+        }
         let relative_path_str = relative_path.to_string_lossy().to_lowercase();
 
         let file_name = path
@@ -527,13 +966,54 @@ async fn search_files_in_repo(
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let manage = || from_fn_with_state(ProjectPermission::Manage, require_project_permission);
+
     let project_id_router = Router::new()
         .route(
             "/",
-            get(get_project).put(update_project).delete(delete_project),
+            get(get_project)
+                .put(update_project.layer(manage()))
+                .delete(delete_project.layer(from_fn_with_state(
+                    ProjectPermission::Delete,
+                    require_project_permission,
+                ))),
         )
         .route("/branches", get(get_project_branches))
         .route("/search", get(search_project_files))
+        .route(
+            "/repositories",
+            get(get_project_repositories).post(add_project_repository.layer(manage())),
+        )
+        .route(
+            "/repositories/{repository_id}",
+            delete(remove_project_repository).route_layer(manage()),
+        )
+        .route(
+            "/mcp-servers",
+            get(get_project_mcp_servers).put(update_project_mcp_servers.layer(manage())),
+        )
+        .route("/secrets", get(get_project_secrets))
+        .route(
+            "/secrets/{name}",
+            put(upsert_project_secret)
+                .delete(delete_project_secret)
+                .route_layer(manage()),
+        )
+        .route("/members", get(get_project_members))
+        .route(
+            "/members/{user_id}",
+            put(upsert_project_member)
+                .delete(remove_project_member)
+                .route_layer(manage()),
+        )
+        .nest("/webhooks", webhooks::router())
+        .route(
+            "/organization",
+            put(update_project_organization).route_layer(from_fn_with_state(
+                ProjectPermission::Delete,
+                require_project_permission,
+            )),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -1,5 +1,7 @@
 pub mod cursor_setup;
 pub mod drafts;
+pub mod patch;
+pub mod repositories;
 pub mod util;
 
 use axum::{
@@ -8,18 +10,21 @@ use axum::{
         Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    handler::Handler,
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
 use db::models::{
+    audit_event::AuditAction,
     draft::{Draft, DraftType},
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     project::{Project, ProjectError},
     task::{Task, TaskRelationships, TaskStatus},
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+    webhook::WebhookEventType,
 };
 use deployment::{Deployment, DeploymentError};
 use executors::{
@@ -35,8 +40,9 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    forge::{ForgeConnection, ForgeError},
     git::{ConflictOp, WorktreeResetOptions},
-    github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
+    github_service::{CreatePrRequest, GitHubServiceError},
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -45,9 +51,14 @@ use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
+    audit::AuditContext,
     auth::AuthenticatedUser,
     error::ApiError,
-    middleware::{auth::require_auth, load_task_attempt_middleware},
+    middleware::{
+        auth::require_auth,
+        authorization::{ProjectAccess, ProjectPermission, require_project_permission},
+        load_task_attempt_middleware,
+    },
     routes::task_attempts::util::{ensure_worktree_path, handle_images_for_prompt},
 };
 
@@ -104,7 +115,8 @@ pub struct FollowUpResponse {
 
 #[derive(Debug, Deserialize)]
 pub struct TaskAttemptQuery {
-    pub task_id: Option<Uuid>,
+    /// Attempts are listed per task, so the project's View permission can be checked
+    pub task_id: Uuid,
 }
 
 #[derive(Debug, Deserialize)]
@@ -113,10 +125,38 @@ pub struct DiffStreamQuery {
     pub stats_only: bool,
 }
 
-async fn resolve_github_token(deployment: &DeploymentImpl) -> Result<String, ApiError> {
+/// Connect to the forge (GitHub, GitLab or Gitea) hosting the repository's default remote.
+/// Uses the GitHub account `user` linked, if any.
+async fn resolve_forge(
+    deployment: &DeploymentImpl,
+    user: &AuthenticatedUser,
+    repo_path: &std::path::Path,
+) -> Result<ForgeConnection, ForgeError> {
+    let remote_url = deployment
+        .git()
+        .get_remote_url(repo_path)
+        .map_err(|e| ForgeError::UnsupportedRemote(e.to_string()))?;
+    deployment
+        .forge_resolver()
+        .with_linked_user(&user.user_id)
+        .connect(&remote_url)
+        .await
+}
+
+/// Token used to fetch from the repository's remote. Falls back to the GitHub
+/// token when the remote cannot be attributed to a known forge.
+async fn resolve_remote_token(
+    deployment: &DeploymentImpl,
+    repo_path: &std::path::Path,
+) -> Result<Option<String>, ApiError> {
+    let resolver = deployment.forge_resolver();
+    if let Ok(remote_url) = deployment.git().get_remote_url(repo_path)
+        && let Ok(repo_info) = resolver.detect(&remote_url).await
+    {
+        return Ok(resolver.token(&repo_info).await?);
+    }
     match deployment.github_token().await {
-        Ok(Some(token)) => Ok(token),
-        Ok(None) => Err(GitHubServiceError::TokenInvalid.into()),
+        Ok(token) => Ok(token),
         Err(err) => {
             tracing::error!("Failed to load GitHub token: {err}");
             Err(ApiError::Deployment(DeploymentError::from(err)))
@@ -126,10 +166,15 @@ async fn resolve_github_token(deployment: &DeploymentImpl) -> Result<String, Api
 
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
     Query(query): Query<TaskAttemptQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskAttempt>>>, ApiError> {
     let pool = &deployment.db().pool;
-    let attempts = TaskAttempt::fetch_all(pool, query.task_id).await?;
+    let task = Task::find_by_id(pool, query.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ProjectAccess::require(pool, task.project_id, &user, ProjectPermission::View).await?;
+    let attempts = TaskAttempt::fetch_all(pool, Some(task.id)).await?;
     Ok(ResponseJson(ApiResponse::success(attempts)))
 }
 
@@ -167,12 +212,20 @@ pub struct RunAgentSetupResponse {}
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Extension(user): Extension<AuthenticatedUser>,
+    audit: AuditContext,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, ApiError> {
     let executor_profile_id = payload.get_executor_profile_id();
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    ProjectAccess::require(
+        &deployment.db().pool,
+        task.project_id,
+        &user,
+        ProjectPermission::RunAttempts,
+    )
+    .await?;
 
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
@@ -213,6 +266,14 @@ pub async fn create_task_attempt(
         )
         .await;
 
+    audit
+        .event(AuditAction::AttemptStarted)
+        .project(task.project_id)
+        .attempt(&task_attempt)
+        .after(&task_attempt)
+        .record(&deployment)
+        .await;
+
     tracing::info!("Created attempt for task {}", task.id);
 
     Ok(ResponseJson(ApiResponse::success(task_attempt)))
@@ -247,7 +308,7 @@ pub async fn run_agent_setup(
     Ok(ResponseJson(ApiResponse::success(RunAgentSetupResponse {})))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub variant: Option<String>,
@@ -260,6 +321,7 @@ pub struct CreateFollowUpAttempt {
 pub async fn follow_up(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
     tracing::info!("{:?}", task_attempt);
@@ -401,6 +463,48 @@ pub async fn follow_up(
                 .await;
     }
 
+    audit
+        .event(AuditAction::AttemptFollowUp)
+        .target(execution_process.id.to_string())
+        .record(&deployment)
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct HandoffAttemptRequest {
+    pub executor_profile_id: ExecutorProfileId,
+    /// Optional instructions for the new executor, appended after the handed-over context
+    pub prompt: Option<String>,
+}
+
+/// Switch the attempt to a different coding agent, carrying over the conversation and diff
+#[axum::debug_handler]
+pub async fn handoff_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<HandoffAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let _ = ensure_worktree_path(&deployment, &task_attempt).await?;
+
+    let executor_profile_id = payload.executor_profile_id;
+    let execution_process = deployment
+        .container()
+        .handoff_attempt(&task_attempt, executor_profile_id.clone(), payload.prompt)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_handed_off",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
+            }),
+        )
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
@@ -556,13 +660,23 @@ async fn handle_task_attempt_diff_ws(
     task_attempt: TaskAttempt,
     stats_only: bool,
 ) -> anyhow::Result<()> {
-    use futures_util::{SinkExt, StreamExt, TryStreamExt};
-    use utils::log_msg::LogMsg;
-
     let stream = deployment
         .container()
         .stream_diff(&task_attempt, stats_only)
         .await?;
+    forward_diff_stream(socket, stream).await
+}
+
+/// Forward a diff stream to the socket until either side finishes
+async fn forward_diff_stream(
+    socket: WebSocket,
+    stream: futures_util::stream::BoxStream<
+        'static,
+        Result<utils::log_msg::LogMsg, std::io::Error>,
+    >,
+) -> anyhow::Result<()> {
+    use futures_util::{SinkExt, StreamExt, TryStreamExt};
+    use utils::log_msg::LogMsg;
 
     let mut stream = stream.map_ok(|msg: LogMsg| msg.to_ws_message_unchecked());
 
@@ -661,6 +775,7 @@ pub async fn compare_commit_to_head(
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -687,13 +802,25 @@ pub async fn merge_task_attempt(
         commit_message.push_str(description);
     }
 
-    let merge_commit_id = deployment.git().merge_changes(
+    // Additional repositories are merged first so that a failure anywhere can be
+    // rolled back before the primary repository is touched
+    let merged_repositories =
+        repositories::merge_repositories(&deployment, &task_attempt, &commit_message).await?;
+
+    let merge_commit_id = match deployment.git().merge_changes(
         &ctx.project.git_repo_path,
         worktree_path,
         &ctx.task_attempt.branch,
         &ctx.task_attempt.target_branch,
         &commit_message,
-    )?;
+    ) {
+        Ok(merge_commit_id) => merge_commit_id,
+        Err(e) => {
+            repositories::rollback_merges(&deployment, &task_attempt, &merged_repositories);
+            return Err(e.into());
+        }
+    };
+    repositories::record_merges(&deployment, &merged_repositories).await?;
 
     Merge::create_direct(
         pool,
@@ -715,48 +842,60 @@ pub async fn merge_task_attempt(
         )
         .await;
 
+    audit
+        .event(AuditAction::AttemptMerged)
+        .target(&ctx.task_attempt.target_branch)
+        .after(&serde_json::json!({ "merge_commit": merge_commit_id }))
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
 pub async fn push_task_attempt_branch(
     Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(user): Extension<AuthenticatedUser>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
-    let github_token = resolve_github_token(&deployment).await?;
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
 
-    let github_service = GitHubService::new(&github_token)?;
-    github_service.check_token().await?;
+    let forge = resolve_forge(&deployment, &user, &project.git_repo_path).await?;
+    forge.service.check_token().await?;
 
     let ws_path = ensure_worktree_path(&deployment, &task_attempt).await?;
 
-    deployment
-        .git()
-        .push_to_github(&ws_path, &task_attempt.branch, &github_token)?;
+    deployment.git().push_to_remote(
+        &ws_path,
+        &forge.repo_info.clone_url(),
+        &task_attempt.branch,
+        &forge.token,
+    )?;
+
+    audit
+        .event(AuditAction::AttemptPushed)
+        .target(&task_attempt.branch)
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
 pub async fn create_github_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(user): Extension<AuthenticatedUser>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(request): Json<CreateGitHubPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<String, GitHubServiceError>>, ApiError> {
     let github_config = deployment.config().read().await.github.clone();
-    let github_token = match deployment.github_token().await {
-        Ok(Some(token)) => token,
-        Ok(None) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GitHubServiceError::TokenInvalid,
-            )));
-        }
-        Err(err) => {
-            tracing::error!("Failed to load GitHub token: {err}");
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GitHubServiceError::TokenInvalid,
-            )));
-        }
-    };
-    // Create GitHub service instance
-    let github_service = GitHubService::new(&github_token)?;
     // Get the task attempt to access the stored target branch
     let target_branch = request.target_branch.unwrap_or_else(|| {
         // Use the stored target branch from the task attempt as the default
@@ -780,21 +919,44 @@ pub async fn create_github_pr(
         .await?
         .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
 
+    // Resolve the forge hosting the remote (GitHub, GitLab or Gitea)
+    let forge = match resolve_forge(&deployment, &user, &project.git_repo_path).await {
+        Ok(forge) => forge,
+        Err(e) => {
+            tracing::error!("Failed to resolve forge for project {}: {}", project.id, e);
+            return Ok(match e.as_api_data() {
+                Some(data) => ResponseJson(ApiResponse::error_with_data(data)),
+                None => ResponseJson(ApiResponse::error(
+                    format!("Failed to create PR: {}", e).as_str(),
+                )),
+            });
+        }
+    };
+
     let workspace_path = ensure_worktree_path(&deployment, &task_attempt).await?;
 
-    // Push the branch to GitHub first
-    if let Err(e) =
-        deployment
-            .git()
-            .push_to_github(&workspace_path, &task_attempt.branch, &github_token)
-    {
-        tracing::error!("Failed to push branch to GitHub: {}", e);
+    // Push the branch to the remote first
+    if let Err(e) = deployment.git().push_to_remote(
+        &workspace_path,
+        &forge.repo_info.clone_url(),
+        &task_attempt.branch,
+        &forge.token,
+    ) {
+        tracing::error!(
+            "Failed to push branch to {}: {}",
+            forge.repo_info.provider,
+            e
+        );
         let gh_e = GitHubServiceError::from(e);
         if gh_e.is_api_data() {
             return Ok(ResponseJson(ApiResponse::error_with_data(gh_e)));
         } else {
             return Ok(ResponseJson(ApiResponse::error(
-                format!("Failed to push branch to GitHub: {}", gh_e).as_str(),
+                format!(
+                    "Failed to push branch to {}: {}",
+                    forge.repo_info.provider, gh_e
+                )
+                .as_str(),
             )));
         }
     }
@@ -825,12 +987,7 @@ pub async fn create_github_pr(
         head_branch: task_attempt.branch.clone(),
         base_branch: norm_target_branch_name.clone(),
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment
-        .git()
-        .get_github_repo_info(&project.git_repo_path)?;
-
-    match github_service.create_pr(&repo_info, &pr_request).await {
+    match forge.service.create_pr(&forge.repo_info, &pr_request).await {
         Ok(pr_info) => {
             // Update the task attempt with PR information
             if let Err(e) = Merge::create_pr(
@@ -852,20 +1009,45 @@ pub async fn create_github_pr(
                         "task_id": task.id.to_string(),
                         "project_id": project.id.to_string(),
                         "attempt_id": task_attempt.id.to_string(),
+                        "provider": forge.repo_info.provider.as_str(),
                     }),
                 )
                 .await;
 
+            audit
+                .event(AuditAction::PullRequestCreated)
+                .target(&pr_info.url)
+                .after(&serde_json::json!({
+                    "title": pr_request.title,
+                    "head_branch": pr_request.head_branch,
+                    "base_branch": pr_request.base_branch,
+                }))
+                .record(&deployment)
+                .await;
+
+            deployment.webhooks().emit(
+                project.id,
+                WebhookEventType::PrOpened,
+                serde_json::json!({
+                    "task_id": task.id,
+                    "attempt_id": task_attempt.id,
+                    "number": pr_info.number,
+                    "url": pr_info.url,
+                    "target_branch": norm_target_branch_name,
+                }),
+            );
+
             Ok(ResponseJson(ApiResponse::success(pr_info.url)))
         }
         Err(e) => {
             tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
+                "Failed to create {} PR for attempt {}: {}",
+                forge.repo_info.provider,
                 task_attempt.id,
                 e
             );
-            if e.is_api_data() {
-                Ok(ResponseJson(ApiResponse::error_with_data(e)))
+            if let Some(data) = e.as_api_data() {
+                Ok(ResponseJson(ApiResponse::error_with_data(data)))
             } else {
                 Ok(ResponseJson(ApiResponse::error(
                     format!("Failed to create PR: {}", e).as_str(),
@@ -956,7 +1138,9 @@ pub async fn get_task_attempt_branch_status(
             (Some(a), Some(b))
         }
         BranchType::Remote => {
-            let token = resolve_github_token(&deployment).await?;
+            let token = resolve_remote_token(&deployment, &ctx.project.git_repo_path)
+                .await?
+                .ok_or(GitHubServiceError::TokenInvalid)?;
             let (remote_commits_ahead, remote_commits_behind) =
                 deployment.git().get_remote_branch_status(
                     &ctx.project.git_repo_path,
@@ -978,7 +1162,9 @@ pub async fn get_task_attempt_branch_status(
     })) = merges.first()
     {
         // check remote status if the attempt has an open PR
-        let token = resolve_github_token(&deployment).await?;
+        let token = resolve_remote_token(&deployment, &ctx.project.git_repo_path)
+            .await?
+            .ok_or(GitHubServiceError::TokenInvalid)?;
         let (remote_commits_ahead, remote_commits_behind) =
             deployment.git().get_remote_branch_status(
                 &ctx.project.git_repo_path,
@@ -1034,6 +1220,7 @@ pub struct RenameBranchResponse {
 pub async fn change_target_branch(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(payload): Json<ChangeTargetBranchRequest>,
 ) -> Result<ResponseJson<ApiResponse<ChangeTargetBranchResponse>>, ApiError> {
     // Extract new base branch from request body if provided
@@ -1082,6 +1269,14 @@ pub async fn change_target_branch(
         )
         .await;
 
+    audit
+        .event(AuditAction::AttemptTargetBranchChanged)
+        .target(&new_target_branch)
+        .before(&serde_json::json!({ "target_branch": task_attempt.target_branch }))
+        .after(&serde_json::json!({ "target_branch": new_target_branch }))
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(
         ChangeTargetBranchResponse {
             new_target_branch,
@@ -1094,6 +1289,7 @@ pub async fn change_target_branch(
 pub async fn rename_branch(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(payload): Json<RenameBranchRequest>,
 ) -> Result<ResponseJson<ApiResponse<RenameBranchResponse>>, ApiError> {
     let new_branch_name = payload.new_branch_name.trim();
@@ -1186,6 +1382,14 @@ pub async fn rename_branch(
         )
         .await;
 
+    audit
+        .event(AuditAction::AttemptBranchRenamed)
+        .target(new_branch_name)
+        .before(&serde_json::json!({ "branch": old_branch }))
+        .after(&serde_json::json!({ "branch": new_branch_name }))
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(RenameBranchResponse {
         branch: new_branch_name.to_string(),
     })))
@@ -1195,6 +1399,7 @@ pub async fn rename_branch(
 pub async fn rebase_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
     Json(payload): Json<RebaseTaskAttemptRequest>,
 ) -> Result<ResponseJson<ApiResponse<(), GitOperationError>>, ApiError> {
     let old_base_branch = payload
@@ -1203,11 +1408,6 @@ pub async fn rebase_task_attempt(
     let new_base_branch = payload
         .new_base_branch
         .unwrap_or(task_attempt.target_branch.clone());
-    let github_token = match deployment.github_token().await {
-        Ok(token) => token,
-        Err(err) => return Err(ApiError::Deployment(DeploymentError::from(err))),
-    };
-
     let pool = &deployment.db().pool;
 
     let task = task_attempt
@@ -1215,6 +1415,7 @@ pub async fn rebase_task_attempt(
         .await?
         .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
     let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+    let github_token = resolve_remote_token(&deployment, &ctx.project.git_repo_path).await?;
     match deployment
         .git()
         .check_branch_exists(&ctx.project.git_repo_path, &new_base_branch)?
@@ -1282,6 +1483,14 @@ pub async fn rebase_task_attempt(
         )
         .await;
 
+    audit
+        .event(AuditAction::AttemptRebased)
+        .target(&new_base_branch)
+        .before(&serde_json::json!({ "target_branch": old_base_branch }))
+        .after(&serde_json::json!({ "target_branch": new_base_branch }))
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -1309,6 +1518,7 @@ pub async fn delete_task_attempt_file(
     Extension(task_attempt): Extension<TaskAttempt>,
     Query(query): Query<DeleteFileQuery>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     let container_ref = deployment
         .container()
@@ -1317,7 +1527,7 @@ pub async fn delete_task_attempt_file(
     let worktree_path = std::path::Path::new(&container_ref);
 
     // Use GitService to delete file and commit
-    let _commit_id = deployment
+    let commit_id = deployment
         .git()
         .delete_file_and_commit(worktree_path, &query.file_path)
         .map_err(|e| {
@@ -1330,6 +1540,13 @@ pub async fn delete_task_attempt_file(
             ApiError::GitService(e)
         })?;
 
+    audit
+        .event(AuditAction::AttemptFileDeleted)
+        .target(&query.file_path)
+        .after(&serde_json::json!({ "commit": commit_id }))
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -1456,6 +1673,7 @@ pub async fn get_task_attempt_children(
 pub async fn stop_task_attempt_execution(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
     deployment.container().try_stop(&task_attempt).await;
 
@@ -1468,6 +1686,11 @@ pub async fn stop_task_attempt_execution(
         )
         .await;
 
+    audit
+        .event(AuditAction::AttemptStopped)
+        .record(&deployment)
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -1481,7 +1704,9 @@ pub struct AttachPrResponse {
 
 pub async fn attach_existing_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(user): Extension<AuthenticatedUser>,
     State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
 ) -> Result<ResponseJson<ApiResponse<AttachPrResponse>>, ApiError> {
     let pool = &deployment.db().pool;
 
@@ -1497,8 +1722,6 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let github_token = resolve_github_token(&deployment).await?;
-
     // Get project and repo info
     let Some(task) = task_attempt.parent_task(pool).await? else {
         return Err(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound));
@@ -1507,14 +1730,12 @@ pub async fn attach_existing_pr(
         return Err(ApiError::Project(ProjectError::ProjectNotFound));
     };
 
-    let github_service = GitHubService::new(&github_token)?;
-    let repo_info = deployment
-        .git()
-        .get_github_repo_info(&project.git_repo_path)?;
+    let forge = resolve_forge(&deployment, &user, &project.git_repo_path).await?;
 
     // List all PRs for branch (open, closed, and merged)
-    let prs = github_service
-        .list_all_prs_for_branch(&repo_info, &task_attempt.branch)
+    let prs = forge
+        .service
+        .list_all_prs_for_branch(&forge.repo_info, &task_attempt.branch)
         .await?;
 
     // Take the first PR (prefer open, but also accept merged/closed)
@@ -1545,6 +1766,12 @@ pub async fn attach_existing_pr(
             Task::update_status(pool, task.id, TaskStatus::Done).await?;
         }
 
+        audit
+            .event(AuditAction::PullRequestAttached)
+            .target(&pr_info.url)
+            .record(&deployment)
+            .await;
+
         Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
             pr_attached: true,
             pr_url: Some(pr_info.url),
@@ -1562,34 +1789,66 @@ pub async fn attach_existing_pr(
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let run = || from_fn_with_state(ProjectPermission::RunAttempts, require_project_permission);
+    let merge = || from_fn_with_state(ProjectPermission::Merge, require_project_permission);
+
     let task_attempt_id_router = Router::new()
         .route("/", get(get_task_attempt))
-        .route("/follow-up", post(follow_up))
-        .route("/run-agent-setup", post(run_agent_setup))
+        .route("/follow-up", post(follow_up).route_layer(run()))
+        .route("/handoff", post(handoff_attempt).route_layer(run()))
+        .route("/run-agent-setup", post(run_agent_setup).route_layer(run()))
         .route(
             "/draft",
             get(drafts::get_draft)
-                .put(drafts::save_draft)
-                .delete(drafts::delete_draft),
+                .put(drafts::save_draft.layer(run()))
+                .delete(drafts::delete_draft.layer(run())),
         )
-        .route("/draft/queue", post(drafts::set_draft_queue))
-        .route("/replace-process", post(replace_process))
+        .route(
+            "/draft/queue",
+            post(drafts::set_draft_queue).route_layer(run()),
+        )
+        .route("/replace-process", post(replace_process).route_layer(run()))
         .route("/commit-info", get(get_commit_info))
         .route("/commit-compare", get(compare_commit_to_head))
-        .route("/start-dev-server", post(start_dev_server))
+        .route("/patch", get(patch::export_patch))
+        .route(
+            "/start-dev-server",
+            post(start_dev_server).route_layer(run()),
+        )
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
-        .route("/merge", post(merge_task_attempt))
-        .route("/push", post(push_task_attempt_branch))
-        .route("/rebase", post(rebase_task_attempt))
-        .route("/conflicts/abort", post(abort_conflicts_task_attempt))
-        .route("/pr", post(create_github_pr))
-        .route("/pr/attach", post(attach_existing_pr))
-        .route("/delete-file", post(delete_task_attempt_file))
+        .route("/merge", post(merge_task_attempt).route_layer(merge()))
+        .route("/push", post(push_task_attempt_branch).route_layer(merge()))
+        .route("/rebase", post(rebase_task_attempt).route_layer(merge()))
+        .route(
+            "/conflicts/abort",
+            post(abort_conflicts_task_attempt).route_layer(merge()),
+        )
+        .route("/pr", post(create_github_pr).route_layer(merge()))
+        .route("/pr/attach", post(attach_existing_pr).route_layer(merge()))
+        .route(
+            "/delete-file",
+            post(delete_task_attempt_file).route_layer(run()),
+        )
         .route("/children", get(get_task_attempt_children))
-        .route("/stop", post(stop_task_attempt_execution))
-        .route("/change-target-branch", post(change_target_branch))
-        .route("/rename-branch", post(rename_branch))
+        .route(
+            "/stop",
+            post(stop_task_attempt_execution).route_layer(run()),
+        )
+        .route(
+            "/change-target-branch",
+            post(change_target_branch).route_layer(run()),
+        )
+        .route("/rename-branch", post(rename_branch).route_layer(run()))
+        .route("/repositories", get(repositories::get_attempt_repositories))
+        .route(
+            "/repositories/{repository_id}/diff/ws",
+            get(repositories::stream_repository_diff_ws),
+        )
+        .route(
+            "/repositories/{repository_id}/pr",
+            post(repositories::create_repository_pr).route_layer(merge()),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_task_attempt_middleware,
@@ -1597,6 +1856,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_task_attempts).post(create_task_attempt))
+        .route("/from-patch", post(patch::create_task_attempt_from_patch))
         .nest("/{id}", task_attempt_id_router)
         .layer(from_fn_with_state(deployment.clone(), require_auth));
 
@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::{
+    task::Task,
+    task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, git::PatchConflict};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::authorization::{ProjectAccess, ProjectPermission},
+    routes::task_attempts::util::ensure_worktree_path,
+};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum PatchFormat {
+    /// `git format-patch` mbox of the attempt's commits
+    #[default]
+    Mbox,
+    /// Single unified diff of the worktree against the merge base
+    Diff,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchExportQuery {
+    #[serde(default)]
+    pub format: PatchFormat,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateTaskAttemptFromPatchBody {
+    pub task_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    pub base_branch: String,
+    /// `git format-patch` mbox or unified diff
+    pub patch: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PatchImportResult {
+    pub task_attempt: TaskAttempt,
+    /// Hunks that could not be applied; everything else was committed
+    pub conflicts: Vec<PatchConflict>,
+}
+
+pub async fn export_patch(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PatchExportQuery>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
+    let repo_path = ctx.project.git_repo_path.as_path();
+
+    let (body, content_type, extension) = match query.format {
+        PatchFormat::Mbox => (
+            deployment.git().format_patch(
+                repo_path,
+                &task_attempt.branch,
+                &task_attempt.target_branch,
+            )?,
+            "application/mbox",
+            "mbox",
+        ),
+        PatchFormat::Diff => {
+            let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+            let base_commit = deployment.git().get_base_commit(
+                repo_path,
+                &task_attempt.branch,
+                &task_attempt.target_branch,
+            )?;
+            (
+                deployment.git().diff_patch(&worktree_path, &base_commit)?,
+                "text/x-diff",
+                "diff",
+            )
+        }
+    };
+
+    let filename = format!("{}.{extension}", task_attempt.branch.replace('/', "-"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Create an attempt whose branch starts with an uploaded patch applied to the base branch
+pub async fn create_task_attempt_from_patch(
+    State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateTaskAttemptFromPatchBody>,
+) -> Result<ResponseJson<ApiResponse<PatchImportResult>>, ApiError> {
+    if payload.patch.trim().is_empty() {
+        return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "Patch is empty".to_string(),
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    ProjectAccess::require(pool, task.project_id, &user, ProjectPermission::RunAttempts).await?;
+
+    let attempt_id = Uuid::new_v4();
+    let git_branch_name = deployment
+        .container()
+        .git_branch_from_task_attempt(&attempt_id, &task.title)
+        .await;
+    let task_attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: payload.executor_profile_id.executor,
+            base_branch: payload.base_branch.clone(),
+            branch: git_branch_name,
+        },
+        attempt_id,
+        task.id,
+        &user.user_id,
+    )
+    .await?;
+
+    let conflicts =
+        match apply_patch_to_attempt(&deployment, &task_attempt, &task, &payload.patch).await {
+            Ok(conflicts) => conflicts,
+            Err(e) => {
+                if let Err(cleanup_err) = deployment.container().delete(&task_attempt).await {
+                    tracing::warn!(
+                        "Failed to clean up worktree of attempt {}: {}",
+                        task_attempt.id,
+                        cleanup_err
+                    );
+                }
+                TaskAttempt::delete(pool, task_attempt.id).await?;
+                return Err(e);
+            }
+        };
+
+    // Reload to pick up the container ref set while creating the worktree
+    let task_attempt = TaskAttempt::find_by_id(pool, attempt_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_created_from_patch",
+            serde_json::json!({
+                "task_id": task_attempt.task_id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+                "conflicts": conflicts.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(PatchImportResult {
+        task_attempt,
+        conflicts,
+    })))
+}
+
+async fn apply_patch_to_attempt(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    task: &Task,
+    patch: &str,
+) -> Result<Vec<PatchConflict>, ApiError> {
+    let worktree_path = deployment.container().create(task_attempt).await?;
+
+    let task_uuid_str = task.id.to_string();
+    let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
+    let message = format!("Apply patch: {} (anyon {})", task.title, first_uuid_section);
+
+    Ok(deployment
+        .git()
+        .apply_patch(Path::new(&worktree_path), patch, &message)?)
+}
@@ -0,0 +1,349 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State, ws::WebSocketUpgrade},
+    response::{IntoResponse, Json as ResponseJson},
+};
+use db::models::{
+    audit_event::AuditAction,
+    project_repository::ProjectRepository,
+    task_attempt::{TaskAttempt, TaskAttemptError},
+    task_attempt_repository::TaskAttemptRepository,
+};
+use deployment::Deployment;
+use git2::BranchType;
+use serde::Serialize;
+use services::services::{
+    container::ContainerService,
+    github_service::{CreatePrRequest, GitHubServiceError},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use super::{CreateGitHubPrRequest, DiffStreamQuery, forward_diff_stream, resolve_forge};
+use crate::{
+    DeploymentImpl, audit::AuditContext, auth::AuthenticatedUser, error::ApiError,
+    routes::task_attempts::util::ensure_worktree_path,
+};
+
+/// Worktree state of one additional repository of a multi-repository attempt
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptRepositoryStatus {
+    pub repository: ProjectRepository,
+    pub attempt_repository: TaskAttemptRepository,
+    pub commits_ahead: Option<usize>,
+    pub commits_behind: Option<usize>,
+    pub has_uncommitted_changes: Option<bool>,
+}
+
+/// Branch positions recorded before a squash merge so it can be undone
+pub(super) struct MergedRepository {
+    attempt_repository_id: Uuid,
+    repo_path: PathBuf,
+    target_branch: String,
+    base_oid: String,
+    task_oid: String,
+    merge_commit: String,
+}
+
+async fn load_repositories(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+) -> Result<Vec<(ProjectRepository, TaskAttemptRepository)>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut repositories = Vec::new();
+    for attempt_repository in
+        TaskAttemptRepository::find_by_task_attempt_id(pool, task_attempt.id).await?
+    {
+        if let Some(repository) =
+            ProjectRepository::find_by_id(pool, attempt_repository.project_repository_id).await?
+        {
+            repositories.push((repository, attempt_repository));
+        }
+    }
+    Ok(repositories)
+}
+
+async fn find_repository(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    repository_id: Uuid,
+) -> Result<Option<(ProjectRepository, TaskAttemptRepository)>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(attempt_repository) =
+        TaskAttemptRepository::find_by_repository(pool, task_attempt.id, repository_id).await?
+    else {
+        return Ok(None);
+    };
+    Ok(ProjectRepository::find_by_id(pool, repository_id)
+        .await?
+        .map(|repository| (repository, attempt_repository)))
+}
+
+pub async fn get_attempt_repositories(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptRepositoryStatus>>>, ApiError> {
+    let git = deployment.git();
+    let statuses = load_repositories(&deployment, &task_attempt)
+        .await?
+        .into_iter()
+        .map(|(repository, attempt_repository)| {
+            let branch_status = git
+                .get_branch_status(
+                    &repository.git_repo_path,
+                    &task_attempt.branch,
+                    &attempt_repository.target_branch,
+                )
+                .ok();
+            let worktree_path = std::path::Path::new(&attempt_repository.worktree_path);
+            let has_uncommitted_changes = worktree_path
+                .exists()
+                .then(|| {
+                    git.is_worktree_clean(worktree_path)
+                        .ok()
+                        .map(|clean| !clean)
+                })
+                .flatten();
+            AttemptRepositoryStatus {
+                repository,
+                attempt_repository,
+                commits_ahead: branch_status.map(|(ahead, _)| ahead),
+                commits_behind: branch_status.map(|(_, behind)| behind),
+                has_uncommitted_changes,
+            }
+        })
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(statuses)))
+}
+
+pub async fn stream_repository_diff_ws(
+    ws: WebSocketUpgrade,
+    Query(params): Query<DiffStreamQuery>,
+    Path((_, repository_id)): Path<(Uuid, Uuid)>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some((_, attempt_repository)) =
+        find_repository(&deployment, &task_attempt, repository_id).await?
+    else {
+        return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "Repository not found for task attempt".to_string(),
+        )));
+    };
+    let stats_only = params.stats_only;
+    Ok(ws.on_upgrade(move |socket| async move {
+        let stream = deployment
+            .container()
+            .stream_repository_diff(&task_attempt, &attempt_repository, stats_only)
+            .await;
+        let result = match stream {
+            Ok(stream) => forward_diff_stream(socket, stream).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("repository diff WS closed: {}", e);
+        }
+    }))
+}
+
+/// Squash-merge every additional repository that has commits on the attempt branch.
+/// If any merge fails, the ones already performed are rolled back before returning.
+pub(super) async fn merge_repositories(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    commit_message: &str,
+) -> Result<Vec<MergedRepository>, ApiError> {
+    let git = deployment.git();
+    let mut merged: Vec<MergedRepository> = Vec::new();
+    for (repository, attempt_repository) in load_repositories(deployment, task_attempt).await? {
+        let repo_path = repository.git_repo_path;
+        let target_branch = attempt_repository.target_branch;
+        let result = (|| {
+            let (ahead, _) =
+                git.get_branch_status(&repo_path, &task_attempt.branch, &target_branch)?;
+            if ahead == 0 {
+                return Ok(None);
+            }
+            let base_oid = git.get_branch_oid(&repo_path, &target_branch)?;
+            let task_oid = git.get_branch_oid(&repo_path, &task_attempt.branch)?;
+            let merge_commit = git.merge_changes(
+                &repo_path,
+                std::path::Path::new(&attempt_repository.worktree_path),
+                &task_attempt.branch,
+                &target_branch,
+                commit_message,
+            )?;
+            Ok::<_, ApiError>(Some((base_oid, task_oid, merge_commit)))
+        })();
+
+        match result {
+            Ok(Some((base_oid, task_oid, merge_commit))) => merged.push(MergedRepository {
+                attempt_repository_id: attempt_repository.id,
+                repo_path,
+                target_branch,
+                base_oid,
+                task_oid,
+                merge_commit,
+            }),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!(
+                    "Failed to merge repository '{}' for attempt {}: {}",
+                    repository.name,
+                    task_attempt.id,
+                    e
+                );
+                rollback_merges(deployment, task_attempt, &merged);
+                return Err(e);
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// Move base and task branches back to where they were before `merged` was applied
+pub(super) fn rollback_merges(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    merged: &[MergedRepository],
+) {
+    for repository in merged.iter().rev() {
+        if let Err(e) = deployment.git().rollback_merge(
+            &repository.repo_path,
+            &repository.target_branch,
+            &repository.base_oid,
+            &task_attempt.branch,
+            &repository.task_oid,
+        ) {
+            tracing::error!(
+                "Failed to roll back merge in {}: {}",
+                repository.repo_path.display(),
+                e
+            );
+        }
+    }
+}
+
+pub(super) async fn record_merges(
+    deployment: &DeploymentImpl,
+    merged: &[MergedRepository],
+) -> Result<(), ApiError> {
+    for repository in merged {
+        TaskAttemptRepository::update_merge_commit(
+            &deployment.db().pool,
+            repository.attempt_repository_id,
+            &repository.merge_commit,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Push the attempt branch of an additional repository and open a PR on its forge
+pub async fn create_repository_pr(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(user): Extension<AuthenticatedUser>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, repository_id)): Path<(Uuid, Uuid)>,
+    audit: AuditContext,
+    Json(request): Json<CreateGitHubPrRequest>,
+) -> Result<ResponseJson<ApiResponse<String, GitHubServiceError>>, ApiError> {
+    let Some((repository, attempt_repository)) =
+        find_repository(&deployment, &task_attempt, repository_id).await?
+    else {
+        return Ok(ResponseJson(ApiResponse::error("Repository not found")));
+    };
+
+    let forge = match resolve_forge(&deployment, &user, &repository.git_repo_path).await {
+        Ok(forge) => forge,
+        Err(e) => {
+            return Ok(match e.as_api_data() {
+                Some(data) => ResponseJson(ApiResponse::error_with_data(data)),
+                None => ResponseJson(ApiResponse::error(&format!("Failed to create PR: {e}"))),
+            });
+        }
+    };
+
+    ensure_worktree_path(&deployment, &task_attempt).await?;
+    if let Err(e) = deployment.git().push_to_remote(
+        std::path::Path::new(&attempt_repository.worktree_path),
+        &forge.repo_info.clone_url(),
+        &task_attempt.branch,
+        &forge.token,
+    ) {
+        let gh_e = GitHubServiceError::from(e);
+        if gh_e.is_api_data() {
+            return Ok(ResponseJson(ApiResponse::error_with_data(gh_e)));
+        }
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Failed to push branch to {}: {}",
+            forge.repo_info.provider, gh_e
+        ))));
+    }
+
+    let target_branch = request
+        .target_branch
+        .unwrap_or_else(|| attempt_repository.target_branch.clone());
+    // Remote branches are formatted as {remote}/{branch} locally
+    let base_branch = if matches!(
+        deployment
+            .git()
+            .find_branch_type(&repository.git_repo_path, &target_branch)?,
+        BranchType::Remote
+    ) {
+        let remote = deployment
+            .git()
+            .get_remote_name_from_branch_name(&repository.git_repo_path, &target_branch)?;
+        target_branch
+            .strip_prefix(&format!("{remote}/"))
+            .unwrap_or(&target_branch)
+            .to_string()
+    } else {
+        target_branch
+    };
+    let pr_request = CreatePrRequest {
+        title: request.title,
+        body: request.body,
+        head_branch: task_attempt.branch.clone(),
+        base_branch,
+    };
+    match forge.service.create_pr(&forge.repo_info, &pr_request).await {
+        Ok(pr_info) => {
+            TaskAttemptRepository::update_pr(
+                &deployment.db().pool,
+                attempt_repository.id,
+                pr_info.number,
+                &pr_info.url,
+            )
+            .await?;
+            audit
+                .event(AuditAction::PullRequestCreated)
+                .target(&pr_info.url)
+                .after(&serde_json::json!({
+                    "repository_id": repository.id,
+                    "title": pr_request.title,
+                    "head_branch": pr_request.head_branch,
+                    "base_branch": pr_request.base_branch,
+                }))
+                .record(&deployment)
+                .await;
+            Ok(ResponseJson(ApiResponse::success(pr_info.url)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to create {} PR for repository '{}' of attempt {}: {}",
+                forge.repo_info.provider,
+                repository.name,
+                task_attempt.id,
+                e
+            );
+            Ok(match e.as_api_data() {
+                Some(data) => ResponseJson(ApiResponse::error_with_data(data)),
+                None => ResponseJson(ApiResponse::error(&format!("Failed to create PR: {e}"))),
+            })
+        }
+    }
+}
@@ -7,6 +7,7 @@ use axum::{
         Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    handler::Handler,
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
@@ -30,7 +31,14 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, auth::AuthenticatedUser, error::ApiError, middleware::load_task_middleware,
+    DeploymentImpl,
+    auth::AuthenticatedUser,
+    error::ApiError,
+    middleware::{
+        authorization::{ProjectAccess, ProjectPermission, require_project_permission},
+        load_task_middleware,
+    },
+    routes::events::ResumeQuery,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,8 +48,16 @@ pub struct TaskQuery {
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
     Query(query): Query<TaskQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
+    ProjectAccess::require(
+        &deployment.db().pool,
+        query.project_id,
+        &user,
+        ProjectPermission::View,
+    )
+    .await?;
     let tasks =
         Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
             .await?;
@@ -52,24 +68,34 @@ pub async fn get_tasks(
 pub async fn stream_tasks_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
+    Extension(user): Extension<AuthenticatedUser>,
     Query(query): Query<TaskQuery>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id).await {
+    Query(resume): Query<ResumeQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    ProjectAccess::require(
+        &deployment.db().pool,
+        query.project_id,
+        &user,
+        ProjectPermission::View,
+    )
+    .await?;
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id, resume.since).await {
             tracing::warn!("tasks WS closed: {}", e);
         }
-    })
+    }))
 }
 
 async fn handle_tasks_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
     project_id: Uuid,
+    since: Option<u64>,
 ) -> anyhow::Result<()> {
     // Get the raw stream and convert LogMsg to WebSocket messages
     let mut stream = deployment
         .events()
-        .stream_tasks_raw(project_id)
+        .stream_tasks_raw(project_id, since)
         .await?
         .map_ok(|msg| msg.to_ws_message_unchecked());
 
@@ -108,6 +134,13 @@ pub async fn create_task(
     Extension(user): Extension<AuthenticatedUser>, // ✅ 추가
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    ProjectAccess::require(
+        &deployment.db().pool,
+        payload.project_id,
+        &user,
+        ProjectPermission::EditTasks,
+    )
+    .await?;
     let id = Uuid::new_v4();
 
     tracing::debug!(
@@ -138,7 +171,7 @@ pub async fn create_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
     pub executor_profile_id: ExecutorProfileId,
@@ -150,6 +183,16 @@ pub async fn create_task_and_start(
     Extension(user): Extension<AuthenticatedUser>, // ✅ 추가
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
+    // Creates a task as well as starting it
+    for permission in [ProjectPermission::EditTasks, ProjectPermission::RunAttempts] {
+        ProjectAccess::require(
+            &deployment.db().pool,
+            payload.task.project_id,
+            &user,
+            permission,
+        )
+        .await?;
+    }
     let task_id = Uuid::new_v4();
     let task = Task::create(&deployment.db().pool, &payload.task, task_id, &user.user_id).await?; // ✅ user_id 추가
 
@@ -363,8 +406,16 @@ pub async fn delete_task(
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let edit_tasks =
+        || from_fn_with_state(ProjectPermission::EditTasks, require_project_permission);
+
     let task_id_router = Router::new()
-        .route("/", get(get_task).put(update_task).delete(delete_task))
+        .route(
+            "/",
+            get(get_task)
+                .put(update_task.layer(edit_tasks()))
+                .delete(delete_task.layer(edit_tasks())),
+        )
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
@@ -0,0 +1,170 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    audit_event::AuditAction,
+    project::Project,
+    webhook::{CreateWebhook, UpdateWebhook, Webhook},
+    webhook_delivery::WebhookDelivery,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::webhooks::WebhookError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    audit::AuditContext,
+    error::ApiError,
+    middleware::authorization::{ProjectPermission, require_project_permission},
+};
+
+const DEFAULT_DELIVERIES: i64 = 50;
+const MAX_DELIVERIES: i64 = 200;
+
+/// A newly created webhook. `secret` is only ever returned here.
+#[derive(Debug, Serialize, TS)]
+pub struct CreatedWebhook {
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    pub limit: Option<i64>,
+}
+
+async fn find_webhook(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    webhook_id: Uuid,
+) -> Result<Webhook, ApiError> {
+    Webhook::find_by_id(&deployment.db().pool, webhook_id)
+        .await?
+        .filter(|webhook| webhook.project_id == project.id)
+        .ok_or_else(|| WebhookError::NotFound.into())
+}
+
+pub async fn get_webhooks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    let webhooks = Webhook::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn create_webhook(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    audit: AuditContext,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<CreatedWebhook>>, ApiError> {
+    let (webhook, secret) = deployment.webhooks().create(project.id, &payload).await?;
+    audit
+        .event(AuditAction::ProjectWebhookCreated)
+        .target(&webhook.url)
+        .after(&webhook)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(CreatedWebhook {
+        webhook,
+        secret,
+    })))
+}
+
+pub async fn update_webhook(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, webhook_id)): Path<(Uuid, Uuid)>,
+    audit: AuditContext,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    let previous = find_webhook(&deployment, &project, webhook_id).await?;
+    let webhook = deployment.webhooks().update(&previous, &payload).await?;
+    audit
+        .event(AuditAction::ProjectWebhookUpdated)
+        .target(&webhook.url)
+        .before(&previous)
+        .after(&webhook)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn delete_webhook(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, webhook_id)): Path<(Uuid, Uuid)>,
+    audit: AuditContext,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let webhook = find_webhook(&deployment, &project, webhook_id).await?;
+    deployment.webhooks().delete(&webhook).await?;
+    audit
+        .event(AuditAction::ProjectWebhookDeleted)
+        .target(&webhook.url)
+        .before(&webhook)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// The webhook's most recent deliveries, newest first
+pub async fn get_webhook_deliveries(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, webhook_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let webhook = find_webhook(&deployment, &project, webhook_id).await?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERIES)
+        .clamp(1, MAX_DELIVERIES);
+    let deliveries =
+        WebhookDelivery::find_by_webhook_id(&deployment.db().pool, webhook.id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+/// Queue a delivery's payload to be sent again
+pub async fn redeliver_webhook_delivery(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, webhook_id, delivery_id)): Path<(Uuid, Uuid, Uuid)>,
+    audit: AuditContext,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let webhook = find_webhook(&deployment, &project, webhook_id).await?;
+    let delivery = WebhookDelivery::find_by_id(&deployment.db().pool, delivery_id)
+        .await?
+        .filter(|delivery| delivery.webhook_id == webhook.id)
+        .ok_or(WebhookError::DeliveryNotFound)?;
+    let redelivery = deployment.webhooks().redeliver(&delivery).await?;
+    audit
+        .event(AuditAction::ProjectWebhookRedelivered)
+        .target(&webhook.url)
+        .after(&redelivery)
+        .record(&deployment)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(redelivery)))
+}
+
+/// Webhooks of a project, nested under `/projects/{id}/webhooks`
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_webhooks).post(create_webhook))
+        .route("/{webhook_id}", put(update_webhook).delete(delete_webhook))
+        .route("/{webhook_id}/deliveries", get(get_webhook_deliveries))
+        .route(
+            "/{webhook_id}/deliveries/{delivery_id}/redeliver",
+            post(redeliver_webhook_delivery),
+        )
+        .route_layer(from_fn_with_state(
+            ProjectPermission::Manage,
+            require_project_permission,
+        ))
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use server::batch::use_batch_data_dir;
+use tempfile::TempDir;
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+// One test, since it changes the process environment
+#[test]
+fn batch_runs_keep_the_local_agent_setup() {
+    let user_dir = TempDir::new().unwrap();
+    std::fs::write(user_dir.path().join("profiles.json"), r#"{"executors":{}}"#).unwrap();
+    // SAFETY: no other thread is running yet
+    unsafe {
+        std::env::set_var("ANYON_ASSET_DIR", user_dir.path());
+        std::env::remove_var("ANYON_TOOL_CACHE_DIR");
+    }
+
+    let data_dir = TempDir::new().unwrap();
+    use_batch_data_dir(data_dir.path(), None).unwrap();
+    assert_eq!(
+        read(&data_dir.path().join("profiles.json")),
+        r#"{"executors":{}}"#
+    );
+    assert_eq!(utils::assets::asset_dir(), data_dir.path());
+    assert_eq!(
+        std::env::var_os("ANYON_TOOL_CACHE_DIR").unwrap(),
+        user_dir.path().join("tools")
+    );
+
+    // --profiles wins over the local install's profiles
+    let custom = user_dir.path().join("ci-profiles.json");
+    std::fs::write(&custom, r#"{"executors":{"CODEX":{}}}"#).unwrap();
+    let data_dir = TempDir::new().unwrap();
+    use_batch_data_dir(data_dir.path(), Some(&custom)).unwrap();
+    assert_eq!(
+        read(&data_dir.path().join("profiles.json")),
+        r#"{"executors":{"CODEX":{}}}"#
+    );
+}
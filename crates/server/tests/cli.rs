@@ -0,0 +1,32 @@
+mod common;
+
+use clap::Parser;
+use common::TestServer;
+use db::models::api_token::TokenScope;
+use server::cli::{self, Cli};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn approve_with_an_api_token_reaches_the_approval() {
+    let server = TestServer::start().await;
+    let token = server
+        .api_token("github_1", vec![TokenScope::Read, TokenScope::AttemptsRun])
+        .await;
+    let process = Uuid::new_v4().to_string();
+
+    let cli = Cli::parse_from([
+        "anyon",
+        "--url",
+        &server.url,
+        "--token",
+        &token,
+        "approve",
+        "missing-approval",
+        "--process",
+        &process,
+    ]);
+    let err = cli::run(cli).await.unwrap_err().to_string();
+
+    // Past the token check, the unknown approval is reported rather than a 403
+    assert!(err.contains("404"), "{err}");
+}
@@ -0,0 +1,249 @@
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use db::models::{
+    api_token::{ApiToken, CreateApiToken, TokenScope},
+    execution_process::{
+        CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    execution_process_logs::ExecutionProcessLogs,
+    executor_session::{CreateExecutorSession, ExecutorSession},
+    project::{CreateProject, Project},
+    task::{CreateTask, Task},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use deployment::Deployment;
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+    },
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntry, NormalizedEntryType, utils::ConversationPatch},
+    profile::ExecutorProfileId,
+};
+use server::{DeploymentImpl, batch::use_data_dir, routes};
+use services::services::{api_tokens, container::ContainerService};
+use tempfile::TempDir;
+use tokio::sync::{Mutex, MutexGuard};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// The deployment reads its data directory from the environment, so tests take turns
+static ENV_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A deployment in a temporary data directory, with the API served on a local port
+pub struct TestServer {
+    pub deployment: DeploymentImpl,
+    pub url: String,
+    pub data_dir: TempDir,
+    _env: MutexGuard<'static, ()>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        let env = ENV_LOCK.lock().await;
+        let data_dir = TempDir::new().unwrap();
+        use_data_dir(data_dir.path());
+        let deployment = DeploymentImpl::new().await.unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = routes::router(deployment.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Self {
+            deployment,
+            url: format!("http://{addr}"),
+            data_dir,
+            _env: env,
+        }
+    }
+
+    /// Personal API token for `user_id` with `scopes`
+    pub async fn api_token(&self, user_id: &str, scopes: Vec<TokenScope>) -> String {
+        let generated = api_tokens::generate();
+        ApiToken::create(
+            &self.deployment.db().pool,
+            user_id,
+            0,
+            user_id,
+            &CreateApiToken {
+                name: "test".to_string(),
+                scopes,
+                expires_in_days: None,
+            },
+            &generated.prefix,
+            &generated.hash,
+            None,
+        )
+        .await
+        .unwrap();
+        generated.token
+    }
+
+    /// Project owned by `user_id` on a fresh git repository with one commit
+    pub async fn project(&self, user_id: &str) -> Project {
+        let repo = self
+            .data_dir
+            .path()
+            .join(format!("repo-{}", Uuid::new_v4()));
+        init_repo(&repo);
+        Project::create(
+            &self.deployment.db().pool,
+            &CreateProject {
+                name: "Test project".to_string(),
+                git_repo_path: repo.to_string_lossy().to_string(),
+                use_existing_repo: true,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+                init_submodules: None,
+                sparse_checkout_patterns: None,
+                sparse_auto_expand: None,
+            },
+            Uuid::new_v4(),
+            user_id,
+        )
+        .await
+        .unwrap()
+    }
+
+    pub async fn task(&self, project: &Project, user_id: &str, title: &str) -> Task {
+        Task::create(
+            &self.deployment.db().pool,
+            &CreateTask::from_title_description(project.id, title.to_string(), None),
+            Uuid::new_v4(),
+            user_id,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Attempt on the project's current branch, with its worktree created
+    pub async fn attempt(&self, project: &Project, user_id: &str) -> TaskAttempt {
+        let pool = &self.deployment.db().pool;
+        let task = self.task(project, user_id, "Attempt").await;
+        let base_branch = git2::Repository::open(&project.git_repo_path)
+            .unwrap()
+            .head()
+            .unwrap()
+            .shorthand()
+            .unwrap()
+            .to_string();
+        let id = Uuid::new_v4();
+        let attempt = TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch,
+                branch: format!("anyon/{id}"),
+            },
+            id,
+            task.id,
+            user_id,
+        )
+        .await
+        .unwrap();
+        self.deployment.container().create(&attempt).await.unwrap();
+        TaskAttempt::find_by_id(pool, id).await.unwrap().unwrap()
+    }
+
+    /// Coding agent run of the attempt on `executor`, with an executor session, recorded
+    /// without starting the agent
+    pub async fn coding_agent_run(
+        &self,
+        attempt: &TaskAttempt,
+        executor: BaseCodingAgent,
+    ) -> ExecutionProcess {
+        let pool = &self.deployment.db().pool;
+        let process = ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                task_attempt_id: attempt.id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                        prompt: "Do the task".to_string(),
+                        executor_profile_id: ExecutorProfileId::new(executor),
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+            },
+            Uuid::new_v4(),
+            None,
+        )
+        .await
+        .unwrap();
+        ExecutorSession::create(
+            pool,
+            &CreateExecutorSession {
+                task_attempt_id: attempt.id,
+                execution_process_id: process.id,
+                prompt: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        process
+    }
+
+    /// Store `messages` as the assistant's conversation in the process's logs and mark it
+    /// completed, as if the agent had run and exited
+    pub async fn finish_run(&self, process: &ExecutionProcess, messages: &[&str]) {
+        let pool = &self.deployment.db().pool;
+        let logs: Vec<LogMsg> = messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| {
+                LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                    index,
+                    NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::AssistantMessage,
+                        content: message.to_string(),
+                        metadata: None,
+                    },
+                ))
+            })
+            .collect();
+        ExecutionProcessLogs::append_log_line(
+            pool,
+            process.id,
+            &ExecutionProcessLogs::serialize_logs(&logs).unwrap(),
+        )
+        .await
+        .unwrap();
+        ExecutionProcess::update_completion(
+            pool,
+            process.id,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await
+        .unwrap();
+    }
+}
+
+fn init_repo(path: &Path) {
+    let repo = git2::Repository::init(path).unwrap();
+    std::fs::write(path.join("README.md"), "# Test\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("README.md")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Initial commit",
+        &tree,
+        &[],
+    )
+    .unwrap();
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::TestServer;
+use db::models::{api_token::TokenScope, execution_process_logs::ExecutionProcessLogs};
+use deployment::Deployment;
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntry, NormalizedEntryType, utils::ConversationPatch},
+};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use utils::log_msg::LogMsg;
+
+async fn conversation(server: &TestServer, token: &str, id: uuid::Uuid) -> (StatusCode, Value) {
+    let response = reqwest::Client::new()
+        .get(format!(
+            "{}/api/execution-processes/{id}/conversation",
+            server.url
+        ))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, response.json().await.unwrap_or_default())
+}
+
+#[tokio::test]
+async fn conversation_returns_the_final_form_of_stored_entries() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let run = server
+        .coding_agent_run(&attempt, BaseCodingAgent::ClaudeCode)
+        .await;
+    server.finish_run(&run, &["Drafting", "Done"]).await;
+    // Streamed entries are updated in place as the agent writes them
+    let replaced = LogMsg::JsonPatch(ConversationPatch::replace(
+        0,
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: "Drafted".to_string(),
+            metadata: None,
+        },
+    ));
+    ExecutionProcessLogs::append_log_line(
+        &server.deployment.db().pool,
+        run.id,
+        &ExecutionProcessLogs::serialize_logs(&[replaced]).unwrap(),
+    )
+    .await
+    .unwrap();
+    let token = server.api_token("github_1", vec![TokenScope::Read]).await;
+
+    let (status, body) = conversation(&server, &token, run.id).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let contents: Vec<_> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["content"].clone())
+        .collect();
+    assert_eq!(contents, [json!("Drafted"), json!("Done")]);
+}
+
+#[tokio::test]
+async fn conversation_is_limited_to_project_members() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let run = server
+        .coding_agent_run(&attempt, BaseCodingAgent::ClaudeCode)
+        .await;
+    server.finish_run(&run, &["Done"]).await;
+    let outsider = server.api_token("github_2", vec![TokenScope::Read]).await;
+
+    let (status, _) = conversation(&server, &outsider, run.id).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
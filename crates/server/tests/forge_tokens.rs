@@ -0,0 +1,101 @@
+mod common;
+
+use common::TestServer;
+use db::models::api_token::TokenScope;
+use deployment::Deployment;
+use reqwest::StatusCode;
+use serde_json::json;
+use services::services::forge::ForgeProvider;
+
+fn store_request(server: &TestServer) -> reqwest::RequestBuilder {
+    reqwest::Client::new()
+        .put(format!("{}/api/auth/forge/token", server.url))
+        .json(&json!({
+            "provider": "gitlab",
+            "host": "http://127.0.0.1:9",
+            "token": "glpat-test",
+        }))
+}
+
+#[tokio::test]
+async fn forge_tokens_need_a_server_admin() {
+    let server = TestServer::start().await;
+
+    let response = store_request(&server).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = reqwest::Client::new()
+        .delete(format!(
+            "{}/api/auth/forge/token?provider=gitlab&host=gitlab.example.com",
+            server.url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // API tokens never act as a server admin
+    let token = server
+        .api_token(
+            "github_1",
+            vec![
+                TokenScope::Read,
+                TokenScope::TasksWrite,
+                TokenScope::AttemptsRun,
+                TokenScope::Merge,
+            ],
+        )
+        .await;
+    let response = store_request(&server)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn ssh_remotes_use_the_stored_instance_url() {
+    let server = TestServer::start().await;
+    let resolver = server.deployment.forge_resolver();
+    resolver
+        .store_host_token(
+            ForgeProvider::Gitea,
+            "http://code.internal:3000/",
+            "code.internal",
+            "gitea-token",
+        )
+        .await
+        .unwrap();
+
+    let repo_info = resolver
+        .detect("ssh://git@code.internal:2222/owner/repo.git")
+        .await
+        .unwrap();
+    assert_eq!(repo_info.provider, ForgeProvider::Gitea);
+    assert_eq!(repo_info.web_base_url(), "http://code.internal:3000");
+    assert_eq!(
+        repo_info.clone_url(),
+        "http://code.internal:3000/owner/repo.git"
+    );
+    assert_eq!(
+        resolver.token(&repo_info).await.unwrap().as_deref(),
+        Some("gitea-token")
+    );
+
+    // HTTP remotes already carry the scheme and port
+    let repo_info = resolver
+        .detect("https://code.internal:8443/owner/repo")
+        .await
+        .unwrap();
+    assert_eq!(
+        repo_info.clone_url(),
+        "https://code.internal:8443/owner/repo.git"
+    );
+
+    resolver
+        .delete_host_token(ForgeProvider::Gitea, "code.internal")
+        .await
+        .unwrap();
+    let repo_info = resolver.detect("git@code.internal:owner/repo.git").await;
+    assert!(repo_info.is_err(), "{repo_info:?}");
+}
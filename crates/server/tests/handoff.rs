@@ -0,0 +1,131 @@
+mod common;
+
+use common::TestServer;
+use db::models::{
+    api_token::TokenScope,
+    execution_process::ExecutionProcess,
+    executor_session::{ExecutorSession, HandoffKind},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use executors::executors::BaseCodingAgent;
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+async fn handoff(server: &TestServer, token: &str, attempt: &TaskAttempt) -> (StatusCode, Value) {
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/api/task-attempts/{}/handoff",
+            server.url, attempt.id
+        ))
+        .bearer_auth(token)
+        .json(&json!({ "executor_profile_id": { "executor": "CLAUDE_CODE" } }))
+        .send()
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, response.json().await.unwrap_or_default())
+}
+
+#[tokio::test]
+async fn record_handoff_stores_the_kind_and_hides_earlier_sessions() {
+    let server = TestServer::start().await;
+    let pool = &server.deployment.db().pool;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+
+    let first = server
+        .coding_agent_run(&attempt, BaseCodingAgent::ClaudeCode)
+        .await;
+    ExecutorSession::update_session_id(pool, first.id, "claude-session")
+        .await
+        .unwrap();
+    let second = server
+        .coding_agent_run(&attempt, BaseCodingAgent::Codex)
+        .await;
+    ExecutorSession::record_handoff(
+        pool,
+        second.id,
+        first.id,
+        "CLAUDE_CODE",
+        HandoffKind::Manual,
+    )
+    .await
+    .unwrap();
+
+    let session = ExecutorSession::find_by_execution_process_id(pool, second.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.handoff_kind, Some(HandoffKind::Manual));
+    assert_eq!(
+        session.handoff_from_executor.as_deref(),
+        Some("CLAUDE_CODE")
+    );
+    assert_eq!(session.handoff_from_execution_process_id, Some(first.id));
+    let first_session = ExecutorSession::find_by_execution_process_id(pool, first.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first_session.handoff_kind, None);
+
+    // The session of the previous executor cannot be resumed by the new one
+    let latest = ExecutionProcess::find_latest_session_id_by_task_attempt(pool, attempt.id)
+        .await
+        .unwrap();
+    assert_eq!(latest, None);
+    ExecutorSession::update_session_id(pool, second.id, "codex-session")
+        .await
+        .unwrap();
+    let latest = ExecutionProcess::find_latest_session_id_by_task_attempt(pool, attempt.id)
+        .await
+        .unwrap();
+    assert_eq!(latest.as_deref(), Some("codex-session"));
+}
+
+#[tokio::test]
+async fn handoff_requires_a_different_agent() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let token = server
+        .api_token("github_1", vec![TokenScope::Read, TokenScope::AttemptsRun])
+        .await;
+
+    let (status, body) = handoff(&server, &token, &attempt).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(
+        body["message"]
+            .as_str()
+            .unwrap()
+            .contains("no coding agent execution"),
+        "{body}"
+    );
+
+    server
+        .coding_agent_run(&attempt, BaseCodingAgent::ClaudeCode)
+        .await;
+    let (status, body) = handoff(&server, &token, &attempt).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(
+        body["message"]
+            .as_str()
+            .unwrap()
+            .contains("already runs on"),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn handoff_needs_the_attempts_run_scope() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    server
+        .coding_agent_run(&attempt, BaseCodingAgent::ClaudeCode)
+        .await;
+    let reader = server.api_token("github_1", vec![TokenScope::Read]).await;
+
+    let (status, _) = handoff(&server, &reader, &attempt).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
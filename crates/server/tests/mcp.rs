@@ -0,0 +1,319 @@
+mod common;
+
+use common::TestServer;
+use db::models::{api_token::TokenScope, task::Task};
+use deployment::Deployment;
+use executors::executors::BaseCodingAgent;
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+/// Minimal streamable HTTP client for the MCP endpoint
+struct McpClient {
+    client: reqwest::Client,
+    url: String,
+    token: String,
+    session_id: Option<String>,
+    next_id: u64,
+}
+
+impl McpClient {
+    async fn connect(server: &TestServer, token: &str) -> Self {
+        let mut mcp = Self {
+            client: reqwest::Client::new(),
+            url: format!("{}/api/mcp", server.url),
+            token: token.to_string(),
+            session_id: None,
+            next_id: 1,
+        };
+        let initialized = mcp
+            .rpc(
+                "initialize",
+                json!({
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "0" }
+                }),
+            )
+            .await;
+        assert!(
+            initialized["result"]["serverInfo"].is_object(),
+            "{initialized}"
+        );
+        mcp.post(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await;
+        mcp
+    }
+
+    async fn post(&mut self, body: Value) -> reqwest::Response {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .bearer_auth(&self.token)
+            .header("accept", "application/json, text/event-stream")
+            .json(&body);
+        if let Some(session_id) = &self.session_id {
+            request = request.header("mcp-session-id", session_id);
+        }
+        let response = request.send().await.unwrap();
+        if let Some(session_id) = response.headers().get("mcp-session-id") {
+            self.session_id = Some(session_id.to_str().unwrap().to_string());
+        }
+        response
+    }
+
+    /// Send a request and return the JSON-RPC response carried in the SSE reply
+    async fn rpc(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        let response = self
+            .post(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await;
+        assert!(response.status().is_success(), "{}", response.status());
+        let body = response.text().await.unwrap();
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|message| message["id"] == json!(id))
+            .unwrap_or_else(|| panic!("No response to {method} in {body}"))
+    }
+
+    /// Call a tool, returning whether it failed and its text content
+    async fn call_tool(&mut self, name: &str, arguments: Value) -> (bool, String) {
+        let response = self
+            .rpc(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await;
+        let result = &response["result"];
+        let text = result["content"][0]["text"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        (result["isError"].as_bool().unwrap_or(false), text)
+    }
+}
+
+#[tokio::test]
+async fn tools_run_with_the_api_tokens_scopes() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let writer = server
+        .api_token("github_1", vec![TokenScope::Read, TokenScope::TasksWrite])
+        .await;
+    let reader = server.api_token("github_1", vec![TokenScope::Read]).await;
+
+    let mut mcp = McpClient::connect(&server, &writer).await;
+    let (failed, text) = mcp
+        .call_tool(
+            "create_task",
+            json!({ "project_id": project.id, "title": "From MCP" }),
+        )
+        .await;
+    assert!(!failed, "{text}");
+
+    // The in-process call to POST /api/tasks rejects a token without tasks:write
+    let mut mcp = McpClient::connect(&server, &reader).await;
+    let (failed, text) = mcp
+        .call_tool(
+            "create_task",
+            json!({ "project_id": project.id, "title": "Not allowed" }),
+        )
+        .await;
+    assert!(failed, "{text}");
+
+    let tasks =
+        Task::find_by_project_id_with_attempt_status(&server.deployment.db().pool, project.id)
+            .await
+            .unwrap();
+    let titles: Vec<_> = tasks.iter().map(|task| task.title.as_str()).collect();
+    assert_eq!(titles, ["From MCP"]);
+}
+
+#[tokio::test]
+async fn requests_without_credentials_are_rejected() {
+    let server = TestServer::start().await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/mcp", server.url))
+        .header("accept", "application/json, text/event-stream")
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+const ATTEMPT_TOOLS: [&str; 8] = [
+    "get_attempt_status",
+    "get_attempt_conversation",
+    "get_attempt_diff",
+    "follow_up",
+    "stop_attempt",
+    "merge_attempt",
+    "create_pr",
+    "wait_for_attempt",
+];
+
+fn all_scopes() -> Vec<TokenScope> {
+    vec![
+        TokenScope::Read,
+        TokenScope::TasksWrite,
+        TokenScope::AttemptsRun,
+        TokenScope::Merge,
+    ]
+}
+
+/// Parse the JSON a tool returned, failing the test if the tool reported an error
+fn tool_json((failed, text): (bool, String)) -> Value {
+    assert!(!failed, "{text}");
+    serde_json::from_str(&text).unwrap()
+}
+
+#[tokio::test]
+async fn attempt_tools_are_listed() {
+    let server = TestServer::start().await;
+    let token = server.api_token("github_1", all_scopes()).await;
+
+    let mut mcp = McpClient::connect(&server, &token).await;
+    let listed = mcp.rpc("tools/list", json!({})).await;
+    let names: Vec<_> = listed["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    for tool in ATTEMPT_TOOLS {
+        assert!(names.contains(&tool), "{tool} missing from {names:?}");
+    }
+}
+
+#[tokio::test]
+async fn attempt_tools_report_a_finished_run() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let run = server
+        .coding_agent_run(&attempt, BaseCodingAgent::ClaudeCode)
+        .await;
+    server.finish_run(&run, &["Working on it", "Done"]).await;
+    let worktree = std::path::PathBuf::from(attempt.container_ref.as_deref().unwrap());
+    std::fs::write(worktree.join("notes.txt"), "from the agent\n").unwrap();
+    let token = server.api_token("github_1", all_scopes()).await;
+    let mut mcp = McpClient::connect(&server, &token).await;
+    let args = json!({ "attempt_id": attempt.id });
+
+    let status = tool_json(mcp.call_tool("get_attempt_status", args.clone()).await);
+    assert_eq!(status["is_running"], json!(false));
+    assert_eq!(status["branch"], json!(attempt.branch));
+    assert_eq!(status["latest_agent_run"]["id"], json!(run.id.to_string()));
+    assert_eq!(status["latest_agent_run"]["status"], json!("completed"));
+
+    let conversation = tool_json(
+        mcp.call_tool(
+            "get_attempt_conversation",
+            json!({ "attempt_id": attempt.id, "last": 1 }),
+        )
+        .await,
+    );
+    assert_eq!(
+        conversation["execution_process_id"],
+        json!(run.id.to_string())
+    );
+    assert_eq!(conversation["total_entries"], json!(2));
+    assert_eq!(
+        conversation["entries"],
+        json!([{ "kind": "assistant", "content": "Done" }])
+    );
+
+    let diff = tool_json(mcp.call_tool("get_attempt_diff", args.clone()).await);
+    let diff = diff["diff"].as_str().unwrap();
+    assert!(diff.contains("notes.txt"), "{diff}");
+    assert!(diff.contains("+from the agent"), "{diff}");
+
+    let waited = tool_json(
+        mcp.call_tool(
+            "wait_for_attempt",
+            json!({ "attempt_id": attempt.id, "timeout_seconds": 10 }),
+        )
+        .await,
+    );
+    assert_eq!(waited["timed_out"], json!(false));
+    assert_eq!(waited["status"]["is_running"], json!(false));
+
+    let stopped = tool_json(mcp.call_tool("stop_attempt", args).await);
+    assert_eq!(stopped["success"], json!(true));
+}
+
+#[tokio::test]
+async fn merge_attempt_merges_the_committed_changes() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let worktree = std::path::PathBuf::from(attempt.container_ref.as_deref().unwrap());
+    std::fs::write(worktree.join("notes.txt"), "merged\n").unwrap();
+    server
+        .deployment
+        .git()
+        .commit(&worktree, "Add notes")
+        .unwrap();
+    let token = server.api_token("github_1", all_scopes()).await;
+
+    let mut mcp = McpClient::connect(&server, &token).await;
+    let merged = tool_json(
+        mcp.call_tool("merge_attempt", json!({ "attempt_id": attempt.id }))
+            .await,
+    );
+    assert_eq!(merged["success"], json!(true));
+
+    let repo = git2::Repository::open(&project.git_repo_path).unwrap();
+    let head = repo.head().unwrap().peel_to_tree().unwrap();
+    assert!(head.get_name("notes.txt").is_some());
+}
+
+#[tokio::test]
+async fn attempt_tools_surface_api_errors() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let token = server.api_token("github_1", all_scopes()).await;
+    let reader = server.api_token("github_1", vec![TokenScope::Read]).await;
+
+    let mut mcp = McpClient::connect(&server, &token).await;
+    let (failed, text) = mcp
+        .call_tool(
+            "follow_up",
+            json!({ "attempt_id": attempt.id, "prompt": "  " }),
+        )
+        .await;
+    assert!(failed, "{text}");
+    assert!(text.contains("Prompt must not be empty"), "{text}");
+
+    // The project has no GitHub remote to open a pull request against
+    let (failed, text) = mcp
+        .call_tool(
+            "create_pr",
+            json!({ "attempt_id": attempt.id, "title": "From MCP" }),
+        )
+        .await;
+    assert!(failed, "{text}");
+
+    // Running and merging need more than the read scope
+    let mut mcp = McpClient::connect(&server, &reader).await;
+    let (failed, text) = mcp
+        .call_tool(
+            "follow_up",
+            json!({ "attempt_id": attempt.id, "prompt": "Keep going" }),
+        )
+        .await;
+    assert!(failed, "{text}");
+    let (failed, text) = mcp
+        .call_tool("merge_attempt", json!({ "attempt_id": attempt.id }))
+        .await;
+    assert!(failed, "{text}");
+    let (failed, text) = mcp
+        .call_tool("stop_attempt", json!({ "attempt_id": attempt.id }))
+        .await;
+    assert!(failed, "{text}");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::TestServer;
+use db::models::{api_token::TokenScope, project_mcp_server::ProjectMcpServer};
+use deployment::Deployment;
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn task_images_and_containers_need_project_access() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    let task = server.task(&project, "github_1", "Private").await;
+    let attempt = server.attempt(&project, "github_1").await;
+    let outsider = server
+        .api_token("github_2", vec![TokenScope::Read, TokenScope::TasksWrite])
+        .await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/images/task/{}", server.url, task.id))
+        .bearer_auth(&outsider)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = client
+        .get(format!("{}/api/containers/info", server.url))
+        .query(&[("ref", attempt.container_ref.unwrap())])
+        .bearer_auth(&outsider)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let owner = server
+        .api_token("github_1", vec![TokenScope::Read, TokenScope::TasksWrite])
+        .await;
+    let response = client
+        .get(format!("{}/api/images/task/{}", server.url, task.id))
+        .bearer_auth(&owner)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn mcp_server_credentials_are_masked_without_manage() {
+    let server = TestServer::start().await;
+    let project = server.project("github_1").await;
+    ProjectMcpServer::replace_for_project(
+        &server.deployment.db().pool,
+        project.id,
+        &HashMap::from([(
+            "docs".to_string(),
+            json!({ "command": "docs-mcp", "env": { "API_KEY": "sk-live-123" } }),
+        )]),
+    )
+    .await
+    .unwrap();
+
+    // API tokens never grant Manage, so even the owner's token sees masked values
+    let token = server.api_token("github_1", vec![TokenScope::Read]).await;
+    let body: Value = reqwest::Client::new()
+        .get(format!(
+            "{}/api/projects/{}/mcp-servers",
+            server.url, project.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let config = &body["data"][0]["config"];
+    assert_eq!(config["command"], "docs-mcp");
+    assert_eq!(config["env"]["API_KEY"], "********");
+}
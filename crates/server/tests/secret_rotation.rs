@@ -0,0 +1,103 @@
+mod common;
+
+use common::TestServer;
+use db::models::secret::SecretRecord;
+use deployment::Deployment;
+use services::services::secret_store::{SecretDescriptor, SecretKeys, SecretStore};
+
+const USER: &str = "github_1";
+
+fn descriptor(name: &str) -> SecretDescriptor<'_> {
+    SecretDescriptor {
+        provider: "test",
+        name,
+    }
+}
+
+fn keys(current: i64, keys: &[(i64, &str)]) -> SecretKeys {
+    let keys = keys
+        .iter()
+        .map(|(version, key)| format!(r#""{version}": "{key}""#))
+        .collect::<Vec<_>>()
+        .join(", ");
+    SecretKeys::parse_keyfile(&format!(r#"{{"current": {current}, "keys": {{{keys}}}}}"#)).unwrap()
+}
+
+async fn records(server: &TestServer, names: &[&str]) -> Vec<SecretRecord> {
+    let mut records = Vec::new();
+    for name in names {
+        records.push(
+            SecretRecord::get(&server.deployment.db().pool, USER, "test", name)
+                .await
+                .unwrap()
+                .unwrap(),
+        );
+    }
+    records
+}
+
+#[tokio::test]
+async fn rotation_moves_secrets_to_the_current_key() {
+    let server = TestServer::start().await;
+    let db = server.deployment.db().clone();
+    let (old_key, new_key) = (SecretKeys::generate_key(), SecretKeys::generate_key());
+
+    let v1 = SecretStore::with_keys(db.clone(), &keys(1, &[(1, &old_key)])).unwrap();
+    v1.put_secret(USER, descriptor("a"), b"alpha")
+        .await
+        .unwrap();
+    v1.put_secret(USER, descriptor("b"), b"beta").await.unwrap();
+
+    let v2 = SecretStore::with_keys(db.clone(), &keys(2, &[(1, &old_key), (2, &new_key)])).unwrap();
+    let rotation = v2.rotate().await.unwrap();
+    assert_eq!(rotation.key_version, 2);
+    assert_eq!(rotation.rotated, 2);
+
+    // The old key can be dropped once everything is rotated
+    let retired = SecretStore::with_keys(db, &keys(2, &[(2, &new_key)])).unwrap();
+    for (name, value) in [("a", "alpha"), ("b", "beta")] {
+        assert_eq!(
+            retired
+                .get_secret_string(USER, descriptor(name))
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(value)
+        );
+    }
+    assert_eq!(v2.rotate().await.unwrap().rotated, 0);
+}
+
+#[tokio::test]
+async fn failed_rotation_leaves_every_secret_unchanged() {
+    let server = TestServer::start().await;
+    let db = server.deployment.db().clone();
+    let (old_key, new_key) = (SecretKeys::generate_key(), SecretKeys::generate_key());
+
+    let v1 = SecretStore::with_keys(db.clone(), &keys(1, &[(1, &old_key)])).unwrap();
+    v1.put_secret(USER, descriptor("a"), b"alpha")
+        .await
+        .unwrap();
+    v1.put_secret(USER, descriptor("b"), b"beta").await.unwrap();
+    // Written last, so the rotation fails after re-encrypting the others
+    SecretRecord::upsert(&db.pool, USER, "test", "corrupt", &[0u8; 40], 1)
+        .await
+        .unwrap();
+    let before = records(&server, &["a", "b", "corrupt"]).await;
+
+    let v2 = SecretStore::with_keys(db.clone(), &keys(2, &[(1, &old_key), (2, &new_key)])).unwrap();
+    assert!(v2.rotate().await.is_err());
+
+    let after = records(&server, &["a", "b", "corrupt"]).await;
+    for (before, after) in before.iter().zip(&after) {
+        assert_eq!(after.key_version, 1, "{}", after.name);
+        assert_eq!(after.secret_blob, before.secret_blob, "{}", after.name);
+    }
+    assert_eq!(
+        v1.get_secret_string(USER, descriptor("a"))
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("alpha")
+    );
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::TestServer;
+use db::models::{
+    project_repository::{CreateProjectRepository, ProjectRepository},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use deployment::Deployment;
+use executors::executors::BaseCodingAgent;
+use services::services::container::ContainerService;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn failed_repository_worktree_rolls_back_the_workspace() {
+    let server = TestServer::start().await;
+    let pool = &server.deployment.db().pool;
+    let project = server.project("github_1").await;
+    let other = server.project("github_1").await;
+    ProjectRepository::create(
+        pool,
+        project.id,
+        &CreateProjectRepository {
+            name: "other".to_string(),
+            git_repo_path: other.git_repo_path.to_string_lossy().to_string(),
+            default_target_branch: None,
+        },
+    )
+    .await
+    .unwrap();
+    ProjectRepository::create(
+        pool,
+        project.id,
+        &CreateProjectRepository {
+            name: "missing".to_string(),
+            git_repo_path: server
+                .data_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+            default_target_branch: Some("main".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    let task = server.task(&project, "github_1", "Workspace").await;
+    let id = Uuid::new_v4();
+    let attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: BaseCodingAgent::ClaudeCode,
+            base_branch: git2::Repository::open(&project.git_repo_path)
+                .unwrap()
+                .head()
+                .unwrap()
+                .shorthand()
+                .unwrap()
+                .to_string(),
+            branch: format!("anyon/{id}"),
+        },
+        id,
+        task.id,
+        "github_1",
+    )
+    .await
+    .unwrap();
+
+    server
+        .deployment
+        .container()
+        .create(&attempt)
+        .await
+        .unwrap_err();
+
+    // Neither the primary worktree nor the one created before the failure is left behind
+    for repo_path in [&project.git_repo_path, &other.git_repo_path] {
+        let repo = git2::Repository::open(repo_path).unwrap();
+        assert!(
+            repo.worktrees().unwrap().is_empty(),
+            "{}",
+            repo_path.display()
+        );
+    }
+    let attempt = TaskAttempt::find_by_id(pool, id).await.unwrap().unwrap();
+    assert_eq!(attempt.container_ref, None);
+}
@@ -0,0 +1,66 @@
+//! Personal API tokens for scripts and CI
+//!
+//! Tokens look like `anyon_pat_<43 url-safe base64 chars>`. Only their SHA-256 hash is stored,
+//! so a leaked database does not leak usable tokens.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use db::models::api_token::ApiToken;
+use rand::{RngCore, rngs::OsRng};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+pub const TOKEN_PREFIX: &str = "anyon_pat_";
+/// Characters of the token kept in plaintext so users can tell tokens apart
+const DISPLAY_PREFIX_LEN: usize = TOKEN_PREFIX.len() + 6;
+
+/// A freshly issued token; `token` is shown to the user once and then discarded
+pub struct GeneratedToken {
+    pub token: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+pub fn generate() -> GeneratedToken {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = format!("{TOKEN_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes));
+    GeneratedToken {
+        prefix: token[..DISPLAY_PREFIX_LEN].to_string(),
+        hash: hash(&token),
+        token,
+    }
+}
+
+pub fn hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Look up a presented token. Returns None for unknown or expired tokens and records use
+/// of valid ones.
+pub async fn authenticate(pool: &SqlitePool, token: &str) -> Result<Option<ApiToken>, sqlx::Error> {
+    if !token.starts_with(TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    let Some(api_token) = ApiToken::find_by_hash(pool, &hash(token)).await? else {
+        return Ok(None);
+    };
+    if api_token.is_expired() {
+        return Ok(None);
+    }
+    ApiToken::touch(pool, api_token.id).await?;
+    Ok(Some(api_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_tokens_match_their_hash_and_prefix() {
+        let generated = generate();
+        assert!(generated.token.starts_with(&generated.prefix));
+        assert!(generated.prefix.starts_with(TOKEN_PREFIX));
+        assert_eq!(generated.hash, hash(&generated.token));
+        assert_ne!(generated.token, generate().token);
+    }
+}
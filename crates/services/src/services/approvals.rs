@@ -17,7 +17,7 @@ use executors::{
 use futures::future::{BoxFuture, FutureExt, Shared};
 use sqlx::{Error as SqlxError, SqlitePool};
 use thiserror::Error;
-use tokio::sync::{RwLock, oneshot};
+use tokio::sync::{RwLock, broadcast, oneshot};
 use utils::{
     approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
     log_msg::LogMsg,
@@ -47,6 +47,7 @@ pub struct Approvals {
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    requests: broadcast::Sender<ApprovalRequest>,
 }
 
 #[derive(Debug, Error)]
@@ -67,13 +68,27 @@ pub enum ApprovalError {
 
 impl Approvals {
     pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+        let (requests, _) = broadcast::channel(64);
         Self {
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
+            requests,
         }
     }
 
+    /// Approval requests as they are created
+    pub fn subscribe(&self) -> broadcast::Receiver<ApprovalRequest> {
+        self.requests.subscribe()
+    }
+
+    /// Execution process a pending approval belongs to
+    pub fn pending_execution_process(&self, id: &str) -> Option<Uuid> {
+        self.pending
+            .get(id)
+            .map(|pending| pending.execution_process_id)
+    }
+
     pub async fn create_with_waiter(
         &self,
         request: ApprovalRequest,
@@ -115,6 +130,7 @@ impl Approvals {
                     request.tool_name,
                     idx
                 );
+                let _ = self.requests.send(request.clone());
             } else {
                 tracing::warn!(
                     "No matching tool use entry found for approval request: tool='{}', execution_process_id={}",
@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Error as AnyhowError;
 use axum::http::{HeaderName, header::ACCEPT};
@@ -11,11 +15,21 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::RwLock;
 use ts_rs::TS;
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct AuthService {
     pub client_id: String,
-    pub device_codes: Arc<RwLock<Option<DeviceCodes>>>,
+    /// Pending device flows by the `flow_id` returned from [`AuthService::device_start`]
+    device_flows: Arc<RwLock<HashMap<String, PendingDeviceFlow>>>,
+}
+
+/// A device flow waiting for the user to authorize it on GitHub
+struct PendingDeviceFlow {
+    codes: DeviceCodes,
+    /// User of the session that started the flow, or None for the server's own login
+    owner: Option<String>,
+    expires_at: Instant,
 }
 
 #[derive(Debug, Error)]
@@ -34,6 +48,8 @@ pub enum AuthError {
 
 #[derive(Serialize, Deserialize, TS)]
 pub struct DeviceFlowStartResponse {
+    /// Passed back when polling; only the starter of the flow can complete it
+    pub flow_id: String,
     pub user_code: String,
     pub verification_uri: String,
     pub expires_in: u32,
@@ -71,11 +87,15 @@ impl AuthService {
 
         AuthService {
             client_id: client_id_str,
-            device_codes: Arc::new(RwLock::new(None)), // Initially no device codes
+            device_flows: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    pub async fn device_start(&self) -> Result<DeviceFlowStartResponse, AuthError> {
+    /// Start a device flow for `owner`, the signed-in user, or None for the server's own login
+    pub async fn device_start(
+        &self,
+        owner: Option<String>,
+    ) -> Result<DeviceFlowStartResponse, AuthError> {
         let client = OctocrabBuilder::new()
             .base_uri("https://github.com")?
             .add_header(ACCEPT, "application/json".to_string())
@@ -86,11 +106,22 @@ impl AuthService {
                 ["user:email", "repo"],
             )
             .await?;
-        self.device_codes
-            .write()
-            .await
-            .replace(device_codes.clone()); // Store the device codes for later polling
+        let flow_id = Uuid::new_v4().simple().to_string();
+        {
+            let mut flows = self.device_flows.write().await;
+            let now = Instant::now();
+            flows.retain(|_, flow| flow.expires_at > now);
+            flows.insert(
+                flow_id.clone(),
+                PendingDeviceFlow {
+                    codes: device_codes.clone(),
+                    owner,
+                    expires_at: now + Duration::from_secs(device_codes.expires_in),
+                },
+            );
+        }
         Ok(DeviceFlowStartResponse {
+            flow_id,
             user_code: device_codes.user_code,
             verification_uri: device_codes.verification_uri,
             expires_in: device_codes.expires_in as u32,
@@ -98,12 +129,19 @@ impl AuthService {
         })
     }
 
-    pub async fn device_poll(&self) -> Result<UserInfo, AuthError> {
+    /// Poll the flow `flow_id`, which only the `owner` that started it may complete
+    pub async fn device_poll(
+        &self,
+        flow_id: &str,
+        owner: Option<&str>,
+    ) -> Result<UserInfo, AuthError> {
         let device_codes = {
-            let guard = self.device_codes.read().await;
-            guard
-                .as_ref()
+            let flows = self.device_flows.read().await;
+            flows
+                .get(flow_id)
+                .filter(|flow| flow.owner.as_deref() == owner && flow.expires_at > Instant::now())
                 .ok_or(AuthError::DeviceFlowNotStarted)?
+                .codes
                 .clone()
         };
         let client = OctocrabBuilder::new()
@@ -112,11 +150,19 @@ impl AuthService {
             .build()?;
         let poll_response = device_codes
             .poll_once(&client, &SecretString::from(self.client_id.clone()))
-            .await?;
-        let access_token = poll_response.either(
-            |OAuth { access_token, .. }| Ok(access_token),
-            |c| Err(AuthError::Pending(c)),
-        )?;
+            .await;
+        // Anything but a pending authorization ends the flow
+        let access_token = match poll_response {
+            Ok(response) => response.either(
+                |OAuth { access_token, .. }| Ok(access_token),
+                |c| Err(AuthError::Pending(c)),
+            ),
+            Err(e) => Err(e.into()),
+        };
+        if !matches!(access_token, Err(AuthError::Pending(_))) {
+            self.device_flows.write().await.remove(flow_id);
+        }
+        let access_token = access_token?;
         let client = OctocrabBuilder::new()
             .add_header(
                 HeaderName::try_from("User-Agent").unwrap(),
@@ -137,3 +183,53 @@ impl AuthService {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn service_with_flow(owner: Option<&str>) -> AuthService {
+        let service = AuthService {
+            client_id: "client".to_string(),
+            device_flows: Arc::new(RwLock::new(HashMap::new())),
+        };
+        let codes: DeviceCodes = serde_json::from_value(serde_json::json!({
+            "device_code": "device",
+            "user_code": "USER-CODE",
+            "verification_uri": "https://github.com/login/device",
+            "expires_in": 900,
+            "interval": 5,
+        }))
+        .unwrap();
+        service.device_flows.write().await.insert(
+            "flow".to_string(),
+            PendingDeviceFlow {
+                codes,
+                owner: owner.map(str::to_string),
+                expires_at: Instant::now() + Duration::from_secs(900),
+            },
+        );
+        service
+    }
+
+    #[tokio::test]
+    async fn only_the_starter_of_a_device_flow_can_poll_it() {
+        let service = service_with_flow(Some("oidc_alice")).await;
+        for (flow_id, owner) in [
+            ("flow", None),
+            ("flow", Some("oidc_mallory")),
+            ("other", Some("oidc_alice")),
+        ] {
+            let result = service.device_poll(flow_id, owner).await;
+            assert!(
+                matches!(result, Err(AuthError::DeviceFlowNotStarted)),
+                "{flow_id} {owner:?}"
+            );
+        }
+
+        // The server's own login can't be completed by a signed-in user either
+        let service = service_with_flow(None).await;
+        let result = service.device_poll("flow", Some("oidc_alice")).await;
+        assert!(matches!(result, Err(AuthError::DeviceFlowNotStarted)));
+    }
+}
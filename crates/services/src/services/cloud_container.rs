@@ -18,6 +18,7 @@ use db::{
     models::{
         execution_process::{ExecutionContext, ExecutionProcess, ExecutionProcessStatus},
         task_attempt::TaskAttempt,
+        task_attempt_repository::TaskAttemptRepository,
     },
 };
 use executors::{
@@ -601,6 +602,18 @@ where
         self.inner.stream_diff(task_attempt, stats_only).await
     }
 
+    async fn stream_repository_diff(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository: &TaskAttemptRepository,
+        stats_only: bool,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
+    {
+        self.inner
+            .stream_repository_diff(task_attempt, repository, stats_only)
+            .await
+    }
+
     async fn git_branch_prefix(&self) -> String {
         self.inner.git_branch_prefix().await
     }
@@ -14,9 +14,10 @@ use db::{
             ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
-        executor_session::{CreateExecutorSession, ExecutorSession},
+        executor_session::{CreateExecutorSession, ExecutorSession, HandoffKind},
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
+        task_attempt_repository::TaskAttemptRepository,
     },
 };
 use executors::{
@@ -27,9 +28,12 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     command::{CommandRuntime, HostCommandRuntime},
-    executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
-    profile::{ExecutorConfigs, ExecutorProfileId, to_default_variant},
+    executors::{BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        stderr_processor::is_rate_limit_message, utils::ConversationPatch,
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId, FallbackTrigger, to_default_variant},
 };
 use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
@@ -44,6 +48,7 @@ use uuid::Uuid;
 
 use crate::services::{
     git::{GitService, GitServiceError},
+    handoff::{HandoffBundle, conversation_from_history},
     image::ImageService,
     worktree_manager::{WorktreeError, WorktreeManager},
 };
@@ -99,6 +104,12 @@ pub enum ContainerError {
     KillFailed(std::io::Error),
     #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
+    #[error("Attempt has no coding agent execution to hand off")]
+    NothingToHandOff,
+    #[error("Attempt already runs on {0}")]
+    AlreadyRunsOn(BaseCodingAgent),
+    #[error("Executor profile {0} is not configured")]
+    UnconfiguredProfile(ExecutorProfileId),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -239,6 +250,14 @@ pub trait ContainerService {
         stats_only: bool,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>;
 
+    /// Stream diff updates for one additional repository of a multi-repository attempt.
+    async fn stream_repository_diff(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository: &TaskAttemptRepository,
+        stats_only: bool,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>;
+
     /// Fetch the MsgStore for a given execution ID, panicking if missing.
     async fn get_msg_store_by_id(&self, uuid: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores().read().await;
@@ -689,10 +708,20 @@ pub trait ContainerService {
             }
 
             // Emit NextAction with failure context for coding agent requests
-            if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
-                &start_error
-            {
-                let help_text = format!("The required executable `{program}` is not installed.");
+            let help_text = match &start_error {
+                ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) => {
+                    Some(format!(
+                        "The required executable `{program}` is not installed."
+                    ))
+                }
+                ContainerError::ExecutorError(ExecutorError::ToolNotCached { package }) => {
+                    Some(format!(
+                        "`{package}` has not been installed in the tool cache. Install it from the agent tools settings or allow npx fallback."
+                    ))
+                }
+                _ => None,
+            };
+            if let Some(help_text) = help_text {
                 let error_message = NormalizedEntry {
                     timestamp: None,
                     entry_type: NormalizedEntryType::ErrorMessage {
@@ -775,6 +804,264 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Conversation of an execution, read from its live store or re-normalized from stored logs
+    async fn normalized_conversation(&self, execution_id: &Uuid) -> Vec<NormalizedEntry> {
+        let history: Vec<LogMsg> = if let Some(store) = self.get_msg_store_by_id(execution_id).await
+        {
+            store.get_history()
+        } else if let Some(stream) = self.stream_normalized_logs(execution_id).await {
+            // Re-normalization ends with `Finished` once the normalizer drops its temporary store
+            stream
+                .take_while(|msg| future::ready(!matches!(msg, Ok(LogMsg::Finished))))
+                .filter_map(|msg| future::ready(msg.ok()))
+                .collect()
+                .await
+        } else {
+            Vec::new()
+        };
+        conversation_from_history(&history)
+    }
+
+    /// Unified diff of the attempt's worktree against the commit it branched from, with binary
+    /// files kept so the next agent can replay it in full
+    async fn worktree_patch(&self, task_attempt: &TaskAttempt) -> Result<String, ContainerError> {
+        let task = task_attempt
+            .parent_task(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let project = task
+            .parent_project(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let worktree_path = PathBuf::from(self.ensure_container_exists(task_attempt).await?);
+        let base_commit = self.git().get_base_commit(
+            &project.git_repo_path,
+            &task_attempt.branch,
+            &task_attempt.target_branch,
+        )?;
+        Ok(self.git().diff_patch(&worktree_path, &base_commit)?)
+    }
+
+    async fn handoff_bundle(
+        &self,
+        ctx: &ExecutionContext,
+        reason: String,
+    ) -> Result<HandoffBundle, ContainerError> {
+        Ok(HandoffBundle {
+            task_prompt: ctx.task.to_prompt(),
+            conversation: self
+                .normalized_conversation(&ctx.execution_process.id)
+                .await,
+            diff: self.worktree_patch(&ctx.task_attempt).await?,
+            reason,
+        })
+    }
+
+    /// Continue a failed coding agent run with the next executor of its fallback chain.
+    /// Returns whether a fallback execution was started.
+    async fn try_start_fallback(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent
+            || !matches!(
+                ctx.execution_process.status,
+                ExecutionProcessStatus::Completed | ExecutionProcessStatus::Failed
+            )
+        {
+            return Ok(false);
+        }
+        let action = ctx.execution_process.executor_action()?;
+        let Some(failed_profile) = coding_agent_request(action) else {
+            return Ok(false);
+        };
+
+        // Walk back through earlier fallbacks to find the profile whose chain is being followed
+        let pool = &self.db().pool;
+        let mut tried = vec![failed_profile.clone()];
+        let mut process_id = ctx.execution_process.id;
+        while let Some(session) =
+            ExecutorSession::find_by_execution_process_id(pool, process_id).await?
+        {
+            let (Some(HandoffKind::Fallback), Some(from_id)) = (
+                session.handoff_kind,
+                session.handoff_from_execution_process_id,
+            ) else {
+                break;
+            };
+            let Some(from) = ExecutionProcess::find_by_id(pool, from_id).await? else {
+                break;
+            };
+            let Some(profile) = from.executor_action().ok().and_then(coding_agent_request) else {
+                break;
+            };
+            tried.push(profile);
+            process_id = from_id;
+        }
+        let root = tried.last().unwrap_or(&failed_profile).clone();
+
+        let configs = ExecutorConfigs::get_cached();
+        let Some(fallback) = configs.fallback_chain(&root) else {
+            return Ok(false);
+        };
+
+        let mut entries = self
+            .normalized_conversation(&ctx.execution_process.id)
+            .await;
+        // Stderr the normalizer has not flushed yet still counts towards rate limiting
+        if let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await
+            && store
+                .get_history()
+                .iter()
+                .any(|msg| matches!(msg, LogMsg::Stderr(chunk) if is_rate_limit_message(chunk)))
+        {
+            entries.push(NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::RateLimited,
+                },
+                content: "Rate limit reported on stderr".to_string(),
+                metadata: None,
+            });
+        }
+
+        let Some(trigger) = fallback.triggered_by(ctx.execution_process.exit_code, &entries) else {
+            return Ok(false);
+        };
+        let Some(next_profile) = fallback
+            .chain
+            .iter()
+            .find(|profile| !tried.contains(profile) && configs.get_coding_agent(profile).is_some())
+            .cloned()
+        else {
+            tracing::info!(
+                "Fallback chain for {} is exhausted for task attempt {}",
+                root,
+                ctx.task_attempt.id
+            );
+            return Ok(false);
+        };
+
+        let reason = match trigger {
+            FallbackTrigger::NonZeroExit => format!(
+                "{failed_profile} exited with code {}.",
+                ctx.execution_process.exit_code.unwrap_or_default()
+            ),
+            FallbackTrigger::RateLimit => format!("{failed_profile} hit a rate limit."),
+            FallbackTrigger::Error { error_type } => {
+                format!("{failed_profile} reported an error ({error_type:?}).")
+            }
+        };
+        tracing::info!(
+            "Falling back from {} to {} for task attempt {}: {}",
+            failed_profile,
+            next_profile,
+            ctx.task_attempt.id,
+            reason
+        );
+
+        let bundle = self.handoff_bundle(ctx, reason).await?;
+        self.start_handoff_execution(
+            ctx,
+            next_profile,
+            bundle.to_prompt(),
+            action.next_action().cloned().map(Box::new),
+            HandoffKind::Fallback,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Switch an attempt to a different coding agent. The new executor starts a fresh session
+    /// seeded with the task, the latest conversation and the current diff.
+    async fn handoff_attempt(
+        &self,
+        task_attempt: &TaskAttempt,
+        executor_profile_id: ExecutorProfileId,
+        instructions: Option<String>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let latest = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            pool,
+            task_attempt.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        .ok_or(ContainerError::NothingToHandOff)?;
+        let Some(from_profile) = coding_agent_request(latest.executor_action()?) else {
+            return Err(ContainerError::NothingToHandOff);
+        };
+        if from_profile.executor == executor_profile_id.executor {
+            return Err(ContainerError::AlreadyRunsOn(from_profile.executor));
+        }
+        if ExecutorConfigs::get_cached()
+            .get_coding_agent(&executor_profile_id)
+            .is_none()
+        {
+            return Err(ContainerError::UnconfiguredProfile(executor_profile_id));
+        }
+
+        // The previous executor must not keep editing the worktree
+        self.try_stop(task_attempt).await;
+
+        let ctx = ExecutionProcess::load_context(pool, latest.id).await?;
+        let project = ctx
+            .task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let reason =
+            format!("The user switched this attempt from {from_profile} to {executor_profile_id}.");
+        let mut prompt = self.handoff_bundle(&ctx, reason).await?.to_prompt();
+        if let Some(instructions) = instructions.filter(|text| !text.trim().is_empty()) {
+            prompt.push_str("\n\n## Next step\n\n");
+            prompt.push_str(instructions.trim());
+        }
+
+        self.start_handoff_execution(
+            &ctx,
+            executor_profile_id,
+            prompt,
+            self.cleanup_action(project.cleanup_script),
+            HandoffKind::Manual,
+        )
+        .await
+    }
+
+    /// Start a fresh coding agent session that takes over from `from` and record the switch
+    async fn start_handoff_execution(
+        &self,
+        from: &ExecutionContext,
+        executor_profile_id: ExecutorProfileId,
+        prompt: String,
+        next_action: Option<Box<ExecutorAction>>,
+        kind: HandoffKind,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let from_executor = coding_agent_request(from.execution_process.executor_action()?)
+            .map(|profile| profile.to_string())
+            .unwrap_or_default();
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+            }),
+            next_action,
+        );
+        let execution_process = self
+            .start_execution(
+                &from.task_attempt,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        ExecutorSession::record_handoff(
+            &self.db().pool,
+            execution_process.id,
+            from.execution_process.id,
+            &from_executor,
+            kind,
+        )
+        .await?;
+        Ok(execution_process)
+    }
+
     async fn exit_plan_mode_tool(&self, ctx: ExecutionContext) -> Result<(), ContainerError> {
         let execution_id = ctx.execution_process.id;
 
@@ -834,3 +1121,16 @@ pub trait ContainerService {
         Ok(())
     }
 }
+
+/// Executor profile of a coding agent action
+fn coding_agent_request(action: &ExecutorAction) -> Option<ExecutorProfileId> {
+    match action.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => {
+            Some(request.executor_profile_id.clone())
+        }
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+            Some(request.executor_profile_id.clone())
+        }
+        ExecutorActionType::ScriptRequest(_) => None,
+    }
+}
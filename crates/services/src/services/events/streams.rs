@@ -1,12 +1,19 @@
+use std::sync::Arc;
+
 use db::models::{
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
     task::{Task, TaskWithAttemptStatus},
+    task_attempt::TaskAttempt,
 };
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt, future, future::BoxFuture, stream::BoxStream};
+use json_patch::Patch;
 use serde_json::json;
-use tokio_stream::wrappers::BroadcastStream;
-use utils::log_msg::LogMsg;
+use sqlx::SqlitePool;
+use utils::{
+    log_msg::{LogMsg, SequencedMsg, StreamMsg},
+    msg_store::MsgStore,
+};
 use uuid::Uuid;
 
 use super::{
@@ -16,171 +23,215 @@ use super::{
 };
 
 impl EventService {
-    /// Stream raw task messages for a specific project with initial snapshot
+    /// Resume after `since` when the store still has the messages after it, otherwise start
+    /// from `snapshot`, preceded by `ResyncRequired` if a resume was asked for. Messages after
+    /// the resume point or snapshot go through `filter`.
+    async fn resumable_stream<F>(
+        &self,
+        since: Option<u64>,
+        snapshot: impl Future<Output = Result<LogMsg, EventError>>,
+        filter: F,
+    ) -> Result<BoxStream<'static, Result<StreamMsg, std::io::Error>>, EventError>
+    where
+        F: Fn(LogMsg) -> BoxFuture<'static, Option<LogMsg>> + Send + 'static,
+    {
+        let (initial, history, rx) =
+            match since.and_then(|since| self.msg_store.subscribe_since(since)) {
+                Some((history, rx)) => (Vec::new(), history, rx),
+                None => {
+                    // Subscribe before taking the snapshot so no change falls in between
+                    let (seq, rx) = self.msg_store.subscribe();
+                    let mut initial = Vec::new();
+                    if since.is_some() {
+                        initial.push(Ok(StreamMsg::ResyncRequired));
+                    }
+                    let msg = snapshot.await?;
+                    initial.push(Ok(StreamMsg::Msg(SequencedMsg { seq, msg })));
+                    (initial, Vec::new(), rx)
+                }
+            };
+
+        let filtered = MsgStore::sequenced_stream(history, rx).filter_map(move |item| match item {
+            Ok(StreamMsg::Msg(SequencedMsg { seq, msg })) => filter(msg)
+                .map(move |msg| msg.map(|msg| Ok(StreamMsg::Msg(SequencedMsg { seq, msg }))))
+                .boxed(),
+            other => future::ready(Some(other)).boxed(),
+        });
+
+        Ok(futures::stream::iter(initial).chain(filtered).boxed())
+    }
+
+    /// Stream raw task messages for a specific project with initial snapshot, or resumed
+    /// after `since`
     pub async fn stream_tasks_raw(
         &self,
         project_id: Uuid,
-    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
-    {
+        since: Option<u64>,
+    ) -> Result<BoxStream<'static, Result<StreamMsg, std::io::Error>>, EventError> {
         // Get initial snapshot of tasks
-        let tasks = Task::find_by_project_id_with_attempt_status(&self.db.pool, project_id).await?;
+        let snapshot = async {
+            let tasks =
+                Task::find_by_project_id_with_attempt_status(&self.db.pool, project_id).await?;
 
-        // Convert task array to object keyed by task ID
-        let tasks_map: serde_json::Map<String, serde_json::Value> = tasks
-            .into_iter()
-            .map(|task| (task.id.to_string(), serde_json::to_value(task).unwrap()))
-            .collect();
+            // Convert task array to object keyed by task ID
+            let tasks_map: serde_json::Map<String, serde_json::Value> = tasks
+                .into_iter()
+                .map(|task| (task.id.to_string(), serde_json::to_value(task).unwrap()))
+                .collect();
 
-        let initial_patch = json!([{
-            "op": "replace",
-            "path": "/tasks",
-            "value": tasks_map
-        }]);
-        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+            let initial_patch = json!([{
+                "op": "replace",
+                "path": "/tasks",
+                "value": tasks_map
+            }]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(
+                serde_json::from_value(initial_patch).unwrap(),
+            ))
+        };
 
         // Clone necessary data for the async filter
         let db_pool = self.db.pool.clone();
 
-        // Get filtered event stream
-        let filtered_stream =
-            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
-                let db_pool = db_pool.clone();
-                async move {
-                    match msg_result {
-                        Ok(LogMsg::JsonPatch(patch)) => {
-                            // Filter events based on project_id
-                            if let Some(patch_op) = patch.0.first() {
-                                // Check if this is a direct task patch (new format)
-                                if patch_op.path().starts_with("/tasks/") {
-                                    match patch_op {
-                                        json_patch::PatchOperation::Add(op) => {
-                                            // Parse task data directly from value
-                                            if let Ok(task) =
-                                                serde_json::from_value::<TaskWithAttemptStatus>(
-                                                    op.value.clone(),
-                                                )
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
-                                        }
-                                        json_patch::PatchOperation::Replace(op) => {
-                                            // Parse task data directly from value
-                                            if let Ok(task) =
-                                                serde_json::from_value::<TaskWithAttemptStatus>(
-                                                    op.value.clone(),
-                                                )
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+        // Filter live events to this project
+        let filter = move |msg: LogMsg| {
+            let db_pool = db_pool.clone();
+            async move {
+                match msg {
+                    LogMsg::JsonPatch(patch) => {
+                        // Filter events based on project_id
+                        if let Some(patch_op) = patch.0.first() {
+                            // Check if this is a direct task patch (new format)
+                            if patch_op.path().starts_with("/tasks/") {
+                                match patch_op {
+                                    json_patch::PatchOperation::Add(op) => {
+                                        // Parse task data directly from value
+                                        if let Ok(task) =
+                                            serde_json::from_value::<TaskWithAttemptStatus>(
+                                                op.value.clone(),
+                                            )
+                                            && task.project_id == project_id
+                                        {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        json_patch::PatchOperation::Remove(_) => {
-                                            // For remove operations, we need to check project membership differently
-                                            // We could cache this information or let it pass through for now
-                                            // Since we don't have the task data, we'll allow all removals
-                                            // and let the client handle filtering
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                    }
+                                    json_patch::PatchOperation::Replace(op) => {
+                                        // Parse task data directly from value
+                                        if let Ok(task) =
+                                            serde_json::from_value::<TaskWithAttemptStatus>(
+                                                op.value.clone(),
+                                            )
+                                            && task.project_id == project_id
+                                        {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        _ => {}
                                     }
-                                } else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
-                                    && let Ok(event_patch) =
-                                        serde_json::from_value::<EventPatch>(event_patch_value)
-                                {
-                                    // Handle old EventPatch format for non-task records
-                                    match &event_patch.value.record {
-                                        RecordTypes::Task(task) => {
-                                            if task.project_id == project_id {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    json_patch::PatchOperation::Remove(_) => {
+                                        // For remove operations, we need to check project membership differently
+                                        // We could cache this information or let it pass through for now
+                                        // Since we don't have the task data, we'll allow all removals
+                                        // and let the client handle filtering
+                                        return Some(LogMsg::JsonPatch(patch));
+                                    }
+                                    _ => {}
+                                }
+                            } else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
+                                && let Ok(event_patch) =
+                                    serde_json::from_value::<EventPatch>(event_patch_value)
+                            {
+                                // Handle old EventPatch format for non-task records
+                                match &event_patch.value.record {
+                                    RecordTypes::Task(task) => {
+                                        if task.project_id == project_id {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        RecordTypes::DeletedTask {
-                                            project_id: Some(deleted_project_id),
-                                            ..
-                                        } => {
-                                            if *deleted_project_id == project_id {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    }
+                                    RecordTypes::DeletedTask {
+                                        project_id: Some(deleted_project_id),
+                                        ..
+                                    } => {
+                                        if *deleted_project_id == project_id {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        RecordTypes::TaskAttempt(attempt) => {
-                                            // Check if this task_attempt belongs to a task in our project
-                                            if let Ok(Some(task)) =
-                                                Task::find_by_id(&db_pool, attempt.task_id).await
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    }
+                                    RecordTypes::TaskAttempt(attempt) => {
+                                        // Check if this task_attempt belongs to a task in our project
+                                        if let Ok(Some(task)) =
+                                            Task::find_by_id(&db_pool, attempt.task_id).await
+                                            && task.project_id == project_id
+                                        {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        RecordTypes::DeletedTaskAttempt {
-                                            task_id: Some(deleted_task_id),
-                                            ..
-                                        } => {
-                                            // Check if deleted attempt belonged to a task in our project
-                                            if let Ok(Some(task)) =
-                                                Task::find_by_id(&db_pool, *deleted_task_id).await
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
+                                    }
+                                    RecordTypes::DeletedTaskAttempt {
+                                        task_id: Some(deleted_task_id),
+                                        ..
+                                    } => {
+                                        // Check if deleted attempt belonged to a task in our project
+                                        if let Ok(Some(task)) =
+                                            Task::find_by_id(&db_pool, *deleted_task_id).await
+                                            && task.project_id == project_id
+                                        {
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
-                                        _ => {}
                                     }
+                                    _ => {}
                                 }
                             }
-                            None
                         }
-                        Ok(other) => Some(Ok(other)), // Pass through non-patch messages
-                        Err(_) => None,               // Filter out broadcast errors
+                        None
                     }
+                    other => Some(other), // Pass through non-patch messages
                 }
-            });
-
-        // Start with initial snapshot, then live updates
-        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
-        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+            }
+            .boxed()
+        };
 
-        Ok(combined_stream)
+        self.resumable_stream(since, snapshot, filter).await
     }
 
-    /// Stream execution processes for a specific task attempt with initial snapshot (raw LogMsg format for WebSocket)
+    /// Stream execution processes for a specific task attempt with initial snapshot, or resumed
+    /// after `since` (raw LogMsg format for WebSocket)
     pub async fn stream_execution_processes_for_attempt_raw(
         &self,
         task_attempt_id: Uuid,
         show_soft_deleted: bool,
-    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
-    {
+        since: Option<u64>,
+    ) -> Result<BoxStream<'static, Result<StreamMsg, std::io::Error>>, EventError> {
         // Get initial snapshot of execution processes (filtering at SQL level)
-        let processes = ExecutionProcess::find_by_task_attempt_id(
-            &self.db.pool,
-            task_attempt_id,
-            show_soft_deleted,
-        )
-        .await?;
+        let snapshot = async {
+            let processes = ExecutionProcess::find_by_task_attempt_id(
+                &self.db.pool,
+                task_attempt_id,
+                show_soft_deleted,
+            )
+            .await?;
 
-        // Convert processes array to object keyed by process ID
-        let processes_map: serde_json::Map<String, serde_json::Value> = processes
-            .into_iter()
-            .map(|process| {
-                (
-                    process.id.to_string(),
-                    serde_json::to_value(process).unwrap(),
-                )
-            })
-            .collect();
+            // Convert processes array to object keyed by process ID
+            let processes_map: serde_json::Map<String, serde_json::Value> = processes
+                .into_iter()
+                .map(|process| {
+                    (
+                        process.id.to_string(),
+                        serde_json::to_value(process).unwrap(),
+                    )
+                })
+                .collect();
 
-        let initial_patch = json!([{
-            "op": "replace",
-            "path": "/execution_processes",
-            "value": processes_map
-        }]);
-        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+            let initial_patch = json!([{
+                "op": "replace",
+                "path": "/execution_processes",
+                "value": processes_map
+            }]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(
+                serde_json::from_value(initial_patch).unwrap(),
+            ))
+        };
 
-        // Get filtered event stream
-        let filtered_stream = BroadcastStream::new(self.msg_store.get_receiver()).filter_map(
-            move |msg_result| async move {
-                match msg_result {
-                    Ok(LogMsg::JsonPatch(patch)) => {
+        // Filter live events to this task attempt
+        let filter = move |msg: LogMsg| {
+            async move {
+                match msg {
+                    LogMsg::JsonPatch(patch) => {
                         // Filter events based on task_attempt_id
                         if let Some(patch_op) = patch.0.first() {
                             // Check if this is a modern execution process patch
@@ -197,9 +248,9 @@ impl EventService {
                                             if !show_soft_deleted && process.dropped {
                                                 let remove_patch =
                                                     execution_process_patch::remove(process.id);
-                                                return Some(Ok(LogMsg::JsonPatch(remove_patch)));
+                                                return Some(LogMsg::JsonPatch(remove_patch));
                                             }
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
                                     }
                                     json_patch::PatchOperation::Replace(op) => {
@@ -213,15 +264,15 @@ impl EventService {
                                             if !show_soft_deleted && process.dropped {
                                                 let remove_patch =
                                                     execution_process_patch::remove(process.id);
-                                                return Some(Ok(LogMsg::JsonPatch(remove_patch)));
+                                                return Some(LogMsg::JsonPatch(remove_patch));
                                             }
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
                                     }
                                     json_patch::PatchOperation::Remove(_) => {
                                         // For remove operations, we can't verify task_attempt_id
                                         // so we allow all removals and let the client handle filtering
-                                        return Some(Ok(LogMsg::JsonPatch(patch)));
+                                        return Some(LogMsg::JsonPatch(patch));
                                     }
                                     _ => {}
                                 }
@@ -237,9 +288,9 @@ impl EventService {
                                             if !show_soft_deleted && process.dropped {
                                                 let remove_patch =
                                                     execution_process_patch::remove(process.id);
-                                                return Some(Ok(LogMsg::JsonPatch(remove_patch)));
+                                                return Some(LogMsg::JsonPatch(remove_patch));
                                             }
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
                                     }
                                     RecordTypes::DeletedExecutionProcess {
@@ -247,7 +298,7 @@ impl EventService {
                                         ..
                                     } => {
                                         if *deleted_attempt_id == task_attempt_id {
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                                            return Some(LogMsg::JsonPatch(patch));
                                         }
                                     }
                                     _ => {}
@@ -256,119 +307,254 @@ impl EventService {
                         }
                         None
                     }
-                    Ok(other) => Some(Ok(other)), // Pass through non-patch messages
-                    Err(_) => None,               // Filter out broadcast errors
+                    other => Some(other), // Pass through non-patch messages
                 }
-            },
-        );
-
-        // Start with initial snapshot, then live updates
-        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
-        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+            }
+            .boxed()
+        };
 
-        Ok(combined_stream)
+        self.resumable_stream(since, snapshot, filter).await
     }
 
-    /// Stream drafts for all task attempts in a project with initial snapshot (raw LogMsg)
+    /// Stream drafts for all task attempts in a project with initial snapshot, or resumed after
+    /// `since` (raw LogMsg)
     pub async fn stream_drafts_for_project_raw(
         &self,
         project_id: Uuid,
-    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
-    {
-        // Load all attempt ids for tasks in this project
-        let attempt_ids: Vec<Uuid> = sqlx::query_scalar(
-            r#"SELECT ta.id
+        since: Option<u64>,
+    ) -> Result<BoxStream<'static, Result<StreamMsg, std::io::Error>>, EventError> {
+        let snapshot = async {
+            // Load all attempt ids for tasks in this project
+            let attempt_ids: Vec<Uuid> = sqlx::query_scalar(
+                r#"SELECT ta.id
                FROM task_attempts ta
                JOIN tasks t ON t.id = ta.task_id
               WHERE t.project_id = ?"#,
-        )
-        .bind(project_id)
-        .fetch_all(&self.db.pool)
-        .await?;
-
-        // Build initial drafts map keyed by attempt_id
-        let mut drafts_map: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
-        for attempt_id in attempt_ids {
-            let fu = Draft::find_by_task_attempt_and_type(
-                &self.db.pool,
-                attempt_id,
-                DraftType::FollowUp,
             )
-            .await?
-            .unwrap_or(Draft {
-                id: uuid::Uuid::new_v4(),
-                task_attempt_id: attempt_id,
-                draft_type: DraftType::FollowUp,
-                retry_process_id: None,
-                prompt: String::new(),
-                queued: false,
-                sending: false,
-                variant: None,
-                image_ids: None,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-                version: 0,
-            });
-            let re =
-                Draft::find_by_task_attempt_and_type(&self.db.pool, attempt_id, DraftType::Retry)
-                    .await?;
-            let entry = json!({
-                "follow_up": fu,
-                "retry": serde_json::to_value(re).unwrap_or(serde_json::Value::Null),
-            });
-            drafts_map.insert(attempt_id.to_string(), entry);
-        }
+            .bind(project_id)
+            .fetch_all(&self.db.pool)
+            .await?;
 
-        let initial_patch = json!([
-            {
-                "op": "replace",
-                "path": "/drafts",
-                "value": drafts_map
+            // Build initial drafts map keyed by attempt_id
+            let mut drafts_map: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+            for attempt_id in attempt_ids {
+                let fu = Draft::find_by_task_attempt_and_type(
+                    &self.db.pool,
+                    attempt_id,
+                    DraftType::FollowUp,
+                )
+                .await?
+                .unwrap_or(Draft {
+                    id: uuid::Uuid::new_v4(),
+                    task_attempt_id: attempt_id,
+                    draft_type: DraftType::FollowUp,
+                    retry_process_id: None,
+                    prompt: String::new(),
+                    queued: false,
+                    sending: false,
+                    variant: None,
+                    image_ids: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                    version: 0,
+                });
+                let re = Draft::find_by_task_attempt_and_type(
+                    &self.db.pool,
+                    attempt_id,
+                    DraftType::Retry,
+                )
+                .await?;
+                let entry = json!({
+                    "follow_up": fu,
+                    "retry": serde_json::to_value(re).unwrap_or(serde_json::Value::Null),
+                });
+                drafts_map.insert(attempt_id.to_string(), entry);
             }
-        ]);
-        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+            let initial_patch = json!([
+                {
+                    "op": "replace",
+                    "path": "/drafts",
+                    "value": drafts_map
+                }
+            ]);
+            Ok::<_, EventError>(LogMsg::JsonPatch(
+                serde_json::from_value(initial_patch).unwrap(),
+            ))
+        };
 
         let db_pool = self.db.pool.clone();
         // Live updates: accept direct draft patches and filter by project membership
-        let filtered_stream =
-            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
-                let db_pool = db_pool.clone();
-                async move {
-                    match msg_result {
-                        Ok(LogMsg::JsonPatch(patch)) => {
-                            if let Some(op) = patch.0.first() {
-                                let path = op.path();
-                                if let Some(rest) = path.strip_prefix("/drafts/")
-                                    && let Some((attempt_str, _)) = rest.split_once('/')
-                                    && let Ok(attempt_id) = Uuid::parse_str(attempt_str)
+        let filter = move |msg: LogMsg| {
+            let db_pool = db_pool.clone();
+            async move {
+                match msg {
+                    LogMsg::JsonPatch(patch) => {
+                        if let Some(op) = patch.0.first() {
+                            let path = op.path();
+                            if let Some(rest) = path.strip_prefix("/drafts/")
+                                && let Some((attempt_str, _)) = rest.split_once('/')
+                                && let Ok(attempt_id) = Uuid::parse_str(attempt_str)
+                            {
+                                // Check project membership
+                                if let Ok(Some(task_attempt)) =
+                                    db::models::task_attempt::TaskAttempt::find_by_id(
+                                        &db_pool, attempt_id,
+                                    )
+                                    .await
+                                    && let Ok(Some(task)) = db::models::task::Task::find_by_id(
+                                        &db_pool,
+                                        task_attempt.task_id,
+                                    )
+                                    .await
+                                    && task.project_id == project_id
                                 {
-                                    // Check project membership
-                                    if let Ok(Some(task_attempt)) =
-                                        db::models::task_attempt::TaskAttempt::find_by_id(
-                                            &db_pool, attempt_id,
-                                        )
-                                        .await
-                                        && let Ok(Some(task)) = db::models::task::Task::find_by_id(
-                                            &db_pool,
-                                            task_attempt.task_id,
-                                        )
-                                        .await
-                                        && task.project_id == project_id
-                                    {
-                                        return Some(Ok(LogMsg::JsonPatch(patch)));
-                                    }
+                                    return Some(LogMsg::JsonPatch(patch));
                                 }
                             }
+                        }
+                        None
+                    }
+                    other => Some(other),
+                }
+            }
+            .boxed()
+        };
+
+        self.resumable_stream(since, snapshot, filter).await
+    }
+
+    /// All events, resumed after `since` like [`MsgStore::resumable_stream`], limited to
+    /// records of projects `can_view` accepts. Removals that no longer name a project are
+    /// passed through, as on the per-project streams.
+    pub fn stream_events_for_projects<F>(
+        &self,
+        since: Option<u64>,
+        can_view: F,
+    ) -> BoxStream<'static, Result<StreamMsg, std::io::Error>>
+    where
+        F: Fn(Uuid) -> BoxFuture<'static, bool> + Send + Sync + 'static,
+    {
+        let db_pool = self.db.pool.clone();
+        let can_view = Arc::new(can_view);
+        self.msg_store
+            .resumable_stream(since)
+            .filter_map(move |item| {
+                let db_pool = db_pool.clone();
+                let can_view = can_view.clone();
+                async move {
+                    let Ok(StreamMsg::Msg(SequencedMsg {
+                        msg: LogMsg::JsonPatch(patch),
+                        ..
+                    })) = &item
+                    else {
+                        return Some(item);
+                    };
+                    match patch_project_id(&db_pool, patch).await {
+                        Ok(Some(project_id)) => can_view(project_id).await.then_some(item),
+                        Ok(None) => {
+                            matches!(patch.0.first(), Some(json_patch::PatchOperation::Remove(_)))
+                                .then_some(item)
+                        }
+                        Err(err) => {
+                            tracing::warn!("Failed to resolve the project of an event: {}", err);
                             None
                         }
-                        Ok(other) => Some(Ok(other)),
-                        Err(_) => None,
                     }
                 }
-            });
+            })
+            .boxed()
+    }
+}
+
+/// Project of the record a patch touches; None when the patch doesn't identify one
+async fn patch_project_id(pool: &SqlitePool, patch: &Patch) -> Result<Option<Uuid>, sqlx::Error> {
+    let Some(patch_op) = patch.0.first() else {
+        return Ok(None);
+    };
+    let value = match patch_op {
+        json_patch::PatchOperation::Add(op) => Some(&op.value),
+        json_patch::PatchOperation::Replace(op) => Some(&op.value),
+        _ => None,
+    };
+    let path = patch_op.path();
+
+    if path.starts_with("/tasks/") {
+        return Ok(value
+            .and_then(|value| serde_json::from_value::<TaskWithAttemptStatus>(value.clone()).ok())
+            .map(|task| task.project_id));
+    }
+    if path.starts_with("/execution_processes/") {
+        return match value
+            .and_then(|value| serde_json::from_value::<ExecutionProcess>(value.clone()).ok())
+        {
+            Some(process) => attempt_project_id(pool, process.task_attempt_id).await,
+            None => Ok(None),
+        };
+    }
+    if path.starts_with("/task_attempts/") {
+        return match value
+            .and_then(|value| serde_json::from_value::<TaskAttempt>(value.clone()).ok())
+        {
+            Some(attempt) => task_project_id(pool, attempt.task_id).await,
+            None => Ok(None),
+        };
+    }
+    if let Some(rest) = path.strip_prefix("/drafts/") {
+        return match rest
+            .split('/')
+            .next()
+            .and_then(|attempt_id| Uuid::parse_str(attempt_id).ok())
+        {
+            Some(attempt_id) => attempt_project_id(pool, attempt_id).await,
+            None => Ok(None),
+        };
+    }
+
+    // Old EventPatch format carries the record itself
+    let Ok(event_patch) =
+        serde_json::to_value(patch_op).and_then(serde_json::from_value::<EventPatch>)
+    else {
+        return Ok(None);
+    };
+    match event_patch.value.record {
+        RecordTypes::Task(task) => Ok(Some(task.project_id)),
+        RecordTypes::DeletedTask { project_id, .. } => Ok(project_id),
+        RecordTypes::TaskAttempt(attempt) => task_project_id(pool, attempt.task_id).await,
+        RecordTypes::DeletedTaskAttempt {
+            task_id: Some(task_id),
+            ..
+        } => task_project_id(pool, task_id).await,
+        RecordTypes::ExecutionProcess(process) => {
+            attempt_project_id(pool, process.task_attempt_id).await
+        }
+        RecordTypes::Draft(draft) | RecordTypes::RetryDraft(draft) => {
+            attempt_project_id(pool, draft.task_attempt_id).await
+        }
+        RecordTypes::DeletedExecutionProcess {
+            task_attempt_id: Some(attempt_id),
+            ..
+        }
+        | RecordTypes::DeletedDraft {
+            task_attempt_id: Some(attempt_id),
+            ..
+        } => attempt_project_id(pool, attempt_id).await,
+        _ => Ok(None),
+    }
+}
+
+async fn task_project_id(pool: &SqlitePool, task_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+    Ok(Task::find_by_id(pool, task_id)
+        .await?
+        .map(|task| task.project_id))
+}
 
-        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
-        let combined_stream = initial_stream.chain(filtered_stream).boxed();
-        Ok(combined_stream)
+async fn attempt_project_id(
+    pool: &SqlitePool,
+    task_attempt_id: Uuid,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    match TaskAttempt::find_by_id(pool, task_attempt_id).await? {
+        Some(attempt) => task_project_id(pool, attempt.task_id).await,
+        None => Ok(None),
     }
 }
@@ -0,0 +1,384 @@
+use std::{process::Stdio, sync::Arc, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use executors::{
+    diagnostics::{AuthSource, DiagnosticProbe},
+    executors::{BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    mcp_config::read_agent_config,
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    tool_cache::{NpmPackage, ToolCache},
+};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use tokio::{process::Command, sync::RwLock};
+use ts_rs::TS;
+use utils::shell::resolve_executable_path;
+
+use super::secret_store::{PROVIDER_CLAUDE, SECRET_CLAUDE_ACCESS, SecretStore};
+
+/// Reports are reused for this long unless a refresh is requested
+const CACHE_TTL_SECS: i64 = 300;
+/// Generous because `npx` may download the package on first run
+const VERSION_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct HealthCheck {
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl HealthCheck {
+    fn new(status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutorHealth {
+    pub executor: BaseCodingAgent,
+    /// False when a check failed that will stop the executor from starting
+    pub available: bool,
+    pub version: Option<String>,
+    pub binary: HealthCheck,
+    pub version_check: HealthCheck,
+    pub auth: HealthCheck,
+    pub mcp_config: HealthCheck,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutorHealthReport {
+    pub executors: Vec<ExecutorHealth>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Diagnoses every configured executor's installation, version, credentials and MCP config
+#[derive(Clone)]
+pub struct ExecutorHealthService {
+    secret_store: SecretStore,
+    user_id: String,
+    cache: Arc<RwLock<Option<ExecutorHealthReport>>>,
+}
+
+impl ExecutorHealthService {
+    pub fn new(secret_store: SecretStore, user_id: String) -> Self {
+        Self {
+            secret_store,
+            user_id,
+            cache: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Cached report, re-running the checks when stale or when `refresh` is set
+    pub async fn report(&self, refresh: bool) -> ExecutorHealthReport {
+        if !refresh
+            && let Some(report) = self.cache.read().await.as_ref()
+            && Utc::now() - report.checked_at < TimeDelta::seconds(CACHE_TTL_SECS)
+        {
+            return report.clone();
+        }
+
+        let report = self.check_all().await;
+        *self.cache.write().await = Some(report.clone());
+        report
+    }
+
+    async fn check_all(&self) -> ExecutorHealthReport {
+        let configs = ExecutorConfigs::get_cached();
+        let mut executors: Vec<BaseCodingAgent> = configs.executors.keys().copied().collect();
+        executors.sort_by_key(|executor| executor.to_string());
+
+        let checks = executors.into_iter().filter_map(|executor| {
+            configs
+                .get_coding_agent(&ExecutorProfileId::new(executor))
+                .map(|agent| self.check_executor(executor, agent))
+        });
+        ExecutorHealthReport {
+            executors: join_all(checks).await,
+            checked_at: Utc::now(),
+        }
+    }
+
+    pub async fn check_executor(
+        &self,
+        executor: BaseCodingAgent,
+        agent: CodingAgent,
+    ) -> ExecutorHealth {
+        let probe = agent.diagnostic_probe().await;
+        let (binary, resolved) = check_binary(&probe).await;
+        let (version_check, version) = match resolved {
+            Some(program) => check_version(&probe, program).await,
+            None => (
+                HealthCheck::new(CheckStatus::Skipped, "No binary to query"),
+                None,
+            ),
+        };
+        let auth = self.check_auth(&probe.auth_sources).await;
+        let mcp_config = check_mcp_config(&agent).await;
+
+        ExecutorHealth {
+            executor,
+            available: binary.status != CheckStatus::Error
+                && version_check.status != CheckStatus::Error,
+            version,
+            binary,
+            version_check,
+            auth,
+            mcp_config,
+        }
+    }
+
+    async fn check_auth(&self, sources: &[AuthSource]) -> HealthCheck {
+        if sources.is_empty() {
+            return HealthCheck::new(CheckStatus::Skipped, "No credentials required");
+        }
+
+        let mut looked_for = Vec::new();
+        for source in sources {
+            match source {
+                AuthSource::EnvVar(name) => {
+                    if std::env::var(name).is_ok_and(|value| !value.trim().is_empty()) {
+                        return HealthCheck::new(CheckStatus::Ok, format!("{name} is set"));
+                    }
+                    looked_for.push(name.clone());
+                }
+                AuthSource::File(path) => {
+                    if path.exists() {
+                        return HealthCheck::new(
+                            CheckStatus::Ok,
+                            format!("Found credentials at {}", path.display()),
+                        );
+                    }
+                    looked_for.push(path.display().to_string());
+                }
+                AuthSource::StoredSecret(provider) => {
+                    if *provider == PROVIDER_CLAUDE {
+                        match self
+                            .secret_store
+                            .get_secret(&self.user_id, SECRET_CLAUDE_ACCESS)
+                            .await
+                        {
+                            Ok(Some(_)) => {
+                                return HealthCheck::new(
+                                    CheckStatus::Ok,
+                                    "Signed in through the app",
+                                );
+                            }
+                            Ok(None) => {}
+                            Err(err) => {
+                                tracing::warn!(
+                                    "Failed to read stored {provider} credentials: {err}"
+                                )
+                            }
+                        }
+                    }
+                    looked_for.push(format!("{provider} login"));
+                }
+            }
+        }
+
+        // Agents may also keep credentials in places we cannot inspect, e.g. the macOS keychain
+        HealthCheck::new(
+            CheckStatus::Warning,
+            format!("No credentials found (checked {})", looked_for.join(", ")),
+        )
+    }
+}
+
+async fn check_binary(probe: &DiagnosticProbe) -> (HealthCheck, Option<std::path::PathBuf>) {
+    let Some(command) = &probe.command else {
+        return (
+            HealthCheck::new(CheckStatus::Skipped, "Does not run a local binary"),
+            None,
+        );
+    };
+    let parts = match command.build_initial() {
+        Ok(parts) => parts,
+        Err(err) => {
+            return (
+                HealthCheck::new(CheckStatus::Error, format!("Invalid command: {err}")),
+                None,
+            );
+        }
+    };
+
+    if let Some((path, _)) = ToolCache::global().resolve(parts.program(), parts.args()) {
+        return (
+            HealthCheck::new(
+                CheckStatus::Ok,
+                format!("Using cached install at {}", path.display()),
+            ),
+            Some(path),
+        );
+    }
+    if !ExecutorConfigs::get_cached().tools.allow_npx_fallback
+        && NpmPackage::is_npx(parts.program())
+    {
+        return (
+            HealthCheck::new(
+                CheckStatus::Error,
+                format!(
+                    "{} is not in the tool cache and npx fallback is disabled",
+                    NpmPackage::npx_spec(parts.args())
+                ),
+            ),
+            None,
+        );
+    }
+
+    match resolve_executable_path(parts.program()).await {
+        Some(path) => {
+            let mut message = format!("{} found at {}", parts.program(), path.display());
+            if parts.program() == "npx"
+                && let Some(package) = parts.args().iter().find(|arg| !arg.starts_with('-'))
+            {
+                message.push_str(&format!(", runs {package}"));
+            }
+            (HealthCheck::new(CheckStatus::Ok, message), Some(path))
+        }
+        None => {
+            let hint = if parts.program() == "npx" {
+                " (install Node.js to get npx)"
+            } else {
+                ""
+            };
+            (
+                HealthCheck::new(
+                    CheckStatus::Error,
+                    format!("`{}` was not found on PATH{hint}", parts.program()),
+                ),
+                None,
+            )
+        }
+    }
+}
+
+async fn check_version(
+    probe: &DiagnosticProbe,
+    program: std::path::PathBuf,
+) -> (HealthCheck, Option<String>) {
+    let (Some(command), Some(version_args)) = (&probe.command, &probe.version_args) else {
+        return (
+            HealthCheck::new(CheckStatus::Skipped, "No version command"),
+            None,
+        );
+    };
+    let parts = match command.build_follow_up(version_args) {
+        Ok(parts) => parts,
+        Err(err) => {
+            return (
+                HealthCheck::new(CheckStatus::Error, format!("Invalid command: {err}")),
+                None,
+            );
+        }
+    };
+
+    let (program, args) = ToolCache::global()
+        .resolve(parts.program(), parts.args())
+        .unwrap_or((program, parts.args().to_vec()));
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(VERSION_TIMEOUT, output).await {
+        Err(_) => (
+            HealthCheck::new(CheckStatus::Warning, "Timed out waiting for the version"),
+            None,
+        ),
+        Ok(Err(err)) => (
+            HealthCheck::new(CheckStatus::Error, format!("Failed to run: {err}")),
+            None,
+        ),
+        Ok(Ok(output)) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(str::to_string);
+            (
+                HealthCheck::new(
+                    CheckStatus::Ok,
+                    version.as_deref().unwrap_or("Version command succeeded"),
+                ),
+                version,
+            )
+        }
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or("no output");
+            (
+                HealthCheck::new(
+                    CheckStatus::Error,
+                    format!("Version command exited with {}: {detail}", output.status),
+                ),
+                None,
+            )
+        }
+    }
+}
+
+async fn check_mcp_config(agent: &CodingAgent) -> HealthCheck {
+    if !agent.supports_mcp() {
+        return HealthCheck::new(CheckStatus::Skipped, "MCP is not supported");
+    }
+    let Some(path) = agent.default_mcp_config_path() else {
+        return HealthCheck::new(CheckStatus::Skipped, "No MCP config file");
+    };
+    if !path.exists() {
+        return HealthCheck::new(
+            CheckStatus::Skipped,
+            format!("{} does not exist yet", path.display()),
+        );
+    }
+
+    match read_agent_config(&path, &agent.get_mcp_config()).await {
+        Ok(_) => HealthCheck::new(CheckStatus::Ok, format!("Parsed {}", path.display())),
+        Err(err) => HealthCheck::new(
+            CheckStatus::Error,
+            format!("{} could not be parsed: {err}", path.display()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::command::CommandBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_binary_is_an_error() {
+        let probe = DiagnosticProbe {
+            command: Some(CommandBuilder::new("definitely-not-an-installed-agent")),
+            version_args: Some(vec!["--version".to_string()]),
+            auth_sources: Vec::new(),
+        };
+        let (check, resolved) = check_binary(&probe).await;
+        assert_eq!(check.status, CheckStatus::Error);
+        assert!(resolved.is_none());
+    }
+
+    #[tokio::test]
+    async fn executors_without_a_command_are_skipped() {
+        let (check, resolved) = check_binary(&DiagnosticProbe::default()).await;
+        assert_eq!(check.status, CheckStatus::Skipped);
+        assert!(resolved.is_none());
+    }
+}
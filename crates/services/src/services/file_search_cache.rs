@@ -21,6 +21,7 @@ use ts_rs::TS;
 use super::{
     file_ranker::{FileRanker, FileStats},
     git::GitService,
+    sparse_checkout::SparseCone,
 };
 
 /// Search mode for different use cases
@@ -133,12 +134,14 @@ impl FileSearchCache {
         }
     }
 
-    /// Search files in repository using cache
+    /// Search files in repository using cache, limited to `sparse_cone` when the
+    /// project's worktrees only check out part of the tree
     pub async fn search(
         &self,
         repo_path: &Path,
         query: &str,
         mode: SearchMode,
+        sparse_cone: Option<&SparseCone>,
     ) -> Result<Vec<SearchResult>, CacheError> {
         let repo_path_buf = repo_path.to_path_buf();
 
@@ -148,7 +151,9 @@ impl FileSearchCache {
             && head_info.oid == cached.head_sha
         {
             // Cache hit - perform fast search with mode-based filtering
-            return Ok(self.search_in_cache(&cached, query, mode).await);
+            return Ok(self
+                .search_in_cache(&cached, query, mode, sparse_cone)
+                .await);
         }
 
         // Cache miss - trigger background refresh and return error
@@ -221,6 +226,7 @@ impl FileSearchCache {
         cached: &CachedRepo,
         query: &str,
         mode: SearchMode,
+        sparse_cone: Option<&SparseCone>,
     ) -> Vec<SearchResult> {
         let query_lower = query.to_lowercase();
         let mut results = Vec::new();
@@ -242,6 +248,13 @@ impl FileSearchCache {
                     }
                 }
 
+                // Skip paths that attempt worktrees will not check out
+                if let Some(cone) = sparse_cone
+                    && !cone.contains_path(&indexed_file.path, indexed_file.is_file)
+                {
+                    continue;
+                }
+
                 results.push(SearchResult {
                     path: indexed_file.path.clone(),
                     is_file: indexed_file.is_file,
@@ -0,0 +1,676 @@
+use std::{fmt, future::Future, net::IpAddr, time::Duration};
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use ts_rs::TS;
+
+use crate::services::{
+    github_service::{
+        CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError, RepositoryInfo,
+    },
+    secret_store::{
+        PROVIDER_GITEA, PROVIDER_GITLAB, SECRET_GITHUB_OAUTH, SECRET_GITHUB_PAT, SecretDescriptor,
+        SecretStore, SecretStoreError,
+    },
+    webhooks::is_public,
+};
+
+#[path = "forge/gitea.rs"]
+pub mod gitea;
+#[path = "forge/gitlab.rs"]
+pub mod gitlab;
+
+pub use gitea::GiteaService;
+pub use gitlab::GitLabService;
+
+/// Hosting provider behind a git remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+pub enum ForgeProvider {
+    #[serde(rename = "github")]
+    GitHub,
+    #[serde(rename = "gitlab")]
+    GitLab,
+    #[serde(rename = "gitea")]
+    Gitea,
+}
+
+impl ForgeProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ForgeProvider::GitHub => "github",
+            ForgeProvider::GitLab => "gitlab",
+            ForgeProvider::Gitea => "gitea",
+        }
+    }
+
+    /// Guess the provider from a host name. Self-hosted instances with a
+    /// neutral host name are only recognised once a token was stored for them.
+    pub fn from_host(host: &str) -> Option<Self> {
+        let host = host.to_ascii_lowercase();
+        if host == "github.com" || host.ends_with(".github.com") {
+            Some(ForgeProvider::GitHub)
+        } else if host.contains("gitlab") {
+            Some(ForgeProvider::GitLab)
+        } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+            Some(ForgeProvider::Gitea)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ForgeProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ForgeProvider::GitHub => "GitHub",
+            ForgeProvider::GitLab => "GitLab",
+            ForgeProvider::Gitea => "Gitea",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ForgeError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error("{provider} request failed: {source}")]
+    Http {
+        provider: ForgeProvider,
+        source: reqwest::Error,
+    },
+    #[error("{provider} API responded with status {status}: {message}")]
+    Api {
+        provider: ForgeProvider,
+        status: u16,
+        message: String,
+    },
+    #[error("{0} token is invalid or expired.")]
+    TokenInvalid(ForgeProvider),
+    #[error("Insufficient permissions")]
+    InsufficientPermissions,
+    #[error("Repository not found or no access")]
+    RepoNotFoundOrNoAccess,
+    #[error("No {0} token configured")]
+    NoToken(ForgeProvider),
+    #[error("Unsupported remote URL: {0}")]
+    UnsupportedRemote(String),
+    #[error("{0} resolves to a private or loopback address")]
+    PrivateAddress(String),
+    #[error(transparent)]
+    SecretStore(#[from] SecretStoreError),
+}
+
+impl ForgeError {
+    pub fn is_api_data(&self) -> bool {
+        match self {
+            ForgeError::GitHub(err) => err.is_api_data(),
+            ForgeError::TokenInvalid(_)
+            | ForgeError::NoToken(_)
+            | ForgeError::InsufficientPermissions
+            | ForgeError::RepoNotFoundOrNoAccess => true,
+            _ => false,
+        }
+    }
+
+    pub fn should_retry(&self) -> bool {
+        match self {
+            ForgeError::GitHub(err) => err.should_retry(),
+            ForgeError::Http { .. } => true,
+            ForgeError::Api { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Map provider specific failures onto the error codes the frontend
+    /// already understands for GitHub.
+    pub fn as_api_data(&self) -> Option<GitHubServiceError> {
+        match self {
+            ForgeError::GitHub(GitHubServiceError::TokenInvalid)
+            | ForgeError::TokenInvalid(_)
+            | ForgeError::NoToken(_) => Some(GitHubServiceError::TokenInvalid),
+            ForgeError::GitHub(GitHubServiceError::InsufficientPermissions)
+            | ForgeError::InsufficientPermissions => {
+                Some(GitHubServiceError::InsufficientPermissions)
+            }
+            ForgeError::GitHub(GitHubServiceError::RepoNotFoundOrNoAccess)
+            | ForgeError::RepoNotFoundOrNoAccess => {
+                Some(GitHubServiceError::RepoNotFoundOrNoAccess)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Repository coordinates on a forge, derived from a remote or PR URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRepoInfo {
+    pub provider: ForgeProvider,
+    /// `http` or `https`; SSH remotes take it from the instance's stored base URL, or
+    /// assume HTTPS.
+    pub scheme: String,
+    /// Host including an explicit port, e.g. `gitlab.example.com:8443`.
+    pub host: String,
+    /// Owner or, for GitLab, the full (possibly nested) group path.
+    pub owner: String,
+    pub repo_name: String,
+}
+
+impl ForgeRepoInfo {
+    /// Parse a remote URL and detect the provider from its host name.
+    pub fn from_remote_url(remote_url: &str) -> Result<Self, ForgeError> {
+        let (_, host, _) = split_remote_url(remote_url)?;
+        let host_name = host.split(':').next().unwrap_or(&host);
+        let provider = ForgeProvider::from_host(host_name)
+            .ok_or_else(|| ForgeError::UnsupportedRemote(remote_url.to_string()))?;
+        Self::parse(remote_url, provider)
+    }
+
+    /// Parse a remote, merge request or pull request URL for a known provider.
+    /// Supports SSH (`git@host:path`, `ssh://`) and HTTP(S) forms.
+    pub fn parse(remote_url: &str, provider: ForgeProvider) -> Result<Self, ForgeError> {
+        let (scheme, host, path) = split_remote_url(remote_url)?;
+
+        // GitLab separates the project path from resources with `/-/`
+        let path = path.split("/-/").next().unwrap_or_default();
+        let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match provider {
+            ForgeProvider::GitHub | ForgeProvider::Gitea => segments.truncate(2),
+            ForgeProvider::GitLab => {
+                if let Some(pos) = segments.iter().position(|s| *s == "merge_requests") {
+                    segments.truncate(pos);
+                }
+            }
+        }
+
+        if segments.len() < 2 {
+            return Err(ForgeError::UnsupportedRemote(remote_url.to_string()));
+        }
+        let repo_name = segments.pop().unwrap_or_default();
+        let repo_name = repo_name.strip_suffix(".git").unwrap_or(repo_name);
+
+        Ok(Self {
+            provider,
+            scheme,
+            host,
+            owner: segments.join("/"),
+            repo_name: repo_name.to_string(),
+        })
+    }
+
+    pub fn web_base_url(&self) -> String {
+        format!("{}://{}", self.scheme, self.host)
+    }
+
+    /// Host name without port, used as the key for stored tokens.
+    pub fn host_name(&self) -> &str {
+        self.host.split(':').next().unwrap_or(&self.host)
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo_name)
+    }
+
+    /// HTTP(S) URL of the repository, used to push with the forge token.
+    pub fn clone_url(&self) -> String {
+        format!("{}/{}.git", self.web_base_url(), self.full_name())
+    }
+}
+
+impl From<&ForgeRepoInfo> for GitHubRepoInfo {
+    fn from(info: &ForgeRepoInfo) -> Self {
+        GitHubRepoInfo {
+            owner: info.owner.clone(),
+            repo_name: info.repo_name.clone(),
+        }
+    }
+}
+
+/// Split a remote URL into `(scheme, host, path)`.
+fn split_remote_url(remote_url: &str) -> Result<(String, String, String), ForgeError> {
+    let unsupported = || ForgeError::UnsupportedRemote(remote_url.to_string());
+    let url = remote_url.trim().trim_end_matches('/');
+
+    let (scheme, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        ("https", rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        ("http", rest)
+    } else if let Some(rest) = url.strip_prefix("ssh://") {
+        let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+        let (authority, path) = rest.split_once('/').ok_or_else(unsupported)?;
+        // Drop the SSH port, the HTTP API lives on the default port
+        let host = authority.split(':').next().unwrap_or(authority);
+        return Ok(("https".to_string(), host.to_string(), path.to_string()));
+    } else if !url.contains("://") && url.contains(':') {
+        // scp-like syntax: git@host:owner/repo.git
+        let rest = url.split_once('@').map_or(url, |(_, r)| r);
+        let (host, path) = rest.split_once(':').ok_or_else(unsupported)?;
+        return Ok(("https".to_string(), host.to_string(), path.to_string()));
+    } else {
+        return Err(unsupported());
+    };
+
+    let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+    let (host, path) = rest.split_once('/').ok_or_else(unsupported)?;
+    if host.is_empty() {
+        return Err(unsupported());
+    }
+    Ok((scheme.to_string(), host.to_string(), path.to_string()))
+}
+
+/// Merge request operations shared by all supported forges.
+#[async_trait]
+pub trait ForgeService: Send + Sync {
+    fn provider(&self) -> ForgeProvider;
+
+    async fn check_token(&self) -> Result<(), ForgeError>;
+
+    async fn get_repository(&self, repo_info: &ForgeRepoInfo)
+    -> Result<RepositoryInfo, ForgeError>;
+
+    async fn create_pr(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError>;
+
+    async fn update_pr_status(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, ForgeError>;
+
+    /// List all pull/merge requests for a branch (including closed/merged)
+    async fn list_all_prs_for_branch(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError>;
+}
+
+#[async_trait]
+impl ForgeService for GitHubService {
+    fn provider(&self) -> ForgeProvider {
+        ForgeProvider::GitHub
+    }
+
+    async fn check_token(&self) -> Result<(), ForgeError> {
+        Ok(GitHubService::check_token(self).await?)
+    }
+
+    async fn get_repository(
+        &self,
+        repo_info: &ForgeRepoInfo,
+    ) -> Result<RepositoryInfo, ForgeError> {
+        Ok(GitHubService::get_repository(self, &repo_info.into()).await?)
+    }
+
+    async fn create_pr(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(GitHubService::create_pr(self, &repo_info.into(), request).await?)
+    }
+
+    async fn update_pr_status(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(GitHubService::update_pr_status(self, &repo_info.into(), pr_number).await?)
+    }
+
+    async fn list_all_prs_for_branch(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        Ok(GitHubService::list_all_prs_for_branch(self, &repo_info.into(), branch_name).await?)
+    }
+}
+
+/// Build the service for `provider`, talking to the instance at `base_url`.
+/// The base URL is ignored for GitHub, which always uses the public API.
+pub fn forge_service(
+    provider: ForgeProvider,
+    base_url: &str,
+    token: &str,
+) -> Result<Box<dyn ForgeService>, ForgeError> {
+    Ok(match provider {
+        ForgeProvider::GitHub => Box::new(GitHubService::new(token)?),
+        ForgeProvider::GitLab => Box::new(GitLabService::new(base_url, token)?),
+        ForgeProvider::Gitea => Box::new(GiteaService::new(base_url, token)?),
+    })
+}
+
+/// A forge service ready to act on one repository.
+pub struct ForgeConnection {
+    pub repo_info: ForgeRepoInfo,
+    pub service: Box<dyn ForgeService>,
+    /// Token used for the API; also valid for HTTPS pushes to the same host.
+    pub token: String,
+}
+
+/// Resolves remotes to providers and loads the matching token from the [`SecretStore`].
+///
+/// GitHub keeps using the PAT/OAuth secrets from the device flow. GitLab and Gitea
+/// tokens are stored per host, which also registers self-hosted instances whose
+/// host name does not reveal the provider.
+#[derive(Clone)]
+pub struct ForgeResolver {
+    secret_store: SecretStore,
+    user_id: String,
+    /// Signed-in user whose linked GitHub token is preferred over the server's
+    linked_user_id: Option<String>,
+}
+
+impl ForgeResolver {
+    pub fn new(secret_store: SecretStore, user_id: impl Into<String>) -> Self {
+        Self {
+            secret_store,
+            user_id: user_id.into(),
+            linked_user_id: None,
+        }
+    }
+
+    /// Prefer the GitHub token `user_id` linked to their account, if any
+    pub fn with_linked_user(mut self, user_id: impl Into<String>) -> Self {
+        self.linked_user_id = Some(user_id.into());
+        self
+    }
+
+    pub async fn detect(&self, remote_url: &str) -> Result<ForgeRepoInfo, ForgeError> {
+        let (_, host, _) = split_remote_url(remote_url)?;
+        let host_name = host.split(':').next().unwrap_or(&host);
+        if ForgeProvider::from_host(host_name) != Some(ForgeProvider::GitHub) {
+            for provider in [ForgeProvider::GitLab, ForgeProvider::Gitea] {
+                if self.host_token(provider, host_name).await?.is_some() {
+                    let mut repo_info = ForgeRepoInfo::parse(remote_url, provider)?;
+                    // An SSH remote says nothing about how the instance serves HTTP
+                    let ssh_remote = !remote_url.trim().starts_with("http://")
+                        && !remote_url.trim().starts_with("https://");
+                    if ssh_remote
+                        && let Some(base_url) = self.host_base_url(provider, host_name).await?
+                        && let Some((scheme, rest)) = base_url.split_once("://")
+                    {
+                        repo_info.scheme = scheme.to_string();
+                        repo_info.host = rest.split('/').next().unwrap_or(rest).to_string();
+                    }
+                    return Ok(repo_info);
+                }
+            }
+        }
+        ForgeRepoInfo::from_remote_url(remote_url)
+    }
+
+    pub async fn token(&self, repo_info: &ForgeRepoInfo) -> Result<Option<String>, ForgeError> {
+        match repo_info.provider {
+            ForgeProvider::GitHub => {
+                if let Some(linked_user_id) = &self.linked_user_id
+                    && linked_user_id != &self.user_id
+                    && let Some(token) = self
+                        .secret_store
+                        .get_secret_string(linked_user_id, SECRET_GITHUB_OAUTH)
+                        .await?
+                {
+                    return Ok(Some(token));
+                }
+                for descriptor in [SECRET_GITHUB_PAT, SECRET_GITHUB_OAUTH] {
+                    if let Some(token) = self
+                        .secret_store
+                        .get_secret_string(&self.user_id, descriptor)
+                        .await?
+                    {
+                        return Ok(Some(token));
+                    }
+                }
+                Ok(None)
+            }
+            provider => self.host_token(provider, repo_info.host_name()).await,
+        }
+    }
+
+    pub async fn connect(&self, remote_url: &str) -> Result<ForgeConnection, ForgeError> {
+        let repo_info = self.detect(remote_url).await?;
+        let token = self
+            .token(&repo_info)
+            .await?
+            .ok_or(ForgeError::NoToken(repo_info.provider))?;
+        let service = forge_service(repo_info.provider, &repo_info.web_base_url(), &token)?;
+        Ok(ForgeConnection {
+            repo_info,
+            service,
+            token,
+        })
+    }
+
+    /// Store a token for a self-hosted GitLab or Gitea instance, along with the base URL
+    /// (`http://gitea.local:3000`) the instance serves its API and HTTP remotes on.
+    pub async fn store_host_token(
+        &self,
+        provider: ForgeProvider,
+        base_url: &str,
+        host: &str,
+        token: &str,
+    ) -> Result<(), ForgeError> {
+        let name = host_secret_name(host);
+        let descriptor = host_secret_descriptor(provider, &name)?;
+        self.secret_store
+            .put_secret(&self.user_id, descriptor, token.as_bytes())
+            .await?;
+        let name = host_base_url_name(host);
+        let descriptor = host_secret_descriptor(provider, &name)?;
+        self.secret_store
+            .put_secret(
+                &self.user_id,
+                descriptor,
+                base_url.trim_end_matches('/').as_bytes(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_host_token(
+        &self,
+        provider: ForgeProvider,
+        host: &str,
+    ) -> Result<(), ForgeError> {
+        for name in [host_secret_name(host), host_base_url_name(host)] {
+            let descriptor = host_secret_descriptor(provider, &name)?;
+            self.secret_store
+                .delete_secret(&self.user_id, descriptor)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn host_token(
+        &self,
+        provider: ForgeProvider,
+        host: &str,
+    ) -> Result<Option<String>, ForgeError> {
+        let name = host_secret_name(host);
+        let descriptor = host_secret_descriptor(provider, &name)?;
+        Ok(self
+            .secret_store
+            .get_secret_string(&self.user_id, descriptor)
+            .await?)
+    }
+
+    async fn host_base_url(
+        &self,
+        provider: ForgeProvider,
+        host: &str,
+    ) -> Result<Option<String>, ForgeError> {
+        let name = host_base_url_name(host);
+        let descriptor = host_secret_descriptor(provider, &name)?;
+        Ok(self
+            .secret_store
+            .get_secret_string(&self.user_id, descriptor)
+            .await?)
+    }
+}
+
+fn host_secret_name(host: &str) -> String {
+    format!("pat:{}", normalized_host(host))
+}
+
+fn host_base_url_name(host: &str) -> String {
+    format!("base_url:{}", normalized_host(host))
+}
+
+fn normalized_host(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    host.split(':').next().unwrap_or(&host).to_string()
+}
+
+fn host_secret_descriptor(
+    provider: ForgeProvider,
+    name: &str,
+) -> Result<SecretDescriptor<'_>, ForgeError> {
+    let provider = match provider {
+        ForgeProvider::GitLab => PROVIDER_GITLAB,
+        ForgeProvider::Gitea => PROVIDER_GITEA,
+        ForgeProvider::GitHub => {
+            return Err(ForgeError::UnsupportedRemote(
+                "GitHub tokens are managed through the GitHub login flow".to_string(),
+            ));
+        }
+    };
+    Ok(SecretDescriptor { provider, name })
+}
+
+/// Refuse an instance URL whose host resolves to a loopback, private or link-local address,
+/// so forge settings can't be used to reach services on the server's own network, unless
+/// `ANYON_FORGE_ALLOW_PRIVATE=true`
+pub async fn ensure_public_base_url(base_url: &str) -> Result<(), ForgeError> {
+    let invalid = || ForgeError::UnsupportedRemote(base_url.to_string());
+    let parsed = reqwest::Url::parse(base_url).map_err(|_| invalid())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid());
+    }
+    let host = parsed.host_str().ok_or_else(invalid)?;
+    let port = parsed.port_or_known_default().ok_or_else(invalid)?;
+    let allow_private = std::env::var("ANYON_FORGE_ALLOW_PRIVATE")
+        .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"));
+    if allow_private {
+        return Ok(());
+    }
+
+    let addrs: Vec<IpAddr> = match host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| invalid())?
+            .map(|addr| addr.ip())
+            .collect(),
+    };
+    if addrs.is_empty() || addrs.into_iter().any(|ip| !is_public(ip)) {
+        return Err(ForgeError::PrivateAddress(base_url.to_string()));
+    }
+    Ok(())
+}
+
+/// Retry transient forge API failures with the same policy as the GitHub client. Only for
+/// idempotent requests; creating a pull request goes through [`find_created_pr`] instead.
+pub(crate) async fn with_retry<T, F, Fut>(provider: ForgeProvider, f: F) -> Result<T, ForgeError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ForgeError>>,
+{
+    f.retry(
+        &ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter(),
+    )
+    .when(|e| e.should_retry())
+    .notify(|err: &ForgeError, dur: Duration| {
+        tracing::warn!(
+            "{} API call failed, retrying after {:.2}s: {}",
+            provider,
+            dur.as_secs_f64(),
+            err
+        );
+    })
+    .await
+}
+
+/// Pull request creation is sent once, since repeating it could open a duplicate. A transient
+/// failure may still have reached the forge, so an open pull request of the head branch is
+/// returned in place of such an error.
+pub(crate) async fn find_created_pr<S: ForgeService + ?Sized>(
+    service: &S,
+    repo_info: &ForgeRepoInfo,
+    request: &CreatePrRequest,
+    error: ForgeError,
+) -> Result<PullRequestInfo, ForgeError> {
+    if !error.should_retry() {
+        return Err(error);
+    }
+    let open = service
+        .list_all_prs_for_branch(repo_info, &request.head_branch)
+        .await?
+        .into_iter()
+        .find(|pr| matches!(pr.status, MergeStatus::Open));
+    match open {
+        Some(pr) => {
+            tracing::warn!(
+                "{} pull request creation for branch {} failed ({}), but #{} is open",
+                service.provider(),
+                request.head_branch,
+                error,
+                pr.number
+            );
+            Ok(pr)
+        }
+        None => Err(error),
+    }
+}
+
+/// Send a request and decode a JSON body, mapping HTTP failures onto [`ForgeError`].
+pub(crate) async fn send_json<T: DeserializeOwned>(
+    provider: ForgeProvider,
+    request: reqwest::RequestBuilder,
+) -> Result<T, ForgeError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|source| ForgeError::Http { provider, source })?;
+    let status = response.status();
+    if !status.is_success() {
+        let message = response.text().await.unwrap_or_default();
+        return Err(match status.as_u16() {
+            401 => ForgeError::TokenInvalid(provider),
+            403 => ForgeError::InsufficientPermissions,
+            404 => ForgeError::RepoNotFoundOrNoAccess,
+            code => ForgeError::Api {
+                provider,
+                status: code,
+                message: message.trim().to_string(),
+            },
+        });
+    }
+    response
+        .json::<T>()
+        .await
+        .map_err(|source| ForgeError::Http { provider, source })
+}
+
+pub(crate) fn http_client(provider: ForgeProvider) -> Result<reqwest::Client, ForgeError> {
+    reqwest::Client::builder()
+        .user_agent(concat!("anyon/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|source| ForgeError::Http { provider, source })
+}
@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use super::{
+    ForgeError, ForgeProvider, ForgeRepoInfo, ForgeService, find_created_pr, http_client,
+    send_json, with_retry,
+};
+use crate::services::github_service::{CreatePrRequest, RepositoryInfo};
+
+const PROVIDER: ForgeProvider = ForgeProvider::Gitea;
+const PAGE_SIZE: usize = 50;
+const MAX_PAGES: usize = 10;
+
+#[derive(Debug, Deserialize)]
+struct GiteaBranchRef {
+    #[serde(rename = "ref")]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaPullRequest {
+    number: i64,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: GiteaBranchRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaOwner {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaRepository {
+    id: i64,
+    name: String,
+    full_name: String,
+    owner: GiteaOwner,
+    description: Option<String>,
+    clone_url: String,
+    ssh_url: String,
+    default_branch: Option<String>,
+    #[serde(default)]
+    private: bool,
+}
+
+/// Gitea (and Forgejo) pull requests through the REST API v1.
+#[derive(Debug, Clone)]
+pub struct GiteaService {
+    client: reqwest::Client,
+    api_base: String,
+    token: String,
+}
+
+impl GiteaService {
+    /// `base_url` is the web root of the instance, e.g. `https://gitea.example.com`
+    pub fn new(base_url: &str, token: &str) -> Result<Self, ForgeError> {
+        Ok(Self {
+            client: http_client(PROVIDER)?,
+            api_base: format!("{}/api/v1", base_url.trim_end_matches('/')),
+            token: token.to_string(),
+        })
+    }
+
+    fn repo_url(&self, repo_info: &ForgeRepoInfo) -> String {
+        format!(
+            "{}/repos/{}/{}",
+            self.api_base, repo_info.owner, repo_info.repo_name
+        )
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get(url)
+            .header("Authorization", format!("token {}", self.token))
+    }
+
+    fn map_pull_request(pr: GiteaPullRequest) -> PullRequestInfo {
+        let status = match pr.state.as_str() {
+            "open" => MergeStatus::Open,
+            "closed" if pr.merged || pr.merged_at.is_some() => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestInfo {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+        }
+    }
+}
+
+#[async_trait]
+impl ForgeService for GiteaService {
+    fn provider(&self) -> ForgeProvider {
+        PROVIDER
+    }
+
+    async fn check_token(&self) -> Result<(), ForgeError> {
+        let url = format!("{}/user", self.api_base);
+        send_json::<serde_json::Value>(PROVIDER, self.get(&url)).await?;
+        Ok(())
+    }
+
+    async fn get_repository(
+        &self,
+        repo_info: &ForgeRepoInfo,
+    ) -> Result<RepositoryInfo, ForgeError> {
+        let url = self.repo_url(repo_info);
+        let repo: GiteaRepository =
+            with_retry(PROVIDER, || send_json(PROVIDER, self.get(&url))).await?;
+        Ok(RepositoryInfo {
+            id: repo.id,
+            name: repo.name,
+            full_name: repo.full_name,
+            owner: repo.owner.login,
+            description: repo.description.filter(|d| !d.is_empty()),
+            clone_url: repo.clone_url,
+            ssh_url: repo.ssh_url,
+            default_branch: repo.default_branch.unwrap_or_else(|| "main".to_string()),
+            private: repo.private,
+        })
+    }
+
+    async fn create_pr(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let url = format!("{}/pulls", self.repo_url(repo_info));
+        let body = json!({
+            "head": request.head_branch,
+            "base": request.base_branch,
+            "title": request.title,
+            "body": request.body.as_deref().unwrap_or(""),
+        });
+        let pr: GiteaPullRequest = match send_json(
+            PROVIDER,
+            self.client
+                .post(&url)
+                .header("Authorization", format!("token {}", self.token))
+                .json(&body),
+        )
+        .await
+        {
+            Ok(pr) => pr,
+            Err(e) => return find_created_pr(self, repo_info, request, e).await,
+        };
+
+        info!(
+            "Created Gitea PR #{} for branch {} in {}",
+            pr.number,
+            request.head_branch,
+            repo_info.full_name()
+        );
+        Ok(Self::map_pull_request(pr))
+    }
+
+    async fn update_pr_status(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let url = format!("{}/pulls/{pr_number}", self.repo_url(repo_info));
+        let pr: GiteaPullRequest =
+            with_retry(PROVIDER, || send_json(PROVIDER, self.get(&url))).await?;
+        Ok(Self::map_pull_request(pr))
+    }
+
+    async fn list_all_prs_for_branch(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        // The list endpoint cannot filter by head branch, so page through and filter locally
+        let url = format!("{}/pulls", self.repo_url(repo_info));
+        let mut matching = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let page = page.to_string();
+            let limit = PAGE_SIZE.to_string();
+            let prs: Vec<GiteaPullRequest> = with_retry(PROVIDER, || {
+                send_json(
+                    PROVIDER,
+                    self.get(&url).query(&[
+                        ("state", "all"),
+                        ("page", page.as_str()),
+                        ("limit", limit.as_str()),
+                    ]),
+                )
+            })
+            .await?;
+            let exhausted = prs.len() < PAGE_SIZE;
+            matching.extend(
+                prs.into_iter()
+                    .filter(|pr| pr.head.name == branch_name)
+                    .map(Self::map_pull_request),
+            );
+            if exhausted {
+                break;
+            }
+        }
+        Ok(matching)
+    }
+}
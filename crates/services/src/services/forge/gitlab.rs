@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use super::{
+    ForgeError, ForgeProvider, ForgeRepoInfo, ForgeService, find_created_pr, http_client,
+    send_json, with_retry,
+};
+use crate::services::github_service::{CreatePrRequest, RepositoryInfo};
+
+const PROVIDER: ForgeProvider = ForgeProvider::GitLab;
+
+#[derive(Debug, Deserialize)]
+struct GitLabMergeRequest {
+    iid: i64,
+    web_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNamespace {
+    full_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabProject {
+    id: i64,
+    name: String,
+    path_with_namespace: String,
+    namespace: GitLabNamespace,
+    description: Option<String>,
+    http_url_to_repo: String,
+    ssh_url_to_repo: String,
+    default_branch: Option<String>,
+    visibility: Option<String>,
+}
+
+/// GitLab merge requests through the REST API v4 (gitlab.com or self-hosted).
+#[derive(Debug, Clone)]
+pub struct GitLabService {
+    client: reqwest::Client,
+    api_base: String,
+    token: String,
+}
+
+impl GitLabService {
+    /// `base_url` is the web root of the instance, e.g. `https://gitlab.example.com`
+    pub fn new(base_url: &str, token: &str) -> Result<Self, ForgeError> {
+        Ok(Self {
+            client: http_client(PROVIDER)?,
+            api_base: format!("{}/api/v4", base_url.trim_end_matches('/')),
+            token: token.to_string(),
+        })
+    }
+
+    fn project_url(&self, repo_info: &ForgeRepoInfo) -> String {
+        // Projects are addressed by their URL-encoded full path
+        let id = repo_info.full_name().replace('/', "%2F");
+        format!("{}/projects/{id}", self.api_base)
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client.get(url).header("PRIVATE-TOKEN", &self.token)
+    }
+
+    fn map_merge_request(mr: GitLabMergeRequest) -> PullRequestInfo {
+        let status = match mr.state.as_str() {
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status,
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+#[async_trait]
+impl ForgeService for GitLabService {
+    fn provider(&self) -> ForgeProvider {
+        PROVIDER
+    }
+
+    async fn check_token(&self) -> Result<(), ForgeError> {
+        let url = format!("{}/user", self.api_base);
+        send_json::<serde_json::Value>(PROVIDER, self.get(&url)).await?;
+        Ok(())
+    }
+
+    async fn get_repository(
+        &self,
+        repo_info: &ForgeRepoInfo,
+    ) -> Result<RepositoryInfo, ForgeError> {
+        let url = self.project_url(repo_info);
+        let project: GitLabProject =
+            with_retry(PROVIDER, || send_json(PROVIDER, self.get(&url))).await?;
+        Ok(RepositoryInfo {
+            id: project.id,
+            name: project.name,
+            full_name: project.path_with_namespace,
+            owner: project.namespace.full_path,
+            description: project.description,
+            clone_url: project.http_url_to_repo,
+            ssh_url: project.ssh_url_to_repo,
+            default_branch: project.default_branch.unwrap_or_else(|| "main".to_string()),
+            private: project.visibility.as_deref() != Some("public"),
+        })
+    }
+
+    async fn create_pr(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let url = format!("{}/merge_requests", self.project_url(repo_info));
+        let body = json!({
+            "source_branch": request.head_branch,
+            "target_branch": request.base_branch,
+            "title": request.title,
+            "description": request.body.as_deref().unwrap_or(""),
+        });
+        let mr: GitLabMergeRequest = match send_json(
+            PROVIDER,
+            self.client
+                .post(&url)
+                .header("PRIVATE-TOKEN", &self.token)
+                .json(&body),
+        )
+        .await
+        {
+            Ok(mr) => mr,
+            Err(e) => return find_created_pr(self, repo_info, request, e).await,
+        };
+
+        info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid,
+            request.head_branch,
+            repo_info.full_name()
+        );
+        Ok(Self::map_merge_request(mr))
+    }
+
+    async fn update_pr_status(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let url = format!("{}/merge_requests/{pr_number}", self.project_url(repo_info));
+        let mr: GitLabMergeRequest =
+            with_retry(PROVIDER, || send_json(PROVIDER, self.get(&url))).await?;
+        Ok(Self::map_merge_request(mr))
+    }
+
+    async fn list_all_prs_for_branch(
+        &self,
+        repo_info: &ForgeRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        let url = format!("{}/merge_requests", self.project_url(repo_info));
+        let mrs: Vec<GitLabMergeRequest> = with_retry(PROVIDER, || {
+            send_json(
+                PROVIDER,
+                self.get(&url).query(&[
+                    ("source_branch", branch_name),
+                    ("state", "all"),
+                    ("per_page", "100"),
+                ]),
+            )
+        })
+        .await?;
+        Ok(mrs.into_iter().map(Self::map_merge_request).collect())
+    }
+}
@@ -119,6 +119,16 @@ pub struct WorktreeResetOutcome {
     pub applied: bool,
 }
 
+/// Hunk of an imported patch that did not apply cleanly
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PatchConflict {
+    pub path: String,
+    /// 1-based index of the hunk within the file's patch
+    pub hunk: usize,
+    /// The rejected hunk, including its `@@` header
+    pub content: String,
+}
+
 /// Target for diff generation
 pub enum DiffTarget<'p> {
     /// Work-in-progress branch checked out in this worktree
@@ -460,10 +470,14 @@ impl GitService {
                     if content_omitted {
                         (path_opt, None)
                     } else {
-                        let details = delta
-                            .old_file()
-                            .path()
-                            .map(|p| self.create_file_details(p, &delta.old_file().id(), repo));
+                        let details = delta.old_file().path().map(|p| {
+                            self.create_file_details(
+                                p,
+                                &delta.old_file().id(),
+                                delta.old_file().mode(),
+                                repo,
+                            )
+                        });
                         (
                             details.as_ref().and_then(|f| f.file_name.clone()),
                             details.and_then(|f| f.content),
@@ -481,10 +495,14 @@ impl GitService {
                     if content_omitted {
                         (path_opt, None)
                     } else {
-                        let details = delta
-                            .new_file()
-                            .path()
-                            .map(|p| self.create_file_details(p, &delta.new_file().id(), repo));
+                        let details = delta.new_file().path().map(|p| {
+                            self.create_file_details(
+                                p,
+                                &delta.new_file().id(),
+                                delta.new_file().mode(),
+                                repo,
+                            )
+                        });
                         (
                             details.as_ref().and_then(|f| f.file_name.clone()),
                             details.and_then(|f| f.content),
@@ -605,17 +623,45 @@ impl GitService {
         }
     }
 
+    /// Content shown for a submodule entry, matching what `git diff` prints for gitlinks
+    fn submodule_pointer(commit: git2::Oid) -> String {
+        format!("Subproject commit {commit}\n")
+    }
+
+    /// Commit the submodule at `rel_path` currently points to: its checked out HEAD,
+    /// or the gitlink recorded in HEAD when the submodule is not initialised.
+    fn submodule_commit(repo: &Repository, rel_path: &Path) -> Option<git2::Oid> {
+        let workdir = repo.workdir()?;
+        if let Ok(submodule) = Repository::open(workdir.join(rel_path))
+            && let Some(oid) = submodule.head().ok().and_then(|head| head.target())
+        {
+            return Some(oid);
+        }
+        let entry = repo
+            .head()
+            .ok()?
+            .peel_to_tree()
+            .ok()?
+            .get_path(rel_path)
+            .ok()?;
+        (entry.kind() == Some(git2::ObjectType::Commit)).then(|| entry.id())
+    }
+
     /// Create FileDiffDetails from path and blob with filesystem fallback
     fn create_file_details(
         &self,
         path: &Path,
         blob_id: &git2::Oid,
+        mode: git2::FileMode,
         repo: &Repository,
     ) -> FileDiffDetails {
         let file_name = path.to_string_lossy().to_string();
 
         // Try to get content from blob first (for non-zero OIDs)
-        let content = if !blob_id.is_zero() {
+        let content = if mode == git2::FileMode::Commit {
+            // Submodules are gitlinks: the id is a commit in another repository
+            Some(Self::submodule_pointer(*blob_id))
+        } else if !blob_id.is_zero() {
             repo.find_blob(*blob_id)
                 .ok()
                 .and_then(|blob| Self::blob_to_string(&blob))
@@ -703,6 +749,9 @@ impl GitService {
                         .find_blob(entry.id())
                         .ok()
                         .and_then(|b| Self::blob_to_string(&b)),
+                    Ok(entry) if entry.kind() == Some(git2::ObjectType::Commit) => {
+                        Some(Self::submodule_pointer(entry.id()))
+                    }
                     _ => None,
                 }
             } else {
@@ -712,7 +761,15 @@ impl GitService {
             // Load new content from filesystem (worktree) when available
             let new_content = if let Some(ref newp) = new_path_opt {
                 let rel = std::path::Path::new(newp);
-                Self::read_file_to_string(repo, rel)
+                if repo
+                    .workdir()
+                    .is_some_and(|workdir| workdir.join(rel).is_dir())
+                {
+                    // Only submodules show up as directories in a status diff
+                    Self::submodule_commit(repo, rel).map(Self::submodule_pointer)
+                } else {
+                    Self::read_file_to_string(repo, rel)
+                }
             } else {
                 None
             };
@@ -861,6 +918,41 @@ impl GitService {
             }
         }
     }
+    /// Undo a squash merge performed by [`GitService::merge_changes`] by moving the
+    /// base and task branches back to the commits they pointed at beforehand.
+    /// A checked-out base branch is reset with `--keep` so local edits survive.
+    pub fn rollback_merge(
+        &self,
+        repo_path: &Path,
+        base_branch_name: &str,
+        base_oid: &str,
+        task_branch_name: &str,
+        task_oid: &str,
+    ) -> Result<(), GitServiceError> {
+        let git_cli = GitCli::new();
+        match self.find_checkout_path_for_branch(repo_path, base_branch_name)? {
+            Some(base_checkout_path) => {
+                git_cli.git(&base_checkout_path, ["reset", "--keep", base_oid])?;
+            }
+            None => {
+                let base_refname = {
+                    let repo = self.open_repo(repo_path)?;
+                    let branch = Self::find_branch(&repo, base_branch_name)?;
+                    branch.get().name().map(str::to_string).ok_or_else(|| {
+                        GitServiceError::BranchNotFound(base_branch_name.to_string())
+                    })?
+                };
+                git_cli.update_ref(repo_path, &base_refname, base_oid)?;
+            }
+        }
+        git_cli.update_ref(
+            repo_path,
+            &format!("refs/heads/{task_branch_name}"),
+            task_oid,
+        )?;
+        Ok(())
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -915,6 +1007,76 @@ impl GitService {
         Ok(Commit::new(oid))
     }
 
+    /// Export the commits of `branch_name` since it forked from `base_branch_name`
+    /// as a `git format-patch` mbox.
+    pub fn format_patch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let base = self.get_base_commit(repo_path, branch_name, base_branch_name)?;
+        let git = GitCli::new();
+        Ok(git.format_patch(repo_path, &base.to_string(), branch_name)?)
+    }
+
+    /// Unified diff of the worktree against `base_commit`, including untracked and
+    /// binary files, that `git apply` can replay in full.
+    pub fn diff_patch(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+    ) -> Result<String, GitServiceError> {
+        let git = GitCli::new();
+        Ok(git.diff_binary(worktree_path, base_commit)?)
+    }
+
+    /// Apply an uploaded patch to the worktree and commit the result.
+    ///
+    /// `format-patch` mboxes are replayed with `git am --3way` to keep their
+    /// commits. Anything else, or an mbox that does not apply, falls back to
+    /// `git apply --reject`: the hunks that fit are committed with `message` and
+    /// the rest are returned as conflicts.
+    pub fn apply_patch(
+        &self,
+        worktree_path: &Path,
+        patch: &str,
+        message: &str,
+    ) -> Result<Vec<PatchConflict>, GitServiceError> {
+        let patch_file = tempfile::NamedTempFile::new()?;
+        std::fs::write(patch_file.path(), patch)?;
+
+        let git = GitCli::new();
+        self.ensure_cli_commit_identity(worktree_path)?;
+        if patch.starts_with("From ") {
+            match git.am_3way(worktree_path, patch_file.path()) {
+                Ok(()) => return Ok(Vec::new()),
+                Err(e) => tracing::info!("git am failed, applying hunks individually: {e}"),
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        for rejected in git.apply_with_rejects(worktree_path, patch_file.path())? {
+            let rej_path = worktree_path.join(format!("{}.rej", rejected.path));
+            let rej = std::fs::read_to_string(&rej_path).unwrap_or_default();
+            let _ = std::fs::remove_file(&rej_path);
+            let hunks = utils::diff::extract_unified_diff_hunks(&rej);
+            conflicts.extend(
+                rejected
+                    .hunks
+                    .into_iter()
+                    .zip(hunks)
+                    .map(|(hunk, content)| PatchConflict {
+                        path: rejected.path.clone(),
+                        hunk,
+                        content,
+                    }),
+            );
+        }
+        self.commit(worktree_path, message)?;
+        Ok(conflicts)
+    }
+
     pub fn get_remote_branch_status(
         &self,
         repo_path: &Path,
@@ -956,9 +1118,19 @@ impl GitService {
         let statuses = repo.statuses(Some(&mut status_options))?;
 
         if !statuses.is_empty() {
+            let index = repo.index()?;
             let mut dirty_files = Vec::new();
             for entry in statuses.iter() {
                 let status = entry.status();
+                // Files outside a sparse checkout are absent on purpose
+                if status == git2::Status::WT_DELETED
+                    && let Some(path) = entry.path()
+                    && index.get_path(Path::new(path), 0).is_some_and(|e| {
+                        e.flags_extended & git2::IndexEntryExtendedFlag::SKIP_WORKTREE.bits() != 0
+                    })
+                {
+                    continue;
+                }
                 // Only consider files that are actually tracked and modified
                 if status.intersects(
                     git2::Status::INDEX_MODIFIED
@@ -1693,21 +1865,27 @@ impl GitService {
         }
     }
 
-    /// Extract GitHub owner and repo name from git repo path
-    pub fn get_github_repo_info(
-        &self,
-        repo_path: &Path,
-    ) -> Result<GitHubRepoInfo, GitServiceError> {
+    /// Get the URL of the default remote of the repository
+    pub fn get_remote_url(&self, repo_path: &Path) -> Result<String, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
         let remote_name = self.default_remote_name(&repo);
         let remote = repo.find_remote(&remote_name).map_err(|_| {
             GitServiceError::InvalidRepository(format!("No '{remote_name}' remote found"))
         })?;
 
-        let url = remote
+        remote
             .url()
-            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
-        GitHubRepoInfo::from_remote_url(url).map_err(|e| {
+            .map(|url| url.to_string())
+            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))
+    }
+
+    /// Extract GitHub owner and repo name from git repo path
+    pub fn get_github_repo_info(
+        &self,
+        repo_path: &Path,
+    ) -> Result<GitHubRepoInfo, GitServiceError> {
+        let url = self.get_remote_url(repo_path)?;
+        GitHubRepoInfo::from_remote_url(&url).map_err(|e| {
             GitServiceError::InvalidRepository(format!("Failed to parse remote URL: {e}"))
         })
     }
@@ -1749,28 +1927,23 @@ impl GitService {
         })
     }
 
-    pub fn push_to_github(
+    /// Push a branch to the default remote using a forge token. `push_url` is the
+    /// repository's HTTP(S) URL on the forge (see `ForgeRepoInfo::clone_url`), since the
+    /// remote itself may be an SSH URL.
+    pub fn push_to_remote(
         &self,
         worktree_path: &Path,
+        push_url: &str,
         branch_name: &str,
-        github_token: &str,
+        token: &str,
     ) -> Result<(), GitServiceError> {
         let repo = Repository::open(worktree_path)?;
         self.check_worktree_clean(&repo)?;
 
-        // Get the remote
         let remote_name = self.default_remote_name(&repo);
-        let remote = repo.find_remote(&remote_name)?;
-
-        let remote_url = remote
-            .url()
-            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
-        let https_url = self.convert_to_https_url(remote_url);
         let git_cli = GitCli::new();
-        if let Err(e) =
-            git_cli.push_with_token(worktree_path, &https_url, branch_name, github_token)
-        {
-            tracing::error!("Push to GitHub failed: {}", e);
+        if let Err(e) = git_cli.push_with_token(worktree_path, push_url, branch_name, token) {
+            tracing::error!("Push to remote failed: {}", e);
             return Err(e.into());
         }
 
@@ -1793,12 +1966,22 @@ impl GitService {
 
     pub fn convert_to_https_url(&self, url: &str) -> String {
         // Convert SSH URL to HTTPS URL if necessary
-        let new_url = if url.starts_with("git@github.com:") {
-            // Convert git@github.com:owner/repo.git to https://github.com/owner/repo.git
-            url.replace("git@github.com:", "https://github.com/")
-        } else if url.starts_with("ssh://git@github.com/") {
-            // Convert ssh://git@github.com/owner/repo.git to https://github.com/owner/repo.git
-            url.replace("ssh://git@github.com/", "https://github.com/")
+        let new_url = if let Some(rest) = url.strip_prefix("ssh://") {
+            // Convert ssh://git@host[:port]/owner/repo.git to https://host/owner/repo.git
+            let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+            match rest.split_once('/') {
+                Some((authority, path)) => {
+                    let host = authority.split(':').next().unwrap_or(authority);
+                    format!("https://{host}/{path}")
+                }
+                None => url.to_string(),
+            }
+        } else if !url.contains("://")
+            && let Some((user_host, path)) = url.split_once(':')
+        {
+            // Convert git@host:owner/repo.git to https://host/owner/repo.git
+            let host = user_host.split_once('@').map_or(user_host, |(_, h)| h);
+            format!("https://{host}/{path}")
         } else {
             url.to_string()
         };
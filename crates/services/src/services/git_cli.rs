@@ -57,6 +57,21 @@ pub enum ChangeType {
     Unknown(String),
 }
 
+/// Submodule declared in `.gitmodules`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmoduleEntry {
+    pub name: String,
+    pub path: String,
+    pub url: String,
+}
+
+/// Hunks of one file that `git apply --reject` could not apply (1-based, in patch order)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedHunks {
+    pub path: String,
+    pub hunks: Vec<usize>,
+}
+
 /// One entry from a status diff (name-status + paths)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StatusDiffEntry {
@@ -109,6 +124,64 @@ impl GitCli {
         Ok(())
     }
 
+    /// Add a worktree that only materialises the given cone-mode sparse directories.
+    /// The worktree is created without a checkout so files outside the cone are never written.
+    pub fn worktree_add_sparse(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        branch: &str,
+        sparse_dirs: &[String],
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let mut args: Vec<OsString> = vec!["worktree".into(), "add".into(), "--no-checkout".into()];
+        args.push(worktree_path.as_os_str().into());
+        args.push(OsString::from(branch));
+        self.git(repo_path, args)?;
+
+        // Sparse settings live in the per-worktree config, leaving the main checkout untouched
+        self.sparse_checkout_set(worktree_path, sparse_dirs)?;
+        self.git(worktree_path, ["read-tree", "-mu", "HEAD"])?;
+        Ok(())
+    }
+
+    /// Replace the cone-mode sparse checkout directories of a worktree
+    pub fn sparse_checkout_set(
+        &self,
+        worktree_path: &Path,
+        dirs: &[String],
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = vec![
+            "sparse-checkout".into(),
+            "set".into(),
+            "--cone".into(),
+            "--".into(),
+        ];
+        args.extend(dirs.iter().map(OsString::from));
+        self.git(worktree_path, args).map(|_| ())
+    }
+
+    /// Cone directories of a sparse worktree; errors if the worktree is not sparse
+    pub fn sparse_checkout_list(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
+        let out = self.git(worktree_path, ["sparse-checkout", "list"])?;
+        Ok(out
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    }
+
+    /// Add directories to the sparse checkout cone of a worktree
+    pub fn sparse_checkout_add(
+        &self,
+        worktree_path: &Path,
+        dirs: &[String],
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = vec!["sparse-checkout".into(), "add".into(), "--".into()];
+        args.extend(dirs.iter().map(OsString::from));
+        self.git(worktree_path, args).map(|_| ())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -188,6 +261,36 @@ impl GitCli {
         Ok(Self::parse_name_status(&out))
     }
 
+    /// Unified diff of the worktree (including untracked files) against `base_commit`,
+    /// built in a temporary index. Binary files are kept as `--binary` hunks.
+    pub fn diff_binary(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+    ) -> Result<String, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        let _ = self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        let _ = self.git_with_env(worktree_path, ["add", "-A"], &envs)?;
+        self.git_with_env(
+            worktree_path,
+            [
+                OsString::from("diff"),
+                "--cached".into(),
+                "--binary".into(),
+                "--no-color".into(),
+                OsString::from(base_commit.to_string()),
+            ],
+            &envs,
+        )
+    }
+
     /// Return `git status --porcelain` parsed into a structured summary
     pub fn get_worktree_status(&self, worktree_path: &Path) -> Result<WorktreeStatus, GitCliError> {
         let out = self.git(worktree_path, ["status", "--porcelain"])?;
@@ -585,10 +688,276 @@ impl GitCli {
         }
         Ok(files)
     }
+
+    /// Export the commits in `base..head` as a `git format-patch` mbox.
+    pub fn format_patch(
+        &self,
+        repo_path: &Path,
+        base: &str,
+        head: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(
+            repo_path,
+            ["format-patch", "--stdout", &format!("{base}..{head}")],
+        )
+    }
+
+    /// Apply an mbox with `git am --3way`. The session is aborted on failure so
+    /// the worktree is left untouched.
+    pub fn am_3way(&self, worktree_path: &Path, patch_path: &Path) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = vec!["am".into(), "--3way".into()];
+        args.push(patch_path.as_os_str().to_os_string());
+        if let Err(e) = self.git(worktree_path, args) {
+            let _ = self.git(worktree_path, ["am", "--abort"]);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Apply a patch with `git apply --reject`, applying every hunk that fits and
+    /// leaving the others in `<path>.rej`. Returns the rejected hunks per file.
+    pub fn apply_with_rejects(
+        &self,
+        worktree_path: &Path,
+        patch_path: &Path,
+    ) -> Result<Vec<RejectedHunks>, GitCliError> {
+        // `git apply` exits with 1 when hunks were rejected, so inspect stderr directly
+        let out =
+            Command::new(resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?)
+                .arg("-C")
+                .arg(worktree_path)
+                .arg("apply")
+                .arg("--reject")
+                .arg("--verbose")
+                .arg(patch_path)
+                .output()
+                .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let rejected = Self::parse_apply_rejects(&stderr);
+        if !out.status.success() && rejected.is_empty() {
+            return Err(GitCliError::CommandFailed(stderr.trim().to_string()));
+        }
+        Ok(rejected)
+    }
+
+    /// List submodules declared in the worktree's `.gitmodules` (empty if there is none).
+    pub fn list_submodules(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<SubmoduleEntry>, GitCliError> {
+        if !worktree_path.join(".gitmodules").exists() {
+            return Ok(Vec::new());
+        }
+        let out = match self.git(
+            worktree_path,
+            [
+                "config",
+                "-f",
+                ".gitmodules",
+                "--get-regexp",
+                r"^submodule\..*\.(path|url)$",
+            ],
+        ) {
+            Ok(out) => out,
+            // `--get-regexp` exits non-zero when nothing matches
+            Err(GitCliError::CommandFailed(msg)) if msg.is_empty() => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(Self::parse_submodule_config(&out))
+    }
+
+    /// Commit recorded for the submodule at `path` in HEAD.
+    pub fn submodule_head_commit(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+    ) -> Result<String, GitCliError> {
+        Ok(self
+            .git(worktree_path, ["rev-parse", &format!("HEAD:{path}")])?
+            .trim()
+            .to_string())
+    }
+
+    /// Copy submodule URLs from `.gitmodules` into the repository config.
+    pub fn submodule_init(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["submodule", "init"]).map(|_| ())
+    }
+
+    /// Run `git submodule update --init` for the given paths (all submodules if empty).
+    /// `url_overrides` maps submodule names to the URL to clone from for this run only.
+    pub fn submodule_update(
+        &self,
+        worktree_path: &Path,
+        paths: &[&str],
+        url_overrides: &[(&str, &Path)],
+        shallow: bool,
+        recursive: bool,
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = Vec::new();
+        if !url_overrides.is_empty() {
+            // Local clones of submodules are blocked by default since git 2.38.1
+            args.extend(["-c".into(), "protocol.file.allow=always".into()]);
+        }
+        for (name, url) in url_overrides {
+            let mut kv = OsString::from(format!("submodule.{name}.url="));
+            kv.push(url.as_os_str());
+            args.extend(["-c".into(), kv]);
+        }
+        args.extend(["submodule".into(), "update".into(), "--init".into()]);
+        if shallow {
+            args.extend(["--depth".into(), "1".into()]);
+        }
+        if recursive {
+            args.push("--recursive".into());
+        }
+        if !paths.is_empty() {
+            args.push("--".into());
+            args.extend(paths.iter().map(OsString::from));
+        }
+        self.git(worktree_path, args).map(|_| ())
+    }
+
+    /// Point a remote at a different URL.
+    pub fn set_remote_url(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        url: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(repo_path, ["remote", "set-url", remote, url])
+            .map(|_| ())
+    }
+
+    /// Make sure `commit` from `url` is present in the bare cache repository at `cache_path`.
+    /// The commit is pinned under `refs/heads/anyon-cache/<sha>` so it survives gc.
+    pub fn fetch_into_cache(
+        &self,
+        cache_path: &Path,
+        url: &str,
+        commit: &str,
+    ) -> Result<(), GitCliError> {
+        // The URL comes from the repository's .gitmodules, so it must not be taken as an
+        // option or run a command through the ext transport
+        if url.starts_with('-') || url.trim_start().to_ascii_lowercase().starts_with("ext::") {
+            return Err(GitCliError::CommandFailed(format!(
+                "refusing to fetch submodule URL {url}"
+            )));
+        }
+        if !cache_path.exists() {
+            let parent = cache_path.parent().unwrap_or(cache_path);
+            std::fs::create_dir_all(parent)
+                .map_err(|e| GitCliError::CommandFailed(format!("create cache dir failed: {e}")))?;
+            let mut args: Vec<OsString> = vec!["init".into(), "--bare".into(), "--quiet".into()];
+            args.push(cache_path.as_os_str().into());
+            self.git(parent, args)?;
+            self.git(
+                cache_path,
+                ["config", "uploadpack.allowAnySHA1InWant", "true"],
+            )?;
+        }
+        if self
+            .git(
+                cache_path,
+                ["cat-file", "-e", &format!("{commit}^{{commit}}")],
+            )
+            .is_ok()
+        {
+            return Ok(());
+        }
+        let refspec = format!("+{commit}:refs/heads/anyon-cache/{commit}");
+        self.git(
+            cache_path,
+            ["fetch", "--quiet", "--depth", "1", "--", url, &refspec],
+        )
+        .map(|_| ())
+    }
+
+    /// Whether the worktree tracks files with Git LFS and the `git lfs` extension is installed.
+    pub fn uses_lfs(&self, worktree_path: &Path) -> bool {
+        let attributes =
+            std::fs::read_to_string(worktree_path.join(".gitattributes")).unwrap_or_default();
+        attributes.contains("filter=lfs") && self.git(worktree_path, ["lfs", "version"]).is_ok()
+    }
+
+    /// Download LFS objects for the checked out commit and replace pointer files.
+    /// Falls back to `git lfs checkout` (already-fetched objects only) when the pull fails.
+    pub fn lfs_pull(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        if let Err(e) = self.git(worktree_path, ["lfs", "pull"]) {
+            tracing::debug!("git lfs pull failed, falling back to checkout: {}", e);
+            self.git(worktree_path, ["lfs", "checkout"])?;
+        }
+        Ok(())
+    }
+
+    fn parse_submodule_config(output: &str) -> Vec<SubmoduleEntry> {
+        let mut entries: Vec<SubmoduleEntry> = Vec::new();
+        for line in output.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let Some(key) = key.strip_prefix("submodule.") else {
+                continue;
+            };
+            let (name, field) = if let Some(name) = key.strip_suffix(".path") {
+                (name, "path")
+            } else if let Some(name) = key.strip_suffix(".url") {
+                (name, "url")
+            } else {
+                continue;
+            };
+            let idx = match entries.iter().position(|e| e.name == name) {
+                Some(idx) => idx,
+                None => {
+                    entries.push(SubmoduleEntry {
+                        name: name.to_string(),
+                        path: String::new(),
+                        url: String::new(),
+                    });
+                    entries.len() - 1
+                }
+            };
+            let value = value.trim().to_string();
+            if field == "path" {
+                entries[idx].path = value;
+            } else {
+                entries[idx].url = value;
+            }
+        }
+        entries.retain(|e| !e.path.is_empty() && !e.url.is_empty());
+        entries
+    }
 }
 
 // Private methods
 impl GitCli {
+    /// Parse `Applying patch <path> with N rejects...` / `Rejected hunk #N.` lines
+    /// from `git apply --reject --verbose`.
+    fn parse_apply_rejects(stderr: &str) -> Vec<RejectedHunks> {
+        let mut rejected: Vec<RejectedHunks> = Vec::new();
+        let mut current: Option<RejectedHunks> = None;
+        for line in stderr.lines() {
+            if let Some(rest) = line.strip_prefix("Applying patch ")
+                && let Some((path, _)) = rest.rsplit_once(" with ")
+            {
+                rejected.extend(current.take());
+                current = Some(RejectedHunks {
+                    path: path.to_string(),
+                    hunks: Vec::new(),
+                });
+            } else if let Some(rest) = line.strip_prefix("Rejected hunk #")
+                && let Some(entry) = current.as_mut()
+                && let Ok(hunk) = rest.trim_end_matches('.').parse()
+            {
+                entry.hunks.push(hunk);
+            } else if line.starts_with("Applied patch ") || line.starts_with("Checking patch ") {
+                rejected.extend(current.take());
+            }
+        }
+        rejected.extend(current);
+        rejected.retain(|r| !r.hunks.is_empty());
+        rejected
+    }
+
     fn classify_cli_error(&self, msg: String) -> GitCliError {
         let lower = msg.to_ascii_lowercase();
         if lower.contains("authentication failed")
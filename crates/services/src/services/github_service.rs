@@ -192,6 +192,42 @@ impl GitHubService {
         })
     }
 
+    /// Get repository metadata such as the default branch
+    pub async fn get_repository(
+        &self,
+        repo_info: &GitHubRepoInfo,
+    ) -> Result<RepositoryInfo, GitHubServiceError> {
+        let repo = self
+            .client
+            .repos(&repo_info.owner, &repo_info.repo_name)
+            .get()
+            .await
+            .map_err(|error| match GitHubServiceError::from(error) {
+                GitHubServiceError::Client(source) => GitHubServiceError::Repository(format!(
+                    "Cannot access repository {}/{}: {}",
+                    repo_info.owner,
+                    repo_info.repo_name,
+                    format_octocrab_error(&source)
+                )),
+                other => other,
+            })?;
+
+        Ok(RepositoryInfo {
+            id: repo.id.0 as i64,
+            name: repo.name,
+            full_name: repo.full_name.unwrap_or_default(),
+            owner: repo.owner.map(|o| o.login).unwrap_or_default(),
+            description: repo.description,
+            clone_url: repo
+                .clone_url
+                .map(|url| url.to_string())
+                .unwrap_or_default(),
+            ssh_url: repo.ssh_url.unwrap_or_default(),
+            default_branch: repo.default_branch.unwrap_or_else(|| "main".to_string()),
+            private: repo.private.unwrap_or(false),
+        })
+    }
+
     /// Create a pull request on GitHub
     pub async fn create_pr(
         &self,
@@ -0,0 +1,176 @@
+//! Context handed to a different executor when it takes over an attempt
+
+use std::collections::BTreeMap;
+
+use executors::logs::{
+    NormalizedEntry, NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch,
+};
+use utils::log_msg::LogMsg;
+
+/// Opening line of every handoff prompt
+pub const HANDOFF_PROMPT_PREFIX: &str =
+    "You are taking over a task that another coding agent started.";
+
+const MAX_ENTRY_CHARS: usize = 2_000;
+const MAX_CONVERSATION_CHARS: usize = 40_000;
+const MAX_DIFF_CHARS: usize = 60_000;
+
+/// Rebuild the final conversation from a process's log history, keeping the latest
+/// version of every entry
+pub fn conversation_from_history(history: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut entries = BTreeMap::new();
+    for msg in history {
+        if let LogMsg::JsonPatch(patch) = msg
+            && let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+        {
+            entries.insert(index, entry);
+        }
+    }
+    entries.into_values().collect()
+}
+
+/// Everything the next executor needs to continue without access to the previous session
+#[derive(Debug, Clone)]
+pub struct HandoffBundle {
+    pub task_prompt: String,
+    pub conversation: Vec<NormalizedEntry>,
+    /// Unified diff of the worktree against the attempt's base commit
+    pub diff: String,
+    /// Why the previous executor stopped, e.g. "CLAUDE_CODE hit a rate limit"
+    pub reason: String,
+}
+
+impl HandoffBundle {
+    pub fn to_prompt(&self) -> String {
+        let mut prompt = format!(
+            "{HANDOFF_PROMPT_PREFIX}\n\n## Task\n\n{}\n\n## Why you're taking over\n\n{}\n",
+            self.task_prompt.trim(),
+            self.reason
+        );
+
+        let transcript = self.transcript();
+        if !transcript.is_empty() {
+            prompt.push_str("\n## Conversation so far\n\n");
+            prompt.push_str(&transcript);
+        }
+
+        prompt.push_str("\n## Current changes\n\n");
+        if self.diff.trim().is_empty() {
+            prompt.push_str("The worktree has no changes yet.\n");
+        } else {
+            prompt.push_str("```diff\n");
+            prompt.push_str(&truncate(self.diff.trim_end(), MAX_DIFF_CHARS));
+            prompt.push_str("\n```\n");
+        }
+
+        prompt.push_str(
+            "\nReview the changes above, then continue the task from where it was left off.",
+        );
+        prompt
+    }
+
+    /// Condensed transcript, dropping the oldest lines once it grows past the budget
+    fn transcript(&self) -> String {
+        let lines: Vec<String> = self
+            .conversation
+            .iter()
+            .filter_map(|entry| {
+                let label = match &entry.entry_type {
+                    NormalizedEntryType::UserMessage => "User",
+                    NormalizedEntryType::AssistantMessage => "Assistant",
+                    NormalizedEntryType::ToolUse { tool_name, .. } => {
+                        return Some(format!(
+                            "- Tool `{tool_name}`: {}",
+                            truncate(entry.content.trim(), MAX_ENTRY_CHARS)
+                        ));
+                    }
+                    NormalizedEntryType::ErrorMessage { .. } => "Error",
+                    _ => return None,
+                };
+                let content = entry.content.trim();
+                (!content.is_empty())
+                    .then(|| format!("- {label}: {}", truncate(content, MAX_ENTRY_CHARS)))
+            })
+            .collect();
+
+        let mut budget = MAX_CONVERSATION_CHARS;
+        let mut kept = Vec::new();
+        for line in lines.iter().rev() {
+            if line.len() > budget {
+                kept.push("- (earlier conversation omitted)".to_string());
+                break;
+            }
+            budget -= line.len();
+            kept.push(line.clone());
+        }
+        kept.reverse();
+        kept.iter().map(|line| format!("{line}\n")).collect()
+    }
+}
+
+fn truncate(content: &str, max_chars: usize) -> String {
+    match content.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}\n... (truncated)", &content[..idx]),
+        None => content.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{NormalizedEntryError, utils::ConversationPatch};
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn keeps_latest_entry_versions() {
+        let history = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::AssistantMessage, "draft"),
+            )),
+            LogMsg::Stdout("raw".to_string()),
+            LogMsg::JsonPatch(ConversationPatch::replace(
+                0,
+                entry(NormalizedEntryType::AssistantMessage, "final"),
+            )),
+        ];
+        let conversation = conversation_from_history(&history);
+        assert_eq!(conversation.len(), 1);
+        assert_eq!(conversation[0].content, "final");
+    }
+
+    #[test]
+    fn prompt_includes_conversation_and_diff() {
+        let bundle = HandoffBundle {
+            task_prompt: "Fix the login bug".to_string(),
+            conversation: vec![
+                entry(NormalizedEntryType::Thinking, "hidden"),
+                entry(NormalizedEntryType::AssistantMessage, "Found the bug"),
+                entry(
+                    NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::RateLimited,
+                    },
+                    "429 Too Many Requests",
+                ),
+            ],
+            diff: "--- a/login.rs\n+++ b/login.rs\n".to_string(),
+            reason: "CLAUDE_CODE hit a rate limit".to_string(),
+        };
+        let prompt = bundle.to_prompt();
+        assert!(prompt.starts_with(HANDOFF_PROMPT_PREFIX));
+        assert!(prompt.contains("Fix the login bug"));
+        assert!(prompt.contains("- Assistant: Found the bug"));
+        assert!(prompt.contains("- Error: 429 Too Many Requests"));
+        assert!(!prompt.contains("hidden"));
+        assert!(prompt.contains("```diff\n--- a/login.rs"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Pluggable identity providers for browser sign-in
+//!
+//! GitHub sign-in keeps using the device flow in [`super::auth`]. Providers here use a
+//! redirect flow: [`IdentityProvider::begin_login`] returns the URL to send the browser to and
+//! [`IdentityProvider::complete_login`] turns the provider's callback into an identity.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::{RngCore, rngs::OsRng};
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+
+pub mod oidc;
+
+/// 256 random bits, url-safe base64 encoded
+pub(crate) fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// A new session cookie value and the hash to store for it
+pub fn new_session_token() -> (String, String) {
+    let token = random_token();
+    let hash = super::api_tokens::hash(&token);
+    (token, hash)
+}
+
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("Unknown identity provider: {0}")]
+    UnknownProvider(String),
+    #[error("Login request is unknown or expired, please sign in again")]
+    InvalidState,
+    #[error("Identity provider returned an error: {0}")]
+    Provider(String),
+    #[error("Invalid ID token: {0}")]
+    InvalidIdToken(String),
+    #[error("Identity provider is misconfigured: {0}")]
+    Config(String),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Provider shown on the login page
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct IdentityProviderInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// A user as asserted by an identity provider
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    pub provider: String,
+    /// Stable id of the user at the provider
+    pub subject: String,
+    pub email: Option<String>,
+    pub name: Option<String>,
+    pub username: Option<String>,
+}
+
+impl ExternalIdentity {
+    /// Name to show in the UI
+    pub fn display_name(&self) -> String {
+        self.username
+            .clone()
+            .or_else(|| self.name.clone())
+            .or_else(|| self.email.clone())
+            .unwrap_or_else(|| self.subject.clone())
+    }
+}
+
+/// Completed login: who signed in and where they wanted to go
+#[derive(Debug)]
+pub struct CompletedLogin {
+    pub identity: ExternalIdentity,
+    pub return_to: Option<String>,
+}
+
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    fn info(&self) -> IdentityProviderInfo;
+
+    /// URL to redirect the browser to. `return_to` is handed back by `complete_login`.
+    async fn begin_login(&self, return_to: Option<String>) -> Result<String, IdentityError>;
+
+    /// Handle the provider's redirect back with `code` and `state`
+    async fn complete_login(
+        &self,
+        code: &str,
+        state: &str,
+    ) -> Result<CompletedLogin, IdentityError>;
+}
+
+/// Configured redirect-flow identity providers
+#[derive(Clone, Default)]
+pub struct IdentityProviders {
+    providers: Arc<Vec<Arc<dyn IdentityProvider>>>,
+}
+
+impl IdentityProviders {
+    pub fn new(providers: Vec<Arc<dyn IdentityProvider>>) -> Self {
+        Self {
+            providers: Arc::new(providers),
+        }
+    }
+
+    /// Providers configured through environment variables; none when unset
+    pub fn from_env() -> Result<Self, IdentityError> {
+        let mut providers: Vec<Arc<dyn IdentityProvider>> = Vec::new();
+        if let Some(config) = oidc::OidcConfig::from_env()? {
+            providers.push(Arc::new(oidc::OidcProvider::new(config)));
+        }
+        Ok(Self::new(providers))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    pub fn list(&self) -> Vec<IdentityProviderInfo> {
+        self.providers.iter().map(|p| p.info()).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<dyn IdentityProvider>, IdentityError> {
+        self.providers
+            .iter()
+            .find(|p| p.info().id == id)
+            .cloned()
+            .ok_or_else(|| IdentityError::UnknownProvider(id.to_string()))
+    }
+}
@@ -1,4 +1,5 @@
 pub mod analytics;
+pub mod api_tokens;
 pub mod approvals;
 pub mod auth;
 pub mod claude_auth;
@@ -11,16 +12,23 @@ pub mod diff_stream;
 pub mod docker_poc;
 pub mod drafts;
 pub mod events;
+pub mod executor_health;
 pub mod file_ranker;
 pub mod file_search_cache;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod forge;
 pub mod git;
 pub mod git_cli;
 pub mod github_service;
 pub mod github_user_cache;
+pub mod handoff;
+pub mod identity;
 pub mod image;
 pub mod notification;
 pub mod pr_monitor;
+pub mod project_secrets;
 pub mod secret_store;
+pub mod sparse_checkout;
+pub mod webhooks;
 pub mod worktree_manager;
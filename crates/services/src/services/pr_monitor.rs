@@ -6,6 +6,7 @@ use db::{
         merge::{Merge, MergeStatus, PrMerge},
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
+        webhook::WebhookEventType,
     },
 };
 use serde_json::json;
@@ -16,31 +17,29 @@ use tracing::{debug, error, info, warn};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
-    secret_store::{SECRET_GITHUB_OAUTH, SECRET_GITHUB_PAT, SecretStore, SecretStoreError},
+    forge::{ForgeError, ForgeResolver},
+    secret_store::SecretStore,
+    webhooks::WebhookService,
 };
 
 #[derive(Debug, Error)]
 enum PrMonitorError {
-    #[error("No GitHub token configured")]
-    NoGitHubToken,
     #[error(transparent)]
-    GitHubServiceError(#[from] GitHubServiceError),
+    Forge(#[from] ForgeError),
     #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
-    #[error(transparent)]
-    SecretStore(#[from] SecretStoreError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor GitHub PRs and GitLab/Gitea merge requests and update
+/// task status when they are merged
 pub struct PrMonitorService {
     db: DBService,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
-    secret_store: SecretStore,
-    user_id: String,
+    forges: ForgeResolver,
+    webhooks: WebhookService,
 }
 
 impl PrMonitorService {
@@ -49,13 +48,14 @@ impl PrMonitorService {
         analytics: Option<AnalyticsContext>,
         secret_store: SecretStore,
         user_id: String,
+        webhooks: WebhookService,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
-            secret_store,
-            user_id,
+            forges: ForgeResolver::new(secret_store, user_id),
+            webhooks,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -78,7 +78,7 @@ impl PrMonitorService {
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
+    /// Check all open PRs for updates with the token stored for their forge
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
 
@@ -91,8 +91,8 @@ impl PrMonitorService {
 
         for pr_merge in open_prs {
             match self.check_pr_status(&pr_merge).await {
-                Err(PrMonitorError::NoGitHubToken) => {
-                    warn!("No GitHub token configured, cannot check PR status");
+                Err(PrMonitorError::Forge(ForgeError::NoToken(provider))) => {
+                    warn!("No {provider} token configured, cannot check PR status");
                 }
                 Err(e) => {
                     error!(
@@ -108,14 +108,11 @@ impl PrMonitorService {
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        let github_token = self.fetch_github_token().await?;
-
-        let github_service = GitHubService::new(&github_token)?;
-
-        let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+        let forge = self.forges.connect(&pr_merge.pr_info.url).await?;
 
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_merge.pr_info.number)
+        let pr_status = forge
+            .service
+            .update_pr_status(&forge.repo_info, pr_merge.pr_info.number)
             .await?;
 
         debug!(
@@ -125,12 +122,12 @@ impl PrMonitorService {
 
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+            // Update merge status with the latest information from the forge
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
                 pr_status.status.clone(),
-                pr_status.merge_commit_sha,
+                pr_status.merge_commit_sha.clone(),
             )
             .await?;
 
@@ -145,6 +142,21 @@ impl PrMonitorService {
                 );
                 Task::update_status(&self.db.pool, task_attempt.task_id, TaskStatus::Done).await?;
 
+                if let Some(task) = Task::find_by_id(&self.db.pool, task_attempt.task_id).await? {
+                    self.webhooks.emit(
+                        task.project_id,
+                        WebhookEventType::PrMerged,
+                        json!({
+                            "task_id": task.id,
+                            "attempt_id": task_attempt.id,
+                            "number": pr_merge.pr_info.number,
+                            "url": pr_merge.pr_info.url,
+                            "target_branch": pr_merge.target_branch_name,
+                            "merge_commit_sha": pr_status.merge_commit_sha,
+                        }),
+                    );
+                }
+
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
                     && let Ok(Some(task)) =
@@ -165,22 +177,4 @@ impl PrMonitorService {
 
         Ok(())
     }
-
-    async fn fetch_github_token(&self) -> Result<String, PrMonitorError> {
-        if let Some(pat) = self
-            .secret_store
-            .get_secret_string(&self.user_id, SECRET_GITHUB_PAT)
-            .await?
-        {
-            return Ok(pat);
-        }
-        if let Some(token) = self
-            .secret_store
-            .get_secret_string(&self.user_id, SECRET_GITHUB_OAUTH)
-            .await?
-        {
-            return Ok(token);
-        }
-        Err(PrMonitorError::NoGitHubToken)
-    }
 }
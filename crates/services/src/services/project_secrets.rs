@@ -0,0 +1,178 @@
+use db::models::{
+    project_secret::{ProjectSecret, UpsertProjectSecret},
+    secret::SecretRecord,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::secret_store::{SecretDescriptor, SecretStore, SecretStoreError};
+
+/// Owner recorded on project secret values, which belong to the project rather than a user
+pub const PROJECT_SECRET_OWNER: &str = "project";
+
+#[derive(Debug, Error)]
+pub enum ProjectSecretError {
+    #[error(
+        "invalid secret name '{0}': use letters, digits and underscores, not starting with a digit"
+    )]
+    InvalidName(String),
+    #[error("secret '{0}' has no value")]
+    MissingValue(String),
+    #[error("secret '{0}' not found")]
+    NotFound(String),
+    #[error(transparent)]
+    SecretStore(#[from] SecretStoreError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Which kind of process a secret is being exposed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretTarget {
+    Script,
+    CodingAgent,
+}
+
+/// Named per-project secrets exposed to attempts as environment variables
+#[derive(Clone)]
+pub struct ProjectSecretsService {
+    secret_store: SecretStore,
+}
+
+impl ProjectSecretsService {
+    pub fn new(secret_store: SecretStore) -> Self {
+        Self { secret_store }
+    }
+
+    fn provider(project_id: Uuid) -> String {
+        format!("project:{project_id}")
+    }
+
+    pub async fn list(&self, project_id: Uuid) -> Result<Vec<ProjectSecret>, ProjectSecretError> {
+        Ok(ProjectSecret::find_by_project_id(&self.secret_store.pool, project_id).await?)
+    }
+
+    /// Create or update a secret; a new secret must come with a value
+    pub async fn upsert(
+        &self,
+        project_id: Uuid,
+        name: &str,
+        payload: &UpsertProjectSecret,
+    ) -> Result<ProjectSecret, ProjectSecretError> {
+        if !is_valid_env_name(name) {
+            return Err(ProjectSecretError::InvalidName(name.to_string()));
+        }
+        let pool = &self.secret_store.pool;
+        let provider = Self::provider(project_id);
+        let descriptor = SecretDescriptor {
+            provider: &provider,
+            name,
+        };
+
+        match &payload.value {
+            Some(value) => {
+                self.secret_store
+                    .put_secret(PROJECT_SECRET_OWNER, descriptor, value.as_bytes())
+                    .await?
+            }
+            None if ProjectSecret::find_by_name(pool, project_id, name)
+                .await?
+                .is_none() =>
+            {
+                return Err(ProjectSecretError::MissingValue(name.to_string()));
+            }
+            None => {}
+        }
+
+        Ok(ProjectSecret::upsert(
+            pool,
+            project_id,
+            name,
+            payload.expose_to_scripts,
+            payload.expose_to_agents,
+        )
+        .await?)
+    }
+
+    pub async fn delete(&self, project_id: Uuid, name: &str) -> Result<(), ProjectSecretError> {
+        let provider = Self::provider(project_id);
+        self.secret_store
+            .delete_secret(
+                PROJECT_SECRET_OWNER,
+                SecretDescriptor {
+                    provider: &provider,
+                    name,
+                },
+            )
+            .await?;
+        if ProjectSecret::delete(&self.secret_store.pool, project_id, name).await? == 0 {
+            return Err(ProjectSecretError::NotFound(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Remove every stored value of a project; the metadata rows go with the project itself
+    pub async fn delete_all(&self, project_id: Uuid) -> Result<(), ProjectSecretError> {
+        SecretRecord::delete_by_provider(
+            &self.secret_store.pool,
+            PROJECT_SECRET_OWNER,
+            &Self::provider(project_id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Environment variables to set for a process of the given kind
+    pub async fn env_for(
+        &self,
+        project_id: Uuid,
+        target: SecretTarget,
+    ) -> Result<Vec<(String, String)>, ProjectSecretError> {
+        let provider = Self::provider(project_id);
+        let mut env = Vec::new();
+        for secret in self.list(project_id).await? {
+            let exposed = match target {
+                SecretTarget::Script => secret.expose_to_scripts,
+                SecretTarget::CodingAgent => secret.expose_to_agents,
+            };
+            if !exposed {
+                continue;
+            }
+            let descriptor = SecretDescriptor {
+                provider: &provider,
+                name: &secret.name,
+            };
+            match self
+                .secret_store
+                .get_secret_string(PROJECT_SECRET_OWNER, descriptor)
+                .await?
+            {
+                Some(value) => env.push((secret.name, value)),
+                None => tracing::warn!("Project secret {} has no stored value", secret.name),
+            }
+        }
+        Ok(env)
+    }
+}
+
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_env_names() {
+        assert!(is_valid_env_name("STRIPE_API_KEY"));
+        assert!(is_valid_env_name("_private1"));
+        assert!(!is_valid_env_name(""));
+        assert!(!is_valid_env_name("1PASSWORD"));
+        assert!(!is_valid_env_name("DATABASE-URL"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{ops::Deref, sync::Arc};
+use std::{collections::BTreeMap, ops::Deref, process::Command, sync::Arc};
 
 use aes_gcm::{
     Aes256Gcm, Nonce,
@@ -7,9 +7,17 @@ use aes_gcm::{
 use base64::Engine;
 use db::{DBService, models::secret::SecretRecord};
 use rand::{RngCore, rngs::OsRng};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 const SECRET_KEY_ENV: &str = "ANYON_SECRET_KEY";
+const SECRET_KEY_VERSION_ENV: &str = "ANYON_SECRET_KEY_VERSION";
+const RETIRED_KEYS_ENV: &str = "ANYON_SECRET_RETIRED_KEYS";
+const KEYFILE_ENV: &str = "ANYON_SECRET_KEYFILE";
+const KEY_SOURCE_ENV: &str = "ANYON_SECRET_KEY_SOURCE";
+const KEYRING_SERVICE: &str = "anyon";
+const KEYRING_ACCOUNT: &str = "secret-key";
+const KEY_LEN: usize = 32;
 const NONCE_LEN: usize = 12;
 const DEFAULT_KEY_VERSION: i64 = 1;
 
@@ -22,6 +30,8 @@ pub struct SecretDescriptor<'a> {
 
 pub const PROVIDER_GITHUB: &str = "github";
 pub const PROVIDER_CLAUDE: &str = "claude";
+pub const PROVIDER_GITLAB: &str = "gitlab";
+pub const PROVIDER_GITEA: &str = "gitea";
 pub const SECRET_NAME_OAUTH: &str = "oauth_token";
 pub const SECRET_NAME_PAT: &str = "pat";
 pub const SECRET_NAME_CLAUDE_ACCESS: &str = "access_token";
@@ -40,10 +50,14 @@ pub const SECRET_CLAUDE_ACCESS: SecretDescriptor<'static> = SecretDescriptor {
 
 #[derive(Debug, Error)]
 pub enum SecretStoreError {
-    #[error("environment variable ANYON_SECRET_KEY not set")]
+    #[error(
+        "no secret key configured: set ANYON_SECRET_KEY, ANYON_SECRET_KEYFILE or ANYON_SECRET_KEY_SOURCE=keyring"
+    )]
     MissingKey,
-    #[error("ANYON_SECRET_KEY must be base64 encoded 32 bytes: {0}")]
+    #[error("invalid secret key: {0}")]
     InvalidKey(String),
+    #[error("failed to load secret key: {0}")]
+    KeySource(String),
     #[error("encryption error")]
     Encrypt,
     #[error("decryption error")]
@@ -56,33 +70,196 @@ pub enum SecretStoreError {
     Database(#[from] sqlx::Error),
 }
 
+/// On-disk keyfile: `{"current": 2, "keys": {"1": "<base64>", "2": "<base64>"}}`
+#[derive(Debug, Deserialize)]
+struct KeyFile {
+    current: i64,
+    keys: BTreeMap<i64, String>,
+}
+
+/// Encryption keys by version. New secrets use the current version; retired versions are kept
+/// so existing secrets can still be read until they are rotated.
+#[derive(Clone)]
+pub struct SecretKeys {
+    current: i64,
+    keys: BTreeMap<i64, [u8; KEY_LEN]>,
+}
+
+impl SecretKeys {
+    /// Keys from `ANYON_SECRET_KEYFILE`, the OS keyring when `ANYON_SECRET_KEY_SOURCE=keyring`,
+    /// or `ANYON_SECRET_KEY`, in that order
+    pub fn load() -> Result<Self, SecretStoreError> {
+        if let Ok(path) = std::env::var(KEYFILE_ENV) {
+            let contents = std::fs::read_to_string(&path).map_err(|err| {
+                SecretStoreError::KeySource(format!("failed to read {path}: {err}"))
+            })?;
+            return Self::parse_keyfile(&contents);
+        }
+        if std::env::var(KEY_SOURCE_ENV).is_ok_and(|source| source.trim() == "keyring") {
+            let entry = read_keyring()?;
+            // The entry holds either a whole keyfile or just the current key
+            return if entry.starts_with('{') {
+                Self::parse_keyfile(&entry)
+            } else {
+                Self::from_env_with_key(&entry)
+            };
+        }
+        let key = std::env::var(SECRET_KEY_ENV).map_err(|_| SecretStoreError::MissingKey)?;
+        Self::from_env_with_key(&key)
+    }
+
+    /// `key` as the current version (`ANYON_SECRET_KEY_VERSION`, default 1) plus retired keys
+    /// from `ANYON_SECRET_RETIRED_KEYS` as comma-separated `<version>:<base64>` entries
+    fn from_env_with_key(key: &str) -> Result<Self, SecretStoreError> {
+        let current = match std::env::var(SECRET_KEY_VERSION_ENV) {
+            Ok(version) => parse_version(&version)?,
+            Err(_) => DEFAULT_KEY_VERSION,
+        };
+        let mut keys = BTreeMap::new();
+        if let Ok(retired) = std::env::var(RETIRED_KEYS_ENV) {
+            for entry in retired.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (version, key) = entry.split_once(':').ok_or_else(|| {
+                    SecretStoreError::InvalidKey(format!(
+                        "{RETIRED_KEYS_ENV} entries must look like <version>:<base64 key>"
+                    ))
+                })?;
+                keys.insert(parse_version(version)?, decode_key(key)?);
+            }
+        }
+        keys.insert(current, decode_key(key)?);
+        Ok(Self { current, keys })
+    }
+
+    pub fn parse_keyfile(contents: &str) -> Result<Self, SecretStoreError> {
+        let file: KeyFile = serde_json::from_str(contents)
+            .map_err(|err| SecretStoreError::InvalidKey(format!("invalid keyfile: {err}")))?;
+        let keys = file
+            .keys
+            .iter()
+            .map(|(version, key)| Ok((*version, decode_key(key)?)))
+            .collect::<Result<BTreeMap<_, _>, SecretStoreError>>()?;
+        if !keys.contains_key(&file.current) {
+            return Err(SecretStoreError::InvalidKey(format!(
+                "keyfile has no key for current version {}",
+                file.current
+            )));
+        }
+        Ok(Self {
+            current: file.current,
+            keys,
+        })
+    }
+
+    pub fn current_version(&self) -> i64 {
+        self.current
+    }
+
+    /// A random key, base64-encoded as `ANYON_SECRET_KEY` expects
+    pub fn generate_key() -> String {
+        let mut key = [0u8; KEY_LEN];
+        OsRng.fill_bytes(&mut key);
+        base64::engine::general_purpose::STANDARD.encode(key)
+    }
+}
+
+fn parse_version(version: &str) -> Result<i64, SecretStoreError> {
+    version
+        .trim()
+        .parse()
+        .map_err(|_| SecretStoreError::InvalidKey(format!("invalid key version '{version}'")))
+}
+
+fn decode_key(key_b64: &str) -> Result<[u8; KEY_LEN], SecretStoreError> {
+    let key_bytes = base64::engine::general_purpose::STANDARD
+        .decode(key_b64.trim().as_bytes())
+        .map_err(|err| SecretStoreError::InvalidKey(err.to_string()))?;
+    key_bytes
+        .try_into()
+        .map_err(|_| SecretStoreError::InvalidKey("expected 32 decoded bytes".to_string()))
+}
+
+/// Read the key entry from the macOS keychain or the Secret Service on Linux
+fn read_keyring() -> Result<String, SecretStoreError> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("security");
+        command.args([
+            "find-generic-password",
+            "-s",
+            KEYRING_SERVICE,
+            "-a",
+            KEYRING_ACCOUNT,
+            "-w",
+        ]);
+        command
+    } else if cfg!(target_os = "linux") {
+        let mut command = Command::new("secret-tool");
+        command.args([
+            "lookup",
+            "service",
+            KEYRING_SERVICE,
+            "account",
+            KEYRING_ACCOUNT,
+        ]);
+        command
+    } else {
+        return Err(SecretStoreError::KeySource(
+            "the OS keyring is only supported on macOS and Linux".to_string(),
+        ));
+    };
+
+    let output = command.output().map_err(|err| {
+        SecretStoreError::KeySource(format!("failed to query the OS keyring: {err}"))
+    })?;
+    let entry = String::from_utf8(output.stdout)
+        .map_err(|_| SecretStoreError::KeySource("keyring entry is not valid UTF-8".to_string()))?;
+    if !output.status.success() || entry.trim().is_empty() {
+        return Err(SecretStoreError::KeySource(format!(
+            "no '{KEYRING_ACCOUNT}' entry for service '{KEYRING_SERVICE}' in the OS keyring"
+        )));
+    }
+    Ok(entry.trim().to_string())
+}
+
+/// Outcome of re-encrypting stored secrets with the current key
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyRotation {
+    pub key_version: i64,
+    pub rotated: usize,
+}
+
 #[derive(Clone)]
 pub struct SecretStore {
     db: DBService,
-    cipher: Arc<Aes256Gcm>,
+    ciphers: Arc<BTreeMap<i64, Aes256Gcm>>,
     key_version: i64,
 }
 
 impl SecretStore {
     pub fn new(db: DBService) -> Result<Self, SecretStoreError> {
-        let key_b64 = std::env::var(SECRET_KEY_ENV).map_err(|_| SecretStoreError::MissingKey)?;
-        let key_bytes = base64::engine::general_purpose::STANDARD
-            .decode(key_b64.as_bytes())
-            .map_err(|err| SecretStoreError::InvalidKey(err.to_string()))?;
-        if key_bytes.len() != 32 {
-            return Err(SecretStoreError::InvalidKey(
-                "expected 32 decoded bytes".to_string(),
-            ));
-        }
-        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-            .map_err(|err| SecretStoreError::InvalidKey(err.to_string()))?;
+        Self::with_keys(db, &SecretKeys::load()?)
+    }
+
+    pub fn with_keys(db: DBService, keys: &SecretKeys) -> Result<Self, SecretStoreError> {
+        let ciphers = keys
+            .keys
+            .iter()
+            .map(|(version, key)| {
+                Aes256Gcm::new_from_slice(key)
+                    .map(|cipher| (*version, cipher))
+                    .map_err(|err| SecretStoreError::InvalidKey(err.to_string()))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
         Ok(Self {
             db,
-            cipher: Arc::new(cipher),
-            key_version: DEFAULT_KEY_VERSION,
+            ciphers: Arc::new(ciphers),
+            key_version: keys.current,
         })
     }
 
+    pub fn key_version(&self) -> i64 {
+        self.key_version
+    }
+
     pub fn db(&self) -> &DBService {
         &self.db
     }
@@ -93,19 +270,7 @@ impl SecretStore {
         descriptor: SecretDescriptor<'_>,
         value: &[u8],
     ) -> Result<(), SecretStoreError> {
-        let mut nonce_bytes = [0u8; NONCE_LEN];
-        OsRng.fill_bytes(&mut nonce_bytes);
-        #[allow(deprecated)]
-        let nonce = Nonce::from_slice(&nonce_bytes);
-        let ciphertext = self
-            .cipher
-            .encrypt(nonce, value)
-            .map_err(|_| SecretStoreError::Encrypt)?;
-
-        let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
-        blob.extend_from_slice(&nonce_bytes);
-        blob.extend_from_slice(ciphertext.as_slice());
-
+        let blob = self.encrypt(value)?;
         SecretRecord::upsert(
             &self.db.pool,
             user_id,
@@ -130,20 +295,7 @@ impl SecretStore {
             return Ok(None);
         };
 
-        if record.key_version != self.key_version {
-            return Err(SecretStoreError::UnsupportedKeyVersion(record.key_version));
-        }
-        if record.secret_blob.len() <= NONCE_LEN {
-            return Err(SecretStoreError::Decrypt);
-        }
-        let (nonce_bytes, cipher_bytes) = record.secret_blob.split_at(NONCE_LEN);
-        #[allow(deprecated)]
-        let nonce = Nonce::from_slice(nonce_bytes);
-        let plaintext = self
-            .cipher
-            .decrypt(nonce, cipher_bytes)
-            .map_err(|_| SecretStoreError::Decrypt)?;
-
+        let plaintext = self.decrypt(&record)?;
         Ok(Some(plaintext))
     }
 
@@ -161,6 +313,59 @@ impl SecretStore {
         }
     }
 
+    /// Re-encrypt every secret written with a retired key using the current key, in a single
+    /// transaction so a failure leaves all secrets as they were
+    pub async fn rotate(&self) -> Result<KeyRotation, SecretStoreError> {
+        let mut tx = self.db.pool.begin().await?;
+        let records = SecretRecord::find_not_at_key_version(&mut *tx, self.key_version).await?;
+        for record in &records {
+            let plaintext = self.decrypt(record)?;
+            let blob = self.encrypt(&plaintext)?;
+            SecretRecord::update_blob(&mut *tx, record.id, &blob, self.key_version).await?;
+        }
+        tx.commit().await?;
+
+        Ok(KeyRotation {
+            key_version: self.key_version,
+            rotated: records.len(),
+        })
+    }
+
+    fn encrypt(&self, value: &[u8]) -> Result<Vec<u8>, SecretStoreError> {
+        let cipher = self
+            .ciphers
+            .get(&self.key_version)
+            .ok_or(SecretStoreError::UnsupportedKeyVersion(self.key_version))?;
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        #[allow(deprecated)]
+        let nonce = Nonce::from_slice(&nonce_bytes);
+        let ciphertext = cipher
+            .encrypt(nonce, value)
+            .map_err(|_| SecretStoreError::Encrypt)?;
+
+        let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        blob.extend_from_slice(&nonce_bytes);
+        blob.extend_from_slice(ciphertext.as_slice());
+        Ok(blob)
+    }
+
+    fn decrypt(&self, record: &SecretRecord) -> Result<Vec<u8>, SecretStoreError> {
+        let cipher = self
+            .ciphers
+            .get(&record.key_version)
+            .ok_or(SecretStoreError::UnsupportedKeyVersion(record.key_version))?;
+        if record.secret_blob.len() <= NONCE_LEN {
+            return Err(SecretStoreError::Decrypt);
+        }
+        let (nonce_bytes, cipher_bytes) = record.secret_blob.split_at(NONCE_LEN);
+        #[allow(deprecated)]
+        let nonce = Nonce::from_slice(nonce_bytes);
+        cipher
+            .decrypt(nonce, cipher_bytes)
+            .map_err(|_| SecretStoreError::Decrypt)
+    }
+
     pub async fn delete_secret(
         &self,
         user_id: &str,
@@ -178,3 +383,33 @@ impl Deref for SecretStore {
         &self.db
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keyfile_with_retired_keys() {
+        let key_one = base64::engine::general_purpose::STANDARD.encode([1u8; KEY_LEN]);
+        let key_two = base64::engine::general_purpose::STANDARD.encode([2u8; KEY_LEN]);
+        let keys = SecretKeys::parse_keyfile(&format!(
+            r#"{{"current": 2, "keys": {{"1": "{key_one}", "2": "{key_two}"}}}}"#
+        ))
+        .unwrap();
+        assert_eq!(keys.current_version(), 2);
+        assert_eq!(keys.keys.len(), 2);
+
+        assert!(
+            SecretKeys::parse_keyfile(&format!(
+                r#"{{"current": 3, "keys": {{"1": "{key_one}"}}}}"#
+            ))
+            .is_err()
+        );
+        assert!(SecretKeys::parse_keyfile(r#"{"current": 1, "keys": {"1": "c2hvcnQ="}}"#).is_err());
+    }
+
+    #[test]
+    fn generated_keys_decode() {
+        assert!(decode_key(&SecretKeys::generate_key()).is_ok());
+    }
+}
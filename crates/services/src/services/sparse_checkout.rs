@@ -0,0 +1,131 @@
+//! Cone-mode sparse checkout helpers shared by worktree creation, auto-expansion
+//! and file search.
+use std::path::{Component, Path};
+
+/// Directories materialised by a cone-mode sparse checkout.
+///
+/// In cone mode every file at the repository root is checked out, as is every file
+/// directly inside an ancestor of a cone directory and everything below a cone directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseCone {
+    dirs: Vec<String>,
+}
+
+impl SparseCone {
+    /// Parse comma- or newline-separated directories. Returns `None` when no
+    /// directory is given, i.e. the full tree should be checked out.
+    pub fn parse(patterns: &str) -> Option<Self> {
+        let mut cone = Self::default();
+        for pattern in patterns.split([',', '\n']) {
+            cone.add(pattern);
+        }
+        (!cone.dirs.is_empty()).then_some(cone)
+    }
+
+    pub fn dirs(&self) -> &[String] {
+        &self.dirs
+    }
+
+    /// Add a directory to the cone. Returns false if it was already covered.
+    pub fn add(&mut self, dir: &str) -> bool {
+        let Some(dir) = normalize(dir) else {
+            return false;
+        };
+        if self.dirs.iter().any(|d| is_within(&dir, d)) {
+            return false;
+        }
+        self.dirs.retain(|d| !is_within(d, &dir));
+        self.dirs.push(dir);
+        self.dirs.sort();
+        true
+    }
+
+    /// Whether a repository-relative file path is materialised by the cone
+    pub fn contains_file(&self, rel_path: &str) -> bool {
+        let Some(path) = normalize(rel_path) else {
+            return true;
+        };
+        match path.rsplit_once('/') {
+            None => true,
+            Some((parent, _)) => self.contains_dir(parent),
+        }
+    }
+
+    /// Whether a repository-relative directory exists in the sparse worktree
+    pub fn contains_dir(&self, rel_path: &str) -> bool {
+        let Some(dir) = normalize(rel_path) else {
+            return true;
+        };
+        self.dirs
+            .iter()
+            .any(|d| is_within(&dir, d) || is_within(d, &dir))
+    }
+
+    /// `contains_file` or `contains_dir` depending on what `rel_path` is
+    pub fn contains_path(&self, rel_path: &str, is_file: bool) -> bool {
+        if is_file {
+            self.contains_file(rel_path)
+        } else {
+            self.contains_dir(rel_path)
+        }
+    }
+
+    /// Directory to add to the cone so that `rel_path` is checked out, if it is not already
+    pub fn expansion_for(&self, rel_path: &str) -> Option<String> {
+        if self.contains_file(rel_path) {
+            return None;
+        }
+        normalize(rel_path)?
+            .rsplit_once('/')
+            .map(|(parent, _)| parent.to_string())
+    }
+}
+
+/// Strip `./`, trailing slashes and `..`-escapes from a repository-relative path
+fn normalize(path: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in Path::new(path.trim()).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// `path` equals `dir` or lies below it
+fn is_within(path: &str, dir: &str) -> bool {
+    path == dir
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_normalizes_and_dedups() {
+        let cone = SparseCone::parse("services/api/, ./services\nweb,,").unwrap();
+        assert_eq!(cone.dirs(), ["services", "web"]);
+        assert!(SparseCone::parse(" , \n").is_none());
+    }
+
+    #[test]
+    fn cone_membership() {
+        let cone = SparseCone::parse("services/api").unwrap();
+        assert!(cone.contains_file("README.md"));
+        assert!(cone.contains_file("services/Cargo.toml"));
+        assert!(cone.contains_file("services/api/src/main.rs"));
+        assert!(!cone.contains_file("services/web/index.ts"));
+        assert!(cone.contains_dir("services"));
+        assert!(!cone.contains_dir("docs"));
+        assert_eq!(
+            cone.expansion_for("services/web/index.ts").as_deref(),
+            Some("services/web")
+        );
+        assert_eq!(cone.expansion_for("services/api/lib.rs"), None);
+    }
+}
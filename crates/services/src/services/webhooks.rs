@@ -0,0 +1,821 @@
+//! Outbound webhooks for task and attempt lifecycle events
+//!
+//! Events are derived from the event stream's task and execution process patches and from
+//! approval requests; pull request events are emitted by the code that opens and merges them.
+//! Each event is recorded as a delivery for every subscribed webhook of its project, then
+//! POSTed with an HMAC-SHA256 signature of the body. Failed deliveries are retried with
+//! exponential backoff, and the deliveries table keeps the outcome of the latest attempt.
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    secret::SecretRecord,
+    task::{Task, TaskStatus, TaskWithAttemptStatus},
+    webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookEventType},
+    webhook_delivery::{DeliveryAttempt, WebhookDelivery, WebhookDeliveryStatus},
+};
+use hmac::{Hmac, Mac};
+use rand::{RngCore, rngs::OsRng};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::sync::{
+    Notify,
+    broadcast::{self, error::RecvError},
+};
+use utils::{
+    approvals::ApprovalRequest,
+    log_msg::{LogMsg, SequencedMsg},
+    msg_store::MsgStore,
+};
+use uuid::Uuid;
+
+use super::{
+    approvals::Approvals,
+    secret_store::{SecretDescriptor, SecretStore, SecretStoreError},
+};
+
+/// `sha256=` followed by the hex HMAC-SHA256 of the body, keyed with the webhook's secret
+pub const SIGNATURE_HEADER: &str = "X-Anyon-Signature-256";
+pub const EVENT_HEADER: &str = "X-Anyon-Event";
+/// Id of the delivery; redeliveries get a new one, the payload's `id` stays the same
+pub const DELIVERY_HEADER: &str = "X-Anyon-Delivery";
+
+/// Owner recorded on signing secrets, which belong to a project's webhook rather than a user
+const WEBHOOK_SECRET_OWNER: &str = "webhook";
+const SECRET_PREFIX: &str = "whsec_";
+/// Attempts before a delivery is marked failed
+const MAX_ATTEMPTS: i64 = 6;
+/// Delay before the first retry, doubled for each later one
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+/// How often due retries are looked for when no new delivery wakes the worker
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Bytes of a receiver's response kept in the delivery log
+const MAX_RESPONSE_BODY: usize = 2048;
+const DUE_BATCH_SIZE: i64 = 50;
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("invalid webhook URL '{0}': use an http or https URL")]
+    InvalidUrl(String),
+    #[error("could not resolve the host of webhook URL '{0}'")]
+    UnresolvableHost(String),
+    #[error(
+        "webhook URL '{0}' points to a loopback, private or link-local address; set ANYON_WEBHOOK_ALLOW_PRIVATE=true to allow it"
+    )]
+    PrivateAddress(String),
+    #[error("a webhook must subscribe to at least one event")]
+    NoEvents,
+    #[error("webhook not found")]
+    NotFound,
+    #[error("webhook delivery not found")]
+    DeliveryNotFound,
+    #[error(transparent)]
+    SecretStore(#[from] SecretStoreError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Project webhooks and the worker that delivers their events
+#[derive(Clone)]
+pub struct WebhookService {
+    secret_store: SecretStore,
+    wake: Arc<Notify>,
+}
+
+impl WebhookService {
+    pub fn new(secret_store: SecretStore) -> Self {
+        Self {
+            secret_store,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    fn provider(project_id: Uuid) -> String {
+        format!("webhook:{project_id}")
+    }
+
+    async fn store_secret(&self, webhook: &Webhook, secret: &str) -> Result<(), WebhookError> {
+        let provider = Self::provider(webhook.project_id);
+        let name = webhook.id.to_string();
+        let descriptor = SecretDescriptor {
+            provider: &provider,
+            name: &name,
+        };
+        self.secret_store
+            .put_secret(WEBHOOK_SECRET_OWNER, descriptor, secret.as_bytes())
+            .await?;
+        Ok(())
+    }
+
+    async fn signing_secret(&self, webhook: &Webhook) -> Result<Option<String>, WebhookError> {
+        let provider = Self::provider(webhook.project_id);
+        let name = webhook.id.to_string();
+        let descriptor = SecretDescriptor {
+            provider: &provider,
+            name: &name,
+        };
+        Ok(self
+            .secret_store
+            .get_secret_string(WEBHOOK_SECRET_OWNER, descriptor)
+            .await?)
+    }
+
+    /// Create a webhook; its signing secret is returned so it can be shown once
+    pub async fn create(
+        &self,
+        project_id: Uuid,
+        payload: &CreateWebhook,
+    ) -> Result<(Webhook, String), WebhookError> {
+        validate(&payload.url, &payload.events).await?;
+        let secret = payload
+            .secret
+            .clone()
+            .filter(|secret| !secret.is_empty())
+            .unwrap_or_else(generate_secret);
+        let webhook = Webhook::create(
+            &self.secret_store.pool,
+            Uuid::new_v4(),
+            project_id,
+            &payload.url,
+            &payload.events,
+        )
+        .await?;
+        self.store_secret(&webhook, &secret).await?;
+        Ok((webhook, secret))
+    }
+
+    pub async fn update(
+        &self,
+        webhook: &Webhook,
+        payload: &UpdateWebhook,
+    ) -> Result<Webhook, WebhookError> {
+        let url = payload.url.as_deref().unwrap_or(&webhook.url);
+        let events = payload.events.as_deref().unwrap_or(&webhook.events.0);
+        validate(url, events).await?;
+        if let Some(secret) = payload
+            .secret
+            .as_deref()
+            .filter(|secret| !secret.is_empty())
+        {
+            self.store_secret(webhook, secret).await?;
+        }
+        Ok(Webhook::update(
+            &self.secret_store.pool,
+            webhook.id,
+            url,
+            events,
+            payload.enabled.unwrap_or(webhook.enabled),
+        )
+        .await?)
+    }
+
+    /// Delete a webhook with its signing secret and delivery log
+    pub async fn delete(&self, webhook: &Webhook) -> Result<(), WebhookError> {
+        Webhook::delete(&self.secret_store.pool, webhook.id).await?;
+        let provider = Self::provider(webhook.project_id);
+        let name = webhook.id.to_string();
+        self.secret_store
+            .delete_secret(
+                WEBHOOK_SECRET_OWNER,
+                SecretDescriptor {
+                    provider: &provider,
+                    name: &name,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Delete the signing secrets of a deleted project's webhooks
+    pub async fn delete_secrets_for_project(&self, project_id: Uuid) -> Result<(), WebhookError> {
+        SecretRecord::delete_by_provider(
+            &self.secret_store.pool,
+            WEBHOOK_SECRET_OWNER,
+            &Self::provider(project_id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Queue an event for the project's webhooks subscribed to it
+    pub fn emit(&self, project_id: Uuid, event: WebhookEventType, data: Value) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.enqueue(project_id, event, data).await {
+                tracing::error!("Failed to queue {:?} webhook deliveries: {}", event, e);
+            }
+        });
+    }
+
+    async fn enqueue(
+        &self,
+        project_id: Uuid,
+        event: WebhookEventType,
+        data: Value,
+    ) -> Result<(), sqlx::Error> {
+        let pool = &self.secret_store.pool;
+        let webhooks: Vec<Webhook> = Webhook::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .filter(|webhook| webhook.is_subscribed(event))
+            .collect();
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let payload = json!({
+            "id": Uuid::new_v4(),
+            "event": event,
+            "created_at": Utc::now(),
+            "project_id": project_id,
+            "data": data,
+        });
+        for webhook in webhooks {
+            WebhookDelivery::create(pool, webhook.id, event, &payload).await?;
+        }
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    /// Send a delivery's payload again, as a new delivery
+    pub async fn redeliver(
+        &self,
+        delivery: &WebhookDelivery,
+    ) -> Result<WebhookDelivery, WebhookError> {
+        let redelivery = WebhookDelivery::create(
+            &self.secret_store.pool,
+            delivery.webhook_id,
+            delivery.event,
+            &delivery.payload.0,
+        )
+        .await?;
+        self.wake.notify_one();
+        Ok(redelivery)
+    }
+
+    /// Start delivering queued events and watching for lifecycle events
+    pub fn spawn(
+        &self,
+        events: Arc<MsgStore>,
+        approvals: &Approvals,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        let event_stream = events.get_receiver();
+        let approval_requests = approvals.subscribe();
+        tokio::spawn(async move {
+            tokio::join!(
+                service.deliver_loop(),
+                service.watch_lifecycle(event_stream),
+                service.watch_approvals(approval_requests),
+            );
+        })
+    }
+
+    async fn deliver_loop(&self) {
+        loop {
+            if let Err(e) = self.deliver_due().await {
+                tracing::error!("Failed to deliver webhooks: {}", e);
+            }
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    }
+
+    async fn deliver_due(&self) -> Result<(), sqlx::Error> {
+        let pool = &self.secret_store.pool;
+        loop {
+            let due = WebhookDelivery::find_due(pool, Utc::now(), DUE_BATCH_SIZE).await?;
+            for delivery in &due {
+                let attempt = self.attempt(delivery).await;
+                WebhookDelivery::record_attempt(pool, delivery.id, &attempt).await?;
+            }
+            if (due.len() as i64) < DUE_BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn attempt(&self, delivery: &WebhookDelivery) -> DeliveryAttempt {
+        let attempts = delivery.attempts + 1;
+        match self.send(delivery).await {
+            Ok((status, body)) if (200..300).contains(&status) => DeliveryAttempt {
+                status: WebhookDeliveryStatus::Succeeded,
+                response_status: Some(status),
+                response_body: Some(body),
+                error: None,
+                next_attempt_at: None,
+            },
+            Ok((status, body)) => failed_attempt(
+                attempts,
+                Some(status),
+                Some(body),
+                format!("receiver responded with status {status}"),
+            ),
+            Err(error) => failed_attempt(attempts, None, None, error),
+        }
+    }
+
+    /// POST a delivery, returning the response status and the start of its body
+    async fn send(&self, delivery: &WebhookDelivery) -> Result<(i64, String), String> {
+        let pool = &self.secret_store.pool;
+        let webhook = Webhook::find_by_id(pool, delivery.webhook_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("webhook was deleted")?;
+        if !webhook.enabled {
+            return Err("webhook is disabled".to_string());
+        }
+        let secret = self
+            .signing_secret(&webhook)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("webhook has no signing secret")?;
+        let body = serde_json::to_vec(&delivery.payload.0).map_err(|e| e.to_string())?;
+
+        // Resolve again: the host may point somewhere else than when the webhook was saved
+        let target = WebhookTarget::resolve(&webhook.url)
+            .await
+            .map_err(|e| e.to_string())?;
+        let response = target
+            .client()
+            .map_err(|e| e.to_string())?
+            .post(target.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event_name(delivery.event))
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(SIGNATURE_HEADER, sign(&secret, &body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status().as_u16() as i64;
+        let mut body = response.text().await.unwrap_or_default();
+        if let Some((end, _)) = body.char_indices().nth(MAX_RESPONSE_BODY) {
+            body.truncate(end);
+        }
+        Ok((status, body))
+    }
+
+    async fn watch_lifecycle(&self, mut event_stream: broadcast::Receiver<SequencedMsg>) {
+        let pool = &self.secret_store.pool;
+        let mut tracker = LifecycleTracker::default();
+        match Task::find_all_statuses(pool).await {
+            Ok(statuses) => tracker.task_statuses.extend(statuses),
+            Err(e) => tracing::error!("Failed to load task statuses for webhooks: {}", e),
+        }
+
+        loop {
+            let msg = match event_stream.recv().await {
+                Ok(msg) => msg,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Webhooks missed {} events from the event stream", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            let LogMsg::JsonPatch(patch) = msg.msg else {
+                continue;
+            };
+            for op in &patch.0 {
+                let Ok(op) = serde_json::to_value(op) else {
+                    continue;
+                };
+                match tracker.apply(&op) {
+                    Some(LifecycleEvent::Task {
+                        event,
+                        task,
+                        previous_status,
+                    }) => {
+                        let project_id = task.project_id;
+                        let mut data = json!({ "task": task });
+                        if let Some(previous_status) = previous_status {
+                            data["previous_status"] = json!(previous_status);
+                        }
+                        self.emit(project_id, event, data);
+                    }
+                    Some(LifecycleEvent::Process { event, process }) => {
+                        let Ok(ctx) = ExecutionProcess::load_context(pool, process.id).await else {
+                            continue;
+                        };
+                        self.emit(
+                            ctx.task.project_id,
+                            event,
+                            json!({
+                                "task_id": ctx.task.id,
+                                "attempt_id": ctx.task_attempt.id,
+                                "branch": ctx.task_attempt.branch,
+                                "execution_process_id": process.id,
+                                "run_reason": process.run_reason,
+                                "status": process.status,
+                                "exit_code": process.exit_code,
+                            }),
+                        );
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    async fn watch_approvals(&self, mut requests: broadcast::Receiver<ApprovalRequest>) {
+        loop {
+            let request = match requests.recv().await {
+                Ok(request) => request,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+            let Ok(ctx) = ExecutionProcess::load_context(
+                &self.secret_store.pool,
+                request.execution_process_id,
+            )
+            .await
+            else {
+                continue;
+            };
+            self.emit(
+                ctx.task.project_id,
+                WebhookEventType::ApprovalRequested,
+                json!({
+                    "task_id": ctx.task.id,
+                    "attempt_id": ctx.task_attempt.id,
+                    "execution_process_id": request.execution_process_id,
+                    "approval_id": request.id,
+                    "tool_name": request.tool_name,
+                    "tool_input": request.tool_input,
+                    "timeout_at": request.timeout_at,
+                }),
+            );
+        }
+    }
+}
+
+/// A lifecycle event found in the event stream
+enum LifecycleEvent {
+    Task {
+        event: WebhookEventType,
+        task: TaskWithAttemptStatus,
+        previous_status: Option<TaskStatus>,
+    },
+    Process {
+        event: WebhookEventType,
+        process: ExecutionProcess,
+    },
+}
+
+/// Remembers what it needs to tell a lifecycle change from any other update in the event
+/// stream's patches
+#[derive(Default)]
+struct LifecycleTracker {
+    task_statuses: HashMap<Uuid, TaskStatus>,
+    running_processes: HashSet<Uuid>,
+}
+
+impl LifecycleTracker {
+    fn apply(&mut self, op: &Value) -> Option<LifecycleEvent> {
+        let path = op.get("path")?.as_str()?;
+        let removed = op.get("op")?.as_str()? == "remove";
+
+        if let Some(id) = path.strip_prefix("/tasks/") {
+            if removed {
+                self.task_statuses.remove(&id.parse().ok()?);
+                return None;
+            }
+            let task: TaskWithAttemptStatus =
+                serde_json::from_value(op.get("value")?.clone()).ok()?;
+            return match self.task_statuses.insert(task.id, task.status.clone()) {
+                None => Some(LifecycleEvent::Task {
+                    event: WebhookEventType::TaskCreated,
+                    task,
+                    previous_status: None,
+                }),
+                Some(previous) if previous != task.status => Some(LifecycleEvent::Task {
+                    event: WebhookEventType::TaskStatusChanged,
+                    task,
+                    previous_status: Some(previous),
+                }),
+                Some(_) => None,
+            };
+        }
+
+        let id = path.strip_prefix("/execution_processes/")?;
+        if removed {
+            self.running_processes.remove(&id.parse().ok()?);
+            return None;
+        }
+        let process: ExecutionProcess = serde_json::from_value(op.get("value")?.clone()).ok()?;
+        if process.status == ExecutionProcessStatus::Running {
+            let started = self.running_processes.insert(process.id)
+                && process.run_reason == ExecutionProcessRunReason::CodingAgent;
+            return started.then_some(LifecycleEvent::Process {
+                event: WebhookEventType::AttemptStarted,
+                process,
+            });
+        }
+        // Later updates to a finished process, such as its after-head commit, are not events
+        if !self.running_processes.remove(&process.id) {
+            return None;
+        }
+        let event = match (&process.run_reason, &process.status) {
+            (ExecutionProcessRunReason::CodingAgent, ExecutionProcessStatus::Completed) => {
+                WebhookEventType::AttemptFinished
+            }
+            (
+                ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::SetupScript,
+                ExecutionProcessStatus::Failed,
+            ) => WebhookEventType::AttemptFailed,
+            _ => return None,
+        };
+        Some(LifecycleEvent::Process { event, process })
+    }
+}
+
+/// Signature header value for a body: `sha256=` and the hex HMAC-SHA256 keyed with `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{SECRET_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+async fn validate(url: &str, events: &[WebhookEventType]) -> Result<(), WebhookError> {
+    WebhookTarget::resolve(url).await?;
+    if events.is_empty() {
+        return Err(WebhookError::NoEvents);
+    }
+    Ok(())
+}
+
+/// A webhook URL with the addresses its host resolved to
+struct WebhookTarget {
+    url: reqwest::Url,
+    /// Set when the host is a name rather than an IP address
+    domain: Option<String>,
+    addrs: Vec<SocketAddr>,
+}
+
+impl WebhookTarget {
+    /// Resolve the URL's host, refusing loopback, private and link-local addresses so webhooks
+    /// can't be used to reach services on the server's own network, unless
+    /// `ANYON_WEBHOOK_ALLOW_PRIVATE=true`
+    async fn resolve(url: &str) -> Result<Self, WebhookError> {
+        let invalid = || WebhookError::InvalidUrl(url.to_string());
+        let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(invalid());
+        }
+        let host = parsed.host_str().ok_or_else(invalid)?;
+        let port = parsed.port_or_known_default().ok_or_else(invalid)?;
+
+        let ip_literal = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .ok();
+        let (domain, addrs) = match ip_literal {
+            Some(ip) => (None, vec![SocketAddr::new(ip, port)]),
+            None => {
+                let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+                    .await
+                    .map_err(|_| WebhookError::UnresolvableHost(url.to_string()))?
+                    .collect();
+                (Some(host.to_string()), addrs)
+            }
+        };
+        if addrs.is_empty() {
+            return Err(WebhookError::UnresolvableHost(url.to_string()));
+        }
+        if !allow_private_addresses() && addrs.iter().any(|addr| !is_public(addr.ip())) {
+            return Err(WebhookError::PrivateAddress(url.to_string()));
+        }
+
+        Ok(Self {
+            url: parsed,
+            domain,
+            addrs,
+        })
+    }
+
+    /// Client that connects only to the checked addresses and doesn't follow redirects, which
+    /// could lead to an address that was never checked
+    fn client(&self) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent("Anyon-Webhook")
+            .redirect(reqwest::redirect::Policy::none());
+        if let Some(domain) = &self.domain {
+            builder = builder.resolve_to_addrs(domain, &self.addrs);
+        }
+        builder.build()
+    }
+}
+
+fn allow_private_addresses() -> bool {
+    std::env::var("ANYON_WEBHOOK_ALLOW_PRIVATE")
+        .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"))
+}
+
+/// Whether an address is reachable on the public internet
+pub(crate) fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                // Shared address space (carrier-grade NAT)
+                || (a == 100 && (64..128).contains(&b))
+                || a == 0)
+        }
+        IpAddr::V6(ip) => match embedded_ipv4(ip) {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    // Unique local (fc00::/7) and link-local (fe80::/10)
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// IPv4 address an IPv6 address stands for: IPv4-mapped (`::ffff:a.b.c.d`), IPv4-compatible
+/// (`::a.b.c.d`), NAT64 (`64:ff9b::a.b.c.d`) and 6to4 (`2002:aabb:ccdd::`) forms all reach it
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    if let Some(ip) = ip.to_ipv4_mapped() {
+        return Some(ip);
+    }
+    let segments = ip.segments();
+    let octets = ip.octets();
+    let last_four = Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15]);
+    match segments {
+        // `::` and `::1` are the unspecified and loopback addresses, not IPv4-compatible ones
+        [0, 0, 0, 0, 0, 0, ..] if !ip.is_unspecified() && !ip.is_loopback() => Some(last_four),
+        [0x64, 0xff9b, 0, 0, 0, 0, ..] => Some(last_four),
+        [0x2002, ..] => Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5])),
+        _ => None,
+    }
+}
+
+fn event_name(event: WebhookEventType) -> String {
+    serde_json::to_value(event)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Delay before retrying after the given number of attempts, or None to give up
+fn retry_delay(attempts: i64) -> Option<Duration> {
+    (attempts < MAX_ATTEMPTS).then(|| RETRY_BASE_DELAY * 2u32.pow(attempts.max(1) as u32 - 1))
+}
+
+fn failed_attempt(
+    attempts: i64,
+    response_status: Option<i64>,
+    response_body: Option<String>,
+    error: String,
+) -> DeliveryAttempt {
+    let next_attempt_at = retry_delay(attempts)
+        .and_then(|delay| chrono::Duration::from_std(delay).ok())
+        .map(|delay| Utc::now() + delay);
+    DeliveryAttempt {
+        status: if next_attempt_at.is_some() {
+            WebhookDeliveryStatus::Pending
+        } else {
+            WebhookDeliveryStatus::Failed
+        },
+        response_status,
+        response_body,
+        error: Some(error),
+        next_attempt_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_op(op: &str, id: Uuid, status: &str) -> Value {
+        json!({
+            "op": op,
+            "path": format!("/tasks/{id}"),
+            "value": {
+                "id": id,
+                "project_id": Uuid::nil(),
+                "title": "Task",
+                "description": null,
+                "status": status,
+                "parent_task_attempt": null,
+                "created_at": "2025-01-01T00:00:00Z",
+                "updated_at": "2025-01-01T00:00:00Z",
+                "has_in_progress_attempt": false,
+                "has_merged_attempt": false,
+                "last_attempt_failed": false,
+                "executor": "",
+            }
+        })
+    }
+
+    #[test]
+    fn task_patches_become_created_and_status_changed_events() {
+        let mut tracker = LifecycleTracker::default();
+        let id = Uuid::new_v4();
+        assert!(matches!(
+            tracker.apply(&task_op("add", id, "todo")),
+            Some(LifecycleEvent::Task {
+                event: WebhookEventType::TaskCreated,
+                ..
+            })
+        ));
+        assert!(tracker.apply(&task_op("replace", id, "todo")).is_none());
+        assert!(matches!(
+            tracker.apply(&task_op("replace", id, "inprogress")),
+            Some(LifecycleEvent::Task {
+                event: WebhookEventType::TaskStatusChanged,
+                previous_status: Some(TaskStatus::Todo),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn retries_back_off_then_give_up() {
+        assert_eq!(retry_delay(1), Some(RETRY_BASE_DELAY));
+        assert_eq!(retry_delay(3), Some(RETRY_BASE_DELAY * 4));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+        assert_eq!(
+            failed_attempt(MAX_ATTEMPTS, None, None, "timeout".to_string()).status,
+            WebhookDeliveryStatus::Failed
+        );
+    }
+
+    #[tokio::test]
+    async fn private_and_local_urls_are_refused() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.10/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "http://[::10.0.0.5]/hook",
+            "http://[64:ff9b::a9fe:a9fe]/hook",
+            "http://[2002:c0a8:10a::1]/hook",
+        ] {
+            assert!(
+                matches!(
+                    WebhookTarget::resolve(url).await,
+                    Err(WebhookError::PrivateAddress(_))
+                ),
+                "{url}"
+            );
+        }
+        assert!(matches!(
+            WebhookTarget::resolve("ftp://example.com/hook").await,
+            Err(WebhookError::InvalidUrl(_))
+        ));
+        assert!(
+            WebhookTarget::resolve("https://93.184.216.34/hook")
+                .await
+                .is_ok()
+        );
+        assert!(
+            WebhookTarget::resolve("https://[64:ff9b::5db8:d822]/hook")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn signatures_are_hex_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
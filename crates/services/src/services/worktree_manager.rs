@@ -5,19 +5,23 @@ use std::{
 };
 
 use git2::{Error as GitError, Repository};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 use utils::shell::resolve_executable_path;
 
 use super::{
     git::{GitService, GitServiceError},
-    git_cli::GitCli,
+    git_cli::{GitCli, SubmoduleEntry},
+    sparse_checkout::SparseCone,
 };
 
 // Global synchronization for worktree creation to prevent race conditions
 lazy_static::lazy_static! {
     static ref WORKTREE_CREATION_LOCKS: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
         Arc::new(Mutex::new(HashMap::new()));
+    static ref SUBMODULE_CACHE_LOCKS: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> =
+        Mutex::new(HashMap::new());
 }
 
 #[derive(Debug, Error)]
@@ -40,6 +44,18 @@ pub enum WorktreeError {
     Repository(String),
 }
 
+/// Marks a directory holding the worktrees of a multi-repository attempt
+pub const WORKSPACE_MARKER: &str = ".anyon-workspace";
+
+/// Extra checkout steps applied when a worktree is (re)created
+#[derive(Debug, Clone, Default)]
+pub struct WorktreeOptions {
+    /// Initialise submodules (shallow, from a shared object cache)
+    pub init_submodules: bool,
+    /// Only materialise these directories (cone-mode sparse checkout)
+    pub sparse_cone: Option<SparseCone>,
+}
+
 pub struct WorktreeManager;
 
 impl WorktreeManager {
@@ -50,6 +66,7 @@ impl WorktreeManager {
         worktree_path: &Path,
         base_branch: &str,
         create_branch: bool,
+        options: &WorktreeOptions,
     ) -> Result<(), WorktreeError> {
         if create_branch {
             let repo_path_owned = repo_path.to_path_buf();
@@ -71,7 +88,7 @@ impl WorktreeManager {
             .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))??;
         }
 
-        match Self::ensure_worktree_exists(repo_path, branch_name, worktree_path).await {
+        match Self::ensure_worktree_exists(repo_path, branch_name, worktree_path, options).await {
             Ok(()) => Ok(()),
             Err(err) => {
                 error!(
@@ -103,6 +120,7 @@ impl WorktreeManager {
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
+        options: &WorktreeOptions,
     ) -> Result<(), WorktreeError> {
         let path_str = worktree_path.to_string_lossy().to_string();
 
@@ -126,7 +144,8 @@ impl WorktreeManager {
 
         // If worktree doesn't exist or isn't properly set up, recreate it
         info!("Worktree needs recreation at path: {}", path_str);
-        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path).await
+        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path, options).await?;
+        Self::prepare_checkout(worktree_path, options).await
     }
 
     /// Internal worktree recreation function (always recreates)
@@ -134,6 +153,7 @@ impl WorktreeManager {
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
+        options: &WorktreeOptions,
     ) -> Result<(), WorktreeError> {
         let path_str = worktree_path.to_string_lossy().to_string();
         let branch_name_owned = branch_name.to_string();
@@ -178,10 +198,144 @@ impl WorktreeManager {
             &worktree_path_owned,
             &worktree_name,
             &path_str,
+            options.sparse_cone.as_ref(),
         )
         .await
     }
 
+    /// Materialise LFS files and, if requested, submodules in a fresh worktree.
+    /// Failures are logged rather than returned so the attempt can still start.
+    async fn prepare_checkout(
+        worktree_path: &Path,
+        options: &WorktreeOptions,
+    ) -> Result<(), WorktreeError> {
+        let worktree_path = worktree_path.to_path_buf();
+        let init_submodules = options.init_submodules;
+        let sparse_cone = options.sparse_cone.clone();
+        tokio::task::spawn_blocking(move || {
+            let git = GitCli::new();
+            if init_submodules
+                && let Err(e) = Self::init_submodules(&git, &worktree_path, sparse_cone.as_ref())
+            {
+                warn!(
+                    error = %e,
+                    worktree = %worktree_path.display(),
+                    "Failed to initialise submodules"
+                );
+            }
+            if git.uses_lfs(&worktree_path)
+                && let Err(e) = git.lfs_pull(&worktree_path)
+            {
+                warn!(
+                    error = %e,
+                    worktree = %worktree_path.display(),
+                    "Failed to check out Git LFS files"
+                );
+            }
+        })
+        .await
+        .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))
+    }
+
+    fn init_submodules(
+        git: &GitCli,
+        worktree_path: &Path,
+        sparse_cone: Option<&SparseCone>,
+    ) -> Result<(), WorktreeError> {
+        let cli_err = |e: super::git_cli::GitCliError| WorktreeError::GitCli(e.to_string());
+        let mut submodules = git.list_submodules(worktree_path).map_err(cli_err)?;
+        // Submodules outside a sparse cone are not checked out
+        submodules
+            .retain(|submodule| sparse_cone.is_none_or(|cone| cone.contains_dir(&submodule.path)));
+        if submodules.is_empty() {
+            return Ok(());
+        }
+        git.submodule_init(worktree_path).map_err(cli_err)?;
+
+        for submodule in &submodules {
+            if let Err(e) = Self::update_submodule_from_cache(git, worktree_path, submodule) {
+                debug!(
+                    "Cached update of submodule {} failed, fetching directly: {}",
+                    submodule.path, e
+                );
+                if git
+                    .submodule_update(worktree_path, &[&submodule.path], &[], true, false)
+                    .is_err()
+                {
+                    // Servers that refuse shallow fetches of arbitrary commits need a full clone
+                    git.submodule_update(worktree_path, &[&submodule.path], &[], false, false)
+                        .map_err(cli_err)?;
+                }
+            }
+        }
+
+        // Nested submodules are fetched directly; top-level ones are already at the right commit
+        let paths: Vec<&str> = submodules.iter().map(|s| s.path.as_str()).collect();
+        if git
+            .submodule_update(worktree_path, &paths, &[], true, true)
+            .is_err()
+        {
+            git.submodule_update(worktree_path, &paths, &[], false, true)
+                .map_err(cli_err)?;
+        }
+        info!(
+            "Initialised {} submodule(s) in {}",
+            submodules.len(),
+            worktree_path.display()
+        );
+        Ok(())
+    }
+
+    /// Check a submodule out from the shared cache, fetching only the pinned commit into it
+    fn update_submodule_from_cache(
+        git: &GitCli,
+        worktree_path: &Path,
+        submodule: &SubmoduleEntry,
+    ) -> Result<(), WorktreeError> {
+        let cli_err = |e: super::git_cli::GitCliError| WorktreeError::GitCli(e.to_string());
+        if submodule.url.starts_with("./") || submodule.url.starts_with("../") {
+            return Err(WorktreeError::Repository(format!(
+                "relative submodule URL {} is not cached",
+                submodule.url
+            )));
+        }
+        let commit = git
+            .submodule_head_commit(worktree_path, &submodule.path)
+            .map_err(cli_err)?;
+        let cache_path = Self::get_submodule_cache_dir().join(format!(
+            "{:x}.git",
+            Sha256::digest(submodule.url.as_bytes())
+        ));
+
+        {
+            let lock = SUBMODULE_CACHE_LOCKS
+                .lock()
+                .unwrap()
+                .entry(cache_path.clone())
+                .or_insert_with(|| Arc::new(Mutex::new(())))
+                .clone();
+            let _guard = lock.lock().unwrap();
+            git.fetch_into_cache(&cache_path, &submodule.url, &commit)
+                .map_err(cli_err)?;
+        }
+
+        git.submodule_update(
+            worktree_path,
+            &[&submodule.path],
+            &[(&submodule.name, &cache_path)],
+            false,
+            false,
+        )
+        .map_err(cli_err)?;
+        // The clone's origin points at the cache; restore the real remote for the agent
+        git.set_remote_url(
+            &worktree_path.join(&submodule.path),
+            "origin",
+            &submodule.url,
+        )
+        .map_err(cli_err)
+    }
+
     /// Check if a worktree is properly set up (filesystem + git metadata)
     async fn is_worktree_properly_set_up(
         repo_path: &Path,
@@ -308,7 +462,9 @@ impl WorktreeManager {
         worktree_path: &Path,
         worktree_name: &str,
         path_str: &str,
+        sparse_cone: Option<&SparseCone>,
     ) -> Result<(), WorktreeError> {
+        let sparse_dirs = sparse_cone.map(|cone| cone.dirs().to_vec());
         let git_repo_path = git_repo_path.to_path_buf();
         let branch_name = branch_name.to_string();
         let worktree_path = worktree_path.to_path_buf();
@@ -318,7 +474,13 @@ impl WorktreeManager {
         tokio::task::spawn_blocking(move || -> Result<(), WorktreeError> {
             // Prefer git CLI for worktree add to inherit sparse-checkout semantics
             let git = GitCli::new();
-            match git.worktree_add(&git_repo_path, &worktree_path, &branch_name, false) {
+            let add_worktree = || match &sparse_dirs {
+                Some(dirs) => {
+                    git.worktree_add_sparse(&git_repo_path, &worktree_path, &branch_name, dirs)
+                }
+                None => git.worktree_add(&git_repo_path, &worktree_path, &branch_name, false),
+            };
+            match add_worktree() {
                 Ok(()) => {
                     if !worktree_path.exists() {
                         return Err(WorktreeError::Repository(format!(
@@ -339,9 +501,7 @@ impl WorktreeManager {
                     // Force cleanup metadata and try one more time
                     Self::force_cleanup_worktree_metadata(&git_repo_path, &worktree_name)
                         .map_err(WorktreeError::Io)?;
-                    if let Err(e2) =
-                        git.worktree_add(&git_repo_path, &worktree_path, &branch_name, false)
-                    {
+                    if let Err(e2) = add_worktree() {
                         debug!("Retry of git worktree add failed: {}", e2);
                         return Err(WorktreeError::GitCli(e2.to_string()));
                     }
@@ -496,6 +656,44 @@ impl WorktreeManager {
         .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
     }
 
+    /// Create the parent directory that holds one worktree per repository of a
+    /// multi-repository project.
+    pub async fn create_workspace_dir(workspace_path: &Path) -> Result<(), WorktreeError> {
+        tokio::fs::create_dir_all(workspace_path).await?;
+        tokio::fs::write(workspace_path.join(WORKSPACE_MARKER), b"").await?;
+        Ok(())
+    }
+
+    /// The workspace directory containing `worktree_path`, if the worktree belongs
+    /// to a multi-repository attempt.
+    pub fn workspace_root(worktree_path: &Path) -> Option<PathBuf> {
+        let parent = worktree_path.parent()?;
+        parent
+            .join(WORKSPACE_MARKER)
+            .exists()
+            .then(|| parent.to_path_buf())
+    }
+
+    /// Whether `path` is a multi-repository workspace directory
+    pub fn is_workspace_dir(path: &Path) -> bool {
+        path.join(WORKSPACE_MARKER).exists()
+    }
+
+    /// Remove a workspace directory once its worktrees have been cleaned up
+    pub async fn cleanup_workspace_dir(workspace_path: &Path) -> Result<(), WorktreeError> {
+        if Self::is_workspace_dir(workspace_path) {
+            tokio::fs::remove_dir_all(workspace_path).await?;
+            info!("Removed workspace directory: {}", workspace_path.display());
+        }
+        Ok(())
+    }
+
+    /// Directory holding bare repositories that cache submodule objects across attempts.
+    /// Kept outside the worktree base dir so orphan cleanup leaves it alone.
+    pub fn get_submodule_cache_dir() -> PathBuf {
+        utils::path::get_anyon_temp_dir().join("submodule-cache")
+    }
+
     /// Get the base directory for anyon worktrees
     pub fn get_worktree_base_dir() -> std::path::PathBuf {
         if let Ok(custom) = std::env::var("ANYON_WORKTREE_DIR") {
@@ -0,0 +1,355 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+};
+use db::models::merge::MergeStatus;
+use serde_json::{Value, json};
+use services::services::{
+    forge::{
+        ForgeError, ForgeProvider, ForgeRepoInfo, ForgeService, GitLabService, GiteaService,
+        ensure_public_base_url,
+    },
+    github_service::CreatePrRequest,
+};
+
+#[derive(Clone, Default)]
+struct Recorded {
+    auth_headers: Arc<Mutex<Vec<String>>>,
+    bodies: Arc<Mutex<Vec<Value>>>,
+}
+
+impl Recorded {
+    fn record(&self, headers: &HeaderMap, name: &str) {
+        if let Some(value) = headers.get(name).and_then(|v| v.to_str().ok()) {
+            self.auth_headers.lock().unwrap().push(value.to_string());
+        }
+    }
+}
+
+async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn pr_request() -> CreatePrRequest {
+    CreatePrRequest {
+        title: "Add feature".to_string(),
+        body: Some("Body".to_string()),
+        head_branch: "anyon/feature".to_string(),
+        base_branch: "main".to_string(),
+    }
+}
+
+fn gitlab_mr(iid: i64, state: &str, base: &str) -> Value {
+    json!({
+        "iid": iid,
+        "web_url": format!("{base}/group/sub/repo/-/merge_requests/{iid}"),
+        "state": state,
+        "merged_at": if state == "merged" { json!("2025-01-02T03:04:05Z") } else { Value::Null },
+        "merge_commit_sha": if state == "merged" { json!("abc123") } else { Value::Null },
+    })
+}
+
+fn gitea_pr(number: i64, state: &str, merged: bool, head: &str, base: &str) -> Value {
+    json!({
+        "number": number,
+        "html_url": format!("{base}/owner/repo/pulls/{number}"),
+        "state": state,
+        "merged": merged,
+        "merged_at": if merged { json!("2025-01-02T03:04:05Z") } else { Value::Null },
+        "merge_commit_sha": if merged { json!("def456") } else { Value::Null },
+        "head": { "ref": head },
+    })
+}
+
+#[test]
+fn parses_remote_and_pr_urls_for_each_provider() {
+    let info = ForgeRepoInfo::from_remote_url("git@gitlab.example.com:group/sub/repo.git").unwrap();
+    assert_eq!(info.provider, ForgeProvider::GitLab);
+    assert_eq!(info.owner, "group/sub");
+    assert_eq!(info.repo_name, "repo");
+    assert_eq!(info.web_base_url(), "https://gitlab.example.com");
+
+    let info = ForgeRepoInfo::from_remote_url(
+        "https://gitlab.example.com/group/sub/repo/-/merge_requests/12",
+    )
+    .unwrap();
+    assert_eq!(info.full_name(), "group/sub/repo");
+
+    let info =
+        ForgeRepoInfo::from_remote_url("ssh://git@gitea.example.com:2222/owner/repo.git").unwrap();
+    assert_eq!(info.provider, ForgeProvider::Gitea);
+    assert_eq!(info.host, "gitea.example.com");
+    assert_eq!(info.full_name(), "owner/repo");
+
+    let info = ForgeRepoInfo::parse(
+        "http://git.internal:3000/owner/repo/pulls/7",
+        ForgeProvider::Gitea,
+    )
+    .unwrap();
+    assert_eq!(info.web_base_url(), "http://git.internal:3000");
+    assert_eq!(info.host_name(), "git.internal");
+    assert_eq!(info.full_name(), "owner/repo");
+
+    let info = ForgeRepoInfo::from_remote_url("https://github.com/owner/repo/pull/123").unwrap();
+    assert_eq!(info.provider, ForgeProvider::GitHub);
+    assert_eq!(info.full_name(), "owner/repo");
+
+    let err = ForgeRepoInfo::from_remote_url("https://git.internal/owner/repo.git").unwrap_err();
+    assert!(matches!(err, ForgeError::UnsupportedRemote(_)));
+}
+
+#[tokio::test]
+async fn gitlab_creates_lists_and_reads_merge_requests() {
+    let recorded = Recorded::default();
+    let base = Arc::new(Mutex::new(String::new()));
+
+    let router = Router::new()
+        .route(
+            "/api/v4/projects/{id}/merge_requests",
+            get(
+                |State((rec, base)): State<(Recorded, Arc<Mutex<String>>)>,
+                 Path(id): Path<String>,
+                 Query(query): Query<Vec<(String, String)>>,
+                 headers: HeaderMap| async move {
+                    rec.record(&headers, "PRIVATE-TOKEN");
+                    assert_eq!(id, "group/sub/repo");
+                    assert!(query.contains(&("source_branch".into(), "anyon/feature".into())));
+                    let base = base.lock().unwrap().clone();
+                    Json(json!([
+                        gitlab_mr(3, "merged", &base),
+                        gitlab_mr(4, "opened", &base)
+                    ]))
+                },
+            )
+            .post(
+                |State((rec, base)): State<(Recorded, Arc<Mutex<String>>)>,
+                 headers: HeaderMap,
+                 Json(body): Json<Value>| async move {
+                    rec.record(&headers, "PRIVATE-TOKEN");
+                    rec.bodies.lock().unwrap().push(body);
+                    let base = base.lock().unwrap().clone();
+                    (StatusCode::CREATED, Json(gitlab_mr(5, "opened", &base)))
+                },
+            ),
+        )
+        .route(
+            "/api/v4/projects/{id}/merge_requests/{iid}",
+            get(
+                |State((_, base)): State<(Recorded, Arc<Mutex<String>>)>,
+                 Path((_, iid)): Path<(String, i64)>| async move {
+                    let base = base.lock().unwrap().clone();
+                    Json(gitlab_mr(iid, "merged", &base))
+                },
+            ),
+        )
+        .with_state((recorded.clone(), base.clone()));
+
+    let url = serve(router).await;
+    *base.lock().unwrap() = url.clone();
+
+    let repo_info =
+        ForgeRepoInfo::parse(&format!("{url}/group/sub/repo.git"), ForgeProvider::GitLab).unwrap();
+    let service = GitLabService::new(&repo_info.web_base_url(), "glpat-test").unwrap();
+
+    let created = service.create_pr(&repo_info, &pr_request()).await.unwrap();
+    assert_eq!(created.number, 5);
+    assert!(matches!(created.status, MergeStatus::Open));
+    assert!(created.url.ends_with("/group/sub/repo/-/merge_requests/5"));
+
+    let body = recorded.bodies.lock().unwrap()[0].clone();
+    assert_eq!(body["source_branch"], "anyon/feature");
+    assert_eq!(body["target_branch"], "main");
+    assert_eq!(body["description"], "Body");
+
+    let listed = service
+        .list_all_prs_for_branch(&repo_info, "anyon/feature")
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 2);
+    assert!(matches!(listed[0].status, MergeStatus::Merged));
+    assert_eq!(listed[0].merge_commit_sha.as_deref(), Some("abc123"));
+
+    let status = service.update_pr_status(&repo_info, 5).await.unwrap();
+    assert!(matches!(status.status, MergeStatus::Merged));
+    assert!(status.merged_at.is_some());
+
+    // The merge request URL resolves back to the same project
+    let from_pr = ForgeRepoInfo::parse(&status.url, ForgeProvider::GitLab).unwrap();
+    assert_eq!(from_pr, repo_info);
+
+    let headers = recorded.auth_headers.lock().unwrap().clone();
+    assert!(headers.iter().all(|h| h == "glpat-test"));
+    assert_eq!(headers.len(), 2);
+}
+
+#[tokio::test]
+async fn gitea_filters_pull_requests_by_head_branch() {
+    let recorded = Recorded::default();
+    let base = Arc::new(Mutex::new(String::new()));
+
+    let router =
+        Router::new()
+            .route(
+                "/api/v1/repos/{owner}/{repo}/pulls",
+                get(
+                    |State((rec, base)): State<(Recorded, Arc<Mutex<String>>)>,
+                     headers: HeaderMap| async move {
+                        rec.record(&headers, "Authorization");
+                        let base = base.lock().unwrap().clone();
+                        Json(json!([
+                            gitea_pr(1, "closed", true, "anyon/feature", &base),
+                            gitea_pr(2, "open", false, "other", &base),
+                            gitea_pr(3, "closed", false, "anyon/feature", &base),
+                        ]))
+                    },
+                )
+                .post(
+                    |State((rec, base)): State<(Recorded, Arc<Mutex<String>>)>,
+                     headers: HeaderMap,
+                     Json(body): Json<Value>| async move {
+                        rec.record(&headers, "Authorization");
+                        rec.bodies.lock().unwrap().push(body);
+                        let base = base.lock().unwrap().clone();
+                        (
+                            StatusCode::CREATED,
+                            Json(gitea_pr(9, "open", false, "anyon/feature", &base)),
+                        )
+                    },
+                ),
+            )
+            .with_state((recorded.clone(), base.clone()));
+
+    let url = serve(router).await;
+    *base.lock().unwrap() = url.clone();
+
+    let repo_info =
+        ForgeRepoInfo::parse(&format!("{url}/owner/repo.git"), ForgeProvider::Gitea).unwrap();
+    let service = GiteaService::new(&repo_info.web_base_url(), "gitea-token").unwrap();
+
+    let created = service.create_pr(&repo_info, &pr_request()).await.unwrap();
+    assert_eq!(created.number, 9);
+    let body = recorded.bodies.lock().unwrap()[0].clone();
+    assert_eq!(body["head"], "anyon/feature");
+    assert_eq!(body["base"], "main");
+
+    let listed = service
+        .list_all_prs_for_branch(&repo_info, "anyon/feature")
+        .await
+        .unwrap();
+    let numbers: Vec<i64> = listed.iter().map(|pr| pr.number).collect();
+    assert_eq!(numbers, vec![1, 3]);
+    assert!(matches!(listed[0].status, MergeStatus::Merged));
+    assert!(matches!(listed[1].status, MergeStatus::Closed));
+
+    let headers = recorded.auth_headers.lock().unwrap().clone();
+    assert!(headers.iter().all(|h| h == "token gitea-token"));
+}
+
+#[tokio::test]
+async fn unauthorized_responses_map_to_token_invalid() {
+    let router = Router::new().route(
+        "/api/v4/user",
+        get(|| async { (StatusCode::UNAUTHORIZED, Json(json!({"message": "401"}))) }),
+    );
+    let url = serve(router).await;
+
+    let service = GitLabService::new(&url, "expired").unwrap();
+    let err = service.check_token().await.unwrap_err();
+    assert!(matches!(
+        err,
+        ForgeError::TokenInvalid(ForgeProvider::GitLab)
+    ));
+    assert!(err.is_api_data());
+    assert!(!err.should_retry());
+}
+
+#[tokio::test]
+async fn failed_pr_creation_is_not_sent_again() {
+    let recorded = Recorded::default();
+    let base = Arc::new(Mutex::new(String::new()));
+    let open = Arc::new(Mutex::new(false));
+
+    // The forge opens the merge request but the response is lost behind a gateway error
+    let router = Router::new()
+        .route(
+            "/api/v4/projects/{id}/merge_requests",
+            get(
+                |State((_, base, open)): State<(
+                    Recorded,
+                    Arc<Mutex<String>>,
+                    Arc<Mutex<bool>>,
+                )>| async move {
+                    let base = base.lock().unwrap().clone();
+                    if *open.lock().unwrap() {
+                        Json(json!([
+                            gitlab_mr(2, "closed", &base),
+                            gitlab_mr(7, "opened", &base)
+                        ]))
+                    } else {
+                        Json(json!([gitlab_mr(2, "closed", &base)]))
+                    }
+                },
+            )
+            .post(
+                |State((rec, _, open)): State<(Recorded, Arc<Mutex<String>>, Arc<Mutex<bool>>)>,
+                 Json(body): Json<Value>| async move {
+                    let created = body["title"] == "Add feature";
+                    rec.bodies.lock().unwrap().push(body);
+                    *open.lock().unwrap() = created;
+                    StatusCode::BAD_GATEWAY
+                },
+            ),
+        )
+        .with_state((recorded.clone(), base.clone(), open));
+
+    let url = serve(router).await;
+    *base.lock().unwrap() = url.clone();
+
+    let repo_info =
+        ForgeRepoInfo::parse(&format!("{url}/group/sub/repo.git"), ForgeProvider::GitLab).unwrap();
+    let service = GitLabService::new(&repo_info.web_base_url(), "glpat-test").unwrap();
+
+    let created = service.create_pr(&repo_info, &pr_request()).await.unwrap();
+    assert_eq!(created.number, 7);
+    assert_eq!(recorded.bodies.lock().unwrap().len(), 1);
+
+    // Without an open merge request for the branch the original error is returned
+    let mut request = pr_request();
+    request.title = "Rejected".to_string();
+    let err = service.create_pr(&repo_info, &request).await.unwrap_err();
+    assert!(matches!(err, ForgeError::Api { status: 502, .. }));
+    assert_eq!(recorded.bodies.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn instance_urls_on_the_local_network_are_refused() {
+    for base_url in [
+        "http://127.0.0.1:8080",
+        "https://localhost",
+        "https://10.1.2.3/gitlab",
+        "http://[::1]:3000",
+        "http://169.254.169.254",
+    ] {
+        let err = ensure_public_base_url(base_url).await.unwrap_err();
+        assert!(
+            matches!(err, ForgeError::PrivateAddress(_)),
+            "{base_url}: {err}"
+        );
+    }
+    let err = ensure_public_base_url("ftp://gitlab.com")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ForgeError::UnsupportedRemote(_)));
+    ensure_public_base_url("https://93.184.216.34")
+        .await
+        .unwrap();
+}
@@ -1410,3 +1410,28 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn fetch_into_cache_refuses_urls_that_act_as_options_or_commands() {
+    let root = TempDir::new().unwrap();
+    let marker = root.path().join("pwned");
+    let cache_path = root.path().join("cache.git");
+    let git_cli = GitCli::new();
+
+    for url in [
+        format!("--upload-pack=touch {}", marker.display()),
+        format!("ext::sh -c touch% {}", marker.display()),
+        format!("EXT::sh -c touch% {}", marker.display()),
+    ] {
+        let result = git_cli.fetch_into_cache(
+            &cache_path,
+            &url,
+            "0123456789abcdef0123456789abcdef01234567",
+        );
+        assert!(
+            matches!(result, Err(GitCliError::CommandFailed(_))),
+            "{url}: {result:?}"
+        );
+    }
+    assert!(!marker.exists());
+}
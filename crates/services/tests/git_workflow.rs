@@ -6,7 +6,10 @@ use std::{
 
 use services::services::{
     git::{DiffTarget, GitService},
+    git_cli::GitCli,
     github_service::{GitHubRepoInfo, GitHubServiceError},
+    sparse_checkout::SparseCone,
+    worktree_manager::{WorktreeManager, WorktreeOptions},
 };
 use tempfile::TempDir;
 use utils::diff::DiffChangeKind;
@@ -564,6 +567,18 @@ fn convert_to_https_url_handles_common_git_forms() {
 
     let converted = svc.convert_to_https_url("https://github.com/owner/repo/");
     assert_eq!(converted, "https://github.com/owner/repo.git");
+
+    let gitlab_ssh = "ssh://git@gitlab.example.com:2222/group/sub/repo.git";
+    assert_eq!(
+        svc.convert_to_https_url(gitlab_ssh),
+        "https://gitlab.example.com/group/sub/repo.git"
+    );
+
+    let gitea_scp = "git@gitea.example.com:owner/repo.git";
+    assert_eq!(
+        svc.convert_to_https_url(gitea_scp),
+        "https://gitea.example.com/owner/repo.git"
+    );
 }
 
 #[test]
@@ -644,3 +659,272 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@anyon.com"));
     }
 }
+
+#[test]
+fn rollback_merge_restores_base_and_task_branches() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let worktree_path = td.path().join("wt_feature");
+    let s = GitService::new();
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+    s.configure_user(&worktree_path, "Test User", "test@example.com")
+        .unwrap();
+    write_file(&worktree_path, "feature.txt", "feature\n");
+    s.commit(&worktree_path, "feature commit").unwrap();
+
+    let base_oid = s.get_branch_oid(&repo_path, "main").unwrap();
+    let task_oid = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    // main is checked out in the repo, so the merge goes through the CLI path
+    let merge_sha = s
+        .merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .unwrap();
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), merge_sha);
+    assert!(repo_path.join("feature.txt").exists());
+
+    s.rollback_merge(&repo_path, "main", &base_oid, "feature", &task_oid)
+        .unwrap();
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), base_oid);
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), task_oid);
+    assert!(!repo_path.join("feature.txt").exists());
+
+    // Branch not checked out anywhere: only the refs move
+    s.create_branch(&repo_path, "dev").unwrap();
+    s.checkout_branch(&repo_path, "dev").unwrap();
+    s.merge_changes(&repo_path, &worktree_path, "feature", "main", "squash")
+        .unwrap();
+    s.rollback_merge(&repo_path, "main", &base_oid, "feature", &task_oid)
+        .unwrap();
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), base_oid);
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), task_oid);
+}
+
+#[test]
+fn diffs_report_submodule_pointer_changes() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+
+    let lib_path = td.path().join("lib");
+    s.initialize_repo_with_main_branch(&lib_path).unwrap();
+    s.configure_user(&lib_path, "Test User", "test@example.com")
+        .unwrap();
+    write_file(&lib_path, "lib.txt", "v1\n");
+    s.commit(&lib_path, "lib v1").unwrap();
+    let lib_v1 = s.get_head_info(&lib_path).unwrap().oid;
+
+    git.git(
+        &repo_path,
+        [
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            lib_path.to_str().unwrap(),
+            "vendor/lib",
+        ],
+    )
+    .unwrap();
+    git.git(&repo_path, ["commit", "-m", "add submodule"])
+        .unwrap();
+
+    let submodules = git.list_submodules(&repo_path).unwrap();
+    assert_eq!(submodules.len(), 1);
+    assert_eq!(submodules[0].path, "vendor/lib");
+    assert_eq!(submodules[0].url, lib_path.to_str().unwrap());
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.checkout_branch(&repo_path, "feature").unwrap();
+    let sub_checkout = repo_path.join("vendor/lib");
+    s.configure_user(&sub_checkout, "Test User", "test@example.com")
+        .unwrap();
+    write_file(&sub_checkout, "lib.txt", "v2\n");
+    git.git(&sub_checkout, ["commit", "-am", "lib v2"]).unwrap();
+    let lib_v2 = s.get_head_info(&sub_checkout).unwrap().oid;
+
+    let base_commit = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: Path::new(&repo_path),
+                base_commit: &base_commit,
+            },
+            None,
+        )
+        .unwrap();
+    let diff = diffs
+        .iter()
+        .find(|d| d.new_path.as_deref() == Some("vendor/lib"))
+        .expect("submodule change reported");
+    assert!(matches!(diff.change, DiffChangeKind::Modified));
+    assert_eq!(
+        diff.old_content.as_deref(),
+        Some(format!("Subproject commit {lib_v1}\n").as_str())
+    );
+    assert_eq!(
+        diff.new_content.as_deref(),
+        Some(format!("Subproject commit {lib_v2}\n").as_str())
+    );
+
+    git.git(&repo_path, ["commit", "-am", "bump lib"]).unwrap();
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Branch {
+                repo_path: Path::new(&repo_path),
+                branch_name: "feature",
+                base_branch: "main",
+            },
+            None,
+        )
+        .unwrap();
+    let diff = diffs
+        .iter()
+        .find(|d| d.new_path.as_deref() == Some("vendor/lib"))
+        .expect("submodule change reported");
+    assert_eq!(
+        diff.new_content.as_deref(),
+        Some(format!("Subproject commit {lib_v2}\n").as_str())
+    );
+}
+
+#[tokio::test]
+async fn sparse_worktree_only_materialises_cone() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "README.md", "root\n");
+    write_file(&repo_path, "services/Cargo.toml", "[workspace]\n");
+    write_file(&repo_path, "services/api/main.rs", "fn main() {}\n");
+    write_file(&repo_path, "services/web/index.ts", "export {};\n");
+    write_file(&repo_path, "docs/guide.md", "guide\n");
+    let s = GitService::new();
+    s.commit(&repo_path, "monorepo").unwrap();
+
+    let worktree_path = td.path().join("wt_sparse");
+    let options = WorktreeOptions {
+        sparse_cone: SparseCone::parse("services/api"),
+        ..Default::default()
+    };
+    WorktreeManager::create_worktree(
+        &repo_path,
+        "feature",
+        &worktree_path,
+        "main",
+        true,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert!(worktree_path.join("README.md").exists());
+    assert!(worktree_path.join("services/Cargo.toml").exists());
+    assert!(worktree_path.join("services/api/main.rs").exists());
+    assert!(!worktree_path.join("services/web/index.ts").exists());
+    assert!(!worktree_path.join("docs").exists());
+    // Files outside the cone must not show up as deletions
+    assert!(s.is_worktree_clean(&worktree_path).unwrap());
+    // The main checkout is unaffected
+    assert!(repo_path.join("docs/guide.md").exists());
+
+    let git = GitCli::new();
+    git.sparse_checkout_add(&worktree_path, &["docs".to_string()])
+        .unwrap();
+    assert!(worktree_path.join("docs/guide.md").exists());
+    assert_eq!(
+        git.sparse_checkout_list(&worktree_path).unwrap(),
+        vec!["docs".to_string(), "services/api".to_string()]
+    );
+}
+
+#[test]
+fn patch_export_and_import_reports_rejected_hunks() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let lines = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\n";
+    write_file(&repo_path, "f.txt", lines);
+    s.commit(&repo_path, "add f").unwrap();
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.create_branch(&repo_path, "clean").unwrap();
+    let feature_wt = td.path().join("wt_feature");
+    s.add_worktree(&repo_path, &feature_wt, "feature", false)
+        .unwrap();
+    write_file(
+        &feature_wt,
+        "f.txt",
+        &lines.replace("b\n", "B\n").replace("l\n", "L\n"),
+    );
+    s.commit(&feature_wt, "edit f").unwrap();
+    write_file(&feature_wt, "n.txt", "new\n");
+    s.commit(&feature_wt, "add n").unwrap();
+
+    let mbox = s.format_patch(&repo_path, "feature", "main").unwrap();
+    assert!(mbox.starts_with("From "));
+    assert!(mbox.contains("Subject: [PATCH 2/2] add n"));
+
+    // Applies cleanly: commits are replayed as-is
+    let clean_wt = td.path().join("wt_clean");
+    s.add_worktree(&repo_path, &clean_wt, "clean", false)
+        .unwrap();
+    let conflicts = s.apply_patch(&clean_wt, &mbox, "import").unwrap();
+    assert!(conflicts.is_empty());
+    assert_eq!(
+        s.get_commit_subject(&clean_wt, &s.get_head_info(&clean_wt).unwrap().oid)
+            .unwrap(),
+        "add n"
+    );
+
+    // Conflicting base: the second hunk of f.txt is rejected, the rest is committed
+    write_file(&repo_path, "f.txt", &lines.replace("l\n", "LL\n"));
+    s.commit(&repo_path, "conflict").unwrap();
+    s.create_branch(&repo_path, "import").unwrap();
+    let import_wt = td.path().join("wt_import");
+    s.add_worktree(&repo_path, &import_wt, "import", false)
+        .unwrap();
+    let conflicts = s.apply_patch(&import_wt, &mbox, "import").unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].path, "f.txt");
+    assert_eq!(conflicts[0].hunk, 2);
+    assert!(conflicts[0].content.contains("+L\n"));
+
+    let f = fs::read_to_string(import_wt.join("f.txt")).unwrap();
+    assert!(f.contains("B\n") && f.contains("LL\n"));
+    assert!(import_wt.join("n.txt").exists());
+    assert!(!import_wt.join("f.txt.rej").exists());
+    assert!(s.is_worktree_clean(&import_wt).unwrap());
+}
+
+#[test]
+fn diff_patch_keeps_untracked_and_binary_files() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "f.txt", "a\n");
+    s.commit(&repo_path, "add f").unwrap();
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.create_branch(&repo_path, "clean").unwrap();
+    let feature_wt = td.path().join("wt_feature");
+    s.add_worktree(&repo_path, &feature_wt, "feature", false)
+        .unwrap();
+    write_file(&feature_wt, "f.txt", "b\n");
+    s.commit(&feature_wt, "edit f").unwrap();
+    // Uncommitted binary file
+    fs::write(feature_wt.join("bin.dat"), [0u8, 1, 2, 3]).unwrap();
+
+    let base_commit = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let patch = s.diff_patch(&feature_wt, &base_commit).unwrap();
+    assert!(patch.contains("GIT binary patch"));
+
+    let clean_wt = td.path().join("wt_clean");
+    s.add_worktree(&repo_path, &clean_wt, "clean", false)
+        .unwrap();
+    let conflicts = s.apply_patch(&clean_wt, &patch, "import").unwrap();
+    assert!(conflicts.is_empty());
+    assert_eq!(fs::read_to_string(clean_wt.join("f.txt")).unwrap(), "b\n");
+    assert_eq!(fs::read(clean_wt.join("bin.dat")).unwrap(), [0u8, 1, 2, 3]);
+}
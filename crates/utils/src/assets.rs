@@ -4,16 +4,7 @@ use rust_embed::RustEmbed;
 const PROJECT_ROOT: &str = env!("CARGO_MANIFEST_DIR");
 
 pub fn asset_dir() -> std::path::PathBuf {
-    let path = if let Ok(custom) = std::env::var("ANYON_ASSET_DIR") {
-        std::path::PathBuf::from(custom)
-    } else if cfg!(debug_assertions) {
-        std::path::PathBuf::from(PROJECT_ROOT).join("../../dev_assets")
-    } else {
-        ProjectDirs::from("ai", "slit", "anyon")
-            .expect("OS didn't give us a home directory")
-            .data_dir()
-            .to_path_buf()
-    };
+    let path = resolve_asset_dir();
 
     // Ensure the directory exists
     if !path.exists() {
@@ -26,6 +17,20 @@ pub fn asset_dir() -> std::path::PathBuf {
     // ✔ Windows → %APPDATA%\Example\MyApp
 }
 
+/// [`asset_dir`] without creating it
+pub fn resolve_asset_dir() -> std::path::PathBuf {
+    if let Ok(custom) = std::env::var("ANYON_ASSET_DIR") {
+        std::path::PathBuf::from(custom)
+    } else if cfg!(debug_assertions) {
+        std::path::PathBuf::from(PROJECT_ROOT).join("../../dev_assets")
+    } else {
+        ProjectDirs::from("ai", "slit", "anyon")
+            .expect("OS didn't give us a home directory")
+            .data_dir()
+            .to_path_buf()
+    }
+}
+
 pub fn config_path() -> std::path::PathBuf {
     asset_dir().join("config.json")
 }
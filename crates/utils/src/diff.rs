@@ -91,6 +91,50 @@ pub fn create_unified_diff(file_path: &str, old: &str, new: &str) -> String {
     out
 }
 
+/// Renders a file diff as a patch that `git apply` understands, including the
+/// `diff --git` header and `/dev/null` sides for added and deleted files.
+/// Returns `None` when the contents were omitted or nothing changed.
+pub fn create_git_patch(diff: &Diff) -> Option<String> {
+    if diff.content_omitted || (diff.old_content.is_none() && diff.new_content.is_none()) {
+        return None;
+    }
+    let old_path = diff.old_path.as_deref().or(diff.new_path.as_deref())?;
+    let new_path = diff.new_path.as_deref().unwrap_or(old_path);
+    let old = diff.old_content.as_deref().unwrap_or("");
+    let new = diff.new_content.as_deref().unwrap_or("");
+
+    let mut out = format!("diff --git a/{old_path} b/{new_path}\n");
+    let (from, to) = match diff.change {
+        DiffChangeKind::Added => {
+            out.push_str("new file mode 100644\n");
+            ("/dev/null".to_string(), format!("b/{new_path}"))
+        }
+        DiffChangeKind::Deleted => {
+            out.push_str("deleted file mode 100644\n");
+            (format!("a/{old_path}"), "/dev/null".to_string())
+        }
+        DiffChangeKind::Renamed if old_path != new_path => {
+            out.push_str(&format!("rename from {old_path}\nrename to {new_path}\n"));
+            (format!("a/{old_path}"), format!("b/{new_path}"))
+        }
+        _ => (format!("a/{old_path}"), format!("b/{new_path}")),
+    };
+
+    if old == new {
+        // Only mode or rename lines to report, e.g. an added empty file
+        return (out.lines().count() > 1).then_some(out);
+    }
+
+    out.push_str(
+        &TextDiff::from_lines(old, new)
+            .unified_diff()
+            .context_radius(3)
+            .header(&from, &to)
+            .to_string(),
+    );
+    Some(out)
+}
+
 /// Compute addition/deletion counts between two text snapshots.
 pub fn compute_line_change_counts(old: &str, new: &str) -> (usize, usize) {
     let old = ensure_newline(old);
@@ -12,6 +12,7 @@ pub mod msg_store;
 pub mod path;
 pub mod port_file;
 pub mod response;
+pub mod secret_mask;
 pub mod sentry;
 pub mod shell;
 pub mod stream_ext;
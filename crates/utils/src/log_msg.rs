@@ -7,6 +7,7 @@ pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
 pub const EV_FINISHED: &str = "finished";
+pub const EV_RESYNC_REQUIRED: &str = "resync_required";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
@@ -77,3 +78,51 @@ impl LogMsg {
         }
     }
 }
+
+/// A message and its position in a `MsgStore`. Sequence numbers start at 1 and grow by one per
+/// message, so a client can resume a stream after the last one it saw.
+#[derive(Clone, Debug)]
+pub struct SequencedMsg {
+    pub seq: u64,
+    pub msg: LogMsg,
+}
+
+/// An item of a resumable stream
+#[derive(Clone, Debug)]
+pub enum StreamMsg {
+    Msg(SequencedMsg),
+    /// Messages the client needs are gone, because they were evicted from history or the
+    /// stream fell behind. The client must drop its state and reconnect without a resume point.
+    ResyncRequired,
+}
+
+impl StreamMsg {
+    /// SSE event carrying the sequence number as its id, so `EventSource` resumes with
+    /// `Last-Event-ID`
+    pub fn to_sse_event(&self) -> Event {
+        match self {
+            StreamMsg::Msg(sequenced) => sequenced.msg.to_sse_event().id(sequenced.seq.to_string()),
+            StreamMsg::ResyncRequired => Event::default().event(EV_RESYNC_REQUIRED).data(""),
+        }
+    }
+
+    /// Same JSON as `LogMsg::to_ws_message_unchecked`, with a `seq` field added
+    pub fn to_ws_message_unchecked(&self) -> Message {
+        let json = match self {
+            StreamMsg::Msg(SequencedMsg { seq, msg }) => {
+                let mut value = match msg {
+                    LogMsg::Finished => serde_json::json!({ "finished": true }),
+                    _ => serde_json::to_value(msg)
+                        .unwrap_or_else(|_| serde_json::json!({ "error": "serialization_failed" })),
+                };
+                if let Some(object) = value.as_object_mut() {
+                    object.insert("seq".to_string(), (*seq).into());
+                }
+                value.to_string()
+            }
+            StreamMsg::ResyncRequired => r#"{"resync_required":true}"#.to_string(),
+        };
+
+        Message::Text(json.into())
+    }
+}
@@ -1,20 +1,33 @@
 use std::{
     collections::VecDeque,
     sync::{Arc, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use axum::response::sse::Event;
 use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
-use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{
+    log_msg::{LogMsg, SequencedMsg, StreamMsg},
+    stream_lines::LinesStreamExt,
+};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
 
+/// Sequence numbers start at the store's creation time in microseconds, so a number handed
+/// out before a restart is behind everything the new store has and can't resume silently.
+fn initial_seq() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_micros() as u64)
+}
+
 #[derive(Clone)]
 struct StoredMsg {
+    seq: u64,
     msg: LogMsg,
     bytes: usize,
 }
@@ -22,11 +35,25 @@ struct StoredMsg {
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    last_seq: u64,
+}
+
+impl Inner {
+    fn history_after(&self, since: u64) -> Vec<SequencedMsg> {
+        self.history
+            .iter()
+            .skip_while(|s| s.seq <= since)
+            .map(|s| SequencedMsg {
+                seq: s.seq,
+                msg: s.msg.clone(),
+            })
+            .collect()
+    }
 }
 
 pub struct MsgStore {
     inner: RwLock<Inner>,
-    sender: broadcast::Sender<LogMsg>,
+    sender: broadcast::Sender<SequencedMsg>,
 }
 
 impl Default for MsgStore {
@@ -42,16 +69,24 @@ impl MsgStore {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                last_seq: initial_seq(),
             }),
             sender,
         }
     }
 
     pub fn push(&self, msg: LogMsg) {
-        let _ = self.sender.send(msg.clone()); // live listeners
         let bytes = msg.approx_bytes();
 
         let mut inner = self.inner.write().unwrap();
+        inner.last_seq += 1;
+        let seq = inner.last_seq;
+        // Notify live listeners under the lock, so a subscriber's history and receiver never
+        // overlap or leave a gap
+        let _ = self.sender.send(SequencedMsg {
+            seq,
+            msg: msg.clone(),
+        });
         while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
             if let Some(front) = inner.history.pop_front() {
                 inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
@@ -59,7 +94,7 @@ impl MsgStore {
                 break;
             }
         }
-        inner.history.push_back(StoredMsg { msg, bytes });
+        inner.history.push_back(StoredMsg { seq, msg, bytes });
         inner.total_bytes = inner.total_bytes.saturating_add(bytes);
     }
 
@@ -83,10 +118,32 @@ impl MsgStore {
         self.push(LogMsg::Finished);
     }
 
-    pub fn get_receiver(&self) -> broadcast::Receiver<LogMsg> {
+    pub fn get_receiver(&self) -> broadcast::Receiver<SequencedMsg> {
         self.sender.subscribe()
     }
 
+    /// A receiver along with the sequence number of the last message it won't see
+    pub fn subscribe(&self) -> (u64, broadcast::Receiver<SequencedMsg>) {
+        let inner = self.inner.read().unwrap();
+        (inner.last_seq, self.sender.subscribe())
+    }
+
+    /// The retained messages after `since` and a receiver for the ones that follow.
+    ///
+    /// None when some of those messages were evicted, or when `since` was handed out before a
+    /// restart.
+    pub fn subscribe_since(
+        &self,
+        since: u64,
+    ) -> Option<(Vec<SequencedMsg>, broadcast::Receiver<SequencedMsg>)> {
+        let inner = self.inner.read().unwrap();
+        let first_seq = inner.history.front().map_or(inner.last_seq + 1, |s| s.seq);
+        if since > inner.last_seq || since + 1 < first_seq {
+            return None;
+        }
+        Some((inner.history_after(since), self.sender.subscribe()))
+    }
+
     pub fn get_history(&self) -> Vec<LogMsg> {
         self.inner
             .read()
@@ -101,15 +158,75 @@ impl MsgStore {
     pub fn history_plus_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>> {
-        let (history, rx) = (self.get_history(), self.get_receiver());
+        let (history, rx) = {
+            let inner = self.inner.read().unwrap();
+            (inner.history_after(0), self.sender.subscribe())
+        };
 
-        let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
-        let live = BroadcastStream::new(rx)
-            .filter_map(|res| async move { res.ok().map(Ok::<_, std::io::Error>) });
+        let hist =
+            futures::stream::iter(history.into_iter().map(|s| Ok::<_, std::io::Error>(s.msg)));
+        let live = BroadcastStream::new(rx).filter_map(|res| async move {
+            match res {
+                Ok(s) => Some(Ok::<_, std::io::Error>(s.msg)),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::warn!("Message stream lagged, skipped {} messages", skipped);
+                    None
+                }
+            }
+        });
 
         Box::pin(hist.chain(live))
     }
 
+    /// History then live with sequence numbers, resuming after `since` when given.
+    ///
+    /// When the store can't resume after `since`, the full history follows a `ResyncRequired`.
+    pub fn resumable_stream(
+        &self,
+        since: Option<u64>,
+    ) -> futures::stream::BoxStream<'static, Result<StreamMsg, std::io::Error>> {
+        if let Some(since) = since
+            && let Some((history, rx)) = self.subscribe_since(since)
+        {
+            return Self::sequenced_stream(history, rx);
+        }
+
+        let (history, rx) = {
+            let inner = self.inner.read().unwrap();
+            (inner.history_after(0), self.sender.subscribe())
+        };
+        let resync = since.map(|_| Ok(StreamMsg::ResyncRequired));
+        futures::stream::iter(resync)
+            .chain(Self::sequenced_stream(history, rx))
+            .boxed()
+    }
+
+    /// `history` then the receiver's messages. A lagging receiver has lost messages, so the
+    /// stream ends with `ResyncRequired` instead of skipping them.
+    pub fn sequenced_stream(
+        history: Vec<SequencedMsg>,
+        rx: broadcast::Receiver<SequencedMsg>,
+    ) -> futures::stream::BoxStream<'static, Result<StreamMsg, std::io::Error>> {
+        let hist = futures::stream::iter(history.into_iter().map(|s| Ok(StreamMsg::Msg(s))));
+        let live = BroadcastStream::new(rx)
+            .map(|res| match res {
+                Ok(s) => StreamMsg::Msg(s),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::warn!("Message stream lagged, skipped {} messages", skipped);
+                    StreamMsg::ResyncRequired
+                }
+            })
+            .scan(false, |ended, msg| {
+                if *ended {
+                    return future::ready(None);
+                }
+                *ended = matches!(msg, StreamMsg::ResyncRequired);
+                future::ready(Some(Ok(msg)))
+            });
+
+        hist.chain(live).boxed()
+    }
+
     pub fn stdout_chunked_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<String, std::io::Error>> {
@@ -175,3 +292,95 @@ impl MsgStore {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdout(msg: &StreamMsg) -> (u64, String) {
+        match msg {
+            StreamMsg::Msg(SequencedMsg {
+                seq,
+                msg: LogMsg::Stdout(s),
+            }) => (*seq, s.clone()),
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+
+    #[test]
+    fn subscribe_since_returns_messages_after_the_resume_point() {
+        let store = MsgStore::new();
+        let start = store.subscribe().0;
+        for line in ["a", "b", "c"] {
+            store.push_stdout(line);
+        }
+
+        let (history, _rx) = store.subscribe_since(start + 1).unwrap();
+        assert_eq!(
+            history.iter().map(|s| s.seq).collect::<Vec<_>>(),
+            vec![start + 2, start + 3]
+        );
+        assert!(store.subscribe_since(start + 3).unwrap().0.is_empty());
+        assert!(store.subscribe_since(start + 4).is_none());
+        assert_eq!(store.subscribe().0, start + 3);
+    }
+
+    #[test]
+    fn sequence_numbers_from_before_a_restart_do_not_resume() {
+        let before = MsgStore::new();
+        for line in ["a", "b", "c"] {
+            before.push_stdout(line);
+        }
+        let (since, _rx) = before.subscribe();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+
+        let after = MsgStore::new();
+        assert!(after.subscribe_since(since).is_none());
+        for line in ["a", "b", "c", "d"] {
+            after.push_stdout(line);
+        }
+        assert!(after.subscribe_since(since).is_none());
+    }
+
+    #[tokio::test]
+    async fn resumable_stream_requires_resync_when_it_cannot_resume() {
+        let store = MsgStore::new();
+        let start = store.subscribe().0;
+        store.push_stdout("a");
+
+        let mut stream = store.resumable_stream(Some(start + 7));
+        assert!(matches!(
+            stream.next().await.unwrap().unwrap(),
+            StreamMsg::ResyncRequired
+        ));
+        assert_eq!(
+            stdout(&stream.next().await.unwrap().unwrap()),
+            (start + 1, "a".to_string())
+        );
+
+        store.push_stdout("b");
+        assert_eq!(
+            stdout(&stream.next().await.unwrap().unwrap()),
+            (start + 2, "b".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn lagging_stream_ends_with_resync() {
+        let (tx, rx) = broadcast::channel(1);
+        let mut stream = MsgStore::sequenced_stream(Vec::new(), rx);
+        for seq in 1..=3 {
+            tx.send(SequencedMsg {
+                seq,
+                msg: LogMsg::Stdout(seq.to_string()),
+            })
+            .unwrap();
+        }
+
+        assert!(matches!(
+            stream.next().await.unwrap().unwrap(),
+            StreamMsg::ResyncRequired
+        ));
+        assert!(stream.next().await.is_none());
+    }
+}
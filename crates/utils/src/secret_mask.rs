@@ -0,0 +1,163 @@
+use std::io;
+
+use futures::{Stream, StreamExt};
+
+use crate::log_msg::LogMsg;
+
+/// Replacement written in place of a secret value
+pub const SECRET_MASK: &str = "********";
+/// Shorter values would mask ordinary output, so they are left alone
+const MIN_SECRET_LEN: usize = 4;
+
+/// Replaces known secret values in process output
+#[derive(Debug, Clone, Default)]
+pub struct SecretMasker {
+    /// Longest first so a secret containing another is masked whole
+    secrets: Vec<String>,
+}
+
+impl SecretMasker {
+    pub fn new<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut secrets = Vec::new();
+        for value in values {
+            let value = value.as_ref();
+            secrets.push(value.to_string());
+            // Agents print JSON, where quotes, backslashes and newlines appear escaped
+            if let Ok(escaped) = serde_json::to_string(value) {
+                secrets.push(escaped[1..escaped.len() - 1].to_string());
+            }
+            // Multi-line values such as PEM keys are often printed one line at a time
+            secrets.extend(value.lines().map(str::to_string));
+        }
+        secrets.retain(|secret| secret.len() >= MIN_SECRET_LEN);
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    pub fn mask(&self, text: &str) -> String {
+        let mut masked = text.to_string();
+        for secret in &self.secrets {
+            if masked.contains(secret.as_str()) {
+                masked = masked.replace(secret.as_str(), SECRET_MASK);
+            }
+        }
+        masked
+    }
+
+    /// Length of the longest suffix of `text` that could be the start of a secret
+    fn partial_suffix_len(&self, text: &str) -> usize {
+        let bytes = text.as_bytes();
+        let mut longest = 0;
+        for secret in &self.secrets {
+            let max = (secret.len() - 1).min(bytes.len());
+            if let Some(len) = (longest + 1..=max)
+                .rev()
+                .find(|len| secret.as_bytes().starts_with(&bytes[bytes.len() - len..]))
+            {
+                longest = len;
+            }
+        }
+        longest
+    }
+
+    /// Append a chunk to `pending` and return the part that can be emitted safely.
+    /// A trailing partial match stays in `pending` until the next chunk shows whether it is a
+    /// secret.
+    fn push(&self, pending: &mut String, chunk: &str) -> Option<String> {
+        pending.push_str(chunk);
+        let masked = self.mask(pending);
+        pending.clear();
+
+        // The suffix starts with the same bytes as a secret, so it begins on a char boundary
+        let emit_len = masked.len() - self.partial_suffix_len(&masked);
+        pending.push_str(&masked[emit_len..]);
+        (emit_len > 0).then(|| masked[..emit_len].to_string())
+    }
+}
+
+/// Mask secrets in stdout and stderr, including values split across chunks
+pub fn mask_logs<S>(input: S, masker: SecretMasker) -> impl Stream<Item = Result<LogMsg, io::Error>>
+where
+    S: Stream<Item = Result<LogMsg, io::Error>> + Unpin,
+{
+    async_stream::stream! {
+        let mut pending_stdout = String::new();
+        let mut pending_stderr = String::new();
+
+        tokio::pin!(input);
+
+        while let Some(item) = input.next().await {
+            match item {
+                Ok(LogMsg::Stdout(chunk)) if !masker.is_empty() => {
+                    if let Some(text) = masker.push(&mut pending_stdout, &chunk) {
+                        yield Ok(LogMsg::Stdout(text));
+                    }
+                }
+                Ok(LogMsg::Stderr(chunk)) if !masker.is_empty() => {
+                    if let Some(text) = masker.push(&mut pending_stderr, &chunk) {
+                        yield Ok(LogMsg::Stderr(text));
+                    }
+                }
+                other => yield other,
+            }
+        }
+
+        // Whatever is still held back was not a secret after all
+        if !pending_stdout.is_empty() {
+            yield Ok(LogMsg::Stdout(pending_stdout));
+        }
+        if !pending_stderr.is_empty() {
+            yield Ok(LogMsg::Stderr(pending_stderr));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+
+    #[test]
+    fn masks_secret_values() {
+        let masker = SecretMasker::new(["sk_test_123", "abc"]);
+        assert_eq!(
+            masker.mask("key=sk_test_123 short=abc"),
+            format!("key={SECRET_MASK} short=abc")
+        );
+
+        let masker = SecretMasker::new(["line one\nline two"]);
+        assert_eq!(
+            masker.mask(r#"{"text":"line one\nline two"}"#),
+            format!(r#"{{"text":"{SECRET_MASK}"}}"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn masks_secrets_split_across_chunks() {
+        let masker = SecretMasker::new(["postgres://secret"]);
+        let input = stream::iter(
+            ["url: postg", "res://sec", "ret done\n", "postgres"]
+                .map(|chunk| Ok(LogMsg::Stdout(chunk.to_string()))),
+        );
+
+        let output: String = mask_logs(input, masker)
+            .map(|msg| match msg.unwrap() {
+                LogMsg::Stdout(text) => text,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+        assert_eq!(output, format!("url: {SECRET_MASK} done\npostgres"));
+    }
+}
@@ -17,7 +17,10 @@ type ExecutorType =
   | 'CURSOR_AGENT'
   | 'COPILOT'
   | 'OPENCODE'
-  | 'QWEN_CODE';
+  | 'QWEN_CODE'
+  | 'CUSTOM_AGENT'
+  | 'ACP'
+  | 'MODEL_API';
 
 // ExecutorConfig represents the configuration data for an executor
 // It's a flexible object that conforms to the executor's JSON schema
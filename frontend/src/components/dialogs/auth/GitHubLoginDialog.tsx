@@ -10,10 +10,14 @@ import {
 import { Button } from '@/components/ui/button';
 import { Alert } from '@/components/ui/alert';
 import { useUserSystem } from '@/components/config-provider';
-import { Check, Clipboard, Github } from 'lucide-react';
+import { Check, Clipboard, Github, KeyRound } from 'lucide-react';
 import { Loader } from '@/components/ui/loader';
-import { githubAuthApi } from '@/lib/api';
-import { DeviceFlowStartResponse, DevicePollStatus } from 'shared/types';
+import { githubAuthApi, identityApi } from '@/lib/api';
+import {
+  DeviceFlowStartResponse,
+  DevicePollStatus,
+  IdentityProviderInfo,
+} from 'shared/types';
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
 import NiceModal, { useModal } from '@ebay/nice-modal-react';
 
@@ -32,6 +36,21 @@ const GitHubLoginDialog = NiceModal.create(() => {
     useState<null | DeviceFlowStartResponse>(null);
   const [polling, setPolling] = useState(false);
   const [copied, setCopied] = useState(false);
+  const [ssoProviders, setSsoProviders] = useState<IdentityProviderInfo[]>([]);
+
+  useEffect(() => {
+    identityApi
+      .providers()
+      .then(setSsoProviders)
+      .catch(() => setSsoProviders([]));
+  }, []);
+
+  const handleSsoLogin = (providerId: string) => {
+    window.location.href = identityApi.loginUrl(
+      providerId,
+      window.location.pathname
+    );
+  };
 
   // Dev login doesn't require OAuth token or PAT
   // Just having a username is enough for authentication
@@ -89,7 +108,7 @@ const GitHubLoginDialog = NiceModal.create(() => {
     if (polling && deviceState) {
       const poll = async () => {
         try {
-          const poll_status = await githubAuthApi.poll();
+          const poll_status = await githubAuthApi.poll(deviceState.flow_id);
           switch (poll_status) {
             case DevicePollStatus.SUCCESS:
               setPolling(false);
@@ -359,6 +378,21 @@ const GitHubLoginDialog = NiceModal.create(() => {
                 {fetching ? 'Starting…' : 'Sign in with GitHub'}
               </Button>
             </DialogFooter>
+            {ssoProviders.length > 0 && (
+              <div className="flex flex-col gap-2">
+                {ssoProviders.map((provider) => (
+                  <Button
+                    key={provider.id}
+                    variant="outline"
+                    onClick={() => handleSsoLogin(provider.id)}
+                    disabled={fetching}
+                  >
+                    <KeyRound className="h-4 w-4 mr-2" />
+                    Sign in with {provider.name}
+                  </Button>
+                ))}
+              </div>
+            )}
           </div>
         )}
       </DialogContent>
@@ -8,6 +8,7 @@ import {
   Config,
   CommitInfo,
   CreateFollowUpAttempt,
+  HandoffAttemptRequest,
   CreateGitHubPrRequest,
   CreateTask,
   CreateAndStartTaskRequest,
@@ -51,6 +52,32 @@ import {
   RunAgentSetupRequest,
   RunAgentSetupResponse,
   ClaudeSessionResponse,
+  ExecutorHealthReport,
+  AgentTool,
+  AgentToolsResponse,
+  InstallAgentToolRequest,
+  UpdateToolSettingsRequest,
+  ProjectMcpServer,
+  UpdateProjectMcpServers,
+  ProjectSecret,
+  UpsertProjectSecret,
+  MemberRole,
+  Organization,
+  OrganizationMember,
+  ProjectMember,
+  ProjectMembers,
+  ApiToken,
+  CreateApiToken,
+  CreatedApiToken,
+  IdentityProviderInfo,
+  UserIdentity,
+  AuditEventFilter,
+  AuditEventPage,
+  Webhook,
+  CreateWebhook,
+  UpdateWebhook,
+  CreatedWebhook,
+  WebhookDelivery,
 } from 'shared/types';
 
 // Re-export types for convenience
@@ -288,6 +315,308 @@ export const projectsApi = {
     );
     return handleApiResponse<SearchResult[]>(response);
   },
+
+  getMcpServers: async (id: string): Promise<ProjectMcpServer[]> => {
+    const response = await makeRequest(`/api/projects/${id}/mcp-servers`);
+    return handleApiResponse<ProjectMcpServer[]>(response);
+  },
+
+  updateMcpServers: async (
+    id: string,
+    data: UpdateProjectMcpServers
+  ): Promise<ProjectMcpServer[]> => {
+    const response = await makeRequest(`/api/projects/${id}/mcp-servers`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectMcpServer[]>(response);
+  },
+
+  getSecrets: async (id: string): Promise<ProjectSecret[]> => {
+    const response = await makeRequest(`/api/projects/${id}/secrets`);
+    return handleApiResponse<ProjectSecret[]>(response);
+  },
+
+  upsertSecret: async (
+    id: string,
+    name: string,
+    data: UpsertProjectSecret
+  ): Promise<ProjectSecret> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/secrets/${encodeURIComponent(name)}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectSecret>(response);
+  },
+
+  deleteSecret: async (id: string, name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/secrets/${encodeURIComponent(name)}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getWebhooks: async (id: string): Promise<Webhook[]> => {
+    const response = await makeRequest(`/api/projects/${id}/webhooks`);
+    return handleApiResponse<Webhook[]>(response);
+  },
+
+  createWebhook: async (
+    id: string,
+    data: CreateWebhook
+  ): Promise<CreatedWebhook> => {
+    const response = await makeRequest(`/api/projects/${id}/webhooks`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreatedWebhook>(response);
+  },
+
+  updateWebhook: async (
+    id: string,
+    webhookId: string,
+    data: UpdateWebhook
+  ): Promise<Webhook> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/webhooks/${webhookId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Webhook>(response);
+  },
+
+  deleteWebhook: async (id: string, webhookId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/webhooks/${webhookId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getWebhookDeliveries: async (
+    id: string,
+    webhookId: string
+  ): Promise<WebhookDelivery[]> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/webhooks/${webhookId}/deliveries`
+    );
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+
+  redeliverWebhookDelivery: async (
+    id: string,
+    webhookId: string,
+    deliveryId: string
+  ): Promise<WebhookDelivery> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/webhooks/${webhookId}/deliveries/${deliveryId}/redeliver`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<WebhookDelivery>(response);
+  },
+
+  getMembers: async (id: string): Promise<ProjectMembers> => {
+    const response = await makeRequest(`/api/projects/${id}/members`);
+    return handleApiResponse<ProjectMembers>(response);
+  },
+
+  upsertMember: async (
+    id: string,
+    userId: string,
+    role: MemberRole
+  ): Promise<ProjectMember> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/members/${encodeURIComponent(userId)}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ role }),
+      }
+    );
+    return handleApiResponse<ProjectMember>(response);
+  },
+
+  removeMember: async (id: string, userId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/members/${encodeURIComponent(userId)}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  setOrganization: async (
+    id: string,
+    organizationId: string | null
+  ): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${id}/organization`, {
+      method: 'PUT',
+      body: JSON.stringify({ organization_id: organizationId }),
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Organization APIs
+export const organizationsApi = {
+  list: async (): Promise<Organization[]> => {
+    const response = await makeRequest('/api/organizations');
+    return handleApiResponse<Organization[]>(response);
+  },
+
+  create: async (name: string): Promise<Organization> => {
+    const response = await makeRequest('/api/organizations', {
+      method: 'POST',
+      body: JSON.stringify({ name }),
+    });
+    return handleApiResponse<Organization>(response);
+  },
+
+  delete: async (organizationId: string): Promise<void> => {
+    const response = await makeRequest(`/api/organizations/${organizationId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getMembers: async (
+    organizationId: string
+  ): Promise<OrganizationMember[]> => {
+    const response = await makeRequest(
+      `/api/organizations/${organizationId}/members`
+    );
+    return handleApiResponse<OrganizationMember[]>(response);
+  },
+
+  upsertMember: async (
+    organizationId: string,
+    userId: string,
+    role: MemberRole
+  ): Promise<OrganizationMember> => {
+    const response = await makeRequest(
+      `/api/organizations/${organizationId}/members/${encodeURIComponent(userId)}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ role }),
+      }
+    );
+    return handleApiResponse<OrganizationMember>(response);
+  },
+
+  removeMember: async (
+    organizationId: string,
+    userId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/organizations/${organizationId}/members/${encodeURIComponent(userId)}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Personal API token APIs
+export const apiTokensApi = {
+  list: async (): Promise<ApiToken[]> => {
+    const response = await makeRequest('/api/tokens');
+    return handleApiResponse<ApiToken[]>(response);
+  },
+
+  create: async (data: CreateApiToken): Promise<CreatedApiToken> => {
+    const response = await makeRequest('/api/tokens', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreatedApiToken>(response);
+  },
+
+  revoke: async (tokenId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tokens/${tokenId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Audit log APIs
+const auditEventParams = (
+  filter: Partial<AuditEventFilter>,
+  page?: { limit?: number; offset?: number }
+): string => {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries({ ...filter, ...page })) {
+    if (value === null || value === undefined) continue;
+    params.set(
+      key,
+      value instanceof Date ? value.toISOString() : String(value)
+    );
+  }
+  return params.toString();
+};
+
+export const auditEventsApi = {
+  list: async (
+    filter: Partial<AuditEventFilter> = {},
+    page?: { limit?: number; offset?: number }
+  ): Promise<AuditEventPage> => {
+    const response = await makeRequest(
+      `/api/audit-events?${auditEventParams(filter, page)}`
+    );
+    return handleApiResponse<AuditEventPage>(response);
+  },
+
+  // Download target for every matching event as JSON lines
+  exportUrl: (filter: Partial<AuditEventFilter> = {}): string =>
+    `/api/audit-events/export?${auditEventParams(filter)}`,
+};
+
+// Single sign-on APIs
+export const identityApi = {
+  providers: async (): Promise<IdentityProviderInfo[]> => {
+    const response = await makeRequest('/api/auth/providers');
+    return handleApiResponse<IdentityProviderInfo[]>(response);
+  },
+
+  // Browser navigation target, not a fetch: the provider redirects back to the app
+  loginUrl: (providerId: string, returnTo?: string): string => {
+    const query = returnTo
+      ? `?return_to=${encodeURIComponent(returnTo)}`
+      : '';
+    return `/api/auth/sso/${encodeURIComponent(providerId)}/login${query}`;
+  },
+
+  logout: async (): Promise<void> => {
+    const response = await makeRequest('/api/auth/logout', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  identities: async (): Promise<UserIdentity[]> => {
+    const response = await makeRequest('/api/auth/identities');
+    return handleApiResponse<UserIdentity[]>(response);
+  },
+
+  unlinkGithub: async (): Promise<void> => {
+    const response = await makeRequest('/api/auth/identities/github', {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Management APIs
@@ -404,6 +733,20 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  handoff: async (
+    attemptId: string,
+    data: HandoffAttemptRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/handoff`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
   runAgentSetup: async (
     attemptId: string,
     data: RunAgentSetupRequest
@@ -694,9 +1037,10 @@ export const githubAuthApi = {
     });
     return handleApiResponse<DeviceFlowStartResponse>(response);
   },
-  poll: async (): Promise<DevicePollStatus> => {
+  poll: async (flowId: string): Promise<DevicePollStatus> => {
     const response = await makeRequest('/api/auth/github/device/poll', {
       method: 'POST',
+      body: JSON.stringify({ flow_id: flowId }),
     });
     return handleApiResponse<DevicePollStatus>(response);
   },
@@ -850,6 +1194,39 @@ export const profilesApi = {
   },
 };
 
+// Executors API
+export const executorsApi = {
+  getHealth: async (refresh = false): Promise<ExecutorHealthReport> => {
+    const response = await makeRequest(
+      `/api/executors/health${refresh ? '?refresh=true' : ''}`
+    );
+    return handleApiResponse<ExecutorHealthReport>(response);
+  },
+
+  getTools: async (): Promise<AgentToolsResponse> => {
+    const response = await makeRequest('/api/executors/tools');
+    return handleApiResponse<AgentToolsResponse>(response);
+  },
+
+  installTool: async (data: InstallAgentToolRequest): Promise<AgentTool> => {
+    const response = await makeRequest('/api/executors/tools/install', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<AgentTool>(response);
+  },
+
+  updateToolSettings: async (
+    data: UpdateToolSettingsRequest
+  ): Promise<string> => {
+    const response = await makeRequest('/api/executors/tools/settings', {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<string>(response);
+  },
+};
+
 // Images API
 export const imagesApi = {
   upload: async (file: File): Promise<ImageResponse> => {
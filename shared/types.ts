@@ -8,16 +8,164 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, init_submodules: boolean, sparse_checkout_patterns: string | null, sparse_auto_expand: boolean, created_at: Date, updated_at: Date, };
 
-export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, 
+/**
+ * Initialise submodules in attempt worktrees (defaults to off)
+ */
+init_submodules?: boolean, 
+/**
+ * Comma-separated directories for a cone-mode sparse checkout
+ */
+sparse_checkout_patterns?: string, sparse_auto_expand?: boolean, };
 
-export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, 
+/**
+ * Leaves the current setting unchanged when omitted
+ */
+init_submodules?: boolean, 
+/**
+ * Leaves the current patterns unchanged when omitted; an empty string clears them
+ */
+sparse_checkout_patterns?: string, sparse_auto_expand?: boolean, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
+export type ProjectRepository = { id: string, project_id: string, 
+/**
+ * Directory name used for the repository inside an attempt workspace
+ */
+name: string, git_repo_path: string, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateProjectRepository = { name: string, git_repo_path: string, default_target_branch: string | null, };
+
+export type ProjectMcpServer = { id: string, project_id: string, name: string, 
+/**
+ * Server definition in the same format as the global MCP settings
+ */
+config: JsonValue, created_at: Date, updated_at: Date, };
+
+export type UpdateProjectMcpServers = { servers: { [key in string]?: JsonValue }, };
+
+export type ProjectSecret = { id: string, project_id: string, 
+/**
+ * Environment variable name the value is exposed as
+ */
+name: string, expose_to_scripts: boolean, expose_to_agents: boolean, created_at: Date, updated_at: Date, };
+
+export type UpsertProjectSecret = { 
+/**
+ * New value; omit to keep the stored value and only change where it is exposed
+ */
+value: string | null, expose_to_scripts: boolean, expose_to_agents: boolean, };
+
+export type MemberRole = "viewer" | "contributor" | "maintainer" | "owner";
+
+export type Organization = { id: string, name: string, created_by: string, created_at: Date, updated_at: Date, };
+
+export type OrganizationMember = { organization_id: string, user_id: string, role: MemberRole, created_at: Date, updated_at: Date, };
+
+export type CreateOrganization = { name: string, };
+
+export type UpdateMemberRole = { role: MemberRole, };
+
+export type ProjectMember = { project_id: string, user_id: string, role: MemberRole, created_at: Date, updated_at: Date, };
+
+export type TokenScope = "read" | "tasks:write" | "attempts:run" | "merge";
+
+export type ApiToken = { id: string, user_id: string, name: string, 
+/**
+ * First characters of the token, to tell tokens apart
+ */
+token_prefix: string, scopes: Array<TokenScope>, expires_at: Date | null, last_used_at: Date | null, created_at: Date, };
+
+export type CreateApiToken = { name: string, scopes: Array<TokenScope>, 
+/**
+ * Days until the token expires (at most 3650); omit for a token that never expires
+ */
+expires_in_days: number | null, };
+
+export type AuditActorType = "user" | "system";
+
+export type AuditAction = "attempt_started" | "attempt_follow_up" | "attempt_stopped" | "attempt_merged" | "attempt_pushed" | "attempt_rebased" | "attempt_target_branch_changed" | "attempt_branch_renamed" | "attempt_file_deleted" | "pull_request_created" | "pull_request_attached" | "project_created" | "project_updated" | "project_deleted" | "project_repository_added" | "project_repository_removed" | "project_mcp_servers_updated" | "project_secret_updated" | "project_secret_deleted" | "project_member_updated" | "project_member_removed" | "project_organization_changed" | "project_webhook_created" | "project_webhook_updated" | "project_webhook_deleted" | "project_webhook_redelivered" | "config_updated" | "mcp_config_updated" | "executor_profiles_updated";
+
+export type AuditEvent = { id: string, actor_type: AuditActorType, 
+/**
+ * User id of the actor; null for system actions
+ */
+actor_id: string | null, actor_name: string | null, 
+/**
+ * Whether the request was authenticated with a personal API token
+ */
+via_api_token: boolean, action: AuditAction, project_id: string | null, task_id: string | null, attempt_id: string | null, 
+/**
+ * Other target of the action, such as a secret name, member or branch
+ */
+target: string | null, request_method: string | null, request_path: string | null, ip_address: string | null, user_agent: string | null, 
+/**
+ * Snapshot before the change, for updates and deletes
+ */
+before: unknown, 
+/**
+ * Snapshot after the change, for creates and updates
+ */
+after: unknown, created_at: Date, };
+
+export type AuditEventFilter = { actor_id: string | null, action: AuditAction | null, project_id: string | null, task_id: string | null, attempt_id: string | null, 
+/**
+ * Only events at or after this time
+ */
+since: Date | null, 
+/**
+ * Only events before this time
+ */
+until: Date | null, };
+
+export type WebhookEventType = "task.created" | "task.status_changed" | "attempt.started" | "attempt.finished" | "attempt.failed" | "pr.opened" | "pr.merged" | "approval.requested";
+
+export type Webhook = { id: string, project_id: string, url: string, events: Array<WebhookEventType>, enabled: boolean, created_at: Date, updated_at: Date, };
+
+export type CreateWebhook = { url: string, events: Array<WebhookEventType>, 
+/**
+ * Signing secret; one is generated when omitted
+ */
+secret: string | null, };
+
+export type UpdateWebhook = { url: string | null, events: Array<WebhookEventType> | null, enabled: boolean | null, secret: string | null, };
+
+export type WebhookDeliveryStatus = "pending" | "succeeded" | "failed";
+
+export type WebhookDelivery = { id: string, webhook_id: string, event: WebhookEventType, 
+/**
+ * The JSON body sent to the webhook
+ */
+payload: unknown, status: WebhookDeliveryStatus, attempts: bigint, response_status: bigint | null, 
+/**
+ * Start of the response body, for debugging receivers
+ */
+response_body: string | null, 
+/**
+ * Why the latest attempt failed, if it did
+ */
+error: string | null, next_attempt_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type UserIdentity = { id: string, user_id: string, 
+/**
+ * `oidc`, `github`, ...
+ */
+provider: string, 
+/**
+ * Stable id of the account at the provider
+ */
+subject: string, email: string | null, display_name: string, created_at: Date, updated_at: Date, };
+
+export type IdentityProviderInfo = { id: string, name: string, };
+
+export type TaskAttemptRepository = { id: string, task_attempt_id: string, project_repository_id: string, worktree_path: string, target_branch: string, merge_commit: string | null, pr_number: bigint | null, pr_url: string | null, created_at: Date, updated_at: Date, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
@@ -30,9 +178,9 @@ export type ScriptRequest = { script: string, language: ScriptRequestLanguage, c
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", CUSTOM_AGENT = "CUSTOM_AGENT", ACP = "ACP", MODEL_API = "MODEL_API" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "CUSTOM_AGENT": CustomAgent } | { "ACP": Acp } | { "MODEL_API": ModelApi };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -64,7 +212,11 @@ export type UserSystemInfo = { config: Config, analytics_user_id: string, enviro
 /**
  * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, 
+/**
+ * Fallback chains keyed by executor profile ("CLAUDE_CODE" or "CLAUDE_CODE:PLAN")
+ */
+fallbacks: { [key in string]?: FallbackChain }, };
 
 export type GitHubSecretState = { has_oauth_token: boolean, has_pat: boolean, };
 
@@ -78,8 +230,42 @@ export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, }
 
 export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
 
+export type CheckStatus = "ok" | "warning" | "error" | "skipped";
+
+export type HealthCheck = { status: CheckStatus, message: string, };
+
+export type ExecutorHealth = { executor: BaseCodingAgent, 
+/**
+ * False when a check failed that will stop the executor from starting
+ */
+available: boolean, version: string | null, binary: HealthCheck, version_check: HealthCheck, auth: HealthCheck, mcp_config: HealthCheck, };
+
+export type ExecutorHealthReport = { executors: Array<ExecutorHealth>, checked_at: Date, };
+
+export type AgentTool = { executor_profile_id: ExecutorProfileId, 
+/**
+ * Package and version the profile launches, after any version pin
+ */
+package: NpmPackage, installed: boolean, };
+
+export type AgentToolsResponse = { allow_npx_fallback: boolean, cache_dir: string, tools: Array<AgentTool>, };
+
+export type InstallAgentToolRequest = { executor_profile_id: ExecutorProfileId, 
+/**
+ * Pin the profile to this version before installing; omit to install the current version
+ */
+version: string | null, };
+
+export type UpdateToolSettingsRequest = { allow_npx_fallback: boolean, };
+
 export type CreateFollowUpAttempt = { prompt: string, variant: string | null, image_ids: Array<string> | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type HandoffAttemptRequest = { executor_profile_id: ExecutorProfileId, 
+/**
+ * Optional instructions for the new executor, appended after the handed-over context
+ */
+prompt: string | null, };
+
 export type DraftResponse = { task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, variant: string | null, image_ids: Array<string> | null, version: bigint, };
 
 export type UpdateFollowUpDraftRequest = { prompt: string | null, variant: string | null | null, image_ids: Array<string> | null, version: bigint | null, };
@@ -96,6 +282,24 @@ export type RenameBranchResponse = { branch: string, };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, base_branch: string, };
 
+export type ProjectMembers = { owner_id: string | null, organization_id: string | null, members: Array<ProjectMember>, your_role: MemberRole, };
+
+export type UpdateProjectOrganization = { 
+/**
+ * None stops sharing the project with its organization
+ */
+organization_id: string | null, };
+
+export type CreatedApiToken = { api_token: ApiToken, token: string, };
+
+export type AuditEventPage = { events: Array<AuditEvent>, 
+/**
+ * Whether more events match after this page
+ */
+has_more: boolean, };
+
+export type CreatedWebhook = { webhook: Webhook, secret: string, };
+
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
@@ -120,12 +324,28 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO";
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
-export type DeviceFlowStartResponse = { user_code: string, verification_uri: string, expires_in: number, interval: number, };
+export type DeviceFlowStartResponse = { 
+/**
+ * Passed back when polling; only the starter of the flow can complete it
+ */
+flow_id: string, user_code: string, verification_uri: string, expires_in: number, interval: number, };
+
+export type DevicePollRequest = { flow_id: string, };
 
 export enum DevicePollStatus { SLOW_DOWN = "SLOW_DOWN", AUTHORIZATION_PENDING = "AUTHORIZATION_PENDING", SUCCESS = "SUCCESS" }
 
 export enum CheckTokenResponse { VALID = "VALID", INVALID = "INVALID" }
 
+export type ForgeProvider = "github" | "gitlab" | "gitea";
+
+export type ForgeTokenRequest = { provider: ForgeProvider, 
+/**
+ * Host name (`gitlab.example.com`) or base URL (`http://gitea.local:3000`)
+ */
+host: string, token: string, };
+
+export type ForgeHostQuery = { provider: ForgeProvider, host: string, };
+
 export type ClaudeSessionResponse = { session_id: string, };
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
@@ -164,19 +384,39 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "CUSTOM_AGENT": CustomAgent } | { "ACP": Acp } | { "MODEL_API": ModelApi } };
+
+export type FallbackChain = { 
+/**
+ * Executors to try, in order, once the primary executor fails
+ */
+chain: Array<ExecutorProfileId>, 
+/**
+ * Conditions that hand the attempt over to the next executor
+ */
+triggers: Array<FallbackTrigger>, };
+
+export type FallbackTrigger = { "type": "non_zero_exit" } | { "type": "rate_limit" } | { "type": "error", error_type: NormalizedEntryError };
+
+export type ToolCacheConfig = { 
+/**
+ * Run agents through npx when their pinned version isn't in the tool cache
+ */
+allow_npx_fallback: boolean, };
+
+export type NpmPackage = { name: string, version: string, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
 
 export type GeminiModel = "default" | "flash";
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_plan_tool?: boolean | null, include_apply_patch_tool?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_plan_tool?: boolean | null, include_apply_patch_tool?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -188,13 +428,51 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
+
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
+
+export type CustomAgent = { append_prompt: AppendPrompt, command: string, prompt_input: CustomPromptInput, follow_up_args?: Array<string> | null, session_id_pattern?: string | null, output_format: CustomOutputFormat, output_rules: Array<CustomOutputRule>, mcp_config_path?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
+
+export type CustomPromptInput = "stdin" | "argument";
+
+export type CustomOutputFormat = "lines" | "json_lines";
+
+export type CustomEntryKind = "assistant_message" | "thinking" | "system_message" | "error_message" | "tool_use" | "ignore";
+
+export type CustomToolAction = "file_read" | "file_edit" | "command_run" | "search" | "web_fetch" | "other";
+
+export type CustomOutputRule = { 
+/**
+ * JSON pointer of the value to match in `json_lines` mode, e.g. `/type`
+ */
+field?: string | null, 
+/**
+ * Regex the line (or field) must match; every line matches when unset
+ */
+pattern?: string | null, entry: CustomEntryKind, 
+/**
+ * Entry content template, `{line}` by default
+ */
+content?: string | null, 
+/**
+ * Tool name template for `tool_use` entries
+ */
+tool_name?: string | null, tool_action?: CustomToolAction | null, 
+/**
+ * Template for the tool's path, command, query or URL
+ */
+tool_target?: string | null, };
+
+export type Acp = { append_prompt: AppendPrompt, command: string, session_namespace?: string | null, mcp_config_path?: string | null, auto_approve?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type ModelApi = { append_prompt: AppendPrompt, provider: ModelApiProvider, model: string, base_url?: string | null, api_key_env?: string | null, max_tokens?: number | null, max_turns?: number | null, system_prompt?: string | null, };
 
-export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type ModelApiProvider = "openai" | "anthropic";
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
+
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, };
 
 export type AppendPrompt = string | null;
 
@@ -262,8 +540,34 @@ conflict_op: ConflictOp | null,
  */
 conflicted_files: Array<string>, };
 
+export type AttemptRepositoryStatus = { repository: ProjectRepository, attempt_repository: TaskAttemptRepository, commits_ahead: number | null, commits_behind: number | null, has_uncommitted_changes: boolean | null, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+export type PatchFormat = "mbox" | "diff";
+
+export type CreateTaskAttemptFromPatchBody = { task_id: string, executor_profile_id: ExecutorProfileId, base_branch: string, 
+/**
+ * `git format-patch` mbox or unified diff
+ */
+patch: string, };
+
+export type PatchImportResult = { task_attempt: TaskAttempt, 
+/**
+ * Hunks that could not be applied; everything else was committed
+ */
+conflicts: Array<PatchConflict>, };
+
+export type PatchConflict = { path: string, 
+/**
+ * 1-based index of the hunk within the file's patch
+ */
+hunk: number, 
+/**
+ * The rejected hunk, including its `@@` header
+ */
+content: string, };
+
 export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string, target_branch: string, executor: string, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, task_attempt_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, 
@@ -322,7 +626,7 @@ export type ActionType = { "action": "file_read", path: string, } | { "action":
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
-export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
+export type NormalizedEntryError = { "type": "setup_required" } | { "type": "rate_limited" } | { "type": "other" };
 
 export type ToolResult = { type: ToolResultValueType, 
 /**